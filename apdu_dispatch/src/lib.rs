@@ -0,0 +1,528 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, Ident, Token, Type};
+
+/// One `key` or `key = value` argument of an `#[apdu(...)]` attribute.
+enum ApduArg {
+    Cla(Expr),
+    Ins(Expr),
+    P1(Expr),
+    P2(Expr),
+    Data,
+    Handler(Expr),
+    Context(Type),
+}
+
+impl Parse for ApduArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse::<Ident>()?;
+        match key.to_string().as_str() {
+            "data" => Ok(ApduArg::Data),
+            "context" => {
+                let _: Token![=] = input.parse()?;
+                Ok(ApduArg::Context(input.parse()?))
+            }
+            name => {
+                let _: Token![=] = input.parse()?;
+                let value = input.parse::<Expr>()?;
+                match name {
+                    "cla" => Ok(ApduArg::Cla(value)),
+                    "ins" => Ok(ApduArg::Ins(value)),
+                    "p1" => Ok(ApduArg::P1(value)),
+                    "p2" => Ok(ApduArg::P2(value)),
+                    "handler" => Ok(ApduArg::Handler(value)),
+                    _ => Err(syn::Error::new_spanned(key, "Invalid apdu attribute")),
+                }
+            }
+        }
+    }
+}
+
+fn parse_apdu_args(attrs: &[Attribute]) -> syn::Result<Vec<ApduArg>> {
+    let mut args = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("apdu")) {
+        let parsed = attr.parse_args_with(Punctuated::<ApduArg, Token![,]>::parse_terminated)?;
+        args.extend(parsed);
+    }
+    Ok(args)
+}
+
+/// Description of a CLA route, built from the `#[apdu(cla = ...)]` attribute
+/// of an enumeration variant wrapping another instruction enumeration.
+struct ClaRoute {
+    variant: Ident,
+    cla: Expr,
+    inner: Type,
+}
+
+/// Description of an instruction, built from the `#[apdu(...)]` attribute of
+/// an enumeration variant.
+struct Instruction {
+    variant: Ident,
+    ins: Expr,
+    p1: Option<Expr>,
+    p2: Option<Expr>,
+    data: bool,
+    handler: Option<Expr>,
+}
+
+/// Derives `TryFrom<ApduHeader>` for an enumeration of instructions, and a
+/// `dispatch` method calling the handler associated with each instruction.
+///
+/// Each variant is annotated with `#[apdu(ins = .., p1 = .., p2 = .., data, handler = ..)]`:
+///
+/// * `ins` - INS byte of the instruction (mandatory),
+/// * `p1`, `p2` - patterns of accepted P1 and P2 values. All values are accepted if omitted.
+///   Several variants can share an INS with different patterns, the first variant accepting
+///   the APDU is selected. APDUs with a known INS which no variant accepts are rejected with
+///   `StatusWords::BadP1P2`,
+/// * `data` - the APDU must carry data, otherwise `dispatch` rejects it with
+///   `StatusWords::BadLen` without calling the handler. The conversion from `ApduHeader`
+///   cannot check it, so `data` requires a `handler`,
+/// * `handler` - function called by `dispatch`, of type
+///   `fn(&mut Comm) -> Result<(), Reply>`, or `fn(&mut Comm, &mut Context) -> Result<(), Reply>`
+///   when the enumeration is annotated with `#[apdu(context = Context)]`. Either every
+///   instruction has a handler, and `dispatch` is generated, or none has.
+///
+/// APDUs with an unknown INS are rejected with `StatusWords::BadIns`.
+///
+/// Applications implementing several protocols can route each CLA to its own
+/// instruction enumeration, with single-field variants annotated with
+/// `#[apdu(cla = ..)]`. APDUs with this CLA are converted using the `TryFrom<ApduHeader>`
+/// implementation of the field type, and `dispatch` calls the `dispatch` method
+/// of the field, which must take the same context. When the enumeration only
+/// contains such variants, APDUs with other CLA are rejected with `StatusWords::BadCla`.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(ApduDispatch)]
+/// #[apdu(context = AppContext)]
+/// enum Instruction {
+///     #[apdu(ins = 0x03, p1 = 0, p2 = 0, handler = handle_get_version)]
+///     GetVersion,
+///     #[apdu(ins = 0x06, p1 = 0..=3, p2 = 0x00 | 0x80, data, handler = handle_sign_tx)]
+///     SignTx,
+/// }
+///
+/// loop {
+///     let ins: Instruction = comm.next_command();
+///     ins.dispatch(&mut comm, &mut ctx);
+/// }
+/// ```
+///
+/// Routing two CLA to their own instruction enumeration:
+///
+/// ```
+/// #[derive(ApduDispatch)]
+/// #[apdu(context = AppContext)]
+/// enum Command {
+///     #[apdu(cla = 0xe0)]
+///     App(Instruction),
+///     #[apdu(cla = 0xe1)]
+///     WalletPolicy(PolicyInstruction),
+/// }
+/// ```
+#[proc_macro_derive(ApduDispatch, attributes(apdu))]
+pub fn apdu_dispatch(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match generate_dispatch(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn generate_dispatch(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ApduDispatch can only be derived for enumerations",
+            ))
+        }
+    };
+
+    let mut context = None;
+    for arg in parse_apdu_args(&input.attrs)? {
+        match arg {
+            ApduArg::Context(ty) => context = Some(ty),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "Only `context` is allowed on the enumeration",
+                ))
+            }
+        }
+    }
+
+    let mut routes = Vec::new();
+    let mut instructions = Vec::new();
+    for variant in data.variants.iter() {
+        match &variant.fields {
+            Fields::Unit => (),
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let mut cla = None;
+                for arg in parse_apdu_args(&variant.attrs)? {
+                    match arg {
+                        ApduArg::Cla(e) => cla = Some(e),
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                variant,
+                                "Only `cla` is allowed on single-field variants",
+                            ))
+                        }
+                    }
+                }
+                routes.push(ClaRoute {
+                    variant: variant.ident.clone(),
+                    cla: cla.ok_or_else(|| {
+                        syn::Error::new_spanned(variant, "Missing `#[apdu(cla = ...)]` attribute")
+                    })?,
+                    inner: fields.unnamed[0].ty.clone(),
+                });
+                continue;
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "ApduDispatch only supports unit and single-field variants",
+                ))
+            }
+        }
+        let mut ins = None;
+        let mut instruction = Instruction {
+            variant: variant.ident.clone(),
+            ins: Expr::Verbatim(TokenStream2::new()),
+            p1: None,
+            p2: None,
+            data: false,
+            handler: None,
+        };
+        for arg in parse_apdu_args(&variant.attrs)? {
+            match arg {
+                ApduArg::Ins(e) => ins = Some(e),
+                ApduArg::P1(e) => instruction.p1 = Some(e),
+                ApduArg::P2(e) => instruction.p2 = Some(e),
+                ApduArg::Data => instruction.data = true,
+                ApduArg::Handler(e) => instruction.handler = Some(e),
+                ApduArg::Cla(_) => {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "`cla` is only allowed on single-field variants",
+                    ))
+                }
+                ApduArg::Context(_) => {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "`context` is only allowed on the enumeration",
+                    ))
+                }
+            }
+        }
+        instruction.ins = ins.ok_or_else(|| {
+            syn::Error::new_spanned(variant, "Missing `#[apdu(ins = ...)]` attribute")
+        })?;
+        if instruction.data && instruction.handler.is_none() {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`data` is checked by `dispatch` and requires a `handler`",
+            ));
+        }
+        instructions.push(instruction);
+    }
+
+    // The dispatch method is only generated when every instruction has a handler
+    let with_handler = instructions.iter().filter(|i| i.handler.is_some()).count();
+    if with_handler != 0 && with_handler != instructions.len() {
+        let variant = instructions.iter().find(|i| i.handler.is_none()).unwrap();
+        return Err(syn::Error::new_spanned(
+            &variant.variant,
+            "Missing `handler`: either every instruction or none has one",
+        ));
+    }
+
+    // P1 and P2 are checked in the guards, so that the next variants with the
+    // same INS are tried when they are not accepted
+    let try_from_arms = instructions.iter().map(|i| {
+        let variant = &i.variant;
+        let ins = &i.ins;
+        let p1_check = i.p1.as_ref().map(|p1| {
+            quote! { && matches!(header.p1, #p1) }
+        });
+        let p2_check = i.p2.as_ref().map(|p2| {
+            quote! { && matches!(header.p2, #p2) }
+        });
+        quote! {
+            x if x == #ins #p1_check #p2_check => Ok(#name::#variant),
+        }
+    });
+
+    // Each INS once, when several variants share it
+    let mut known_ins: Vec<&Expr> = Vec::new();
+    for i in instructions.iter() {
+        let ins = &i.ins;
+        if !known_ins
+            .iter()
+            .any(|known| quote!(#known).to_string() == quote!(#ins).to_string())
+        {
+            known_ins.push(ins);
+        }
+    }
+    let bad_p1p2_arm = (!instructions.is_empty()).then(|| {
+        quote! {
+            x if #(x == #known_ins)||* => Err(::ledger_device_sdk::io::StatusWords::BadP1P2),
+        }
+    });
+
+    let route_checks = routes.iter().map(|r| {
+        let variant = &r.variant;
+        let cla = &r.cla;
+        let inner = &r.inner;
+        quote! {
+            if header.cla == #cla {
+                return Ok(#name::#variant(<#inner>::try_from(header)?));
+            }
+        }
+    });
+
+    let unknown = if instructions.is_empty() {
+        quote! { ::ledger_device_sdk::io::StatusWords::BadCla }
+    } else {
+        quote! { ::ledger_device_sdk::io::StatusWords::BadIns }
+    };
+
+    let try_from = quote! {
+        impl TryFrom<::ledger_device_sdk::io::ApduHeader> for #name {
+            type Error = ::ledger_device_sdk::io::StatusWords;
+
+            fn try_from(
+                header: ::ledger_device_sdk::io::ApduHeader,
+            ) -> Result<Self, Self::Error> {
+                #(#route_checks)*
+                match header.ins {
+                    #(#try_from_arms)*
+                    #bad_p1p2_arm
+                    _ => Err(#unknown),
+                }
+            }
+        }
+    };
+
+    if with_handler == 0 && !instructions.is_empty() {
+        return Ok(try_from);
+    }
+
+    let (ctx_param, ctx_arg) = match &context {
+        Some(ty) => (quote! { , ctx: &mut #ty }, quote! { , ctx }),
+        None => (quote! {}, quote! {}),
+    };
+
+    let dispatch_arms = instructions.iter().map(|i| {
+        let variant = &i.variant;
+        let handler = i.handler.as_ref().unwrap();
+        let call = if i.data {
+            quote! {
+                match comm.get_data() {
+                    Ok(data) if !data.is_empty() => #handler(comm #ctx_arg),
+                    Ok(_) => Err(::ledger_device_sdk::io::StatusWords::BadLen.into()),
+                    Err(sw) => Err(sw.into()),
+                }
+            }
+        } else {
+            quote! { #handler(comm #ctx_arg) }
+        };
+        quote! {
+            #name::#variant => {
+                let res: Result<(), ::ledger_device_sdk::io::Reply> = #call;
+                match res {
+                    Ok(()) => comm.reply_ok(),
+                    Err(sw) => comm.reply(sw),
+                }
+            }
+        }
+    });
+
+    let route_arms = routes.iter().map(|r| {
+        let variant = &r.variant;
+        quote! {
+            #name::#variant(inner) => inner.dispatch(comm #ctx_arg),
+        }
+    });
+
+    Ok(quote! {
+        #try_from
+
+        impl #name {
+            /// Calls the handler associated with the instruction, then replies
+            /// with the status word it returned (`0x9000` on success).
+            pub fn dispatch(self, comm: &mut ::ledger_device_sdk::io::Comm #ctx_param) {
+                match self {
+                    #(#route_arms)*
+                    #(#dispatch_arms)*
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn expand(input: DeriveInput) -> String {
+        generate_dispatch(input).unwrap().to_string()
+    }
+
+    fn error(input: DeriveInput) -> String {
+        generate_dispatch(input).unwrap_err().to_string()
+    }
+
+    /// Returns true if `expanded` contains the tokens of `fragment`.
+    fn contains(expanded: &str, fragment: TokenStream2) -> bool {
+        expanded.contains(&fragment.to_string())
+    }
+
+    #[test]
+    fn p1_p2_in_guards() {
+        let expanded = expand(parse_quote! {
+            enum Instruction {
+                #[apdu(ins = 0x02, p1 = 0, handler = get_address)]
+                GetAddress,
+                #[apdu(ins = 0x02, p1 = 1, p2 = 0x00 | 0x80, handler = show_address)]
+                ShowAddress,
+            }
+        });
+        assert!(contains(
+            &expanded,
+            quote! { x if x == 0x02 && matches!(header.p1, 0) => Ok(Instruction::GetAddress), }
+        ));
+        assert!(contains(
+            &expanded,
+            quote! {
+                x if x == 0x02 && matches!(header.p1, 1) && matches!(header.p2, 0x00 | 0x80)
+                    => Ok(Instruction::ShowAddress),
+            }
+        ));
+        // Known INS whose P1 and P2 no variant accepts
+        assert!(contains(
+            &expanded,
+            quote! {
+                x if x == 0x02 => Err(::ledger_device_sdk::io::StatusWords::BadP1P2),
+                _ => Err(::ledger_device_sdk::io::StatusWords::BadIns),
+            }
+        ));
+    }
+
+    #[test]
+    fn data_checked_by_dispatch() {
+        let expanded = expand(parse_quote! {
+            enum Instruction {
+                #[apdu(ins = 0x04, data, handler = sign)]
+                Sign,
+            }
+        });
+        assert!(contains(
+            &expanded,
+            quote! { Ok(_) => Err(::ledger_device_sdk::io::StatusWords::BadLen.into()), }
+        ));
+
+        let e = error(parse_quote! {
+            enum Instruction {
+                #[apdu(ins = 0x04, data)]
+                Sign,
+            }
+        });
+        assert_eq!(
+            e,
+            "`data` is checked by `dispatch` and requires a `handler`"
+        );
+    }
+
+    #[test]
+    fn handlers() {
+        let expanded = expand(parse_quote! {
+            #[apdu(context = AppContext)]
+            enum Instruction {
+                #[apdu(ins = 0x03, handler = get_version)]
+                GetVersion,
+            }
+        });
+        assert!(contains(
+            &expanded,
+            quote! { pub fn dispatch(self, comm: &mut ::ledger_device_sdk::io::Comm, ctx: &mut AppContext) }
+        ));
+        assert!(contains(&expanded, quote! { get_version(comm, ctx) }));
+
+        // Instructions without handlers are only converted
+        let expanded = expand(parse_quote! {
+            enum Instruction {
+                #[apdu(ins = 0x03)]
+                GetVersion,
+            }
+        });
+        assert!(!expanded.contains("dispatch"));
+
+        let e = error(parse_quote! {
+            enum Instruction {
+                #[apdu(ins = 0x03, handler = get_version)]
+                GetVersion,
+                #[apdu(ins = 0x04)]
+                Sign,
+            }
+        });
+        assert_eq!(
+            e,
+            "Missing `handler`: either every instruction or none has one"
+        );
+    }
+
+    #[test]
+    fn cla_routing() {
+        let expanded = expand(parse_quote! {
+            enum Command {
+                #[apdu(cla = 0xe0)]
+                App(Instruction),
+                #[apdu(cla = 0xe1)]
+                WalletPolicy(PolicyInstruction),
+            }
+        });
+        assert!(contains(
+            &expanded,
+            quote! {
+                if header.cla == 0xe1 {
+                    return Ok(Command::WalletPolicy(<PolicyInstruction>::try_from(header)?));
+                }
+            }
+        ));
+        assert!(contains(
+            &expanded,
+            quote! { _ => Err(::ledger_device_sdk::io::StatusWords::BadCla), }
+        ));
+        assert!(contains(
+            &expanded,
+            quote! { Command::App(inner) => inner.dispatch(comm), }
+        ));
+
+        let e = error(parse_quote! {
+            enum Command {
+                App(Instruction),
+            }
+        });
+        assert_eq!(e, "Missing `#[apdu(cla = ...)]` attribute");
+
+        let e = error(parse_quote! {
+            enum Command {
+                #[apdu(cla = 0xe0)]
+                GetVersion,
+            }
+        });
+        assert_eq!(e, "`cla` is only allowed on single-field variants");
+    }
+}
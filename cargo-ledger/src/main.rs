@@ -182,12 +182,30 @@ fn retrieve_metadata(
     }
 }
 
+/// Returns the flags of the application on `device`, as an hexadecimal
+/// string. Applications on devices with Bluetooth get the flag enabling it.
+fn app_flags(device: Device, flags: &str) -> String {
+    match device {
+        Device::Nanos | Device::Nanosplus => flags.to_string(),
+        Device::Nanox | Device::Stax | Device::Flex => {
+            let digits = flags.trim_start_matches("0x");
+            let base = u32::from_str_radix(digits, 16).unwrap_or(0);
+            format!("0x{:x}", base | 0x200)
+        }
+    }
+}
+
 fn build_app(
     device: Device,
     is_load: bool,
     use_prebuilt: Option<PathBuf>,
     remaining_args: Vec<String>,
 ) {
+    let (this_pkg, metadata_ledger, metadata_device) =
+        retrieve_metadata(device, None);
+    let app_name = metadata_ledger.name.as_ref().unwrap_or(&this_pkg.name);
+    let flags = app_flags(device, &metadata_ledger.flags);
+
     let exe_path = match use_prebuilt {
         None => {
             let c_sdk_path = match device {
@@ -231,9 +249,12 @@ fn build_app(
                 },
             }
 
+            // Exposed to the application by `ledger_device_sdk::app_metadata!`
             let mut cargo_cmd = Command::new("cargo")
                 .args(args)
                 .args(&remaining_args)
+                .env("LEDGER_APP_NAME", app_name)
+                .env("LEDGER_APP_FLAGS", &flags)
                 .stdout(Stdio::piped())
                 .spawn()
                 .unwrap();
@@ -262,9 +283,6 @@ fn build_app(
         Some(prebuilt) => prebuilt.canonicalize().unwrap(),
     };
 
-    let (this_pkg, metadata_ledger, metadata_device) =
-        retrieve_metadata(device, None);
-
     let package_path = this_pkg
         .manifest_path
         .parent()
@@ -291,16 +309,6 @@ fn build_app(
     // Retrieve real data size and SDK infos from ELF
     let infos = retrieve_infos(&exe_path).unwrap();
 
-    // Modify flags to enable BLE if targeting Nano X
-    let flags = match device {
-        Device::Nanos | Device::Nanosplus => metadata_ledger.flags,
-        Device::Nanox | Device::Stax | Device::Flex => {
-            let base = u32::from_str_radix(metadata_ledger.flags.as_str(), 16)
-                .unwrap_or(0);
-            format!("0x{:x}", base | 0x200)
-        }
-    };
-
     // Target ID according to target, in case it
     // is not present in the retrieved ELF infos.
     let backup_targetid: String = match device {
@@ -314,7 +322,7 @@ fn build_app(
     // create manifest
     let file = fs::File::create(&app_json).unwrap();
     let mut json = json!({
-        "name": metadata_ledger.name.as_ref().unwrap_or(&this_pkg.name),
+        "name": app_name,
         "version": &this_pkg.version,
         "icon": metadata_device.icon,
         "targetId": infos.target_id.unwrap_or(backup_targetid),
@@ -421,4 +429,11 @@ mod tests {
         assert_eq!(metadata_ledger.path, ["'44/123"]);
         assert_eq!(metadata_nanos.icon, "nanos.gif")
     }
+
+    #[test]
+    fn bluetooth_flag() {
+        assert_eq!(app_flags(Device::Nanosplus, "0x38"), "0x38");
+        assert_eq!(app_flags(Device::Nanox, "0x38"), "0x238");
+        assert_eq!(app_flags(Device::Stax, "0"), "0x200");
+    }
 }
@@ -55,6 +55,166 @@ pub fn include_gif(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Packs the glyphs of a BDF font, for `NbglFont::from_include` of the Rust
+/// SDK. The glyphs are normalized to cells as high as the font, holding one
+/// bit per pixel, row by row, each row padded to a byte.
+#[proc_macro]
+pub fn include_font(input: TokenStream) -> TokenStream {
+    let filename = parse_macro_input!(input as LitStr);
+    let path = format!(
+        "{}/{}",
+        std::env::var("CARGO_MANIFEST_DIR").unwrap(),
+        filename.value()
+    );
+    let font = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|bdf| parse_bdf(&bdf))
+    {
+        Ok(font) => font,
+        Err(e) => {
+            return syn::Error::new_spanned(filename, format!("{path}: {e}"))
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let mut vec_output = Vec::new();
+    write!(
+        &mut vec_output,
+        "({}u16, {}u16, &[",
+        font.ascent, font.descent
+    )
+    .unwrap();
+    for glyph in font.glyphs.iter() {
+        write!(
+            &mut vec_output,
+            "({}u32, {}u8, &{:?}[..]),",
+            glyph.codepoint, glyph.width, glyph.rows
+        )
+        .unwrap();
+    }
+    write!(&mut vec_output, "])").unwrap();
+
+    let stream_output = std::str::from_utf8(&vec_output).unwrap();
+    stream_output.parse().unwrap()
+}
+
+struct BdfGlyph {
+    codepoint: u32,
+    width: u8,
+    rows: Vec<u8>,
+}
+
+struct BdfFont {
+    ascent: u16,
+    descent: u16,
+    glyphs: Vec<BdfGlyph>,
+}
+
+fn parse_number<T: std::str::FromStr>(word: Option<&str>, line: &str) -> Result<T, String> {
+    word.and_then(|w| w.parse().ok())
+        .ok_or_else(|| format!("invalid line \"{line}\""))
+}
+
+// Parse a font in the Glyph Bitmap Distribution Format (BDF). Glyphs with no
+// Unicode encoding are skipped.
+fn parse_bdf(bdf: &str) -> Result<BdfFont, String> {
+    let mut ascent = None;
+    let mut descent = None;
+    let mut glyphs = Vec::new();
+    let mut lines = bdf.lines();
+
+    while let Some(line) = lines.next() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("FONT_ASCENT") => ascent = Some(parse_number::<i32>(words.next(), line)?),
+            Some("FONT_DESCENT") => descent = Some(parse_number::<i32>(words.next(), line)?),
+            Some("STARTCHAR") => {
+                let (Some(ascent), Some(descent)) = (ascent, descent) else {
+                    return Err("missing FONT_ASCENT or FONT_DESCENT".into());
+                };
+                let mut codepoint = -1;
+                let mut advance = 0;
+                let mut bbx = (0, 0, 0, 0);
+                // Cell of the glyph, with one byte per pixel
+                let mut pixels = Vec::new();
+                while let Some(line) = lines.next() {
+                    let mut words = line.split_whitespace();
+                    match words.next() {
+                        Some("ENCODING") => codepoint = parse_number(words.next(), line)?,
+                        Some("DWIDTH") => advance = parse_number(words.next(), line)?,
+                        Some("BBX") => {
+                            bbx = (
+                                parse_number::<i32>(words.next(), line)?,
+                                parse_number::<i32>(words.next(), line)?,
+                                parse_number::<i32>(words.next(), line)?,
+                                parse_number::<i32>(words.next(), line)?,
+                            )
+                        }
+                        Some("BITMAP") => {
+                            if !(0..=255).contains(&advance) {
+                                return Err(format!("glyph {codepoint} is too wide"));
+                            }
+                            let (width, height, x_offset, y_offset) = bbx;
+                            pixels = vec![0u8; (advance * (ascent + descent)).max(0) as usize];
+                            for r in 0..height {
+                                let line = lines.next().ok_or("truncated bitmap")?;
+                                let row = u128::from_str_radix(line.trim(), 16)
+                                    .map_err(|_| format!("invalid bitmap row \"{line}\""))?;
+                                let bits = 4 * line.trim().len() as i32;
+                                let y = ascent - (y_offset + height) + r;
+                                for c in 0..width.min(bits) {
+                                    let x = x_offset + c;
+                                    let set = (row >> (bits - 1 - c)) & 1 == 1;
+                                    if set
+                                        && (0..advance).contains(&x)
+                                        && (0..ascent + descent).contains(&y)
+                                    {
+                                        pixels[(y * advance + x) as usize] = 1;
+                                    }
+                                }
+                            }
+                        }
+                        Some("ENDCHAR") => break,
+                        _ => (),
+                    }
+                }
+                if codepoint < 0 {
+                    continue;
+                }
+                let bytes_per_row = (advance as usize + 7) / 8;
+                let mut rows = vec![0u8; bytes_per_row * (ascent + descent) as usize];
+                for (i, row) in pixels.chunks(advance.max(1) as usize).enumerate() {
+                    for (x, &pixel) in row.iter().enumerate() {
+                        rows[i * bytes_per_row + x / 8] |= pixel << (7 - x % 8);
+                    }
+                }
+                glyphs.push(BdfGlyph {
+                    codepoint: codepoint as u32,
+                    width: advance as u8,
+                    rows,
+                });
+            }
+            _ => (),
+        }
+    }
+
+    let (Some(ascent), Some(descent)) = (ascent, descent) else {
+        return Err("missing FONT_ASCENT or FONT_DESCENT".into());
+    };
+    if !(0..=255).contains(&ascent) || !(0..=255).contains(&descent) {
+        return Err("invalid font height".into());
+    }
+    // Sorted for lookups by binary search
+    glyphs.sort_by_key(|glyph| glyph.codepoint);
+    glyphs.dedup_by_key(|glyph| glyph.codepoint);
+    Ok(BdfFont {
+        ascent: ascent as u16,
+        descent: descent as u16,
+        glyphs,
+    })
+}
+
 fn generate_glyph(filename: LitStr, glyph_type: GlyphType) -> TokenStream {
     let path = format!(
         "{}/{}",
@@ -0,0 +1,137 @@
+//! Transport to a device connected over USB
+//!
+//! APDUs are exchanged in HID reports of 64 bytes, each starting with the
+//! channel, the tag of APDU packets and the index of the report in the APDU.
+//! The first report of an APDU also holds its length on 2 big-endian bytes.
+
+use crate::{Error, Transport};
+use hidapi::{HidApi, HidDevice};
+
+/// USB vendor identifier of Ledger
+const LEDGER_VENDOR_ID: u16 = 0x2c97;
+/// HID usage page of the APDU interface
+const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+
+const PACKET_LEN: usize = 64;
+const CHANNEL: u16 = 0x0101;
+const TAG_APDU: u8 = 0x05;
+/// Length of the channel, the tag and the index of a report
+const HEADER_LEN: usize = 5;
+
+/// Timeout of the reads, long enough for the user to review a transaction
+const READ_TIMEOUT_MS: i32 = 5 * 60 * 1000;
+
+impl From<hidapi::HidError> for Error {
+    fn from(e: hidapi::HidError) -> Self {
+        Error::Hid(e)
+    }
+}
+
+/// Splits `apdu` into HID reports.
+fn wrap(apdu: &[u8]) -> Vec<[u8; PACKET_LEN]> {
+    let mut data = (apdu.len() as u16).to_be_bytes().to_vec();
+    data.extend_from_slice(apdu);
+    data.chunks(PACKET_LEN - HEADER_LEN)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut packet = [0u8; PACKET_LEN];
+            packet[..2].copy_from_slice(&CHANNEL.to_be_bytes());
+            packet[2] = TAG_APDU;
+            packet[3..5].copy_from_slice(&(index as u16).to_be_bytes());
+            packet[HEADER_LEN..HEADER_LEN + chunk.len()].copy_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// Returns the data of the report `packet`, expected at `index`, and the
+/// length of the whole response if it is the first report.
+fn unwrap(packet: &[u8], index: u16) -> Result<(Option<usize>, &[u8]), Error> {
+    let header_ok = packet.len() > HEADER_LEN
+        && packet[..2] == CHANNEL.to_be_bytes()
+        && packet[2] == TAG_APDU
+        && packet[3..5] == index.to_be_bytes();
+    if !header_ok {
+        return Err(Error::InvalidResponse(format!("HID report {index}")));
+    }
+    let data = &packet[HEADER_LEN..];
+    match data {
+        [len0, len1, data @ ..] if index == 0 => {
+            Ok((Some(u16::from_be_bytes([*len0, *len1]) as usize), data))
+        }
+        _ if index == 0 => Err(Error::InvalidResponse("HID report without length".into())),
+        _ => Ok((None, data)),
+    }
+}
+
+/// Transport to the first Ledger device connected over USB.
+pub struct HidTransport {
+    device: HidDevice,
+}
+
+impl HidTransport {
+    /// Opens the APDU interface of the first Ledger device found.
+    pub fn open() -> Result<HidTransport, Error> {
+        let api = HidApi::new()?;
+        let info = api
+            .device_list()
+            .find(|info| {
+                info.vendor_id() == LEDGER_VENDOR_ID
+                    && (info.usage_page() == LEDGER_USAGE_PAGE || info.interface_number() == 0)
+            })
+            .ok_or(Error::DeviceNotFound)?;
+        Ok(HidTransport {
+            device: info.open_device(&api)?,
+        })
+    }
+}
+
+impl Transport for HidTransport {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, Error> {
+        for packet in wrap(apdu) {
+            // Reports are preceded by the report number, 0 for Ledger devices
+            let mut report = vec![0u8];
+            report.extend_from_slice(&packet);
+            self.device.write(&report)?;
+        }
+
+        let mut response = Vec::new();
+        let mut expected = 0;
+        let mut index = 0;
+        loop {
+            let mut packet = [0u8; PACKET_LEN];
+            let len = self.device.read_timeout(&mut packet, READ_TIMEOUT_MS)?;
+            if len == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::TimedOut).into());
+            }
+            let (total, data) = unwrap(&packet[..len], index)?;
+            expected = total.unwrap_or(expected);
+            response.extend_from_slice(data);
+            if response.len() >= expected {
+                response.truncate(expected);
+                return Ok(response);
+            }
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn framing() {
+        let apdu = vec![0xab; 100];
+        let packets = wrap(&apdu);
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0][..7], [0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 100]);
+        assert_eq!(packets[1][..5], [0x01, 0x01, 0x05, 0x00, 0x01]);
+
+        let (len, data) = unwrap(&packets[0], 0).unwrap();
+        assert_eq!(len, Some(100));
+        assert_eq!(data.len(), PACKET_LEN - HEADER_LEN - 2);
+        assert_eq!(unwrap(&packets[1], 1).unwrap().0, None);
+        assert!(unwrap(&packets[1], 2).is_err());
+    }
+}
@@ -0,0 +1,374 @@
+//! Host-side client for end-to-end tests of Ledger device applications
+//!
+//! [`Client`] exchanges APDUs with an application through a [`Transport`]:
+//!
+//! - [`TcpTransport`] connects to the APDU port of Speculos, or to an
+//!   application running natively with the `emulation` feature of
+//!   `ledger_device_sdk`,
+//! - `HidTransport`, with the `hid` feature, talks to a device connected over
+//!   USB,
+//! - with the `speculos` feature, an application launched by
+//!   `speculos_harness` is a transport as well, and [`Client`] drives its
+//!   reviews while a command waits for the user.
+//!
+//! The client follows the conventions of the SDK: responses longer than an
+//! APDU are fetched with GET RESPONSE, and status words are decoded into
+//! [`StatusWord`], so that tests are plain Rust tests without any Python
+//! tooling.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use ledger_device_client::{Client, Command, ResponseExt, StatusWord, TcpTransport};
+//!
+//! let client = Client::new(TcpTransport::connect("127.0.0.1:9999").unwrap());
+//!
+//! let version = client.send(&Command::new(0xe0, 0x01)).unwrap();
+//! assert_eq!(version, [1, 0, 0]);
+//!
+//! let response = client.exchange(&Command::new(0xe0, 0xff)).unwrap();
+//! assert_eq!(response.status(), StatusWord::BadIns);
+//! ```
+
+#[cfg(feature = "hid")]
+mod hid;
+#[cfg(feature = "speculos")]
+mod speculos;
+mod tcp;
+
+#[cfg(feature = "hid")]
+pub use hid::HidTransport;
+pub use speculos_harness::Response;
+pub use tcp::TcpTransport;
+
+use std::fmt;
+
+/// INS of the GET RESPONSE command fetching the rest of a chained response
+const INS_GET_RESPONSE: u8 = 0xc0;
+
+/// Maximum length of the data of a command
+pub const MAX_DATA_LEN: usize = 255;
+
+/// Errors of the client
+#[derive(Debug)]
+pub enum Error {
+    /// The connection to the device failed.
+    Io(std::io::Error),
+    /// The USB connection to the device failed.
+    #[cfg(feature = "hid")]
+    Hid(hidapi::HidError),
+    /// The exchange with the application under Speculos failed.
+    #[cfg(feature = "speculos")]
+    Speculos(speculos_harness::Error),
+    /// No device is connected.
+    DeviceNotFound,
+    /// The data of a command is longer than [`MAX_DATA_LEN`] bytes.
+    CommandTooLong,
+    /// The device answered with a malformed response.
+    InvalidResponse(String),
+    /// The application replied with an error status word.
+    Status(StatusWord),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            #[cfg(feature = "hid")]
+            Error::Hid(e) => write!(f, "HID error: {e}"),
+            #[cfg(feature = "speculos")]
+            Error::Speculos(e) => write!(f, "speculos error: {e}"),
+            Error::DeviceNotFound => write!(f, "device not found"),
+            Error::CommandTooLong => write!(f, "command data longer than {MAX_DATA_LEN} bytes"),
+            Error::InvalidResponse(reason) => write!(f, "invalid response: {reason}"),
+            Error::Status(sw) => write!(f, "status word {sw}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// Status word of a response, with the values of `StatusWords` and
+/// `SyscallError` of `ledger_device_sdk`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StatusWord {
+    Ok,
+    NothingReceived,
+    BadCla,
+    BadIns,
+    BadP1P2,
+    BadLen,
+    UserCancelled,
+    Unknown,
+    Panic,
+    /// Failure of a syscall, `0x68XX` where `XX` is the exception code
+    Syscall(u8),
+    /// Status word defined by the application
+    Other(u16),
+}
+
+impl From<u16> for StatusWord {
+    fn from(sw: u16) -> StatusWord {
+        match sw {
+            0x9000 => StatusWord::Ok,
+            0x6982 => StatusWord::NothingReceived,
+            0x6e00 => StatusWord::BadCla,
+            0x6e01 => StatusWord::BadIns,
+            0x6e02 => StatusWord::BadP1P2,
+            0x6e03 => StatusWord::BadLen,
+            0x6e04 => StatusWord::UserCancelled,
+            0x6d00 => StatusWord::Unknown,
+            0xe000 => StatusWord::Panic,
+            0x6800..=0x68ff => StatusWord::Syscall(sw as u8),
+            _ => StatusWord::Other(sw),
+        }
+    }
+}
+
+impl From<StatusWord> for u16 {
+    fn from(sw: StatusWord) -> u16 {
+        match sw {
+            StatusWord::Ok => 0x9000,
+            StatusWord::NothingReceived => 0x6982,
+            StatusWord::BadCla => 0x6e00,
+            StatusWord::BadIns => 0x6e01,
+            StatusWord::BadP1P2 => 0x6e02,
+            StatusWord::BadLen => 0x6e03,
+            StatusWord::UserCancelled => 0x6e04,
+            StatusWord::Unknown => 0x6d00,
+            StatusWord::Panic => 0xe000,
+            StatusWord::Syscall(code) => 0x6800 | code as u16,
+            StatusWord::Other(sw) => sw,
+        }
+    }
+}
+
+impl fmt::Display for StatusWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#06x} ({self:?})", u16::from(*self))
+    }
+}
+
+/// Exchange of raw APDUs with a device
+pub trait Transport {
+    /// Sends the command `apdu`, and returns the raw response, status word
+    /// included.
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// APDU command, built with [`Command::new`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Command {
+    pub cla: u8,
+    pub ins: u8,
+    pub p1: u8,
+    pub p2: u8,
+    pub data: Vec<u8>,
+}
+
+impl Command {
+    /// Returns a command without parameters nor data.
+    pub fn new(cla: u8, ins: u8) -> Command {
+        Command {
+            cla,
+            ins,
+            p1: 0,
+            p2: 0,
+            data: Vec::new(),
+        }
+    }
+
+    pub fn p1(self, p1: u8) -> Command {
+        Command { p1, ..self }
+    }
+
+    pub fn p2(self, p2: u8) -> Command {
+        Command { p2, ..self }
+    }
+
+    pub fn data(self, data: impl Into<Vec<u8>>) -> Command {
+        Command {
+            data: data.into(),
+            ..self
+        }
+    }
+
+    /// Serializes the command, with the length of its data.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let lc = u8::try_from(self.data.len()).map_err(|_| Error::CommandTooLong)?;
+        let mut apdu = vec![self.cla, self.ins, self.p1, self.p2, lc];
+        apdu.extend_from_slice(&self.data);
+        Ok(apdu)
+    }
+}
+
+/// Decoding of the status word of a [`Response`], the response type of
+/// `speculos_harness`. Status words are checked with `Response::assert_sw`,
+/// which accepts a [`StatusWord`].
+pub trait ResponseExt {
+    fn status(&self) -> StatusWord;
+
+    /// Returns the data if the status word is `0x9000`, or the status word
+    /// as an error otherwise.
+    fn into_result(self) -> Result<Vec<u8>, Error>;
+}
+
+impl ResponseExt for Response {
+    fn status(&self) -> StatusWord {
+        self.sw.into()
+    }
+
+    fn into_result(self) -> Result<Vec<u8>, Error> {
+        match self.status() {
+            StatusWord::Ok => Ok(self.data),
+            sw => Err(Error::Status(sw)),
+        }
+    }
+}
+
+fn parse_response(bytes: &[u8]) -> Result<Response, Error> {
+    Response::from_bytes(bytes)
+        .ok_or_else(|| Error::InvalidResponse("APDU response without status word".into()))
+}
+
+/// Client exchanging commands with an application through a transport
+pub struct Client<T> {
+    transport: T,
+}
+
+impl<T: Transport> Client<T> {
+    pub fn new(transport: T) -> Client<T> {
+        Client { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Sends `command`, and returns its response. The rest of the responses
+    /// chained with a `61XX` status word is fetched with GET RESPONSE.
+    pub fn exchange(&self, command: &Command) -> Result<Response, Error> {
+        let bytes = self.transport.exchange(&command.to_bytes()?)?;
+        let mut response = parse_response(&bytes)?;
+        while response.sw & 0xff00 == 0x6100 {
+            let le = response.sw as u8;
+            let get_response = [command.cla, INS_GET_RESPONSE, 0, 0, le];
+            let next = parse_response(&self.transport.exchange(&get_response)?)?;
+            response.data.extend_from_slice(&next.data);
+            response.sw = next.sw;
+        }
+        Ok(response)
+    }
+
+    /// Sends `command`, and returns the data of its response, or its status
+    /// word as an error if it is not `0x9000`.
+    pub fn send(&self, command: &Command) -> Result<Vec<u8>, Error> {
+        self.exchange(command)?.into_result()
+    }
+
+    /// Sends `data` in chunks of [`MAX_DATA_LEN`] bytes, with the header of
+    /// `command` and its `p1` for the first chunk, and `p1_next` for the
+    /// following ones, a common convention of applications receiving
+    /// payloads longer than an APDU. Returns the data of the response to
+    /// the last chunk. The responses to the other chunks must be `0x9000`.
+    pub fn send_chunks(
+        &self,
+        command: &Command,
+        p1_next: u8,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let mut chunks = data.chunks(MAX_DATA_LEN).peekable();
+        let mut p1 = command.p1;
+        let mut response = Vec::new();
+        while let Some(chunk) = chunks.next() {
+            let chunk_command = command.clone().p1(p1).data(chunk);
+            response = self.send(&chunk_command)?;
+            if chunks.peek().is_some() && !response.is_empty() {
+                return Err(Error::InvalidResponse(
+                    "response data to an intermediate chunk".into(),
+                ));
+            }
+            p1 = p1_next;
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Transport replying with the responses of `responses` in order, and
+    /// recording the commands
+    struct Replay {
+        responses: RefCell<Vec<Vec<u8>>>,
+        commands: RefCell<Vec<Vec<u8>>>,
+    }
+
+    impl Replay {
+        fn new(responses: &[&[u8]]) -> Replay {
+            Replay {
+                responses: RefCell::new(responses.iter().rev().map(|r| r.to_vec()).collect()),
+                commands: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Transport for Replay {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, Error> {
+            self.commands.borrow_mut().push(apdu.to_vec());
+            self.responses
+                .borrow_mut()
+                .pop()
+                .ok_or_else(|| Error::InvalidResponse("no more responses".into()))
+        }
+    }
+
+    #[test]
+    fn status_words() {
+        assert_eq!(StatusWord::from(0x6e03), StatusWord::BadLen);
+        assert_eq!(StatusWord::from(0x6813), StatusWord::Syscall(0x13));
+        assert_eq!(StatusWord::from(0x6a80), StatusWord::Other(0x6a80));
+        assert_eq!(u16::from(StatusWord::Syscall(0x02)), 0x6802);
+        assert_eq!(StatusWord::BadIns.to_string(), "0x6e01 (BadIns)");
+    }
+
+    #[test]
+    fn chained_response() {
+        let client = Client::new(Replay::new(&[&[1, 2, 0x61, 0x02], &[3, 4, 0x90, 0x00]]));
+        let response = client.exchange(&Command::new(0xe0, 0x02).p1(1)).unwrap();
+        assert_eq!(response.data, [1, 2, 3, 4]);
+        assert_eq!(response.status(), StatusWord::Ok);
+        let commands = client.transport().commands.borrow();
+        assert_eq!(commands[0], [0xe0, 0x02, 0x01, 0x00, 0x00]);
+        assert_eq!(commands[1], [0xe0, 0xc0, 0x00, 0x00, 0x02]);
+    }
+
+    #[test]
+    fn chunked_command() {
+        let client = Client::new(Replay::new(&[&[0x90, 0x00], &[0xaa, 0x90, 0x00]]));
+        let data = vec![0x55; MAX_DATA_LEN + 1];
+        let command = Command::new(0xe0, 0x04).p2(7);
+        assert_eq!(client.send_chunks(&command, 0x80, &data).unwrap(), [0xaa]);
+        let commands = client.transport().commands.borrow();
+        assert_eq!(commands[0][..5], [0xe0, 0x04, 0x00, 0x07, 0xff]);
+        assert_eq!(commands[1], [0xe0, 0x04, 0x80, 0x07, 0x01, 0x55]);
+
+        let client = Client::new(Replay::new(&[&[0x6e, 0x04]]));
+        assert!(matches!(
+            client.send(&Command::new(0xe0, 0x04)),
+            Err(Error::Status(StatusWord::UserCancelled))
+        ));
+        assert!(matches!(
+            Command::new(0xe0, 0x04).data(data).to_bytes(),
+            Err(Error::CommandTooLong)
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+//! Applications launched by `speculos_harness`
+//!
+//! Commands are sent through the automation API of Speculos, which also
+//! drives the screen. A command displaying a review only returns once the
+//! user has answered, so [`Client::exchange_with`] runs the interactions
+//! while the command waits.
+
+use crate::{Client, Command, Error, Response, Transport};
+use speculos_harness::{Button, Model, Speculos};
+use std::thread;
+
+/// Maximum number of presses to reach a text of a review
+const MAX_PRESSES: usize = 20;
+
+impl From<speculos_harness::Error> for Error {
+    fn from(e: speculos_harness::Error) -> Self {
+        Error::Speculos(e)
+    }
+}
+
+impl Transport for Speculos {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(Speculos::exchange(self, apdu)?)
+    }
+}
+
+impl Client<Speculos> {
+    /// Sends `command`, and runs `interact` with the emulator meanwhile, for
+    /// instance to review and approve what the command displays. Returns
+    /// the response once both are done.
+    pub fn exchange_with<F>(&self, command: &Command, interact: F) -> Result<Response, Error>
+    where
+        F: FnOnce(&Speculos) -> Result<(), Error>,
+    {
+        thread::scope(|scope| {
+            let exchange = scope.spawn(|| self.exchange(command));
+            let interacted = interact(self.transport());
+            let response = exchange.join().expect("APDU exchange panicked");
+            interacted.and(response)
+        })
+    }
+
+    /// Sends `command`, checks that its review displays each of `texts` in
+    /// order, and selects `approve` to approve it. On Nano devices, the pages
+    /// are browsed with the right button and `approve` is selected with
+    /// both buttons.
+    ///
+    /// # Panics
+    ///
+    /// Panics on Stax and Flex, whose reviews are driven with
+    /// [`Client::exchange_with`] and the touch screen.
+    pub fn exchange_approved(
+        &self,
+        command: &Command,
+        texts: &[&str],
+        approve: &str,
+    ) -> Result<Response, Error> {
+        let speculos = self.transport();
+        assert!(
+            !matches!(speculos.model(), Model::Stax | Model::Flex),
+            "touch screen reviews are not supported"
+        );
+        self.exchange_with(command, |speculos| {
+            speculos.wait_for_text(texts.first().unwrap_or(&approve))?;
+            for text in texts.iter().chain([&approve]) {
+                speculos.navigate_until_text(Button::Right, text, MAX_PRESSES)?;
+            }
+            Ok(speculos.press(Button::Both)?)
+        })
+    }
+}
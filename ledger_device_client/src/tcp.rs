@@ -0,0 +1,69 @@
+//! Transport to the APDU port of Speculos
+//!
+//! Each command is preceded by its length on 4 big-endian bytes, and each
+//! response by the length of its data, status word excluded. Applications
+//! running natively with the `emulation` feature of `ledger_device_sdk` use
+//! the same framing.
+
+use crate::{Error, Transport};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+/// Transport to a Speculos instance, or to an application running natively.
+pub struct TcpTransport {
+    stream: TcpStream,
+}
+
+impl TcpTransport {
+    /// Connects to `address`, such as `"127.0.0.1:9999"`, the default APDU
+    /// port of Speculos.
+    pub fn connect(address: impl ToSocketAddrs) -> Result<TcpTransport, Error> {
+        Ok(TcpTransport {
+            stream: TcpStream::connect(address)?,
+        })
+    }
+}
+
+impl Transport for TcpTransport {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut stream = &self.stream;
+        let mut command = (apdu.len() as u32).to_be_bytes().to_vec();
+        command.extend_from_slice(apdu);
+        stream.write_all(&command)?;
+
+        let mut len = [0u8; 4];
+        stream.read_exact(&mut len)?;
+        // The status word follows the data
+        let mut response = vec![0u8; u32::from_be_bytes(len) as usize + 2];
+        stream.read_exact(&mut response)?;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn framing() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let device = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut command = [0u8; 9];
+            stream.read_exact(&mut command).unwrap();
+            stream.write_all(&[0, 0, 0, 1, 0x2a, 0x90, 0x00]).unwrap();
+            command
+        });
+
+        let transport = TcpTransport::connect(address).unwrap();
+        let response = transport.exchange(&[0xe0, 0x01, 0x00, 0x00, 0x00]).unwrap();
+        assert_eq!(response, [0x2a, 0x90, 0x00]);
+        assert_eq!(
+            device.join().unwrap(),
+            [0, 0, 0, 5, 0xe0, 0x01, 0x00, 0x00, 0x00]
+        );
+    }
+}
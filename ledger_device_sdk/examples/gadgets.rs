@@ -63,6 +63,10 @@ extern "C" fn sample_main() {
 
     let scrolled_message = "Arbitrary long text goes here, with numbers -1234567890";
     gadgets::MessageScroller::new(scrolled_message).event_loop();
+    gadgets::MessageScroller::new(scrolled_message)
+        .speed(3)
+        .pause_at_ends(20)
+        .event_loop();
 
     loop {
         match gadgets::Menu::new(&[&"Top0", &"Top1", &"Top2", &"Top3", &"Next"]).show() {
@@ -134,5 +138,5 @@ extern "C" fn sample_main() {
     checkmark.instant_erase();
     wait_any();
 
-    ledger_device_sdk::exit_app(0);
+    ledger_device_sdk::return_to_dashboard();
 }
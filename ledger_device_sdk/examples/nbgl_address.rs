@@ -36,6 +36,8 @@ extern "C" fn sample_main() {
     let success = NbglAddressReview::new()
         .glyph(&FERRIS)
         .verify_str("Verify Address")
+        .derivation_path("m/44'/1'/0'/0/0")
+        .account(0)
         .show(addr_hex);
     NbglReviewStatus::new()
         .status_type(StatusType::Address)
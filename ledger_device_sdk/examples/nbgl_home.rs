@@ -6,7 +6,7 @@ use ledger_device_sdk as _;
 
 use include_gif::include_gif;
 use ledger_device_sdk::io::*;
-use ledger_device_sdk::nbgl::{init_comm, NbglGlyph, NbglHomeAndSettings};
+use ledger_device_sdk::nbgl::{init_comm, HomeAndSettingsEvent, NbglGlyph, NbglHomeAndSettings};
 use ledger_device_sdk::nvm::*;
 use ledger_device_sdk::NVMData;
 use ledger_secure_sdk_sys::*;
@@ -55,7 +55,7 @@ extern "C" fn sample_main() {
 
     let settings_strings = [["Switch title", "Switch subtitle"]];
     // Display the home screen.
-    NbglHomeAndSettings::new()
+    let mut home = NbglHomeAndSettings::new()
         .glyph(&FERRIS)
         .settings(unsafe { DATA.get_mut() }, &settings_strings)
         .infos(
@@ -63,5 +63,13 @@ extern "C" fn sample_main() {
             env!("CARGO_PKG_VERSION"),
             env!("CARGO_PKG_AUTHORS"),
         )
-        .show::<Instruction>();
+        .tagline("This app demonstrates the home screen.")
+        .info("License", "Apache-2.0");
+    loop {
+        match home.show_with_events::<Instruction>() {
+            // The new value is already stored in DATA
+            HomeAndSettingsEvent::SettingChanged { .. } => (),
+            HomeAndSettingsEvent::Command(_) => break,
+        }
+    }
 }
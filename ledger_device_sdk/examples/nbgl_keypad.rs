@@ -0,0 +1,40 @@
+#![no_std]
+#![no_main]
+
+// Force boot section to be embedded in
+use ledger_device_sdk as _;
+
+use ledger_device_sdk::io::*;
+use ledger_device_sdk::nbgl::{init_comm, NbglKeypad, NbglStatus};
+use ledger_secure_sdk_sys::*;
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    exit_app(1);
+}
+
+#[no_mangle]
+extern "C" fn sample_main() {
+    unsafe {
+        nbgl_refreshReset();
+    }
+
+    let mut comm = Comm::new();
+    // Initialize reference to Comm instance for NBGL
+    // API calls.
+    init_comm(&mut comm);
+
+    // Ask for a 4 to 8 digits code, on a shuffled keypad.
+    let pin = NbglKeypad::new()
+        .title("Enter your PIN")
+        .digits(4, 8)
+        .shuffled()
+        .show();
+
+    match pin {
+        // The entered digits are erased when `pin` is dropped.
+        Some(pin) if pin.as_bytes() == b"1234" => NbglStatus::success("PIN accepted"),
+        Some(_) => NbglStatus::failure("Wrong PIN"),
+        None => NbglStatus::failure("Cancelled"),
+    }
+}
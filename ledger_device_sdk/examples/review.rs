@@ -36,5 +36,5 @@ extern "C" fn sample_main() {
         Some(&CROSSMARK),
     );
     review.show();
-    ledger_device_sdk::exit_app(0);
+    ledger_device_sdk::return_to_dashboard();
 }
@@ -0,0 +1,304 @@
+//! Checked arithmetic on amounts
+//!
+//! Signing flows compute the fees and the total of a transaction from the
+//! amounts it carries. [`Amount`] holds an integer in the smallest unit of an
+//! asset along with its number of decimals, and only provides checked
+//! operations, so that an overflow is reported instead of wrapping around
+//! into an amount the user would approve without knowing it. Adding amounts
+//! with different decimals is an error as well: they must be rescaled
+//! explicitly first.
+//!
+//! Amounts are stored in `u64`, `u128`, or [`U256`] values.
+//!
+//! # Examples
+//!
+//! ```
+//! let value = Amount::<u128>::new(tx.value, 18);
+//! let fee = Amount::new(tx.gas_price, 18).checked_mul(tx.gas_limit as u128)?;
+//! let total = value.checked_add(fee)?;
+//!
+//! let mut buffer = [0u8; 64];
+//! let total = total.format("ETH", &mut buffer)?;
+//! ```
+
+use crate::display::{format_amount, parse_amount, AmountValue, BufferTooSmall};
+use crate::display::{ParseAmountError, ParseAmountValue};
+use crate::io::{Reply, SyscallError};
+
+/// 256-bit unsigned integer, in big-endian order
+pub type U256 = [u8; 32];
+
+/// Error returned by the operations on [`Amount`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AmountError {
+    /// The result does not fit in the integer type
+    Overflow,
+    /// The result would be negative
+    Underflow,
+    /// The amounts do not have the same number of decimals
+    DecimalsMismatch,
+    /// Rescaling to fewer decimals would drop non-zero digits
+    Inexact,
+}
+
+impl From<AmountError> for Reply {
+    fn from(e: AmountError) -> Reply {
+        match e {
+            AmountError::Overflow | AmountError::Underflow => SyscallError::Overflow.into(),
+            AmountError::DecimalsMismatch | AmountError::Inexact => {
+                SyscallError::InvalidParameter.into()
+            }
+        }
+    }
+}
+
+/// Unsigned integers holding an [`Amount`].
+pub trait AmountInteger: AmountValue + Copy + Ord {
+    const ZERO: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    /// Returns the quotient and the remainder of the division by 10.
+    fn div_rem_10(self) -> (Self, u8);
+}
+
+macro_rules! impl_amount_integer {
+    ($($t:ty),*) => {
+        $(
+            impl AmountInteger for $t {
+                const ZERO: $t = 0;
+                const TEN: $t = 10;
+
+                fn checked_add(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn div_rem_10(self) -> ($t, u8) {
+                    (self / 10, (self % 10) as u8)
+                }
+            }
+        )*
+    };
+}
+
+impl_amount_integer!(u64, u128);
+
+impl AmountInteger for U256 {
+    const ZERO: U256 = [0; 32];
+    const TEN: U256 = {
+        let mut ten = [0; 32];
+        ten[31] = 10;
+        ten
+    };
+
+    fn checked_add(self, rhs: U256) -> Option<U256> {
+        let mut sum = [0u8; 32];
+        let mut carry = 0u16;
+        for i in (0..32).rev() {
+            let current = self[i] as u16 + rhs[i] as u16 + carry;
+            sum[i] = current as u8;
+            carry = current >> 8;
+        }
+        (carry == 0).then_some(sum)
+    }
+
+    fn checked_sub(self, rhs: U256) -> Option<U256> {
+        let mut difference = [0u8; 32];
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let current = self[i] as i16 - rhs[i] as i16 - borrow;
+            difference[i] = current as u8;
+            borrow = (current < 0) as i16;
+        }
+        (borrow == 0).then_some(difference)
+    }
+
+    fn checked_mul(self, rhs: U256) -> Option<U256> {
+        // Schoolbook multiplication, with the bytes of the product from the
+        // least significant one
+        let mut product = [0u32; 64];
+        for (i, &a) in self.iter().rev().enumerate() {
+            for (j, &b) in rhs.iter().rev().enumerate() {
+                product[i + j] += a as u32 * b as u32;
+            }
+        }
+        let mut carry = 0u32;
+        for byte in product.iter_mut() {
+            let current = *byte + carry;
+            *byte = current & 0xff;
+            carry = current >> 8;
+        }
+        if carry != 0 || product[32..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        let mut result = [0u8; 32];
+        for (byte, &p) in result.iter_mut().rev().zip(product.iter()) {
+            *byte = p as u8;
+        }
+        Some(result)
+    }
+
+    fn div_rem_10(self) -> (U256, u8) {
+        let mut quotient = self;
+        let mut remainder = 0u16;
+        for byte in quotient.iter_mut() {
+            let current = (remainder << 8) | *byte as u16;
+            *byte = (current / 10) as u8;
+            remainder = current % 10;
+        }
+        (quotient, remainder as u8)
+    }
+}
+
+/// Amount of an asset, in its smallest unit, with its number of decimals.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Amount<V> {
+    value: V,
+    decimals: u8,
+}
+
+impl<V: AmountInteger> Amount<V> {
+    pub const fn new(value: V, decimals: u8) -> Amount<V> {
+        Amount { value, decimals }
+    }
+
+    pub const fn zero(decimals: u8) -> Amount<V> {
+        Amount::new(V::ZERO, decimals)
+    }
+
+    /// Value in the smallest unit of the asset
+    pub fn value(&self) -> V {
+        self.value
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    fn check_decimals(&self, rhs: &Amount<V>) -> Result<(), AmountError> {
+        if self.decimals != rhs.decimals {
+            return Err(AmountError::DecimalsMismatch);
+        }
+        Ok(())
+    }
+
+    pub fn checked_add(self, rhs: Amount<V>) -> Result<Amount<V>, AmountError> {
+        self.check_decimals(&rhs)?;
+        let value = self
+            .value
+            .checked_add(rhs.value)
+            .ok_or(AmountError::Overflow)?;
+        Ok(Amount::new(value, self.decimals))
+    }
+
+    pub fn checked_sub(self, rhs: Amount<V>) -> Result<Amount<V>, AmountError> {
+        self.check_decimals(&rhs)?;
+        let value = self
+            .value
+            .checked_sub(rhs.value)
+            .ok_or(AmountError::Underflow)?;
+        Ok(Amount::new(value, self.decimals))
+    }
+
+    /// Multiplies the amount by `factor`, for instance a gas price by a gas
+    /// limit.
+    pub fn checked_mul(self, factor: V) -> Result<Amount<V>, AmountError> {
+        let value = self
+            .value
+            .checked_mul(factor)
+            .ok_or(AmountError::Overflow)?;
+        Ok(Amount::new(value, self.decimals))
+    }
+
+    /// Converts the amount to `decimals` decimals, for instance to add it to
+    /// an amount of a token with another precision. Rescaling to fewer
+    /// decimals must not drop non-zero digits.
+    pub fn rescale(self, decimals: u8) -> Result<Amount<V>, AmountError> {
+        let mut value = self.value;
+        for _ in decimals..self.decimals {
+            let (quotient, remainder) = value.div_rem_10();
+            if remainder != 0 {
+                return Err(AmountError::Inexact);
+            }
+            value = quotient;
+        }
+        for _ in self.decimals..decimals {
+            value = value.checked_mul(V::TEN).ok_or(AmountError::Overflow)?;
+        }
+        Ok(Amount::new(value, decimals))
+    }
+
+    /// Formats the amount with `ticker` into `out`, see
+    /// [`format_amount`](crate::display::format_amount).
+    pub fn format<'a>(&self, ticker: &str, out: &'a mut [u8]) -> Result<&'a str, BufferTooSmall> {
+        format_amount(self.value, self.decimals, ticker, out)
+    }
+}
+
+impl<V: AmountInteger + ParseAmountValue> Amount<V> {
+    /// Parses the decimal amount `text`, see
+    /// [`parse_amount`](crate::display::parse_amount).
+    pub fn parse(text: &str, decimals: u8) -> Result<Amount<V>, ParseAmountError> {
+        Ok(Amount::new(parse_amount(text, decimals)?, decimals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn checked_operations() {
+        let value = Amount::new(1_500_000u64, 6);
+        let fee = Amount::new(2_500u64, 6).checked_mul(4).unwrap();
+        assert_eq!(value.checked_add(fee), Ok(Amount::new(1_510_000, 6)));
+        assert_eq!(fee.checked_sub(value), Err(AmountError::Underflow));
+        assert_eq!(
+            Amount::new(u64::MAX, 0).checked_add(Amount::new(1, 0)),
+            Err(AmountError::Overflow)
+        );
+        assert_eq!(
+            value.checked_add(Amount::new(1, 8)),
+            Err(AmountError::DecimalsMismatch)
+        );
+        assert_eq!(value.rescale(8), Ok(Amount::new(150_000_000, 8)));
+        assert_eq!(value.rescale(1), Err(AmountError::Inexact));
+        assert_eq!(value.rescale(2), Ok(Amount::new(150, 2)));
+    }
+
+    #[test]
+    fn u256_amounts() {
+        let gas_price = Amount::<U256>::parse("0.000000002", 18).unwrap();
+        let mut gas_limit = [0u8; 32];
+        gas_limit[28..].copy_from_slice(&21_000u32.to_be_bytes());
+        let fee = gas_price.checked_mul(gas_limit).unwrap();
+        let value = Amount::<U256>::parse("1.5", 18).unwrap();
+        let mut buffer = [0u8; 64];
+        assert_eq!(
+            value.checked_add(fee).unwrap().format("ETH", &mut buffer),
+            Ok("1.500042 ETH")
+        );
+        assert_eq!(fee.checked_sub(value), Err(AmountError::Underflow));
+        assert_eq!(
+            Amount::new([0xff; 32], 0).checked_mul(U256::TEN),
+            Err(AmountError::Overflow)
+        );
+        assert_eq!(value.rescale(0), Err(AmountError::Inexact));
+    }
+}
@@ -0,0 +1,130 @@
+//! Name, version and flags of the application, as declared for `cargo ledger`
+//!
+//! [`app_metadata!`](crate::app_metadata) defines an `app` module in the
+//! application, with constants taken from the manifest at build time, so
+//! that the version returned to the host or displayed in the about screen
+//! cannot diverge from the installed one:
+//!
+//! - `NAME`: `name` of the `[package.metadata.ledger]` section, or the
+//!   package name,
+//! - `VERSION`, `VERSION_MAJOR`, `VERSION_MINOR` and `VERSION_PATCH`: the
+//!   package version,
+//! - `FLAGS`: the application flags, including the Bluetooth flag added by
+//!   `cargo ledger` on devices which support it.
+//!
+//! The name and flags are passed to the compiler by `cargo ledger`. When
+//! building with `cargo build`, the package name and no flags are used.
+//!
+//! # Examples
+//!
+//! ```
+//! ledger_device_sdk::app_metadata!();
+//!
+//! fn get_version(comm: &mut Comm) {
+//!     comm.append(&[app::VERSION_MAJOR, app::VERSION_MINOR, app::VERSION_PATCH]);
+//! }
+//!
+//! let home = NbglHomeAndSettings::new().infos(app::NAME, app::VERSION, "Ledger");
+//! ```
+
+/// Defines the `app` module of the application, see the
+/// [`app_metadata`](crate::app_metadata) module.
+#[macro_export]
+macro_rules! app_metadata {
+    () => {
+        /// Metadata of the application, as declared for `cargo ledger`
+        pub mod app {
+            pub const NAME: &str = match option_env!("LEDGER_APP_NAME") {
+                Some(name) => name,
+                None => env!("CARGO_PKG_NAME"),
+            };
+            pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+            pub const VERSION_MAJOR: u8 =
+                $crate::app_metadata::parse_u8(env!("CARGO_PKG_VERSION_MAJOR"));
+            pub const VERSION_MINOR: u8 =
+                $crate::app_metadata::parse_u8(env!("CARGO_PKG_VERSION_MINOR"));
+            pub const VERSION_PATCH: u8 =
+                $crate::app_metadata::parse_u8(env!("CARGO_PKG_VERSION_PATCH"));
+            pub const FLAGS: u32 = match option_env!("LEDGER_APP_FLAGS") {
+                Some(flags) => $crate::app_metadata::parse_flags(flags),
+                None => 0,
+            };
+        }
+    };
+}
+
+/// Parses a decimal version component.
+///
+/// # Panics
+///
+/// Panics, at build time when used in a constant, if `digits` is not a
+/// decimal number below 256.
+pub const fn parse_u8(digits: &str) -> u8 {
+    let digits = digits.as_bytes();
+    assert!(!digits.is_empty(), "empty version component");
+    let mut value: u32 = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        assert!(digits[i].is_ascii_digit(), "invalid version component");
+        value = value * 10 + (digits[i] - b'0') as u32;
+        assert!(value <= u8::MAX as u32, "version component above 255");
+        i += 1;
+    }
+    value as u8
+}
+
+/// Parses application flags, written in hexadecimal with an optional `0x`
+/// prefix.
+///
+/// # Panics
+///
+/// Panics, at build time when used in a constant, if `flags` is not a valid
+/// 32-bit hexadecimal number.
+pub const fn parse_flags(flags: &str) -> u32 {
+    let mut digits = flags.as_bytes();
+    if let [b'0', b'x' | b'X', rest @ ..] = digits {
+        digits = rest;
+    }
+    assert!(!digits.is_empty(), "empty application flags");
+    assert!(digits.len() <= 8, "application flags above 32 bits");
+    let mut value: u32 = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        let digit = match digits[i] {
+            b'0'..=b'9' => digits[i] - b'0',
+            b'a'..=b'f' => digits[i] - b'a' + 10,
+            b'A'..=b'F' => digits[i] - b'A' + 10,
+            _ => panic!("invalid application flags"),
+        };
+        value = (value << 4) | digit as u32;
+        i += 1;
+    }
+    value
+}
+
+/// Returns true if `name` and `version`, typically `app::NAME` and
+/// `app::VERSION`, are the ones the application was installed with.
+pub fn matches_install(name: &str, version: &str) -> bool {
+    let mut buf = [0u8; 64];
+    if crate::registry::app_name(&mut buf) != Some(name) {
+        return false;
+    }
+    crate::registry::app_version(&mut buf) == Some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_u8("0"), 0);
+        assert_eq!(parse_u8("255"), 255);
+        assert_eq!(parse_flags("0"), 0);
+        assert_eq!(parse_flags("0x238"), 0x238);
+        assert_eq!(parse_flags("A00"), 0xa00);
+    }
+}
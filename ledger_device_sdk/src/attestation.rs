@@ -0,0 +1,143 @@
+//! Device attestation with the endorsement keys
+//!
+//! Each device holds two endorsement key pairs, whose public keys are
+//! certified by the device issuer. Applications prove to their backend that
+//! they run on a genuine device by signing a challenge with an endorsement
+//! key, and sending the signature along with the public key and its
+//! certificate:
+//!
+//! - key 1 signs `SHA-256(data || code hash)`, binding the signature to the
+//!   code of the running application,
+//! - key 2 is derived for the application, and signs `SHA-256(data)`.
+//!
+//! Both signatures are DER encoded ECDSA signatures over secp256k1.
+//!
+//! The endorsement syscalls are not available on Nano S.
+//!
+//! # Examples
+//!
+//! ```
+//! let public_key = attestation::public_key(Slot::One)?;
+//! let certificate = attestation::certificate(Slot::One)?;
+//! let (signature, len) = attestation::sign_with_key1(&challenge)?;
+//! comm.append(&public_key);
+//! comm.append(certificate.as_bytes());
+//! comm.append(&signature[..len]);
+//! ```
+
+use ledger_secure_sdk_sys::*;
+
+/// Length of the hash of the application code
+pub const CODE_HASH_LEN: usize = 32;
+/// Length of an endorsement public key, in uncompressed form
+pub const PUBLIC_KEY_LEN: usize = 65;
+/// Maximum length of a DER encoded endorsement signature
+pub const MAX_SIGNATURE_LEN: usize = 72;
+/// Maximum length of the certificate of an endorsement public key
+pub const MAX_CERTIFICATE_LEN: usize = 128;
+
+/// Endorsement key pair
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Slot {
+    One,
+    Two,
+}
+
+impl Slot {
+    fn id(self) -> ENDORSEMENT_slot_t {
+        match self {
+            Slot::One => ENDORSEMENT_SLOT_1,
+            Slot::Two => ENDORSEMENT_SLOT_2,
+        }
+    }
+}
+
+/// Error returned by an endorsement syscall, for instance when the key pair
+/// has not been provisioned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AttestationError(pub u32);
+
+fn check(err: bolos_err_t) -> Result<(), AttestationError> {
+    if err as u32 == SWO_SUCCESS as u32 {
+        Ok(())
+    } else {
+        Err(AttestationError(err as u32))
+    }
+}
+
+/// Certificate of an endorsement public key, signed by the device issuer.
+pub struct Certificate {
+    bytes: [u8; MAX_CERTIFICATE_LEN],
+    len: usize,
+}
+
+impl Certificate {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// Returns the hash of the code of the running application, as signed by
+/// [`sign_with_key1`].
+pub fn code_hash() -> [u8; CODE_HASH_LEN] {
+    let mut hash = [0u8; CODE_HASH_LEN];
+    unsafe {
+        ENDORSEMENT_get_code_hash(hash.as_mut_ptr());
+    }
+    hash
+}
+
+/// Returns the public key of the endorsement key pair `slot`.
+pub fn public_key(slot: Slot) -> Result<[u8; PUBLIC_KEY_LEN], AttestationError> {
+    let mut key = [0u8; PUBLIC_KEY_LEN];
+    let mut len = PUBLIC_KEY_LEN as u8;
+    check(unsafe { ENDORSEMENT_get_public_key(slot.id(), key.as_mut_ptr(), &mut len) })?;
+    Ok(key)
+}
+
+/// Returns the certificate of the public key of the endorsement key pair
+/// `slot`.
+pub fn certificate(slot: Slot) -> Result<Certificate, AttestationError> {
+    let mut certificate = Certificate {
+        bytes: [0u8; MAX_CERTIFICATE_LEN],
+        len: 0,
+    };
+    let mut len = MAX_CERTIFICATE_LEN as u8;
+    check(unsafe {
+        ENDORSEMENT_get_public_key_certificate(slot.id(), certificate.bytes.as_mut_ptr(), &mut len)
+    })?;
+    certificate.len = (len as usize).min(MAX_CERTIFICATE_LEN);
+    Ok(certificate)
+}
+
+/// Signs `data` with the endorsement key 1, along with the code hash of the
+/// application. Returns the DER encoded signature and its length.
+pub fn sign_with_key1(data: &[u8]) -> Result<([u8; MAX_SIGNATURE_LEN], usize), AttestationError> {
+    let mut signature = [0u8; MAX_SIGNATURE_LEN];
+    let mut len = MAX_SIGNATURE_LEN as u32;
+    check(unsafe {
+        ENDORSEMENT_key1_sign_data(
+            data.as_ptr() as *mut u8,
+            data.len() as u32,
+            signature.as_mut_ptr(),
+            &mut len,
+        )
+    })?;
+    Ok((signature, (len as usize).min(MAX_SIGNATURE_LEN)))
+}
+
+/// Signs `data` with the endorsement key 2, derived for the application.
+/// Returns the DER encoded signature and its length.
+pub fn sign_with_key2(data: &[u8]) -> Result<([u8; MAX_SIGNATURE_LEN], usize), AttestationError> {
+    let mut signature = [0u8; MAX_SIGNATURE_LEN];
+    let mut len = MAX_SIGNATURE_LEN as u32;
+    check(unsafe {
+        ENDORSEMENT_key2_derive_and_sign_data(
+            data.as_ptr() as *mut u8,
+            data.len() as u32,
+            signature.as_mut_ptr(),
+            &mut len,
+        )
+    })?;
+    Ok((signature, (len as usize).min(MAX_SIGNATURE_LEN)))
+}
@@ -0,0 +1,320 @@
+//! Token information signed by the Crypto Asset List
+//!
+//! The wallet provides the metadata of the tokens involved in a transaction,
+//! taken from Ledger's Crypto Asset List (CAL), so that the review screens
+//! display their ticker and amounts with the right number of decimals. The
+//! metadata is sent as a [TLV](crate::io::tlv) payload with the following
+//! fields, each once and in this order:
+//!
+//! | Tag    | Field                                            |
+//! |--------|--------------------------------------------------|
+//! | `0x01` | structure type, `0x90`                           |
+//! | `0x02` | version, `1`                                     |
+//! | `0x03` | SLIP-44 coin type of the network                 |
+//! | `0x04` | name of the application                          |
+//! | `0x05` | ticker                                           |
+//! | `0x06` | number of decimals                               |
+//! | `0x07` | unique identifier, such as the contract address  |
+//! | `0x08` | signature                                        |
+//!
+//! The signature is a DER encoded ECDSA signature over secp256k1 of the
+//! SHA-256 hash of the payload up to the signature field, by one of the CAL
+//! keys. These keys differ between the test and production environments:
+//! the application passes the public keys it trusts, usually
+//! [`PRODUCTION_KEY`], to [`TokenInfo::parse`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ledger_device_sdk::cal::{self, TokenInfo};
+//! use ledger_device_sdk::display::format_amount;
+//! use ledger_device_sdk::io::{Comm, Reply, StatusWords};
+//!
+//! const COIN_TYPE: u32 = 535348;
+//!
+//! fn format_token_amount(comm: &mut Comm, value: u64) -> Result<(), Reply> {
+//!     let token = TokenInfo::parse(comm.get_data()?, &[cal::PRODUCTION_KEY])?;
+//!     if token.coin_type != COIN_TYPE || token.application != "Crab" {
+//!         return Err(StatusWords::BadP1P2.into());
+//!     }
+//!     let mut buffer = [0u8; 64];
+//!     let amount = format_amount(value, token.decimals, token.ticker, &mut buffer)
+//!         .map_err(|_| StatusWords::BadLen)?;
+//!     comm.append(amount.as_bytes());
+//!     Ok(())
+//! }
+//! ```
+
+use crate::ecc::{CurvesId, ECPublicKey};
+use crate::hash::{sha2::Sha2_256, HashError, HashInit};
+use crate::io::tlv::{TlvError, TlvReader};
+use crate::io::{Reply, StatusWords, SyscallError};
+
+const TAG_STRUCTURE_TYPE: u32 = 0x01;
+const TAG_VERSION: u32 = 0x02;
+const TAG_COIN_TYPE: u32 = 0x03;
+const TAG_APPLICATION_NAME: u32 = 0x04;
+const TAG_TICKER: u32 = 0x05;
+const TAG_MAGNITUDE: u32 = 0x06;
+const TAG_TUID: u32 = 0x07;
+const TAG_SIGNATURE: u32 = 0x08;
+
+/// Structure type of the token information
+const TYPE_DYNAMIC_TOKEN: u8 = 0x90;
+/// Supported version of the token information
+const VERSION: u8 = 1;
+
+/// Length of a CAL public key, in uncompressed form
+pub const PUBLIC_KEY_LEN: usize = 65;
+/// Public key of the production CAL, signing the metadata provided by Ledger
+/// Live
+pub const PRODUCTION_KEY: [u8; PUBLIC_KEY_LEN] = [
+    0x04, 0x5e, 0x6c, 0x10, 0x20, 0xc1, 0x4d, 0xc4, 0x64, 0x42, 0xfe, 0x89, 0xf9, 0x7c, 0x0b, 0x68,
+    0xcd, 0xb1, 0x59, 0x76, 0xdc, 0x24, 0xf2, 0x4c, 0x31, 0x6e, 0x7b, 0x30, 0xfe, 0x4e, 0x8c, 0xc7,
+    0x6b, 0x14, 0x89, 0x15, 0x0c, 0x21, 0x51, 0x4e, 0xbf, 0x44, 0x0f, 0xf5, 0xde, 0xa5, 0x39, 0x3d,
+    0x83, 0xde, 0x53, 0x58, 0xcd, 0x09, 0x8f, 0xce, 0x8f, 0xd0, 0xf8, 0x1d, 0xaa, 0x94, 0x97, 0x91,
+    0x83,
+];
+/// Maximum length of a DER encoded signature
+pub const MAX_SIGNATURE_LEN: usize = 72;
+/// Maximum length of a ticker
+pub const MAX_TICKER_LEN: usize = 16;
+/// Maximum length of the unique identifier of a token
+pub const MAX_ADDRESS_LEN: usize = 64;
+
+/// Error returned by [`TokenInfo::parse`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CalError {
+    Tlv(TlvError),
+    /// Unknown structure type or version
+    Unsupported,
+    /// A field is missing, duplicated or out of order, or follows the
+    /// signature
+    InvalidFields,
+    /// The payload is not signed by any of the trusted keys
+    InvalidSignature,
+    Hash(HashError),
+}
+
+impl From<TlvError> for CalError {
+    fn from(e: TlvError) -> CalError {
+        CalError::Tlv(e)
+    }
+}
+
+impl From<HashError> for CalError {
+    fn from(e: HashError) -> CalError {
+        CalError::Hash(e)
+    }
+}
+
+impl From<CalError> for Reply {
+    fn from(e: CalError) -> Reply {
+        match e {
+            CalError::Tlv(e) => e.into(),
+            CalError::Unsupported | CalError::InvalidFields => StatusWords::BadLen.into(),
+            CalError::InvalidSignature => SyscallError::Security.into(),
+            CalError::Hash(_) => SyscallError::Cxport.into(),
+        }
+    }
+}
+
+/// Metadata of a token, borrowed from the verified payload.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TokenInfo<'a> {
+    /// SLIP-44 coin type of the network of the token
+    pub coin_type: u32,
+    /// Name of the application handling the token
+    pub application: &'a str,
+    pub ticker: &'a str,
+    pub decimals: u8,
+    /// Unique identifier of the token, such as the address of its contract
+    pub address: &'a [u8],
+}
+
+impl<'a> TokenInfo<'a> {
+    /// Parses the token information `payload`, and verifies that it is
+    /// signed by one of the uncompressed secp256k1 public `keys`.
+    pub fn parse(
+        payload: &'a [u8],
+        keys: &[[u8; PUBLIC_KEY_LEN]],
+    ) -> Result<TokenInfo<'a>, CalError> {
+        let mut fields = [None; TAG_SIGNATURE as usize];
+        let mut signed_len = 0;
+        let mut last_tag = 0;
+        let mut reader = TlvReader::new(payload);
+        while let Some(field) = reader.next() {
+            let field = field?;
+            // Each field once, in the order of the tags, so that none follows
+            // the signature
+            if field.tag <= last_tag || field.tag > TAG_SIGNATURE {
+                return Err(CalError::InvalidFields);
+            }
+            last_tag = field.tag;
+            fields[field.tag as usize - 1] = Some(field);
+            if field.tag != TAG_SIGNATURE {
+                signed_len = reader.offset();
+            }
+        }
+        let field = |tag: u32| fields[tag as usize - 1].ok_or(CalError::InvalidFields);
+        if field(TAG_STRUCTURE_TYPE)?.as_u8()? != TYPE_DYNAMIC_TOKEN
+            || field(TAG_VERSION)?.as_u8()? != VERSION
+        {
+            return Err(CalError::Unsupported);
+        }
+
+        let signature = field(TAG_SIGNATURE)?.as_bytes(MAX_SIGNATURE_LEN)?;
+        let mut hash = [0u8; 32];
+        Sha2_256::new().hash(&payload[..signed_len], &mut hash)?;
+        let trusted = keys.iter().any(|key| {
+            let mut public_key = ECPublicKey::<PUBLIC_KEY_LEN, 'W'>::new(CurvesId::Secp256k1);
+            public_key.pubkey = *key;
+            public_key.verify((signature, signature.len() as u32), &hash)
+        });
+        if !trusted {
+            return Err(CalError::InvalidSignature);
+        }
+
+        let ticker = field(TAG_TICKER)?;
+        ticker.as_bytes(MAX_TICKER_LEN)?;
+        Ok(TokenInfo {
+            coin_type: field(TAG_COIN_TYPE)?.as_u32()?,
+            application: field(TAG_APPLICATION_NAME)?.as_str()?,
+            ticker: ticker.as_str()?,
+            decimals: field(TAG_MAGNITUDE)?.as_u8()?,
+            address: field(TAG_TUID)?.as_bytes(MAX_ADDRESS_LEN)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::ecc::{make_bip32_path, Secp256k1, SeedDerive};
+    use crate::io::tlv::TlvWriter;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    const PATH: [u32; 5] = make_bip32_path(b"m/44'/535348'/0'/0/0");
+
+    /// Writes the fields of a token, up to the unique identifier excluded.
+    fn write_fields(writer: &mut TlvWriter, structure_type: u8, version: u8) {
+        writer.write_u8(TAG_STRUCTURE_TYPE, structure_type).unwrap();
+        writer.write_u8(TAG_VERSION, version).unwrap();
+        writer.write_u32(TAG_COIN_TYPE, 535348).unwrap();
+        writer.write(TAG_APPLICATION_NAME, b"Crab").unwrap();
+        writer.write(TAG_TICKER, b"CRAB").unwrap();
+        writer.write_u8(TAG_MAGNITUDE, 6).unwrap();
+    }
+
+    /// Signs the fields written so far, and returns the public key.
+    fn sign(writer: &mut TlvWriter) -> [u8; PUBLIC_KEY_LEN] {
+        let key = Secp256k1::derive_from_path(&PATH);
+        let mut hash = [0u8; 32];
+        Sha2_256::new().hash(writer.as_bytes(), &mut hash).unwrap();
+        let (signature, len, _) = key.deterministic_sign(&hash).unwrap();
+        writer
+            .write(TAG_SIGNATURE, &signature[..len as usize])
+            .unwrap();
+        key.public_key().unwrap().pubkey
+    }
+
+    #[test]
+    fn parse_token_info() {
+        let mut buffer = [0u8; 200];
+        let mut writer = TlvWriter::new(&mut buffer);
+        write_fields(&mut writer, TYPE_DYNAMIC_TOKEN, VERSION);
+        writer.write(TAG_TUID, &[0xab; 20]).unwrap();
+        let public_key = sign(&mut writer);
+
+        let payload = writer.as_bytes();
+        assert_eq!(
+            TokenInfo::parse(payload, &[public_key]),
+            Ok(TokenInfo {
+                coin_type: 535348,
+                application: "Crab",
+                ticker: "CRAB",
+                decimals: 6,
+                address: &[0xab; 20],
+            })
+        );
+        let mut other_key = public_key;
+        other_key[PUBLIC_KEY_LEN - 1] ^= 1;
+        assert_eq!(
+            TokenInfo::parse(payload, &[other_key]),
+            Err(CalError::InvalidSignature)
+        );
+        assert_eq!(
+            TokenInfo::parse(&payload[..payload.len() - 1], &[public_key]),
+            Err(CalError::Tlv(TlvError::Truncated))
+        );
+    }
+
+    #[test]
+    fn parse_token_info_duplicated_field() {
+        let mut buffer = [0u8; 200];
+        let mut writer = TlvWriter::new(&mut buffer);
+        write_fields(&mut writer, TYPE_DYNAMIC_TOKEN, VERSION);
+        writer.write(TAG_TUID, &[0xab; 20]).unwrap();
+        writer.write(TAG_TICKER, b"USDC").unwrap();
+        let public_key = sign(&mut writer);
+
+        assert_eq!(
+            TokenInfo::parse(writer.as_bytes(), &[public_key]),
+            Err(CalError::InvalidFields)
+        );
+    }
+
+    #[test]
+    fn parse_token_info_out_of_order() {
+        let mut buffer = [0u8; 200];
+        let mut writer = TlvWriter::new(&mut buffer);
+        writer
+            .write_u8(TAG_STRUCTURE_TYPE, TYPE_DYNAMIC_TOKEN)
+            .unwrap();
+        writer.write_u8(TAG_VERSION, VERSION).unwrap();
+        writer.write_u32(TAG_COIN_TYPE, 535348).unwrap();
+        writer.write(TAG_TICKER, b"CRAB").unwrap();
+        writer.write(TAG_APPLICATION_NAME, b"Crab").unwrap();
+        writer.write_u8(TAG_MAGNITUDE, 6).unwrap();
+        writer.write(TAG_TUID, &[0xab; 20]).unwrap();
+        let public_key = sign(&mut writer);
+
+        assert_eq!(
+            TokenInfo::parse(writer.as_bytes(), &[public_key]),
+            Err(CalError::InvalidFields)
+        );
+    }
+
+    #[test]
+    fn parse_token_info_field_after_signature() {
+        // The identifier is not covered by the signature
+        let mut buffer = [0u8; 200];
+        let mut writer = TlvWriter::new(&mut buffer);
+        write_fields(&mut writer, TYPE_DYNAMIC_TOKEN, VERSION);
+        let public_key = sign(&mut writer);
+        writer.write(TAG_TUID, &[0xab; 20]).unwrap();
+
+        assert_eq!(
+            TokenInfo::parse(writer.as_bytes(), &[public_key]),
+            Err(CalError::InvalidFields)
+        );
+    }
+
+    #[test]
+    fn parse_token_info_unsupported() {
+        for (structure_type, version) in [(TYPE_DYNAMIC_TOKEN, VERSION + 1), (0x91, VERSION)] {
+            let mut buffer = [0u8; 200];
+            let mut writer = TlvWriter::new(&mut buffer);
+            write_fields(&mut writer, structure_type, version);
+            writer.write(TAG_TUID, &[0xab; 20]).unwrap();
+            let public_key = sign(&mut writer);
+
+            assert_eq!(
+                TokenInfo::parse(writer.as_bytes(), &[public_key]),
+                Err(CalError::Unsupported)
+            );
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Capabilities of the target device
+//!
+//! Compile-time constants describing the device the application is built
+//! for, so that code shared between targets can use them instead of
+//! repeating `cfg` blocks and magic numbers. Builds for the host have the
+//! capabilities of the Nano S+.
+//!
+//! See [`device_info`](crate::device_info) for the information which is only
+//! known at runtime, such as the versions of the OS.
+//!
+//! # Examples
+//!
+//! ```
+//! if caps::HAS_TOUCH {
+//!     show_swipe_hint();
+//! }
+//! let lines = caps::SCREEN_HEIGHT / LINE_HEIGHT;
+//! ```
+
+/// Width of the screen, in pixels
+#[cfg(target_os = "stax")]
+pub const SCREEN_WIDTH: usize = 400;
+#[cfg(target_os = "flex")]
+pub const SCREEN_WIDTH: usize = 480;
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+pub const SCREEN_WIDTH: usize = 128;
+
+/// Height of the screen, in pixels
+#[cfg(target_os = "nanos")]
+pub const SCREEN_HEIGHT: usize = 32;
+#[cfg(target_os = "stax")]
+pub const SCREEN_HEIGHT: usize = 672;
+#[cfg(target_os = "flex")]
+pub const SCREEN_HEIGHT: usize = 600;
+#[cfg(not(any(target_os = "nanos", target_os = "stax", target_os = "flex")))]
+pub const SCREEN_HEIGHT: usize = 64;
+
+/// Bits per pixel of the screen: monochrome on Nano devices, 16 gray levels
+/// on the e-ink screens
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+pub const COLOR_DEPTH: u8 = 4;
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+pub const COLOR_DEPTH: u8 = 1;
+
+/// The screen is a touch screen, driven with NBGL
+pub const HAS_TOUCH: bool = cfg!(any(target_os = "stax", target_os = "flex"));
+
+/// The device has a Bluetooth Low Energy controller
+pub const HAS_BLE: bool = cfg!(any(
+    target_os = "nanox",
+    target_os = "stax",
+    target_os = "flex"
+));
+
+/// The device has an NFC controller
+pub const HAS_NFC: bool = cfg!(any(target_os = "stax", target_os = "flex"));
+
+/// Size of the NVM pages, in bytes, which are erased as a whole
+#[cfg(target_os = "nanos")]
+pub const NVM_PAGE_SIZE: usize = 64;
+#[cfg(target_os = "nanox")]
+pub const NVM_PAGE_SIZE: usize = 256;
+#[cfg(not(any(target_os = "nanos", target_os = "nanox")))]
+pub const NVM_PAGE_SIZE: usize = 512;
+
+/// Size of the APDU buffer: a 5-byte header and up to 255 bytes of data
+pub const APDU_BUFFER_SIZE: usize = 260;
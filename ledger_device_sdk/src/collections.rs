@@ -0,0 +1,390 @@
+//! Fixed capacity vector and string
+//!
+//! [`ArrayVec`] and [`ArrayString`] store up to `N` elements inline, without
+//! heap allocation, and replace the pairs of a `[u8; N]` buffer and a length
+//! used to assemble responses and display strings. Operations which would
+//! exceed the capacity fail with [`CapacityError`] instead of panicking.
+//!
+//! # Examples
+//!
+//! ```
+//! let mut response = ArrayVec::<u8, 255>::new();
+//! response.push(public_key.len() as u8)?;
+//! response.extend_from_slice(&public_key)?;
+//! comm.append(&response);
+//!
+//! let mut title = ArrayString::<32>::new();
+//! write!(title, "Account {}", index + 1)?;
+//! ```
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+
+/// Error returned when the capacity of an [`ArrayVec`] or an [`ArrayString`]
+/// would be exceeded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CapacityError;
+
+/// Vector of at most `N` elements, stored inline.
+pub struct ArrayVec<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    /// Creates an empty vector.
+    pub const fn new() -> Self {
+        ArrayVec {
+            // An array of `MaybeUninit` does not need initialization
+            items: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+        }
+    }
+
+    /// Number of elements of the vector.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Maximum number of elements of the vector.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Number of elements which can still be pushed.
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Appends `item`, or returns an error if the vector is full.
+    pub fn push(&mut self, item: T) -> Result<(), CapacityError> {
+        if self.len == N {
+            return Err(CapacityError);
+        }
+        self.items[self.len].write(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the last element, or `None` if the vector is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { self.items[self.len].assume_init_read() })
+    }
+
+    /// Shortens the vector to `len` elements, dropping the others. Does
+    /// nothing if the vector is not longer than `len`.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.pop();
+        }
+    }
+
+    /// Removes all the elements.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.items.as_ptr() as *const T, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.items.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+impl<T: Clone, const N: usize> ArrayVec<T, N> {
+    /// Appends the elements of `items`, or returns an error, leaving the
+    /// vector unchanged, if they do not all fit.
+    pub fn extend_from_slice(&mut self, items: &[T]) -> Result<(), CapacityError> {
+        if items.len() > self.remaining_capacity() {
+            return Err(CapacityError);
+        }
+        for item in items {
+            self.items[self.len].write(item.clone());
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// Appends as many elements of `items` as fit, and returns their number.
+    pub fn extend_truncated(&mut self, items: &[T]) -> usize {
+        let count = items.len().min(self.remaining_capacity());
+        // Cannot fail, the elements fit
+        let _ = self.extend_from_slice(&items[..count]);
+        count
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArrayVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new();
+        // Cannot fail, the capacities are the same
+        let _ = clone.extend_from_slice(self);
+        clone
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<T: Clone, const N: usize> TryFrom<&[T]> for ArrayVec<T, N> {
+    type Error = CapacityError;
+
+    fn try_from(items: &[T]) -> Result<Self, CapacityError> {
+        let mut vec = Self::new();
+        vec.extend_from_slice(items)?;
+        Ok(vec)
+    }
+}
+
+/// UTF-8 string of at most `N` bytes, stored inline.
+///
+/// Writing with [`core::fmt::Write`] fails once the capacity is exceeded,
+/// keeping what has been written so far.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ArrayString<const N: usize> {
+    bytes: ArrayVec<u8, N>,
+}
+
+impl<const N: usize> ArrayString<N> {
+    /// Creates an empty string.
+    pub const fn new() -> Self {
+        ArrayString {
+            bytes: ArrayVec::new(),
+        }
+    }
+
+    /// Length of the string, in bytes.
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Maximum length of the string, in bytes.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Appends `s`, or returns an error, leaving the string unchanged, if it
+    /// does not fit.
+    pub fn push_str(&mut self, s: &str) -> Result<(), CapacityError> {
+        self.bytes.extend_from_slice(s.as_bytes())
+    }
+
+    /// Appends as much of `s` as fits, without splitting a character, and
+    /// returns the number of bytes appended.
+    pub fn push_str_truncated(&mut self, s: &str) -> usize {
+        let mut len = s.len().min(self.bytes.remaining_capacity());
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.bytes.extend_truncated(&s.as_bytes()[..len])
+    }
+
+    /// Appends `c`, or returns an error if it does not fit.
+    pub fn push(&mut self, c: char) -> Result<(), CapacityError> {
+        self.push_str(c.encode_utf8(&mut [0u8; 4]))
+    }
+
+    /// Shortens the string to `len` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not on a character boundary.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            assert!(self.is_char_boundary(len), "not a character boundary");
+            self.bytes.truncate(len);
+        }
+    }
+
+    /// Removes all the characters.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only complete UTF-8 strings are appended.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArrayString<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Write for ArrayString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for ArrayString<N> {
+    type Error = CapacityError;
+
+    fn try_from(s: &str) -> Result<Self, CapacityError> {
+        let mut string = Self::new();
+        string.push_str(s)?;
+        Ok(string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use core::fmt::Write;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn array_vec() {
+        let mut vec = ArrayVec::<u8, 4>::new();
+        assert_eq!(vec.push(1), Ok(()));
+        assert_eq!(vec.extend_from_slice(&[2, 3]), Ok(()));
+        assert_eq!(vec.extend_from_slice(&[4, 5]), Err(CapacityError));
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+        assert_eq!(vec.extend_truncated(&[4, 5]), 1);
+        assert_eq!(vec.is_full(), true);
+        assert_eq!(vec.push(6), Err(CapacityError));
+        assert_eq!(vec.pop(), Some(4));
+        vec.truncate(1);
+        assert_eq!(&vec[..], &[1]);
+        assert_eq!(
+            ArrayVec::<u8, 2>::try_from(&[1u8, 2, 3][..]),
+            Err(CapacityError)
+        );
+    }
+
+    #[test]
+    fn array_vec_operations() {
+        // Random sequences of operations, checked against a model
+        crate::testing::check_property(50, |gen| {
+            let mut vec = ArrayVec::<u8, 8>::new();
+            let mut model = [0u8; 8];
+            let mut len = 0;
+            for _ in 0..32 {
+                let item = gen.below(256) as u8;
+                match gen.below(3) {
+                    0 if len < 8 => {
+                        assert_eq!(vec.push(item), Ok(()));
+                        model[len] = item;
+                        len += 1;
+                    }
+                    0 => assert_eq!(vec.push(item), Err(CapacityError)),
+                    1 => {
+                        let expected = len.checked_sub(1).map(|last| model[last]);
+                        len = len.saturating_sub(1);
+                        assert_eq!(vec.pop(), expected);
+                    }
+                    _ => {
+                        len = len.min(gen.below(9) as usize);
+                        vec.truncate(len);
+                    }
+                }
+                assert_eq!(vec.as_slice(), &model[..len]);
+            }
+            Ok(())
+        })?;
+    }
+
+    #[test]
+    fn array_string() {
+        let mut s = ArrayString::<8>::new();
+        assert_eq!(write!(s, "{}-{}", 12, 34).is_ok(), true);
+        assert_eq!(s.as_str(), "12-34");
+        assert_eq!(s.push_str("5678"), Err(CapacityError));
+        assert_eq!(s.push_str_truncated("é€"), 2);
+        assert_eq!(s.as_str(), "12-34é");
+        assert_eq!(write!(s, "{}", 10).is_err(), true);
+        s.clear();
+        assert_eq!(s.push('€'), Ok(()));
+        assert_eq!(s.len(), 3);
+    }
+}
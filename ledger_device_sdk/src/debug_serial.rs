@@ -0,0 +1,141 @@
+//! Debug output over a USB CDC-ACM (virtual serial port) interface
+//!
+//! When the `debug_serial` feature is enabled, the device exposes a CDC-ACM
+//! interface next to the HID one, and the [`debug_write!`](crate::debug_write)
+//! and [`debug_writeln!`](crate::debug_writeln) macros stream formatted logs to
+//! it. This allows printf-style debugging on a physical device, which is
+//! otherwise only possible on Speculos with [`crate::testing::debug_print`].
+//!
+//! The macros only output data in debug builds (`debug_assertions`), and
+//! compile to nothing when the feature is disabled.
+//!
+//! # Examples
+//!
+//! ```
+//! debug_writeln!("received INS {:02x}, {} bytes", ins, data.len());
+//! ```
+//!
+//! On the host, logs can then be read with any serial terminal, for instance
+//! `picocom /dev/ttyACM0`.
+
+use crate::caps::APDU_BUFFER_SIZE;
+use crate::seph;
+use ledger_secure_sdk_sys::seph as sys_seph;
+use ledger_secure_sdk_sys::{
+    USBD_HandleTypeDef, USBD_StatusTypeDef, SEPROXYHAL_TAG_USB_EP_XFER_IN,
+};
+
+/// IN endpoint of the CDC data interface, as defined in `usbd_impl.c`
+const CDC_IN_EP: u8 = 0x83;
+/// Maximum packet size of the CDC data endpoints
+const CDC_DATA_PACKET_SIZE: usize = 64;
+
+extern "C" {
+    fn USBD_LL_Transmit(
+        pdev: *mut USBD_HandleTypeDef,
+        ep_addr: u8,
+        pbuf: *const u8,
+        size: u16,
+    ) -> USBD_StatusTypeDef;
+}
+
+/// Ticker events (100 ms each) to wait for the host to read a packet, after
+/// which the rest of the data is dropped, so that logs do not block the
+/// application when no terminal reads them.
+const TRANSFER_TIMEOUT_TICKS: u32 = 10;
+
+/// Processes the events sent by the MCU until the transfer of a packet on the
+/// CDC IN endpoint completes. Returns false if it times out.
+fn wait_transfer(apdu_buffer: &mut [u8], spi_buffer: &mut [u8; 128]) -> bool {
+    let mut ticks = 0;
+    loop {
+        if !sys_seph::is_status_sent() {
+            sys_seph::send_general_status()
+        }
+        sys_seph::seph_recv(spi_buffer, 0);
+        seph::handle_event(apdu_buffer, spi_buffer);
+        match seph::Events::from(spi_buffer[0]) {
+            seph::Events::USBXFEREvent
+                if spi_buffer[3] & 0x7f == CDC_IN_EP & 0x7f
+                    && spi_buffer[4] == SEPROXYHAL_TAG_USB_EP_XFER_IN as u8 =>
+            {
+                return true
+            }
+            seph::Events::TickerEvent => {
+                ticks += 1;
+                if ticks >= TRANSFER_TIMEOUT_TICKS {
+                    return false;
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Writes `data` to the CDC-ACM interface, by packets of 64 bytes. Each
+/// packet is sent once the host has read the previous one.
+///
+/// USB events received while waiting for the MCU are processed, but APDUs
+/// received at the same time are discarded, so this must not be called while
+/// a command is expected from the host.
+pub fn write(data: &[u8]) {
+    let mut apdu_buffer = [0u8; APDU_BUFFER_SIZE];
+    let mut spi_buffer = [0u8; 128];
+    // Packets are sent to the MCU after one of its events, before the status
+    if !sys_seph::is_status_sent() {
+        sys_seph::send_general_status()
+    }
+    while sys_seph::is_status_sent() {
+        sys_seph::seph_recv(&mut spi_buffer, 0);
+        seph::handle_event(&mut apdu_buffer, &spi_buffer);
+    }
+    for chunk in data.chunks(CDC_DATA_PACKET_SIZE) {
+        unsafe {
+            USBD_LL_Transmit(
+                core::ptr::addr_of_mut!(seph::USBD_Device),
+                CDC_IN_EP,
+                chunk.as_ptr(),
+                chunk.len() as u16,
+            );
+        }
+        if !wait_transfer(&mut apdu_buffer, &mut spi_buffer) {
+            return;
+        }
+    }
+}
+
+/// Writer to the CDC-ACM interface, used by the [`debug_write!`](crate::debug_write) macros.
+pub struct DebugSerial;
+
+impl core::fmt::Write for DebugSerial {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        write(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Writes formatted data to the USB CDC-ACM debug interface, in debug builds only.
+#[macro_export]
+macro_rules! debug_write {
+    ($($arg:tt)*) => {{
+        if cfg!(debug_assertions) {
+            let _ = core::fmt::Write::write_fmt(
+                &mut $crate::debug_serial::DebugSerial,
+                format_args!($($arg)*),
+            );
+        }
+    }};
+}
+
+/// Writes formatted data followed by a newline to the USB CDC-ACM debug
+/// interface, in debug builds only.
+#[macro_export]
+macro_rules! debug_writeln {
+    () => {
+        $crate::debug_write!("\r\n")
+    };
+    ($($arg:tt)*) => {{
+        $crate::debug_write!($($arg)*);
+        $crate::debug_write!("\r\n");
+    }};
+}
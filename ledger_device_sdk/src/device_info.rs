@@ -0,0 +1,119 @@
+//! Versions and features of the device
+//!
+//! [`query`] gathers the versions of the OS and of the MCU firmware, the API
+//! level and the features of the device in a typed struct, so that code
+//! shared between targets, such as a library, can gate functionality at
+//! runtime instead of relying only on `cfg` attributes.
+//!
+//! # Examples
+//!
+//! ```
+//! let info = device_info::query();
+//! if info.os_version_at_least(1, 3, 0) && info.features.ble {
+//!     ...
+//! }
+//! ```
+
+use crate::caps;
+use crate::collections::ArrayString;
+use ledger_secure_sdk_sys::*;
+
+/// Maximum length of the version strings, longer ones are truncated
+pub const VERSION_LEN: usize = 32;
+
+/// Features of the device, see [`DeviceInfo`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Features {
+    /// The screen is driven with NBGL, and the `nbgl` module is available
+    pub nbgl: bool,
+    /// The screen is a touch screen
+    pub touch: bool,
+    /// The device has a Bluetooth Low Energy controller
+    pub ble: bool,
+    /// The device has an NFC controller
+    pub nfc: bool,
+    /// Feature bits reported by the MCU, as returned by `os_seph_features`
+    pub seph: u32,
+}
+
+/// Versions and features of the device, returned by [`query`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Version of the OS running on the secure element, such as "1.1.0"
+    pub os_version: ArrayString<VERSION_LEN>,
+    /// Version of the MCU firmware
+    pub mcu_version: ArrayString<VERSION_LEN>,
+    /// API level of the C SDK the application is built with, `None` on
+    /// Nano S
+    pub api_level: Option<u8>,
+    pub features: Features,
+}
+
+impl DeviceInfo {
+    /// Returns true if the version of the OS is at least
+    /// `major.minor.patch`. Pre-release suffixes, such as "-rc1", are
+    /// ignored.
+    pub fn os_version_at_least(&self, major: u8, minor: u8, patch: u8) -> bool {
+        parse_version(&self.os_version).is_some_and(|version| version >= (major, minor, patch))
+    }
+}
+
+/// Parses a version string such as "1.3.0-rc1" into its numeric components.
+/// Missing components are zero.
+fn parse_version(version: &str) -> Option<(u8, u8, u8)> {
+    let numbers = version.split(['-', '+']).next()?;
+    let mut components = numbers.split('.').map(|c| c.parse::<u8>());
+    let major = components.next()?.ok()?;
+    let minor = components.next().unwrap_or(Ok(0)).ok()?;
+    let patch = components.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+/// Reads a version string with `syscall` into a string.
+fn read_version(syscall: unsafe extern "C" fn(*mut u8, u32) -> u32) -> ArrayString<VERSION_LEN> {
+    let mut buf = [0u8; VERSION_LEN];
+    let len = unsafe { syscall(buf.as_mut_ptr(), VERSION_LEN as u32) } as usize;
+    let bytes = &buf[..len.min(VERSION_LEN)];
+    // The length may include a null terminator
+    let bytes = match bytes.iter().position(|&b| b == 0) {
+        Some(end) => &bytes[..end],
+        None => bytes,
+    };
+    let mut version = ArrayString::new();
+    if let Ok(s) = core::str::from_utf8(bytes) {
+        version.push_str_truncated(s);
+    }
+    version
+}
+
+/// Returns the versions and features of the device.
+pub fn query() -> DeviceInfo {
+    DeviceInfo {
+        os_version: read_version(os_version),
+        mcu_version: read_version(os_seph_version),
+        api_level: API_LEVEL,
+        features: Features {
+            nbgl: cfg!(any(target_os = "stax", target_os = "flex")),
+            touch: caps::HAS_TOUCH,
+            ble: caps::HAS_BLE,
+            nfc: caps::HAS_NFC,
+            seph: unsafe { os_seph_features() },
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn version_components() {
+        assert_eq!(parse_version("1.3.0-rc1"), Some((1, 3, 0)));
+        assert_eq!(parse_version("2.61"), Some((2, 61, 0)));
+        assert_eq!(parse_version("x.1"), None);
+        assert_eq!(parse_version(""), None);
+    }
+}
@@ -0,0 +1,619 @@
+//! Formatting of values for display
+//!
+//! Amounts and byte strings are formatted into caller provided buffers, so
+//! that the resulting strings can be used as review fields without allocating.
+//!
+//! Amounts are transmitted as integers in the smallest unit of the asset, and
+//! displayed with the decimal point placed according to the number of decimals
+//! of the asset, trailing zeros of the fractional part removed and the integer
+//! part grouped by thousands.
+//!
+//! # Examples
+//!
+//! ```
+//! let mut buffer = [0u8; 64];
+//! // "1,234.5 CRAB"
+//! let amount = format_amount(1_234_500_000u64, 6, "CRAB", &mut buffer)?;
+//! ```
+//!
+//! Amounts entered as decimal strings, such as "1,234.5", are converted back to
+//! integers in the smallest unit with [`parse_amount`].
+//!
+//! Integers are formatted without `core::fmt` by the [`number`] module. The
+//! derivation paths shown during address reviews are formatted with
+//! [`format_bip32_path`].
+
+pub mod number;
+
+/// Tag/value pair displayed by the review flows, shared by the NBGL
+/// (`nbgl::NbglReview`) and BAGL (`ui::gadgets::Review`) reviews.
+pub struct Field<'a> {
+    pub name: &'a str,
+    pub value: &'a str,
+}
+
+/// Maximum number of decimal digits of a 256-bit unsigned integer
+pub const MAX_DIGITS: usize = 78;
+
+/// Separator inserted between groups of three digits of the integer part
+const GROUP_SEPARATOR: u8 = b',';
+
+/// Error returned when a formatted value does not fit in the output buffer.
+#[derive(Debug, Eq, PartialEq)]
+pub struct BufferTooSmall;
+
+/// Unsigned integers which can be formatted with [`format_amount`].
+///
+/// `[u8; 32]` values are 256-bit integers, in big-endian order.
+pub trait AmountValue {
+    /// Writes the decimal digits of the value at the end of `digits`, and
+    /// returns the index of the first one.
+    fn to_digits(&self, digits: &mut [u8; MAX_DIGITS]) -> usize;
+}
+
+impl AmountValue for u128 {
+    fn to_digits(&self, digits: &mut [u8; MAX_DIGITS]) -> usize {
+        number::write_u128(*self, digits, MAX_DIGITS)
+    }
+}
+
+impl AmountValue for u64 {
+    fn to_digits(&self, digits: &mut [u8; MAX_DIGITS]) -> usize {
+        (*self as u128).to_digits(digits)
+    }
+}
+
+impl AmountValue for u32 {
+    fn to_digits(&self, digits: &mut [u8; MAX_DIGITS]) -> usize {
+        (*self as u128).to_digits(digits)
+    }
+}
+
+impl AmountValue for [u8; 32] {
+    fn to_digits(&self, digits: &mut [u8; MAX_DIGITS]) -> usize {
+        let mut value = *self;
+        let mut start = MAX_DIGITS;
+        loop {
+            // Long division of the big-endian value by 10
+            let mut remainder = 0u16;
+            for byte in value.iter_mut() {
+                let current = (remainder << 8) | *byte as u16;
+                *byte = (current / 10) as u8;
+                remainder = current % 10;
+            }
+            start -= 1;
+            digits[start] = b'0' + remainder as u8;
+            if value.iter().all(|&b| b == 0) {
+                return start;
+            }
+        }
+    }
+}
+
+/// Bounded writer over the output buffer.
+struct Writer<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn push(&mut self, byte: u8) -> Result<(), BufferTooSmall> {
+        let slot = self.buffer.get_mut(self.len).ok_or(BufferTooSmall)?;
+        *slot = byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn push_all(&mut self, bytes: &[u8]) -> Result<(), BufferTooSmall> {
+        bytes.iter().try_for_each(|&b| self.push(b))
+    }
+}
+
+/// Formats `value`, expressed in the smallest unit of an asset with
+/// `decimals` decimals, into `out`, and returns the formatted string.
+///
+/// The fractional part is written without trailing zeros, the integer part is
+/// grouped by thousands, and `ticker` is appended after a space unless it is
+/// empty. For instance `1234500000` with 6 decimals and the `CRAB` ticker is
+/// formatted as `1,234.5 CRAB`.
+pub fn format_amount<'a, V: AmountValue>(
+    value: V,
+    decimals: u8,
+    ticker: &str,
+    out: &'a mut [u8],
+) -> Result<&'a str, BufferTooSmall> {
+    let mut digits = [0u8; MAX_DIGITS];
+    let start = value.to_digits(&mut digits);
+    let digits = &digits[start..];
+    let decimals = decimals as usize;
+
+    // Values below one unit have a zero integer part, and a fractional part
+    // padded with leading zeros.
+    let (integer, leading_zeros, fraction) = if digits.len() > decimals {
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        (integer, 0, fraction)
+    } else {
+        (&b"0"[..], decimals - digits.len(), digits)
+    };
+    let fraction_len = fraction
+        .iter()
+        .rposition(|&d| d != b'0')
+        .map_or(0, |i| i + 1);
+
+    let mut writer = Writer {
+        buffer: &mut *out,
+        len: 0,
+    };
+    for (i, &digit) in integer.iter().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            writer.push(GROUP_SEPARATOR)?;
+        }
+        writer.push(digit)?;
+    }
+    if fraction_len > 0 {
+        writer.push(b'.')?;
+        (0..leading_zeros).try_for_each(|_| writer.push(b'0'))?;
+        writer.push_all(&fraction[..fraction_len])?;
+    }
+    if !ticker.is_empty() {
+        writer.push(b' ')?;
+        writer.push_all(ticker.as_bytes())?;
+    }
+
+    let len = writer.len;
+    // Only ASCII digits and separators, and the UTF-8 ticker, are written.
+    Ok(unsafe { core::str::from_utf8_unchecked(&out[..len]) })
+}
+
+/// Error returned by [`parse_amount`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParseAmountError {
+    /// The string is empty, or contains characters other than digits, group
+    /// separators in the integer part and a single decimal point.
+    Invalid,
+    /// The fractional part has more significant digits than the decimals of
+    /// the asset.
+    TooManyDecimals,
+    /// The value does not fit in the integer type.
+    Overflow,
+}
+
+/// Unsigned integers which can be parsed with [`parse_amount`].
+///
+/// `[u8; 32]` values are 256-bit integers, in big-endian order.
+pub trait ParseAmountValue: Sized {
+    /// Returns the value of the decimal `digits`, most significant first, or
+    /// `None` if it overflows.
+    fn from_digits(digits: impl Iterator<Item = u8>) -> Option<Self>;
+}
+
+impl ParseAmountValue for u128 {
+    fn from_digits(digits: impl Iterator<Item = u8>) -> Option<Self> {
+        digits.fold(Some(0u128), |value, digit| {
+            value?.checked_mul(10)?.checked_add(digit as u128)
+        })
+    }
+}
+
+impl ParseAmountValue for u64 {
+    fn from_digits(digits: impl Iterator<Item = u8>) -> Option<Self> {
+        u128::from_digits(digits)?.try_into().ok()
+    }
+}
+
+impl ParseAmountValue for u32 {
+    fn from_digits(digits: impl Iterator<Item = u8>) -> Option<Self> {
+        u128::from_digits(digits)?.try_into().ok()
+    }
+}
+
+impl ParseAmountValue for [u8; 32] {
+    fn from_digits(digits: impl Iterator<Item = u8>) -> Option<Self> {
+        let mut value = [0u8; 32];
+        for digit in digits {
+            // Multiplication of the big-endian value by 10, plus the digit
+            let mut carry = digit as u16;
+            for byte in value.iter_mut().rev() {
+                let current = *byte as u16 * 10 + carry;
+                *byte = current as u8;
+                carry = current >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(value)
+    }
+}
+
+/// Returns whether the group separators of the integer part `integer`, if
+/// any, separate groups of three digits, the first one excepted.
+fn valid_groups(integer: &[u8]) -> bool {
+    let mut groups = integer.split(|&c| c == GROUP_SEPARATOR);
+    let first = groups.next().unwrap_or_default();
+    if first.len() == integer.len() {
+        return true;
+    }
+    (1..=3).contains(&first.len()) && groups.all(|group| group.len() == 3)
+}
+
+/// Parses the decimal amount `text`, such as "1,234.5", into an integer in
+/// the smallest unit of an asset with `decimals` decimals, the inverse of
+/// [`format_amount`] without ticker.
+///
+/// Group separators are accepted in the integer part only, between groups of
+/// three digits, and fractional parts longer than `decimals` are accepted as
+/// long as the extra digits are zeros.
+pub fn parse_amount<V: ParseAmountValue>(text: &str, decimals: u8) -> Result<V, ParseAmountError> {
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer.as_bytes(), fraction.as_bytes()),
+        None => (text.as_bytes(), &b""[..]),
+    };
+    let integer_digits = integer.iter().filter(|&&c| c != GROUP_SEPARATOR);
+    if integer_digits.clone().count() == 0 && fraction.is_empty()
+        || !valid_groups(integer)
+        || integer_digits
+            .clone()
+            .chain(fraction)
+            .any(|c| !c.is_ascii_digit())
+    {
+        return Err(ParseAmountError::Invalid);
+    }
+
+    let decimals = decimals as usize;
+    let significant = fraction
+        .iter()
+        .rposition(|&d| d != b'0')
+        .map_or(0, |i| i + 1);
+    if significant > decimals {
+        return Err(ParseAmountError::TooManyDecimals);
+    }
+    // The fractional part is padded with zeros up to the number of decimals
+    let fraction = fraction[..significant]
+        .iter()
+        .copied()
+        .chain(core::iter::repeat(b'0'))
+        .take(decimals);
+
+    V::from_digits(integer_digits.copied().chain(fraction).map(|c| c - b'0'))
+        .ok_or(ParseAmountError::Overflow)
+}
+
+/// Formatting options of byte strings displayed as hexadecimal, such as
+/// hashes or raw data.
+///
+/// # Examples
+///
+/// ```
+/// const HASH_FORMAT: HexFormat = HexFormat::new().group(4).uppercase();
+///
+/// let mut buffer = [0u8; 80];
+/// // "0A1B2C3D 4E5F6071 ..."
+/// let hash = HASH_FORMAT.format(&digest, &mut buffer)?;
+/// review.show(&[Field {
+///     name: "Hash",
+///     value: hash,
+/// }]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HexFormat {
+    group: usize,
+    uppercase: bool,
+    prefix: bool,
+    ellipsis: Option<usize>,
+}
+
+impl HexFormat {
+    pub const fn new() -> HexFormat {
+        HexFormat {
+            group: 0,
+            uppercase: false,
+            prefix: false,
+            ellipsis: None,
+        }
+    }
+
+    /// Separates groups of `bytes` bytes with a space.
+    pub const fn group(self, bytes: usize) -> HexFormat {
+        HexFormat {
+            group: bytes,
+            ..self
+        }
+    }
+
+    pub const fn uppercase(self) -> HexFormat {
+        HexFormat {
+            uppercase: true,
+            ..self
+        }
+    }
+
+    /// Prepends `0x` to the formatted data.
+    pub const fn prefix(self) -> HexFormat {
+        HexFormat {
+            prefix: true,
+            ..self
+        }
+    }
+
+    /// Only displays the first and last `bytes` bytes, separated by an
+    /// ellipsis, when the data is longer than twice `bytes`.
+    pub const fn ellipsis(self, bytes: usize) -> HexFormat {
+        HexFormat {
+            ellipsis: Some(bytes),
+            ..self
+        }
+    }
+
+    /// Formats `data` into `out`, and returns the formatted string.
+    pub fn format<'a>(&self, data: &[u8], out: &'a mut [u8]) -> Result<&'a str, BufferTooSmall> {
+        let mut writer = Writer {
+            buffer: &mut *out,
+            len: 0,
+        };
+        if self.prefix {
+            writer.push_all(b"0x")?;
+        }
+        match self.ellipsis {
+            Some(bytes) if data.len() > 2 * bytes => {
+                self.write_hex(&mut writer, &data[..bytes])?;
+                writer.push_all(b"...")?;
+                self.write_hex(&mut writer, &data[data.len() - bytes..])?;
+            }
+            _ => self.write_hex(&mut writer, data)?,
+        }
+
+        let len = writer.len;
+        // Only ASCII characters are written.
+        Ok(unsafe { core::str::from_utf8_unchecked(&out[..len]) })
+    }
+
+    fn write_hex(&self, writer: &mut Writer, data: &[u8]) -> Result<(), BufferTooSmall> {
+        let digits = if self.uppercase {
+            b"0123456789ABCDEF"
+        } else {
+            b"0123456789abcdef"
+        };
+        for (i, byte) in data.iter().enumerate() {
+            if self.group > 0 && i > 0 && i % self.group == 0 {
+                writer.push(b' ')?;
+            }
+            writer.push(digits[(byte >> 4) as usize])?;
+            writer.push(digits[(byte & 0x0f) as usize])?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for HexFormat {
+    fn default() -> HexFormat {
+        HexFormat::new()
+    }
+}
+
+/// Bit set in the hardened indices of a derivation path
+const HARDENED: u32 = 0x8000_0000;
+
+/// Formats the BIP32 derivation `path`, as built by
+/// [`make_bip32_path`](crate::ecc::make_bip32_path), into `out`, and returns
+/// the formatted string: `m/44'/60'/0'/0/0`, with hardened indices followed
+/// by `'`.
+///
+/// When the path does not fit in `out`, which is sized according to the
+/// screen, the components after the first one are elided until the rest
+/// fits, so that the purpose and the last indices, which tell the account
+/// and the address, remain visible: `m/44'/.../0/0`. The buffer is too small
+/// if even the first and the last components do not fit.
+///
+/// # Examples
+///
+/// ```
+/// let mut buffer = [0u8; 16];
+/// // "m/44'/.../0'/0/0"
+/// let path = format_bip32_path(&make_bip32_path::<5>(b"m/44'/535348'/0'/0/0"), &mut buffer)?;
+/// ```
+pub fn format_bip32_path<'a>(path: &[u32], out: &'a mut [u8]) -> Result<&'a str, BufferTooSmall> {
+    let full_len = 1 + path
+        .iter()
+        .map(|&i| 1 + path_component_len(i))
+        .sum::<usize>();
+
+    // Number of components written at the start and at the end of the path
+    let (head, tail) = if full_len <= out.len() {
+        (path.len(), 0)
+    } else if path.len() > 2 {
+        let mut len = 2 + path_component_len(path[0]) + 4;
+        let tail = path[1..]
+            .iter()
+            .rev()
+            .take_while(|&&i| {
+                len += 1 + path_component_len(i);
+                len <= out.len()
+            })
+            .count();
+        if tail == 0 {
+            return Err(BufferTooSmall);
+        }
+        (1, tail)
+    } else {
+        return Err(BufferTooSmall);
+    };
+
+    let mut writer = Writer {
+        buffer: &mut *out,
+        len: 0,
+    };
+    writer.push(b'm')?;
+    path[..head]
+        .iter()
+        .try_for_each(|&i| write_path_component(&mut writer, i))?;
+    if tail > 0 {
+        writer.push_all(b"/...")?;
+        path[path.len() - tail..]
+            .iter()
+            .try_for_each(|&i| write_path_component(&mut writer, i))?;
+    }
+
+    let len = writer.len;
+    // Only ASCII characters are written.
+    Ok(unsafe { core::str::from_utf8_unchecked(&out[..len]) })
+}
+
+/// Length of a component of a derivation path, without its separator.
+fn path_component_len(index: u32) -> usize {
+    number::Buffer::new().format(index & !HARDENED).len() + (index & HARDENED != 0) as usize
+}
+
+fn write_path_component(writer: &mut Writer, index: u32) -> Result<(), BufferTooSmall> {
+    writer.push(b'/')?;
+    writer.push_all(number::Buffer::new().format(index & !HARDENED).as_bytes())?;
+    if index & HARDENED != 0 {
+        writer.push(b'\'')?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn format_amounts() {
+        let mut buffer = [0u8; 128];
+        assert_eq!(
+            format_amount(1_234_500_000u64, 6, "CRAB", &mut buffer),
+            Ok("1,234.5 CRAB")
+        );
+        assert_eq!(format_amount(0u64, 8, "BTC", &mut buffer), Ok("0 BTC"));
+        assert_eq!(format_amount(12u32, 4, "", &mut buffer), Ok("0.0012"));
+        assert_eq!(format_amount(100u32, 0, "", &mut buffer), Ok("100"));
+        assert_eq!(
+            format_amount(u128::MAX, 18, "ETH", &mut buffer),
+            Ok("340,282,366,920,938,463,463.374607431768211455 ETH")
+        );
+    }
+
+    #[test]
+    fn format_u256_amounts() {
+        let mut buffer = [0u8; 128];
+        let mut value = [0u8; 32];
+        value[24..].copy_from_slice(&1_000_000_000_000_000_000u64.to_be_bytes());
+        assert_eq!(format_amount(value, 18, "ETH", &mut buffer), Ok("1 ETH"));
+        assert_eq!(
+            format_amount([0xff; 32], 0, "", &mut buffer),
+            Ok("115,792,089,237,316,195,423,570,985,008,687,907,853,269,984,665,640,564,039,457,584,007,913,129,639,935")
+        );
+        assert_eq!(
+            format_amount([0x01; 32], 80, "", &mut buffer),
+            Ok("0.00000454086624460063511464984254936031011189294057512315937409637584344757371137")
+        );
+    }
+
+    #[test]
+    fn parse_amounts() {
+        assert_eq!(parse_amount::<u64>("1,234.5", 6), Ok(1_234_500_000));
+        assert_eq!(parse_amount::<u64>("0.0012", 4), Ok(12));
+        assert_eq!(parse_amount::<u64>(".5", 1), Ok(5));
+        assert_eq!(parse_amount::<u32>("100", 0), Ok(100));
+        assert_eq!(parse_amount::<u32>("1.50", 1), Ok(15));
+        assert_eq!(
+            parse_amount::<u32>("1.55", 1),
+            Err(ParseAmountError::TooManyDecimals)
+        );
+        assert_eq!(
+            parse_amount::<u32>("4294967296", 0),
+            Err(ParseAmountError::Overflow)
+        );
+        assert_eq!(parse_amount::<u32>("", 0), Err(ParseAmountError::Invalid));
+        assert_eq!(
+            parse_amount::<u32>("1.2.3", 2),
+            Err(ParseAmountError::Invalid)
+        );
+        assert_eq!(parse_amount::<u32>("-1", 0), Err(ParseAmountError::Invalid));
+        assert_eq!(parse_amount::<u64>("1,234,567", 0), Ok(1_234_567));
+        for text in [
+            "1,23", "1,,2", "12,.5", ",123", "1234,567", "1,2345", "1,234,",
+        ] {
+            assert_eq!(parse_amount::<u64>(text, 2), Err(ParseAmountError::Invalid));
+        }
+
+        let mut one_eth = [0u8; 32];
+        one_eth[24..].copy_from_slice(&1_000_000_000_000_000_000u64.to_be_bytes());
+        assert_eq!(parse_amount::<[u8; 32]>("1", 18), Ok(one_eth));
+        assert_eq!(
+            parse_amount::<[u8; 32]>(
+                "115,792,089,237,316,195,423,570,985,008,687,907,853,269,984,665,640,564,039,457,584,007,913,129,639,935",
+                0
+            ),
+            Ok([0xff; 32])
+        );
+        assert_eq!(
+            parse_amount::<[u8; 32]>(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+                0
+            ),
+            Err(ParseAmountError::Overflow)
+        );
+    }
+
+    #[test]
+    fn format_hex() {
+        let mut buffer = [0u8; 64];
+        let data = [0xde, 0xad, 0xbe, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89];
+        assert_eq!(
+            HexFormat::new().format(&data, &mut buffer),
+            Ok("deadbeef0123456789")
+        );
+        assert_eq!(
+            HexFormat::new()
+                .group(4)
+                .uppercase()
+                .prefix()
+                .format(&data, &mut buffer),
+            Ok("0xDEADBEEF 01234567 89")
+        );
+        assert_eq!(
+            HexFormat::new().ellipsis(2).format(&data, &mut buffer),
+            Ok("dead...6789")
+        );
+        assert_eq!(
+            HexFormat::new().ellipsis(8).format(&data, &mut buffer),
+            Ok("deadbeef0123456789")
+        );
+        assert_eq!(
+            HexFormat::new().format(&data, &mut buffer[..4]),
+            Err(BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn format_bip32_paths() {
+        let mut buffer = [0u8; 64];
+        let path = crate::ecc::make_bip32_path::<5>(b"m/44'/535348'/0'/0/0");
+        assert_eq!(
+            format_bip32_path(&path, &mut buffer),
+            Ok("m/44'/535348'/0'/0/0")
+        );
+        assert_eq!(
+            format_bip32_path(&path, &mut buffer[..16]),
+            Ok("m/44'/.../0'/0/0")
+        );
+        assert_eq!(format_bip32_path(&[], &mut buffer), Ok("m"));
+        assert_eq!(
+            format_bip32_path(&path, &mut buffer[..10]),
+            Err(BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn format_amount_overflow() {
+        let mut buffer = [0u8; 8];
+        assert_eq!(
+            format_amount(1_000_000u64, 0, "CRAB", &mut buffer),
+            Err(BufferTooSmall)
+        );
+    }
+}
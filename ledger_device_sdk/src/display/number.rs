@@ -0,0 +1,220 @@
+//! Decimal formatting of integers without `core::fmt`
+//!
+//! The formatting machinery of `core::fmt` is large and slow on the devices.
+//! [`Buffer`] formats integers two digits at a time into a stack buffer, in
+//! the way of the `itoa` crate, and [`format_fixed`] formats fixed-point
+//! values, such as percentages or rates, into a caller provided buffer.
+//!
+//! # Examples
+//!
+//! ```
+//! let mut number = number::Buffer::new();
+//! title.push_str("Account ")?;
+//! title.push_str(number.format(index + 1))?;
+//!
+//! let mut buffer = [0u8; 16];
+//! // "12.50"
+//! let fee = number::format_fixed(1250u32, 2, &mut buffer)?;
+//! ```
+
+use super::{BufferTooSmall, Writer};
+
+/// Maximum length of a formatted integer: the 39 digits of `u128::MAX`, or
+/// `i128::MIN` with its sign
+pub const MAX_LEN: usize = 40;
+
+/// Pairs of decimal digits, from "00" to "99"
+const DIGIT_PAIRS: &[u8; 200] = b"\
+    0001020304050607080910111213141516171819\
+    2021222324252627282930313233343536373839\
+    4041424344454647484950515253545556575859\
+    6061626364656667686970717273747576777879\
+    8081828384858687888990919293949596979899";
+
+/// Writes the decimal digits of `value` in `buffer`, ending before `end`, and
+/// returns the index of the first one.
+pub(crate) fn write_u64(mut value: u64, buffer: &mut [u8], mut end: usize) -> usize {
+    while value >= 100 {
+        let pair = (value % 100) as usize * 2;
+        value /= 100;
+        end -= 2;
+        buffer[end..end + 2].copy_from_slice(&DIGIT_PAIRS[pair..pair + 2]);
+    }
+    if value >= 10 {
+        let pair = value as usize * 2;
+        end -= 2;
+        buffer[end..end + 2].copy_from_slice(&DIGIT_PAIRS[pair..pair + 2]);
+    } else {
+        end -= 1;
+        buffer[end] = b'0' + value as u8;
+    }
+    end
+}
+
+/// Same as [`write_u64`] for 128-bit values, which are split in chunks of 19
+/// digits so that most divisions are done on 64 bits.
+pub(crate) fn write_u128(value: u128, buffer: &mut [u8], end: usize) -> usize {
+    const CHUNK_DIGITS: usize = 19;
+    const CHUNK: u128 = 10_000_000_000_000_000_000;
+
+    if value <= u64::MAX as u128 {
+        return write_u64(value as u64, buffer, end);
+    }
+    let start = write_u64((value % CHUNK) as u64, buffer, end);
+    let chunk_start = end - CHUNK_DIGITS;
+    buffer[chunk_start..start].fill(b'0');
+    write_u128(value / CHUNK, buffer, chunk_start)
+}
+
+/// Integers which can be formatted with [`Buffer`] and [`format_fixed`].
+pub trait Integer: Copy {
+    /// Writes the decimal digits of the absolute value at the end of
+    /// `digits`, and returns the index of the first one.
+    fn magnitude_digits(self, digits: &mut [u8; MAX_LEN]) -> usize;
+
+    fn is_negative(self) -> bool;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn magnitude_digits(self, digits: &mut [u8; MAX_LEN]) -> usize {
+                    write_u128(self as u128, digits, MAX_LEN)
+                }
+
+                fn is_negative(self) -> bool {
+                    false
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn magnitude_digits(self, digits: &mut [u8; MAX_LEN]) -> usize {
+                    write_u128(self.unsigned_abs() as u128, digits, MAX_LEN)
+                }
+
+                fn is_negative(self) -> bool {
+                    self < 0
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+/// Stack buffer holding a formatted integer.
+pub struct Buffer {
+    bytes: [u8; MAX_LEN],
+}
+
+impl Buffer {
+    pub fn new() -> Buffer {
+        Buffer {
+            bytes: [0u8; MAX_LEN],
+        }
+    }
+
+    /// Formats `value` in decimal, and returns the formatted string, which
+    /// borrows the buffer.
+    pub fn format<I: Integer>(&mut self, value: I) -> &str {
+        let mut start = value.magnitude_digits(&mut self.bytes);
+        if value.is_negative() {
+            start -= 1;
+            self.bytes[start] = b'-';
+        }
+        // Only ASCII digits and the sign are written.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[start..]) }
+    }
+}
+
+impl Default for Buffer {
+    fn default() -> Buffer {
+        Buffer::new()
+    }
+}
+
+/// Formats the fixed-point `value`, scaled by 10 to the power of
+/// `decimals`, into `out`, and returns the formatted string.
+///
+/// Unlike [`format_amount`](super::format_amount), all the decimals are
+/// written, without grouping: `1250` with 2 decimals is formatted as `12.50`.
+pub fn format_fixed<'a, I: Integer>(
+    value: I,
+    decimals: u8,
+    out: &'a mut [u8],
+) -> Result<&'a str, BufferTooSmall> {
+    let mut digits = [0u8; MAX_LEN];
+    let start = value.magnitude_digits(&mut digits);
+    let digits = &digits[start..];
+    let decimals = decimals as usize;
+
+    let mut writer = Writer {
+        buffer: &mut *out,
+        len: 0,
+    };
+    if value.is_negative() {
+        writer.push(b'-')?;
+    }
+    if digits.len() > decimals {
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        writer.push_all(integer)?;
+        if !fraction.is_empty() {
+            writer.push(b'.')?;
+            writer.push_all(fraction)?;
+        }
+    } else {
+        writer.push_all(b"0.")?;
+        (digits.len()..decimals).try_for_each(|_| writer.push(b'0'))?;
+        writer.push_all(digits)?;
+    }
+
+    let len = writer.len;
+    // Only ASCII digits, the sign and the decimal point are written.
+    Ok(unsafe { core::str::from_utf8_unchecked(&out[..len]) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn format_integers() {
+        let mut number = Buffer::new();
+        assert_eq!(number.format(0u8), "0");
+        assert_eq!(number.format(7u32), "7");
+        assert_eq!(number.format(1024usize), "1024");
+        assert_eq!(number.format(-42i16), "-42");
+        assert_eq!(number.format(u64::MAX), "18446744073709551615");
+        assert_eq!(
+            number.format(10_000_000_000_000_000_000u128),
+            "10000000000000000000"
+        );
+        assert_eq!(
+            number.format(i128::MIN),
+            "-170141183460469231731687303715884105728"
+        );
+    }
+
+    #[test]
+    fn format_fixed_points() {
+        let mut buffer = [0u8; 16];
+        assert_eq!(format_fixed(1250u32, 2, &mut buffer), Ok("12.50"));
+        assert_eq!(format_fixed(-5i32, 3, &mut buffer), Ok("-0.005"));
+        assert_eq!(format_fixed(42u8, 0, &mut buffer), Ok("42"));
+        assert_eq!(
+            format_fixed(u64::MAX, 0, &mut [0u8; 4]),
+            Err(BufferTooSmall)
+        );
+    }
+}
@@ -0,0 +1,28 @@
+//! Text encodings of binary data, such as addresses
+//!
+//! Encoders and decoders write into caller provided buffers, and return the
+//! part of the buffer they wrote, so that they can be used without allocating.
+
+pub mod base58;
+pub mod bech32;
+pub mod hex;
+
+/// Error returned when encoding or decoding data.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EncodingError {
+    /// The output does not fit in the output buffer.
+    BufferTooSmall,
+    /// The encoded string contains a character outside of the alphabet.
+    InvalidCharacter,
+    /// The checksum of the encoded string does not match its content.
+    InvalidChecksum,
+    /// The encoded string is too short or too long.
+    InvalidLength,
+    /// The human readable part of a Bech32 string is empty or invalid, or
+    /// not the expected one.
+    InvalidHrp,
+    /// The encoded string mixes lowercase and uppercase characters.
+    MixedCase,
+    /// The bits left over after a conversion are not a valid zero padding.
+    InvalidPadding,
+}
@@ -0,0 +1,200 @@
+//! Base58 and Base58Check, as used by Bitcoin addresses and extended keys
+//!
+//! # Examples
+//!
+//! ```
+//! let mut address = [0u8; 64];
+//! // Version byte followed by the public key hash
+//! let address = base58::encode_check(&payload, &mut address)?;
+//!
+//! let mut payload = [0u8; 32];
+//! let payload = base58::decode_check(address, &mut payload)?;
+//! ```
+
+use super::EncodingError;
+use crate::hash::{sha2::Sha2_256, HashInit};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Length of the Base58Check checksum
+pub const CHECKSUM_LEN: usize = 4;
+
+/// Returns the maximum length of the Base58 encoding of `len` bytes, to size
+/// the output buffer.
+pub const fn encoded_len(len: usize) -> usize {
+    // log(256) / log(58) < 1.37
+    len * 137 / 100 + 1
+}
+
+/// Encodes the bytes of `data` into `out`.
+fn encode_bytes<'a, I>(data: I, out: &'a mut [u8]) -> Result<&'a str, EncodingError>
+where
+    I: Iterator<Item = u8> + Clone,
+{
+    let zeros = data.clone().take_while(|&b| b == 0).count();
+
+    // Base58 digits of the value, least significant first
+    let mut len = 0;
+    for byte in data.skip(zeros) {
+        let mut carry = byte as u32;
+        for digit in out[..len].iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            *out.get_mut(len).ok_or(EncodingError::BufferTooSmall)? = (carry % 58) as u8;
+            len += 1;
+            carry /= 58;
+        }
+    }
+
+    // Each leading zero byte is encoded as a leading '1'
+    if len + zeros > out.len() {
+        return Err(EncodingError::BufferTooSmall);
+    }
+    out[len..len + zeros].fill(0);
+    len += zeros;
+
+    let encoded = &mut out[..len];
+    encoded.reverse();
+    for digit in encoded.iter_mut() {
+        *digit = ALPHABET[*digit as usize];
+    }
+    // Only characters of the alphabet are written.
+    Ok(unsafe { core::str::from_utf8_unchecked(encoded) })
+}
+
+/// Encodes `data` into `out`, and returns the encoded string.
+pub fn encode<'a>(data: &[u8], out: &'a mut [u8]) -> Result<&'a str, EncodingError> {
+    encode_bytes(data.iter().copied(), out)
+}
+
+/// Decodes `encoded` into `out`, and returns the decoded bytes.
+pub fn decode<'a>(encoded: &str, out: &'a mut [u8]) -> Result<&'a [u8], EncodingError> {
+    let encoded = encoded.as_bytes();
+    let zeros = encoded.iter().take_while(|&&c| c == ALPHABET[0]).count();
+
+    // Bytes of the value, least significant first
+    let mut len = 0;
+    for &c in &encoded[zeros..] {
+        let mut carry = ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or(EncodingError::InvalidCharacter)? as u32;
+        for byte in out[..len].iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            *out.get_mut(len).ok_or(EncodingError::BufferTooSmall)? = carry as u8;
+            len += 1;
+            carry >>= 8;
+        }
+    }
+
+    if len + zeros > out.len() {
+        return Err(EncodingError::BufferTooSmall);
+    }
+    out[len..len + zeros].fill(0);
+    len += zeros;
+
+    let decoded = &mut out[..len];
+    decoded.reverse();
+    Ok(decoded)
+}
+
+/// Returns the Base58Check checksum of `data`: the first bytes of its double
+/// SHA-256 hash.
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    // The output buffers have the SHA-256 size, hashing can not fail
+    let _ = Sha2_256::new().hash(data, &mut first);
+    let _ = Sha2_256::new().hash(&first, &mut second);
+    [second[0], second[1], second[2], second[3]]
+}
+
+/// Encodes `data` followed by its checksum into `out`, and returns the encoded
+/// string.
+pub fn encode_check<'a>(data: &[u8], out: &'a mut [u8]) -> Result<&'a str, EncodingError> {
+    let checksum = checksum(data);
+    encode_bytes(data.iter().chain(checksum.iter()).copied(), out)
+}
+
+/// Decodes `encoded` into `out`, verifies its checksum, and returns the
+/// decoded bytes without the checksum.
+pub fn decode_check<'a>(encoded: &str, out: &'a mut [u8]) -> Result<&'a [u8], EncodingError> {
+    let decoded = decode(encoded, out)?;
+    let len = decoded
+        .len()
+        .checked_sub(CHECKSUM_LEN)
+        .ok_or(EncodingError::InvalidLength)?;
+    let (data, expected) = decoded.split_at(len);
+    if checksum(data) != expected {
+        return Err(EncodingError::InvalidChecksum);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn base58() {
+        let mut buffer = [0u8; 64];
+        assert_eq!(encode(b"hello world", &mut buffer), Ok("StV1DL6CwTryKyV"));
+        assert_eq!(
+            encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], &mut buffer),
+            Ok("11233QC4")
+        );
+        assert_eq!(encode(&[], &mut buffer), Ok(""));
+
+        let mut decoded = [0u8; 32];
+        assert_eq!(
+            decode("11233QC4", &mut decoded),
+            Ok(&[0u8, 0, 0x28, 0x7f, 0xb4, 0xcd][..])
+        );
+        assert_eq!(
+            decode("StV1DL6CwTryKyV", &mut decoded),
+            Ok(&b"hello world"[..])
+        );
+        assert_eq!(
+            decode("0OIl", &mut decoded),
+            Err(EncodingError::InvalidCharacter)
+        );
+        assert_eq!(
+            decode("StV1DL6CwTryKyV", &mut decoded[..4]),
+            Err(EncodingError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn base58_check() {
+        // Address of the public key hash 0x010966776006953d5567439e5e39f86a0d273bee
+        let payload = [
+            0x00, 0x01, 0x09, 0x66, 0x77, 0x60, 0x06, 0x95, 0x3d, 0x55, 0x67, 0x43, 0x9e, 0x5e,
+            0x39, 0xf8, 0x6a, 0x0d, 0x27, 0x3b, 0xee,
+        ];
+        let mut buffer = [0u8; 64];
+        assert_eq!(
+            encode_check(&payload, &mut buffer),
+            Ok("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM")
+        );
+
+        let mut decoded = [0u8; 32];
+        assert_eq!(
+            decode_check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM", &mut decoded),
+            Ok(&payload[..])
+        );
+        assert_eq!(
+            decode_check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvN", &mut decoded),
+            Err(EncodingError::InvalidChecksum)
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! Bech32 (BIP 173) and Bech32m (BIP 350), as used by segwit and taproot
+//! addresses and by Cosmos SDK chains
+//!
+//! Bech32 strings encode 5-bit values, to which byte strings are converted
+//! with [`convert_bits`]. [`encode_segwit`] and [`decode_segwit`] handle the
+//! witness version and program of segwit addresses.
+//!
+//! # Examples
+//!
+//! ```
+//! let mut address = [0u8; MAX_LEN];
+//! // Taproot address of an x-only public key
+//! let address = bech32::encode_segwit("bc", 1, &output_key, &mut address)?;
+//!
+//! // Cosmos address of a public key hash
+//! let mut data = [0u8; 64];
+//! let data = bech32::convert_bits(&key_hash, 8, 5, true, &mut data)?;
+//! let address = bech32::encode("cosmos", data, Variant::Bech32, &mut address)?;
+//! ```
+
+use super::EncodingError;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// Maximum length of a Bech32 string
+pub const MAX_LEN: usize = 90;
+
+/// Length of the checksum, in characters
+const CHECKSUM_LEN: usize = 6;
+
+/// Checksum variant
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Variant {
+    /// Bech32, used by segwit version 0 addresses
+    Bech32,
+    /// Bech32m, used by segwit version 1 (taproot) and later addresses
+    Bech32m,
+}
+
+impl Variant {
+    const fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+
+    fn from_constant(constant: u32) -> Option<Variant> {
+        [Variant::Bech32, Variant::Bech32m]
+            .into_iter()
+            .find(|variant| variant.constant() == constant)
+    }
+}
+
+/// Checksum computation over the expanded HRP and the data
+struct Polymod(u32);
+
+impl Polymod {
+    fn new(hrp: &[u8]) -> Polymod {
+        let mut polymod = Polymod(1);
+        for &c in hrp {
+            polymod.push(c.to_ascii_lowercase() >> 5);
+        }
+        polymod.push(0);
+        for &c in hrp {
+            polymod.push(c.to_ascii_lowercase() & 0x1f);
+        }
+        polymod
+    }
+
+    fn push(&mut self, value: u8) {
+        let top = self.0 >> 25;
+        self.0 = ((self.0 & 0x1ffffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                self.0 ^= generator;
+            }
+        }
+    }
+}
+
+fn check_hrp(hrp: &[u8]) -> Result<(), EncodingError> {
+    if hrp.is_empty() || hrp.len() > MAX_LEN - 1 - CHECKSUM_LEN {
+        return Err(EncodingError::InvalidHrp);
+    }
+    if hrp.iter().any(|&c| !(33..=126).contains(&c)) {
+        return Err(EncodingError::InvalidHrp);
+    }
+    Ok(())
+}
+
+/// Encodes the 5-bit values of `data` with the human readable part `hrp` into
+/// `out`, and returns the encoded string, in lowercase.
+pub fn encode<'a>(
+    hrp: &str,
+    data: &[u8],
+    variant: Variant,
+    out: &'a mut [u8],
+) -> Result<&'a str, EncodingError> {
+    let hrp = hrp.as_bytes();
+    check_hrp(hrp)?;
+    let len = hrp.len() + 1 + data.len() + CHECKSUM_LEN;
+    if len > MAX_LEN {
+        return Err(EncodingError::InvalidLength);
+    }
+    if len > out.len() {
+        return Err(EncodingError::BufferTooSmall);
+    }
+
+    let mut polymod = Polymod::new(hrp);
+    for (i, &c) in hrp.iter().enumerate() {
+        out[i] = c.to_ascii_lowercase();
+    }
+    out[hrp.len()] = b'1';
+    let mut pos = hrp.len() + 1;
+    for &value in data {
+        if value > 0x1f {
+            return Err(EncodingError::InvalidCharacter);
+        }
+        polymod.push(value);
+        out[pos] = CHARSET[value as usize];
+        pos += 1;
+    }
+    for _ in 0..CHECKSUM_LEN {
+        polymod.push(0);
+    }
+    let checksum = polymod.0 ^ variant.constant();
+    for i in 0..CHECKSUM_LEN {
+        out[pos + i] = CHARSET[((checksum >> (5 * (5 - i))) & 0x1f) as usize];
+    }
+
+    // Only ASCII characters are written.
+    Ok(unsafe { core::str::from_utf8_unchecked(&out[..len]) })
+}
+
+/// Decodes `encoded`, writing its 5-bit values into `out`, and returns its
+/// human readable part, as written in `encoded`, the 5-bit values and the
+/// checksum variant.
+pub fn decode<'s, 'a>(
+    encoded: &'s str,
+    out: &'a mut [u8],
+) -> Result<(&'s str, &'a [u8], Variant), EncodingError> {
+    let bytes = encoded.as_bytes();
+    if bytes.len() > MAX_LEN {
+        return Err(EncodingError::InvalidLength);
+    }
+    if bytes.iter().any(u8::is_ascii_lowercase) && bytes.iter().any(u8::is_ascii_uppercase) {
+        return Err(EncodingError::MixedCase);
+    }
+    let separator = bytes
+        .iter()
+        .rposition(|&c| c == b'1')
+        .ok_or(EncodingError::InvalidHrp)?;
+    let (hrp, data) = (&bytes[..separator], &bytes[separator + 1..]);
+    check_hrp(hrp)?;
+    if data.len() < CHECKSUM_LEN {
+        return Err(EncodingError::InvalidLength);
+    }
+    let len = data.len() - CHECKSUM_LEN;
+    if len > out.len() {
+        return Err(EncodingError::BufferTooSmall);
+    }
+
+    let mut polymod = Polymod::new(hrp);
+    for (i, &c) in data.iter().enumerate() {
+        let value = CHARSET
+            .iter()
+            .position(|&a| a == c.to_ascii_lowercase())
+            .ok_or(EncodingError::InvalidCharacter)? as u8;
+        polymod.push(value);
+        if i < len {
+            out[i] = value;
+        }
+    }
+    let variant = Variant::from_constant(polymod.0).ok_or(EncodingError::InvalidChecksum)?;
+
+    Ok((&encoded[..separator], &out[..len], variant))
+}
+
+/// Regroups the `from`-bit values of `data` into `to`-bit values written into
+/// `out`, and returns them, typically to convert bytes to 5-bit values
+/// (8 to 5, with `pad`) and back (5 to 8, without `pad`).
+///
+/// When `pad` is false, the remaining bits must be zero padding shorter than
+/// `from` bits.
+pub fn convert_bits<'a>(
+    data: &[u8],
+    from: u32,
+    to: u32,
+    pad: bool,
+    out: &'a mut [u8],
+) -> Result<&'a [u8], EncodingError> {
+    let max = (1u32 << to) - 1;
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut len = 0;
+    let mut push = |value: u32, len: &mut usize| -> Result<(), EncodingError> {
+        let slot = out.get_mut(*len).ok_or(EncodingError::BufferTooSmall)?;
+        *slot = value as u8;
+        *len += 1;
+        Ok(())
+    };
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return Err(EncodingError::InvalidCharacter);
+        }
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            push((acc >> bits) & max, &mut len)?;
+        }
+    }
+    if pad {
+        if bits > 0 {
+            push((acc << (to - bits)) & max, &mut len)?;
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return Err(EncodingError::InvalidPadding);
+    }
+    Ok(&out[..len])
+}
+
+/// Encodes the segwit address of the witness `program` of version `version`,
+/// with the Bech32 variant required by the version.
+pub fn encode_segwit<'a>(
+    hrp: &str,
+    version: u8,
+    program: &[u8],
+    out: &'a mut [u8],
+) -> Result<&'a str, EncodingError> {
+    check_witness(version, program)?;
+    let mut data = [0u8; MAX_LEN];
+    data[0] = version;
+    let len = 1 + convert_bits(program, 8, 5, true, &mut data[1..])?.len();
+    let variant = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    encode(hrp, &data[..len], variant, out)
+}
+
+/// Decodes the segwit address `address`, whose human readable part must be
+/// `hrp`, writing its witness program into `out`. Returns the witness version
+/// and program.
+pub fn decode_segwit<'a>(
+    hrp: &str,
+    address: &str,
+    out: &'a mut [u8],
+) -> Result<(u8, &'a [u8]), EncodingError> {
+    let mut data = [0u8; MAX_LEN];
+    let (decoded_hrp, data, variant) = decode(address, &mut data)?;
+    if !decoded_hrp.eq_ignore_ascii_case(hrp) {
+        return Err(EncodingError::InvalidHrp);
+    }
+    let (&version, data) = data.split_first().ok_or(EncodingError::InvalidLength)?;
+    let expected = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    if variant != expected {
+        return Err(EncodingError::InvalidChecksum);
+    }
+    let program = convert_bits(data, 5, 8, false, out)?;
+    check_witness(version, program)?;
+    Ok((version, program))
+}
+
+/// Checks the witness version and program length rules of BIP 141.
+fn check_witness(version: u8, program: &[u8]) -> Result<(), EncodingError> {
+    let valid = match version {
+        0 => program.len() == 20 || program.len() == 32,
+        1..=16 => (2..=40).contains(&program.len()),
+        _ => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(EncodingError::InvalidLength)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn bech32() {
+        let mut buffer = [0u8; MAX_LEN];
+        assert_eq!(
+            encode("A", &[], Variant::Bech32, &mut buffer),
+            Ok("a12uel5l")
+        );
+        assert_eq!(
+            encode("a", &[], Variant::Bech32m, &mut buffer),
+            Ok("a1lqfn3a")
+        );
+
+        let mut data = [0u8; MAX_LEN];
+        assert_eq!(
+            decode("A12UEL5L", &mut data),
+            Ok(("A", &[][..], Variant::Bech32))
+        );
+        assert_eq!(decode("a12uEl5l", &mut data), Err(EncodingError::MixedCase));
+        assert_eq!(
+            decode("a12uel5m", &mut data),
+            Err(EncodingError::InvalidChecksum)
+        );
+        assert_eq!(
+            decode("pzry9x0s0muk", &mut data),
+            Err(EncodingError::InvalidHrp)
+        );
+    }
+
+    #[test]
+    fn segwit() {
+        let program = [
+            0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3,
+            0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6,
+        ];
+        let mut buffer = [0u8; MAX_LEN];
+        assert_eq!(
+            encode_segwit("bc", 0, &program, &mut buffer),
+            Ok("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+        );
+
+        let mut decoded = [0u8; 40];
+        assert_eq!(
+            decode_segwit(
+                "bc",
+                "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+                &mut decoded
+            ),
+            Ok((0, &program[..]))
+        );
+        assert_eq!(
+            decode_segwit(
+                "tb",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                &mut decoded
+            ),
+            Err(EncodingError::InvalidHrp)
+        );
+        // Version 1 program encoded with Bech32 instead of Bech32m
+        assert_eq!(
+            decode_segwit(
+                "bc",
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7k7grplx",
+                &mut decoded
+            ),
+            Err(EncodingError::InvalidChecksum)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Hexadecimal encoding of raw bytes
+//!
+//! For byte strings displayed to the user, with grouping or ellipsis,
+//! see [`HexFormat`](crate::display::HexFormat).
+//!
+//! # Examples
+//!
+//! ```
+//! let mut buffer = [0u8; 2 + 2 * 20];
+//! // "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+//! let address = hex::encode_prefixed_into(&address, &mut buffer, Case::Lower)?;
+//!
+//! let hash: [u8; 32] = hex::decode_array(text)?;
+//! ```
+
+use super::EncodingError;
+
+/// Case of the letters of the encoded string
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Case {
+    Lower,
+    Upper,
+}
+
+impl Case {
+    fn digits(self) -> &'static [u8; 16] {
+        match self {
+            Case::Lower => b"0123456789abcdef",
+            Case::Upper => b"0123456789ABCDEF",
+        }
+    }
+}
+
+/// Returns the length of the hexadecimal encoding of `len` bytes, without
+/// prefix.
+pub const fn encoded_len(len: usize) -> usize {
+    2 * len
+}
+
+/// Encodes `data` into `out`, and returns the encoded string.
+pub fn encode_into<'a>(
+    data: &[u8],
+    out: &'a mut [u8],
+    case: Case,
+) -> Result<&'a str, EncodingError> {
+    let len = encoded_len(data.len());
+    let out = out.get_mut(..len).ok_or(EncodingError::BufferTooSmall)?;
+    let digits = case.digits();
+    for (byte, pair) in data.iter().zip(out.chunks_exact_mut(2)) {
+        pair[0] = digits[(byte >> 4) as usize];
+        pair[1] = digits[(byte & 0x0f) as usize];
+    }
+    // Only ASCII digits are written.
+    Ok(unsafe { core::str::from_utf8_unchecked(out) })
+}
+
+/// Encodes `data` into `out` after a `0x` prefix, and returns the encoded
+/// string.
+pub fn encode_prefixed_into<'a>(
+    data: &[u8],
+    out: &'a mut [u8],
+    case: Case,
+) -> Result<&'a str, EncodingError> {
+    let len = 2 + encoded_len(data.len());
+    let out = out.get_mut(..len).ok_or(EncodingError::BufferTooSmall)?;
+    out[..2].copy_from_slice(b"0x");
+    encode_into(data, &mut out[2..], case)?;
+    // Only ASCII digits are written.
+    Ok(unsafe { core::str::from_utf8_unchecked(out) })
+}
+
+fn nibble(c: u8) -> Result<u8, EncodingError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(EncodingError::InvalidCharacter),
+    }
+}
+
+/// Decodes `hex`, in either case and with an optional `0x` prefix, into
+/// `out`, and returns the decoded bytes.
+pub fn decode_into<'a>(hex: &str, out: &'a mut [u8]) -> Result<&'a [u8], EncodingError> {
+    let hex = hex.as_bytes();
+    let hex = match hex {
+        [b'0', b'x' | b'X', digits @ ..] => digits,
+        _ => hex,
+    };
+    if hex.len() % 2 != 0 {
+        return Err(EncodingError::InvalidLength);
+    }
+    let out = out
+        .get_mut(..hex.len() / 2)
+        .ok_or(EncodingError::BufferTooSmall)?;
+    for (byte, pair) in out.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Ok(out)
+}
+
+/// Decodes `hex`, in either case and with an optional `0x` prefix, which must
+/// encode exactly `N` bytes.
+pub fn decode_array<const N: usize>(hex: &str) -> Result<[u8; N], EncodingError> {
+    let mut out = [0u8; N];
+    if decode_into(hex, &mut out)?.len() != N {
+        return Err(EncodingError::InvalidLength);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn hex() {
+        let data = [0xde, 0xad, 0x01, 0xef];
+        let mut buffer = [0u8; 10];
+        assert_eq!(encode_into(&data, &mut buffer, Case::Lower), Ok("dead01ef"));
+        assert_eq!(encode_into(&data, &mut buffer, Case::Upper), Ok("DEAD01EF"));
+        assert_eq!(
+            encode_prefixed_into(&data, &mut buffer, Case::Lower),
+            Ok("0xdead01ef")
+        );
+        assert_eq!(
+            encode_prefixed_into(&data, &mut buffer[..9], Case::Lower),
+            Err(EncodingError::BufferTooSmall)
+        );
+
+        let mut decoded = [0u8; 4];
+        assert_eq!(decode_into("DEad01eF", &mut decoded), Ok(&data[..]));
+        assert_eq!(decode_into("0Xdead01ef", &mut decoded), Ok(&data[..]));
+        assert_eq!(decode_into("", &mut decoded), Ok(&[][..]));
+        assert_eq!(
+            decode_into("dea", &mut decoded),
+            Err(EncodingError::InvalidLength)
+        );
+        assert_eq!(
+            decode_into("deag", &mut decoded),
+            Err(EncodingError::InvalidCharacter)
+        );
+        assert_eq!(
+            decode_into("dead01ef00", &mut decoded),
+            Err(EncodingError::BufferTooSmall)
+        );
+
+        assert_eq!(decode_array::<4>("0xdead01ef"), Ok(data));
+        assert_eq!(
+            decode_array::<4>("dead01"),
+            Err(EncodingError::InvalidLength)
+        );
+    }
+}
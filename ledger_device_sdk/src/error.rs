@@ -0,0 +1,205 @@
+//! Error type of the SDK
+//!
+//! The modules of the SDK return their own error types. [`Error`] gathers
+//! them, with `From` conversions so that handlers can propagate any of them
+//! with `?`, and converts into the status word of the response.
+//!
+//! # Examples
+//!
+//! ```
+//! use ledger_device_sdk::Error;
+//!
+//! fn handle_sign(comm: &mut Comm) -> Result<(), Error> {
+//!     let data = comm.get_data()?;
+//!     let path = tlv::find(data, TAG_PATH)?;
+//!     let mut hash = [0u8; 32];
+//!     Sha2_256::new().hash(data, &mut hash)?;
+//!     let (signature, _, _) = derive_key(path)?.deterministic_sign(&hash)?;
+//!     comm.try_append(&signature)?;
+//!     Ok(())
+//! }
+//!
+//! match handle_sign(comm) {
+//!     Ok(()) => comm.reply_ok(),
+//!     Err(e) => comm.reply(e),
+//! }
+//! ```
+
+use crate::amount::AmountError;
+use crate::cal::CalError;
+use crate::collections::CapacityError;
+use crate::display::{BufferTooSmall, ParseAmountError};
+use crate::ecc::CxError;
+use crate::hash::HashError;
+use crate::io::tlv::TlvError;
+use crate::io::{Reply, StatusWords, SyscallError, TooLong};
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use crate::nbgl::{AddressBookError, FontError};
+use crate::nvm::{CounterError, StorageFullError};
+
+/// Error of any module of the SDK.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Command rejected with a status word, such as an invalid length
+    Status(StatusWords),
+    Syscall(SyscallError),
+    /// The response does not fit in the response buffers
+    ResponseTooLong,
+    Tlv(TlvError),
+    Crypto(CxError),
+    Hash(HashError),
+    /// No more space in an NVM collection
+    StorageFull,
+    Counter(CounterError),
+    /// A formatted value does not fit in its buffer
+    BufferTooSmall,
+    /// The capacity of a collection would be exceeded
+    Capacity,
+    ParseAmount(ParseAmountError),
+    Amount(AmountError),
+    Cal(CalError),
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    Font(FontError),
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    AddressBook(AddressBookError),
+}
+
+impl From<StatusWords> for Error {
+    fn from(sw: StatusWords) -> Error {
+        Error::Status(sw)
+    }
+}
+
+impl From<SyscallError> for Error {
+    fn from(e: SyscallError) -> Error {
+        Error::Syscall(e)
+    }
+}
+
+impl From<TooLong> for Error {
+    fn from(_: TooLong) -> Error {
+        Error::ResponseTooLong
+    }
+}
+
+impl From<TlvError> for Error {
+    fn from(e: TlvError) -> Error {
+        Error::Tlv(e)
+    }
+}
+
+impl From<CxError> for Error {
+    fn from(e: CxError) -> Error {
+        Error::Crypto(e)
+    }
+}
+
+impl From<HashError> for Error {
+    fn from(e: HashError) -> Error {
+        Error::Hash(e)
+    }
+}
+
+impl From<StorageFullError> for Error {
+    fn from(_: StorageFullError) -> Error {
+        Error::StorageFull
+    }
+}
+
+impl From<CounterError> for Error {
+    fn from(e: CounterError) -> Error {
+        Error::Counter(e)
+    }
+}
+
+impl From<BufferTooSmall> for Error {
+    fn from(_: BufferTooSmall) -> Error {
+        Error::BufferTooSmall
+    }
+}
+
+impl From<CapacityError> for Error {
+    fn from(_: CapacityError) -> Error {
+        Error::Capacity
+    }
+}
+
+impl From<ParseAmountError> for Error {
+    fn from(e: ParseAmountError) -> Error {
+        Error::ParseAmount(e)
+    }
+}
+
+impl From<AmountError> for Error {
+    fn from(e: AmountError) -> Error {
+        Error::Amount(e)
+    }
+}
+
+impl From<CalError> for Error {
+    fn from(e: CalError) -> Error {
+        Error::Cal(e)
+    }
+}
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+impl From<FontError> for Error {
+    fn from(e: FontError) -> Error {
+        Error::Font(e)
+    }
+}
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+impl From<AddressBookError> for Error {
+    fn from(e: AddressBookError) -> Error {
+        Error::AddressBook(e)
+    }
+}
+
+impl From<Error> for Reply {
+    fn from(e: Error) -> Reply {
+        match e {
+            Error::Status(sw) => sw.into(),
+            Error::Syscall(e) => e.into(),
+            Error::ResponseTooLong => TooLong.into(),
+            Error::Tlv(e) => e.into(),
+            Error::Crypto(_) | Error::Hash(_) => SyscallError::Cxport.into(),
+            Error::StorageFull => SyscallError::NotEnoughSpace.into(),
+            Error::Counter(_) => SyscallError::InvalidCounter.into(),
+            Error::BufferTooSmall | Error::Capacity => SyscallError::Overflow.into(),
+            Error::ParseAmount(_) => SyscallError::InvalidParameter.into(),
+            Error::Amount(e) => e.into(),
+            Error::Cal(e) => e.into(),
+            #[cfg(any(target_os = "stax", target_os = "flex"))]
+            Error::Font(_) => SyscallError::InvalidParameter.into(),
+            #[cfg(any(target_os = "stax", target_os = "flex"))]
+            Error::AddressBook(AddressBookError::Full) => SyscallError::NotEnoughSpace.into(),
+            #[cfg(any(target_os = "stax", target_os = "flex"))]
+            Error::AddressBook(_) => SyscallError::InvalidParameter.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    fn parse(text: &str) -> Result<u64, Error> {
+        let value = crate::display::parse_amount::<u64>(text, 2)?;
+        if value == 0 {
+            return Err(StatusWords::BadLen.into());
+        }
+        Ok(value)
+    }
+
+    #[test]
+    fn status_words() {
+        assert_eq!(parse("1.5"), Ok(150));
+        assert_eq!(Reply::from(parse("0").unwrap_err()).0, 0x6e03);
+        assert_eq!(Reply::from(parse("x").unwrap_err()).0, 0x6802);
+        assert_eq!(Reply::from(Error::from(StorageFullError)).0, 0x6813);
+    }
+}
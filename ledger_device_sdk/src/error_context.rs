@@ -0,0 +1,141 @@
+//! Context of the last errors, for diagnostics in the field
+//!
+//! Handlers record the errors met while processing a command, with an
+//! identifier of the module they occurred in, into a fixed-size ring. The
+//! ring keeps the last [`CAPACITY`] records, so that the chain of errors
+//! leading to a failure deep inside a parser or a cryptographic operation
+//! can be read back with a debug command, without backtraces nor
+//! allocations.
+//!
+//! Module identifiers and error codes are chosen by the application.
+//!
+//! # Examples
+//!
+//! ```
+//! use ledger_device_sdk::error_context::{self, ErrorContext};
+//!
+//! const MODULE_PARSER: u8 = 1;
+//! const MODULE_SIGN: u8 = 2;
+//!
+//! fn sign(comm: &mut Comm) -> Result<(), AppSW> {
+//!     let tx = parse_transaction(comm.get_data()?).context(MODULE_PARSER, 0x10)?;
+//!     let signature = sign_hash(&tx.hash()).context(MODULE_SIGN, 0x01)?;
+//!     ...
+//! }
+//!
+//! match ins {
+//!     Instruction::GetErrorContext => {
+//!         error_context::dump(comm);
+//!         comm.reply_ok();
+//!     }
+//!     ...
+//! }
+//! ```
+
+use crate::io::Comm;
+
+/// Number of records kept by the ring
+pub const CAPACITY: usize = 16;
+
+/// Error recorded with [`push`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErrorRecord {
+    /// Module the error occurred in
+    pub module: u8,
+    /// Error code, specific to the module
+    pub code: u16,
+}
+
+struct Ring {
+    records: [ErrorRecord; CAPACITY],
+    /// Index of the next record to write
+    next: usize,
+    len: usize,
+}
+
+static mut RING: Ring = Ring {
+    records: [ErrorRecord { module: 0, code: 0 }; CAPACITY],
+    next: 0,
+    len: 0,
+};
+
+fn ring() -> &'static mut Ring {
+    unsafe { &mut *core::ptr::addr_of_mut!(RING) }
+}
+
+/// Records an error `code` which occurred in `module`, overwriting the
+/// oldest record if the ring is full.
+pub fn push(module: u8, code: u16) {
+    let ring = ring();
+    ring.records[ring.next] = ErrorRecord { module, code };
+    ring.next = (ring.next + 1) % CAPACITY;
+    ring.len = (ring.len + 1).min(CAPACITY);
+}
+
+/// Returns the recorded errors, from the oldest to the most recent.
+pub fn records() -> impl Iterator<Item = ErrorRecord> {
+    let ring = ring();
+    let start = (ring.next + CAPACITY - ring.len) % CAPACITY;
+    (0..ring.len).map(move |i| ring.records[(start + i) % CAPACITY])
+}
+
+/// Removes all the records, for instance at the start of each command so
+/// that the ring only holds the context of the last failure.
+pub fn clear() {
+    let ring = ring();
+    ring.next = 0;
+    ring.len = 0;
+}
+
+/// Appends the records to the response of `comm`, from the oldest to the
+/// most recent, each one as the module identifier followed by the
+/// big-endian error code.
+pub fn dump(comm: &mut Comm) {
+    for record in records() {
+        comm.append(&[record.module]);
+        comm.append(&record.code.to_be_bytes());
+    }
+}
+
+/// Records the errors of a `Result` with [`push`].
+pub trait ErrorContext {
+    /// Records `code` in `module` if `self` is an error, and returns `self`.
+    fn context(self, module: u8, code: u16) -> Self;
+}
+
+impl<T, E> ErrorContext for Result<T, E> {
+    fn context(self, module: u8, code: u16) -> Self {
+        if self.is_err() {
+            push(module, code);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn ring_wraps() {
+        clear();
+        assert_eq!(Ok::<(), ()>(()).context(1, 1), Ok(()));
+        assert_eq!(records().count(), 0);
+        for code in 0..CAPACITY as u16 + 2 {
+            let _ = Err::<(), ()>(()).context(2, code);
+        }
+        assert_eq!(records().count(), CAPACITY);
+        assert_eq!(records().next(), Some(ErrorRecord { module: 2, code: 2 }));
+        assert_eq!(
+            records().last(),
+            Some(ErrorRecord {
+                module: 2,
+                code: CAPACITY as u16 + 1
+            })
+        );
+        clear();
+    }
+}
@@ -140,6 +140,125 @@ macro_rules! impl_hash {
 }
 pub(crate) use impl_hash;
 
+/// Implements [`HashInit`] with a hasher of the RustCrypto crates instead of
+/// the syscalls, when the SDK is built with the `emulation` feature. The
+/// `cx_hash_t` context is unused.
+#[cfg(feature = "emulation")]
+macro_rules! impl_hash_emulation {
+    ($typename:ident, $hasher:ty) => {
+        #[derive(Default)]
+        #[allow(non_camel_case_types)]
+        pub struct $typename {
+            ctx: cx_hash_t,
+            hasher: $hasher,
+        }
+        impl HashInit for $typename {
+            fn as_ctx_mut(&mut self) -> &mut cx_hash_t {
+                &mut self.ctx
+            }
+
+            fn as_ctx(&self) -> &cx_hash_t {
+                &self.ctx
+            }
+
+            fn new() -> Self {
+                Default::default()
+            }
+
+            fn reset(&mut self) {
+                self.hasher = Default::default();
+            }
+
+            fn get_size(&mut self) -> usize {
+                <$hasher as digest::OutputSizeUser>::output_size()
+            }
+
+            fn hash(
+                &mut self,
+                input: &[u8],
+                output: &mut [u8],
+            ) -> Result<(), $crate::hash::HashError> {
+                self.update(input)?;
+                self.finalize(output)
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), $crate::hash::HashError> {
+                digest::Update::update(&mut self.hasher, input);
+                Ok(())
+            }
+
+            fn finalize(&mut self, output: &mut [u8]) -> Result<(), $crate::hash::HashError> {
+                let size = self.get_size();
+                if size > output.len() {
+                    return Err($crate::hash::HashError::InvalidOutputLength);
+                }
+                let digest = digest::FixedOutputReset::finalize_fixed_reset(&mut self.hasher);
+                output[..size].copy_from_slice(&digest);
+                Ok(())
+            }
+        }
+    };
+
+    // Extendable output functions, with an output of `$size` bits
+    ($typename:ident, $hasher:ty, $size:expr) => {
+        #[derive(Default)]
+        #[allow(non_camel_case_types)]
+        pub struct $typename {
+            ctx: cx_hash_t,
+            hasher: $hasher,
+        }
+        impl HashInit for $typename {
+            fn as_ctx_mut(&mut self) -> &mut cx_hash_t {
+                &mut self.ctx
+            }
+
+            fn as_ctx(&self) -> &cx_hash_t {
+                &self.ctx
+            }
+
+            fn new() -> Self {
+                Default::default()
+            }
+
+            fn reset(&mut self) {
+                self.hasher = Default::default();
+            }
+
+            fn get_size(&mut self) -> usize {
+                $size / 8
+            }
+
+            fn hash(
+                &mut self,
+                input: &[u8],
+                output: &mut [u8],
+            ) -> Result<(), $crate::hash::HashError> {
+                self.update(input)?;
+                self.finalize(output)
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), $crate::hash::HashError> {
+                digest::Update::update(&mut self.hasher, input);
+                Ok(())
+            }
+
+            fn finalize(&mut self, output: &mut [u8]) -> Result<(), $crate::hash::HashError> {
+                let size = self.get_size();
+                if size > output.len() {
+                    return Err($crate::hash::HashError::InvalidOutputLength);
+                }
+                digest::ExtendableOutputReset::finalize_xof_reset_into(
+                    &mut self.hasher,
+                    &mut output[..size],
+                );
+                Ok(())
+            }
+        }
+    };
+}
+#[cfg(feature = "emulation")]
+pub(crate) use impl_hash_emulation;
+
 #[cfg(test)]
 mod tests {
     use crate::assert_eq_err as assert_eq;
@@ -1,11 +1,26 @@
 use super::HashInit;
-use ledger_secure_sdk_sys::{cx_blake2b_init_no_throw, cx_blake2b_t, cx_hash_t};
+use ledger_secure_sdk_sys::cx_hash_t;
+#[cfg(not(feature = "emulation"))]
+use ledger_secure_sdk_sys::{cx_blake2b_init_no_throw, cx_blake2b_t};
 
+#[cfg(not(feature = "emulation"))]
 use super::impl_hash;
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Blake2b_256, cx_blake2b_t, cx_blake2b_init_no_throw, 256);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Blake2b_384, cx_blake2b_t, cx_blake2b_init_no_throw, 384);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Blake2b_512, cx_blake2b_t, cx_blake2b_init_no_throw, 512);
 
+#[cfg(feature = "emulation")]
+use super::impl_hash_emulation;
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Blake2b_256, blake2::Blake2b<digest::consts::U32>);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Blake2b_384, blake2::Blake2b<digest::consts::U48>);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Blake2b_512, blake2::Blake2b<digest::consts::U64>);
+
 #[cfg(test)]
 mod tests {
     use crate::assert_eq_err as assert_eq;
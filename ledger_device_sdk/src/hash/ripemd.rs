@@ -1,9 +1,18 @@
 use super::HashInit;
-use ledger_secure_sdk_sys::{cx_hash_t, cx_ripemd160_init_no_throw, cx_ripemd160_t};
+use ledger_secure_sdk_sys::cx_hash_t;
+#[cfg(not(feature = "emulation"))]
+use ledger_secure_sdk_sys::{cx_ripemd160_init_no_throw, cx_ripemd160_t};
 
+#[cfg(not(feature = "emulation"))]
 use super::impl_hash;
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Ripemd160, cx_ripemd160_t, cx_ripemd160_init_no_throw);
 
+#[cfg(feature = "emulation")]
+use super::impl_hash_emulation;
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Ripemd160, ripemd::Ripemd160);
+
 #[cfg(test)]
 mod tests {
     use crate::assert_eq_err as assert_eq;
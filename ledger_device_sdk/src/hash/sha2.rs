@@ -1,20 +1,39 @@
 use super::HashInit;
+use ledger_secure_sdk_sys::cx_hash_t;
+#[cfg(not(feature = "emulation"))]
 use ledger_secure_sdk_sys::{
-    cx_hash_t, cx_sha224_init_no_throw, cx_sha256_init_no_throw, cx_sha256_t,
-    cx_sha384_init_no_throw, cx_sha512_init_no_throw, cx_sha512_t,
+    cx_sha224_init_no_throw, cx_sha256_init_no_throw, cx_sha256_t, cx_sha384_init_no_throw,
+    cx_sha512_init_no_throw, cx_sha512_t,
 };
 
+#[cfg(not(feature = "emulation"))]
 use super::impl_hash;
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha2_224, cx_sha256_t, cx_sha224_init_no_throw);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha2_256, cx_sha256_t, cx_sha256_init_no_throw);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha2_384, cx_sha512_t, cx_sha384_init_no_throw);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha2_512, cx_sha512_t, cx_sha512_init_no_throw);
 
+#[cfg(feature = "emulation")]
+use super::impl_hash_emulation;
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha2_224, sha2::Sha224);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha2_256, sha2::Sha256);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha2_384, sha2::Sha384);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha2_512, sha2::Sha512);
+
 #[cfg(test)]
 mod tests {
     use crate::assert_eq_err as assert_eq;
     use crate::hash::sha2::*;
-    use crate::testing::TestType;
+    use crate::testing::{Bencher, TestType};
+    use testmacro::bench_item as bench;
     use testmacro::test_item as test;
 
     const TEST_HASH: &[u8; 29] = b"Not your keys, not your coins";
@@ -96,4 +115,10 @@ mod tests {
         ];
         assert_eq!(&output, &expected);
     }
+
+    #[bench]
+    fn bench_sha2256(b: &mut Bencher) {
+        let mut output = [0u8; 32];
+        b.iter(|| Sha2_256::new().hash(TEST_HASH, &mut output));
+    }
 }
@@ -1,18 +1,45 @@
 use super::HashInit;
+use ledger_secure_sdk_sys::cx_hash_t;
+#[cfg(not(feature = "emulation"))]
 use ledger_secure_sdk_sys::{
-    cx_hash_t, cx_keccak_init_no_throw, cx_sha3_init_no_throw, cx_sha3_t,
-    cx_shake128_init_no_throw, cx_shake256_init_no_throw,
+    cx_keccak_init_no_throw, cx_sha3_init_no_throw, cx_sha3_t, cx_shake128_init_no_throw,
+    cx_shake256_init_no_throw,
 };
 
+#[cfg(not(feature = "emulation"))]
 use super::impl_hash;
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Keccak256, cx_sha3_t, cx_keccak_init_no_throw, 256);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha3_224, cx_sha3_t, cx_sha3_init_no_throw, 224);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha3_256, cx_sha3_t, cx_sha3_init_no_throw, 256);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha3_384, cx_sha3_t, cx_sha3_init_no_throw, 384);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Sha3_512, cx_sha3_t, cx_sha3_init_no_throw, 512);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Shake128, cx_sha3_t, cx_shake128_init_no_throw, 128);
+#[cfg(not(feature = "emulation"))]
 impl_hash!(Shake256, cx_sha3_t, cx_shake256_init_no_throw, 256);
 
+#[cfg(feature = "emulation")]
+use super::impl_hash_emulation;
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Keccak256, sha3::Keccak256);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha3_224, sha3::Sha3_224);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha3_256, sha3::Sha3_256);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha3_384, sha3::Sha3_384);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Sha3_512, sha3::Sha3_512);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Shake128, sha3::Shake128, 128);
+#[cfg(feature = "emulation")]
+impl_hash_emulation!(Shake256, sha3::Shake256, 256);
+
 #[cfg(test)]
 mod tests {
     use crate::assert_eq_err as assert_eq;
@@ -0,0 +1,45 @@
+//! Heap of the global allocator
+//!
+//! The heap is a static buffer of [`HEAP_SIZE`] bytes, set at build time with
+//! the `HEAP_SIZE` environment variable, for instance in the application's
+//! `.cargo/config.toml`:
+//!
+//! ```toml
+//! [env]
+//! HEAP_SIZE = "16384"
+//! ```
+//!
+//! The allocator is always available on Stax, Flex, Nano S+ and Nano X, and
+//! enabled with the `heap` feature on Nano S.
+//!
+//! When the heap is exhausted, the allocation error panics as usual, so that
+//! the application replies with the panic status word. Calling
+//! [`enable_out_of_memory_screen`] beforehand also displays an error screen.
+
+use core::alloc::Layout;
+
+pub use ledger_secure_sdk_sys::{heap_free, heap_used, HEAP_SIZE};
+
+/// Makes allocation failures display an "Out of memory" error screen, then
+/// exit the application.
+pub fn enable_out_of_memory_screen() {
+    ledger_secure_sdk_sys::set_alloc_error_hook(out_of_memory);
+}
+
+/// Displays the error screen without allocating, since the heap is exhausted.
+fn out_of_memory(_layout: Layout) {
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    unsafe {
+        ledger_secure_sdk_sys::ux_sync_status(
+            "Out of memory\0".as_ptr() as *const core::ffi::c_char,
+            false,
+        );
+    }
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    crate::ui::gadgets::popup("Out of memory");
+
+    let mut comm = crate::io::Comm::new();
+    comm.reply(crate::io::StatusWords::Panic);
+    crate::exit(crate::ExitCode::Failure);
+}
@@ -0,0 +1,148 @@
+//! Localized strings
+//!
+//! Applications declare their user facing strings once per language in an
+//! [`I18n`] table, and refer to them by identifier. The translation matching
+//! the language selected in the OS settings is returned, or the one of the
+//! first language of the table if the application does not support it.
+//! Screens displaying translated strings should be redrawn when
+//! [`Event::Language`](crate::io::Event::Language) is received.
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Copy, Clone)]
+//! enum Str {
+//!     ReviewTx,
+//!     SignTx,
+//! }
+//!
+//! impl StringId for Str {
+//!     fn index(self) -> usize {
+//!         self as usize
+//!     }
+//! }
+//!
+//! static STRINGS: I18n<2> = I18n::new(&[
+//!     (Language::English, ["Review transaction", "Sign transaction"]),
+//!     (Language::French, ["Vérifier la transaction", "Signer la transaction"]),
+//! ]);
+//!
+//! let result = NbglReview::new()
+//!     .titles(STRINGS.get(Str::ReviewTx), "", STRINGS.get(Str::SignTx))
+//!     .show(&fields);
+//! ```
+
+use ledger_secure_sdk_sys::*;
+
+/// Languages which can be selected in the OS settings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Language {
+    English = 0,
+    French = 1,
+    Spanish = 2,
+    Brazilian = 3,
+    German = 4,
+    Russian = 5,
+    Turkish = 6,
+}
+
+impl Language {
+    /// Returns the language selected in the OS settings. Unknown languages
+    /// are reported as [`Language::English`].
+    pub fn current() -> Language {
+        let id = unsafe { os_setting_get(OS_SETTING_LANGUAGE as u32, core::ptr::null_mut(), 0) };
+        Language::from_id(id as u8).unwrap_or(Language::English)
+    }
+
+    /// Returns the language identified by `id` in the OS settings.
+    pub fn from_id(id: u8) -> Option<Language> {
+        match id {
+            0 => Some(Language::English),
+            1 => Some(Language::French),
+            2 => Some(Language::Spanish),
+            3 => Some(Language::Brazilian),
+            4 => Some(Language::German),
+            5 => Some(Language::Russian),
+            6 => Some(Language::Turkish),
+            _ => None,
+        }
+    }
+}
+
+/// Identifier of a string of an [`I18n`] table, typically a fieldless enum.
+pub trait StringId: Copy {
+    /// Index of the string in the translations of the table.
+    fn index(self) -> usize;
+}
+
+impl StringId for usize {
+    fn index(self) -> usize {
+        self
+    }
+}
+
+/// Table of `N` strings translated in several languages.
+pub struct I18n<const N: usize> {
+    translations: &'static [(Language, [&'static str; N])],
+}
+
+impl<const N: usize> I18n<N> {
+    /// Creates a table from the translations of each supported language.
+    /// The first language is used when the OS language is not supported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `translations` is empty.
+    pub const fn new(translations: &'static [(Language, [&'static str; N])]) -> I18n<N> {
+        if translations.is_empty() {
+            panic!("No translations.");
+        }
+        I18n { translations }
+    }
+
+    /// Returns the string `id`, in the language selected in the OS settings.
+    pub fn get<I: StringId>(&self, id: I) -> &'static str {
+        self.get_for(Language::current(), id)
+    }
+
+    /// Returns the string `id` in `language`, or in the first language of the
+    /// table if `language` is not supported.
+    pub fn get_for<I: StringId>(&self, language: Language, id: I) -> &'static str {
+        let strings = self
+            .translations
+            .iter()
+            .find(|(l, _)| *l == language)
+            .unwrap_or(&self.translations[0]);
+        strings.1[id.index()]
+    }
+
+    /// Returns whether the table has translations for `language`.
+    pub fn supports(&self, language: Language) -> bool {
+        self.translations.iter().any(|(l, _)| *l == language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    static STRINGS: I18n<2> = I18n::new(&[
+        (Language::English, ["Approve", "Reject"]),
+        (Language::French, ["Approuver", "Rejeter"]),
+    ]);
+
+    #[test]
+    fn i18n_lookup() {
+        assert_eq!(STRINGS.get_for(Language::French, 1), "Rejeter");
+        assert_eq!(STRINGS.get_for(Language::English, 0), "Approve");
+        // Falls back to the first language
+        assert_eq!(STRINGS.get_for(Language::German, 0), "Approve");
+        assert_eq!(STRINGS.supports(Language::German), false);
+        assert_eq!(Language::from_id(3), Some(Language::Brazilian));
+        assert_eq!(Language::from_id(42), None);
+    }
+}
@@ -0,0 +1,141 @@
+//! Dismissal of reviews left unattended
+//!
+//! Once [`enable`]d, a review displayed by the SDK (`nbgl::NbglReview` and
+//! the other NBGL reviews, or `ui::gadgets::Review` on Nano) is dismissed
+//! after a delay without any user interaction, as if the user rejected it, so
+//! that the application returns to its home screen instead of leaving a
+//! transaction pending on an unattended device.
+//!
+//! After the review returns, [`timed_out`] tells a dismissal from a
+//! rejection by the user, so that the application replies to the pending
+//! command with a timeout status word, through its own [`Comm`](crate::io::Comm).
+//!
+//! The delay is measured with the ticker events received while the review
+//! waits for the user.
+//!
+//! # Examples
+//!
+//! ```
+//! inactivity::enable(60);
+//! ...
+//! let approved = NbglReview::new().titles("Review", "", "Sign").show(&fields);
+//! if inactivity::timed_out() {
+//!     return Err(SyscallError::Timeout.into());
+//! }
+//! ```
+
+use crate::io::TICKER_PERIOD_MS;
+
+struct Inactivity {
+    ticks: u32,
+    /// Ticker events before the dismissal
+    remaining: u32,
+    /// A review is displayed
+    reviewing: bool,
+    /// The last review was dismissed
+    expired: bool,
+}
+
+static mut INACTIVITY: Inactivity = Inactivity {
+    ticks: 0,
+    remaining: 0,
+    reviewing: false,
+    expired: false,
+};
+
+fn inactivity() -> &'static mut Inactivity {
+    unsafe { &mut *core::ptr::addr_of_mut!(INACTIVITY) }
+}
+
+/// Dismisses the reviews after `seconds` seconds without user interaction.
+pub fn enable(seconds: u32) {
+    let inactivity = inactivity();
+    inactivity.ticks = (seconds.saturating_mul(1000) / TICKER_PERIOD_MS).max(1);
+    inactivity.remaining = inactivity.ticks;
+}
+
+/// Lets the reviews wait for the user without a time limit, the default.
+pub fn disable() {
+    inactivity().ticks = 0;
+}
+
+/// Returns true if the last review was dismissed by the inactivity delay.
+pub fn timed_out() -> bool {
+    inactivity().expired
+}
+
+/// Runs `review`, which displays a review and waits for the user, with the
+/// inactivity delay armed.
+pub(crate) fn review<R>(review: impl FnOnce() -> R) -> R {
+    let inactivity = inactivity();
+    inactivity.remaining = inactivity.ticks;
+    inactivity.expired = false;
+    inactivity.reviewing = true;
+    let result = review();
+    inactivity.reviewing = false;
+    result
+}
+
+/// Returns true while the displayed review must be dismissed, for the review
+/// loops of the Nano devices.
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+pub(crate) fn dismissing() -> bool {
+    let inactivity = inactivity();
+    inactivity.reviewing && inactivity.expired
+}
+
+/// Called on each user interaction, to restart the inactivity delay.
+pub(crate) fn activity() {
+    let inactivity = inactivity();
+    inactivity.remaining = inactivity.ticks;
+}
+
+/// Called on each ticker event, to dismiss the displayed review when the
+/// inactivity delay has elapsed.
+pub(crate) fn tick() {
+    let inactivity = inactivity();
+    if !inactivity.reviewing || inactivity.expired || inactivity.ticks == 0 {
+        return;
+    }
+    inactivity.remaining -= 1;
+    if inactivity.remaining > 0 {
+        return;
+    }
+    inactivity.expired = true;
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    unsafe {
+        ledger_secure_sdk_sys::ux_sync_setReturnCode(ledger_secure_sdk_sys::UX_SYNC_RET_REJECTED);
+        ledger_secure_sdk_sys::ux_sync_setEnded(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn dismissal() {
+        enable(1);
+        let ticks = 1000 / TICKER_PERIOD_MS;
+        let expired = review(|| {
+            for _ in 0..ticks - 1 {
+                tick();
+            }
+            activity();
+            for _ in 0..ticks - 1 {
+                tick();
+            }
+            let before = inactivity().expired;
+            tick();
+            (before, inactivity().expired)
+        });
+        assert_eq!(expired, (false, true));
+        assert_eq!(timed_out(), true);
+        disable();
+        review(tick);
+        assert_eq!(timed_out(), false);
+    }
+}
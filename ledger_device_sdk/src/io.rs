@@ -7,11 +7,23 @@ use ledger_secure_sdk_sys::*;
 
 #[cfg(feature = "ccid")]
 use crate::ccid;
+use crate::i18n::Language;
 use crate::seph;
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+use crate::uxapp::{UxEvent, BOLOS_UX_OK};
 use core::convert::{Infallible, TryFrom};
-use core::ops::{Index, IndexMut};
+use core::ops::{Index, IndexMut, RangeInclusive};
+
+#[cfg(feature = "emulation")]
+pub mod emulation;
+pub mod mock;
+pub mod tlv;
 
-#[derive(Copy, Clone)]
+/// Derives `TryFrom<ApduHeader>` and a `dispatch` method for an enumeration
+/// of instructions. See the [`apdu_dispatch`] crate for the attribute syntax.
+pub use apdu_dispatch::ApduDispatch;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u16)]
 pub enum StatusWords {
     Ok = 0x9000,
@@ -25,11 +37,16 @@ pub enum StatusWords {
     Panic = 0xe000,
 }
 
-#[derive(Debug)]
+/// Cause of the failure of a syscall, from the exception code raised by the
+/// OS. Handlers can match on the cause, and reply with the corresponding
+/// status word, `0x68XX` where `XX` is the exception code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum SyscallError {
     InvalidParameter = 2,
     Overflow,
+    /// Security condition not satisfied, such as a derivation path outside
+    /// of the ones allowed for the application
     Security,
     InvalidCrc,
     InvalidChecksum,
@@ -37,7 +54,17 @@ pub enum SyscallError {
     NotSupported,
     InvalidState,
     Timeout,
+    /// Code not known by the SDK
     Unspecified,
+    /// The application was requested to exit
+    AppExit,
+    IoOverflow,
+    IoHeader,
+    IoState,
+    IoReset,
+    Cxport,
+    System,
+    NotEnoughSpace,
 }
 
 impl From<u32> for SyscallError {
@@ -52,11 +79,59 @@ impl From<u32> for SyscallError {
             8 => SyscallError::NotSupported,
             9 => SyscallError::InvalidState,
             10 => SyscallError::Timeout,
+            12 => SyscallError::AppExit,
+            13 => SyscallError::IoOverflow,
+            14 => SyscallError::IoHeader,
+            15 => SyscallError::IoState,
+            16 => SyscallError::IoReset,
+            17 => SyscallError::Cxport,
+            18 => SyscallError::System,
+            19 => SyscallError::NotEnoughSpace,
             _ => SyscallError::Unspecified,
         }
     }
 }
 
+impl From<SyscallError> for u32 {
+    fn from(e: SyscallError) -> u32 {
+        e as u32
+    }
+}
+
+impl SyscallError {
+    /// Returns the status word replied for this error.
+    pub fn status_word(self) -> u16 {
+        0x6800 + self as u16
+    }
+}
+
+#[cfg(debug_assertions)]
+impl core::fmt::Display for SyscallError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let description = match self {
+            SyscallError::InvalidParameter => "invalid parameter",
+            SyscallError::Overflow => "overflow",
+            SyscallError::Security => "security condition not satisfied",
+            SyscallError::InvalidCrc => "invalid CRC",
+            SyscallError::InvalidChecksum => "invalid checksum",
+            SyscallError::InvalidCounter => "invalid counter",
+            SyscallError::NotSupported => "not supported",
+            SyscallError::InvalidState => "invalid state",
+            SyscallError::Timeout => "timeout",
+            SyscallError::Unspecified => "unspecified error",
+            SyscallError::AppExit => "application exit",
+            SyscallError::IoOverflow => "IO overflow",
+            SyscallError::IoHeader => "invalid IO header",
+            SyscallError::IoState => "invalid IO state",
+            SyscallError::IoReset => "IO reset",
+            SyscallError::Cxport => "cryptographic library error",
+            SyscallError::System => "system error",
+            SyscallError::NotEnoughSpace => "not enough space",
+        };
+        write!(f, "{} (0x{:04x})", description, self.status_word())
+    }
+}
+
 /// Provide a type that will be used for replying
 /// an APDU with either a StatusWord or an SyscallError
 #[derive(Debug)]
@@ -71,7 +146,17 @@ impl From<StatusWords> for Reply {
 
 impl From<SyscallError> for Reply {
     fn from(exc: SyscallError) -> Reply {
-        Reply(0x6800 + exc as u16)
+        Reply(exc.status_word())
+    }
+}
+
+/// Error returned when response data does not fit in the response buffers.
+#[derive(Debug, Eq, PartialEq)]
+pub struct TooLong;
+
+impl From<TooLong> for Reply {
+    fn from(_: TooLong) -> Reply {
+        SyscallError::Overflow.into()
     }
 }
 
@@ -96,12 +181,247 @@ pub enum Event<T> {
     TouchEvent,
     /// Ticker
     Ticker,
+    /// USB connection state change
+    Usb(UsbEvent),
+    /// The language selected in the OS settings has changed
+    Language(Language),
+    /// The battery level is critical and the device is about to power off,
+    /// see [`power::set_shutdown_handler`](crate::power::set_shutdown_handler)
+    BatteryCritical,
+}
+
+/// USB connection state changes, returned by [`Comm::next_event`] in [`Event::Usb`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum UsbEvent {
+    /// The USB cable has been plugged
+    Attached,
+    /// The USB cable has been unplugged
+    Detached,
+    /// The host suspended the USB bus
+    Suspended,
+    /// The host resumed the USB bus
+    Resumed,
+}
+
+/// Touch gestures, passed to the hook set with [`Comm::set_touch_hook`].
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TouchGesture {
+    /// The screen has been touched and released without moving significantly
+    Tap {
+        x: u16,
+        y: u16,
+    },
+    SwipeLeft,
+    SwipeRight,
+    SwipeUp,
+    SwipeDown,
+}
+
+/// Minimum distance, in pixels, between the touch and release positions of a swipe
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+const SWIPE_MIN_DISTANCE: u16 = 40;
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+impl TouchGesture {
+    /// Classifies a touch from its touch and release positions.
+    fn from_positions(start: (u16, u16), end: (u16, u16)) -> TouchGesture {
+        let dx = end.0 as i32 - start.0 as i32;
+        let dy = end.1 as i32 - start.1 as i32;
+        let min = SWIPE_MIN_DISTANCE as i32;
+        if dx.abs() >= dy.abs() && dx.abs() >= min {
+            if dx < 0 {
+                TouchGesture::SwipeLeft
+            } else {
+                TouchGesture::SwipeRight
+            }
+        } else if dy.abs() >= min {
+            if dy < 0 {
+                TouchGesture::SwipeUp
+            } else {
+                TouchGesture::SwipeDown
+            }
+        } else {
+            TouchGesture::Tap { x: end.0, y: end.1 }
+        }
+    }
+
+    /// Returns true if the gesture is a tap inside `region`.
+    pub fn is_tap_in(&self, region: &TouchRegion) -> bool {
+        match *self {
+            TouchGesture::Tap { x, y } => region.contains(x, y),
+            _ => false,
+        }
+    }
+}
+
+/// Rectangular area of the screen, in pixels.
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TouchRegion {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+impl TouchRegion {
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x - self.x < self.width && y >= self.y && y - self.y < self.height
+    }
+}
+
+/// User interface events, i.e. every [`Event`] which is not an APDU command.
+///
+/// This allows UI code to consume its own event stream while protocol code
+/// only deals with commands, see [`Event::split`] and [`Comm::next_command_with`].
+#[derive(Eq, PartialEq)]
+pub enum UiEvent {
+    /// Button press or release event
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    Button(ButtonEvent),
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    TouchEvent,
+    /// Ticker
+    Ticker,
+    /// USB connection state change
+    Usb(UsbEvent),
+    /// The language selected in the OS settings has changed
+    Language(Language),
+    /// The battery level is critical and the device is about to power off,
+    /// see [`power::set_shutdown_handler`](crate::power::set_shutdown_handler)
+    BatteryCritical,
+}
+
+impl<T> Event<T> {
+    /// Splits an event between the command stream (`Ok`) and the user
+    /// interface event stream (`Err`).
+    pub fn split(self) -> Result<T, UiEvent> {
+        match self {
+            Event::Command(ins) => Ok(ins),
+            #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+            Event::Button(btn) => Err(UiEvent::Button(btn)),
+            #[cfg(any(target_os = "stax", target_os = "flex"))]
+            Event::TouchEvent => Err(UiEvent::TouchEvent),
+            Event::Ticker => Err(UiEvent::Ticker),
+            Event::Usb(usb) => Err(UiEvent::Usb(usb)),
+            Event::Language(language) => Err(UiEvent::Language(language)),
+            Event::BatteryCritical => Err(UiEvent::BatteryCritical),
+        }
+    }
+}
+
+/// Record of an APDU exchange, passed to the hook set with [`Comm::set_trace_hook`].
+///
+/// Payload bytes are only provided in debug builds (`debug_assertions`).
+pub enum TraceRecord<'a> {
+    /// Incoming command
+    Command {
+        header: ApduHeader,
+        /// Length of the command data
+        data_len: usize,
+        /// Command data, in debug builds only
+        data: Option<&'a [u8]>,
+    },
+    /// Outgoing response
+    Response {
+        /// Status word
+        sw: u16,
+        /// Length of the response data
+        data_len: usize,
+        /// Response data, in debug builds only
+        data: Option<&'a [u8]>,
+    },
+}
+
+/// Commands delivered as raw bytes to a handler, see [`Comm::set_raw_handler`].
+struct RawRoute {
+    cla: u8,
+    ins: RangeInclusive<u8>,
+    handler: fn(&mut Comm),
+}
+
+/// Communication statistics, returned by [`Comm::stats`].
+#[derive(Copy, Clone, Default, Debug)]
+pub struct CommStats {
+    /// Number of commands delivered to the application
+    pub commands: u32,
+    /// Number of received bytes, APDU headers included
+    pub bytes_in: u32,
+    /// Number of transmitted bytes, status words included
+    pub bytes_out: u32,
+    /// Number of commands automatically rejected because of a wrong length,
+    /// CLA or INS
+    pub rejected: u32,
+    /// Last transmitted status word
+    pub last_sw: Option<u16>,
+}
+
+/// Screensaver configured with [`Comm::set_screensaver`].
+struct Screensaver {
+    /// Inactivity delay, in ticker periods
+    ticks: u32,
+    show: fn(),
+    restore: fn(),
+    /// Ticker periods elapsed without user interaction
+    idle_ticks: u32,
+    shown: bool,
+    /// Set when the screensaver is dismissed by a button press or a touch,
+    /// until the buttons or the screen are released
+    dismissing: bool,
+}
+
+/// Period of the ticker events sent by the MCU, in milliseconds.
+pub const TICKER_PERIOD_MS: u32 = 100;
+
+/// INS of the ISO 7816 GET RESPONSE command, used by the host to fetch the
+/// remaining bytes of a chained response.
+const INS_GET_RESPONSE: u8 = 0xc0;
+
+/// Maximum number of response data bytes sent in a single chained response APDU.
+const CHAINING_CHUNK_LEN: usize = 256;
+
+/// State of a response which did not fit in the APDU buffer, and is sent
+/// using the ISO 7816 `61xx` / GET RESPONSE convention.
+struct ResponseChaining {
+    /// Buffer receiving the response bytes which do not fit in the APDU buffer
+    buffer: Option<&'static mut [u8]>,
+    /// Number of bytes held in `buffer`
+    len: usize,
+    /// Number of bytes of `buffer` already sent to the host
+    offset: usize,
+    /// Status word to send with the last chunk of the response
+    sw: u16,
+}
+
+impl ResponseChaining {
+    const fn new() -> Self {
+        Self {
+            buffer: None,
+            len: 0,
+            offset: 0,
+            sw: 0,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+        self.offset = 0;
+        self.sw = 0;
+    }
+
+    /// Status word announcing `remaining` bytes to the host (`61xx`, where
+    /// `xx` is `00` when at least 256 bytes remain).
+    fn remaining_sw(remaining: usize) -> u16 {
+        0x6100 | (remaining.min(CHAINING_CHUNK_LEN) & 0xff) as u16
+    }
 }
 
 /// Manages the communication of the device: receives events such as button presses, incoming
 /// APDU requests, and provides methods to build and transmit APDU responses.
 pub struct Comm {
-    pub apdu_buffer: [u8; 260],
+    pub apdu_buffer: [u8; crate::caps::APDU_BUFFER_SIZE],
     pub rx: usize,
     pub tx: usize,
     pub event_pending: bool,
@@ -112,6 +432,26 @@ pub struct Comm {
     /// with wrong CLA byte is received. If set to [`None`], all CLA are accepted.
     /// Can be set using [`Comm::set_expected_cla`] method.
     pub expected_cla: Option<u8>,
+    /// Additional accepted values for the APDU CLA byte, for applications implementing
+    /// several protocols. Can be set using [`Comm::set_expected_clas`] method.
+    pub expected_clas: &'static [u8],
+    chaining: ResponseChaining,
+    trace_hook: Option<fn(&TraceRecord)>,
+    ux_step: Option<fn()>,
+    raw_route: Option<RawRoute>,
+    usb_powered: Option<bool>,
+    language: Option<Language>,
+    ticker_divider: u32,
+    ticker_count: u32,
+    stats: CommStats,
+    idle_timeout: Option<(u32, fn())>,
+    idle_ticks: u32,
+    screensaver: Option<Screensaver>,
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    touch_hook: Option<fn(TouchGesture)>,
+    /// Position where the screen is being touched, if it is
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    touch_start: Option<(u16, u16)>,
 }
 
 impl Default for Comm {
@@ -137,13 +477,36 @@ impl Comm {
     /// Creates a new [`Comm`] instance, which accepts any CLA APDU by default.
     pub const fn new() -> Self {
         Self {
-            apdu_buffer: [0u8; 260],
+            apdu_buffer: [0u8; crate::caps::APDU_BUFFER_SIZE],
             rx: 0,
             tx: 0,
             event_pending: false,
             #[cfg(not(any(target_os = "stax", target_os = "flex")))]
             buttons: ButtonsState::new(),
             expected_cla: None,
+            expected_clas: &[],
+            chaining: ResponseChaining::new(),
+            trace_hook: None,
+            ux_step: None,
+            raw_route: None,
+            usb_powered: None,
+            language: None,
+            ticker_divider: 1,
+            ticker_count: 0,
+            stats: CommStats {
+                commands: 0,
+                bytes_in: 0,
+                bytes_out: 0,
+                rejected: 0,
+                last_sw: None,
+            },
+            idle_timeout: None,
+            idle_ticks: 0,
+            screensaver: None,
+            #[cfg(any(target_os = "stax", target_os = "flex"))]
+            touch_hook: None,
+            #[cfg(any(target_os = "stax", target_os = "flex"))]
+            touch_start: None,
         }
     }
 
@@ -166,6 +529,441 @@ impl Comm {
         self
     }
 
+    /// Enables response chaining: response data appended beyond the first 256
+    /// bytes is stored in `buffer`, and sent by chunks using the ISO 7816
+    /// convention. The first chunk is sent with a `61xx` status word, where `xx`
+    /// is the number of remaining bytes (`00` meaning 256 or more), and the host
+    /// fetches the following chunks with GET RESPONSE (`INS = 0xc0`) commands.
+    /// The status word given to [`Comm::reply`] is sent with the last chunk.
+    ///
+    /// Pending response data is discarded when any other command is received.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - Buffer for the response data exceeding the APDU buffer. Its
+    ///   length bounds the size of a response, minus the first 256 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// static mut RESPONSE_BUFFER: [u8; 1024] = [0u8; 1024];
+    ///
+    /// let mut comm = Comm::new()
+    ///     .set_chaining_buffer(unsafe { &mut *core::ptr::addr_of_mut!(RESPONSE_BUFFER) });
+    /// ```
+    pub fn set_chaining_buffer(mut self, buffer: &'static mut [u8]) -> Self {
+        self.chaining.buffer = Some(buffer);
+        self
+    }
+
+    /// Sets a hook called with a [`TraceRecord`] for every incoming command and
+    /// outgoing response, which can be used to implement logging or diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Function called with the trace records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn trace(record: &TraceRecord) {
+    ///     if let TraceRecord::Response { sw, .. } = record {
+    ///         ...
+    ///     }
+    /// }
+    ///
+    /// let mut comm = Comm::new().set_trace_hook(trace);
+    /// ```
+    pub fn set_trace_hook(mut self, hook: fn(&TraceRecord)) -> Self {
+        self.trace_hook = Some(hook);
+        self
+    }
+
+    /// Sets a function called on every ticker event received while waiting for
+    /// events, so that animations (spinners, scrolling text...) keep running
+    /// while the application is blocked in [`Comm::next_event`] or
+    /// [`Comm::next_command`].
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - Function updating the user interface.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn animate() {
+    ///     ...
+    /// }
+    ///
+    /// let mut comm = Comm::new().set_ux_step(animate);
+    /// ```
+    pub fn set_ux_step(mut self, step: fn()) -> Self {
+        self.ux_step = Some(step);
+        self
+    }
+
+    /// Sets a function called after `seconds` seconds without any command or user
+    /// interaction (button press, screen touch), typically to return to the home
+    /// screen or to wipe session state. The inactivity delay is measured with
+    /// ticker events, while the application waits for events.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - Inactivity delay.
+    /// * `on_idle` - Function called once the delay has elapsed. It is called again
+    ///   after a new inactivity delay.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn on_idle() {
+    ///     clear_session();
+    /// }
+    ///
+    /// let mut comm = Comm::new().set_idle_timeout(60, on_idle);
+    /// ```
+    pub fn set_idle_timeout(mut self, seconds: u32, on_idle: fn()) -> Self {
+        let ticks = (seconds.saturating_mul(1000) / TICKER_PERIOD_MS).max(1);
+        self.idle_timeout = Some((ticks, on_idle));
+        self.idle_ticks = 0;
+        self
+    }
+
+    /// Displays a screensaver after `seconds` seconds without any command or
+    /// user interaction, so that the last displayed screen, such as an address
+    /// or an amount, does not remain visible on an unattended device.
+    ///
+    /// The screensaver is dismissed by the next command or user interaction.
+    /// A button press or a touch dismissing it is not reported to the
+    /// application, nor the following release, so that it does not act on the
+    /// hidden screen. As for [`Comm::set_idle_timeout`], the inactivity delay
+    /// is measured while the application waits for events.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - Inactivity delay.
+    /// * `show` - Function displaying the screensaver.
+    /// * `restore` - Function displaying the screen hidden by the screensaver
+    ///   again. With NBGL, it typically releases the layout drawn by `show`
+    ///   and calls `nbgl_screenRedraw`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn show_screensaver() {
+    ///     clear_screen();
+    ///     CRAB.draw(56, 24);
+    ///     screen_update();
+    /// }
+    ///
+    /// fn restore_screen() {
+    ///     unsafe { CURRENT_PAGE.place() };
+    ///     screen_update();
+    /// }
+    ///
+    /// let mut comm = Comm::new().set_screensaver(30, show_screensaver, restore_screen);
+    /// ```
+    pub fn set_screensaver(mut self, seconds: u32, show: fn(), restore: fn()) -> Self {
+        self.screensaver = Some(Screensaver {
+            ticks: (seconds.saturating_mul(1000) / TICKER_PERIOD_MS).max(1),
+            show,
+            restore,
+            idle_ticks: 0,
+            shown: false,
+            dismissing: false,
+        });
+        self
+    }
+
+    /// Returns true while the screensaver is displayed.
+    pub fn is_screensaver_shown(&self) -> bool {
+        self.screensaver.as_ref().is_some_and(|s| s.shown)
+    }
+
+    /// Counts one ticker period of inactivity, and displays the screensaver
+    /// when the inactivity delay has elapsed.
+    fn screensaver_tick(&mut self) {
+        if let Some(screensaver) = self.screensaver.as_mut() {
+            if !screensaver.shown {
+                screensaver.idle_ticks += 1;
+                if screensaver.idle_ticks >= screensaver.ticks {
+                    screensaver.shown = true;
+                    (screensaver.show)();
+                }
+            }
+        }
+    }
+
+    /// Resets the inactivity delays on a command or a user interaction, and
+    /// dismisses the screensaver if it is displayed.
+    ///
+    /// Returns true if the user interaction must not be reported to the
+    /// application, because it dismisses the screensaver. `released` is true
+    /// when the interaction ends: buttons or screen released.
+    fn activity(&mut self, user: bool, released: bool) -> bool {
+        self.idle_ticks = 0;
+        if user {
+            crate::inactivity::activity();
+        }
+        let Some(screensaver) = self.screensaver.as_mut() else {
+            return false;
+        };
+        screensaver.idle_ticks = 0;
+        if screensaver.shown {
+            screensaver.shown = false;
+            screensaver.dismissing = user && !released;
+            (screensaver.restore)();
+            return user;
+        }
+        if user && screensaver.dismissing {
+            screensaver.dismissing = !released;
+            return true;
+        }
+        false
+    }
+
+    /// Sets a function called with the gesture recognized each time the screen
+    /// is released (tap or swipe), so that applications can build custom
+    /// interactive screens. Touch events are still processed by NBGL and
+    /// returned as [`Event::TouchEvent`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn on_touch(gesture: TouchGesture) {
+    ///     match gesture {
+    ///         TouchGesture::SwipeLeft => next_page(),
+    ///         TouchGesture::SwipeRight => previous_page(),
+    ///         _ => (),
+    ///     }
+    /// }
+    ///
+    /// let mut comm = Comm::new().set_touch_hook(on_touch);
+    /// ```
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    pub fn set_touch_hook(mut self, hook: fn(TouchGesture)) -> Self {
+        self.touch_hook = Some(hook);
+        self
+    }
+
+    /// Tracks the touch and release positions of finger events, and calls the
+    /// touch hook with the recognized gesture on release.
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    fn track_touch(&mut self, spi_buffer: &[u8; 128]) {
+        let position = (
+            u16::from_be_bytes([spi_buffer[4], spi_buffer[5]]),
+            u16::from_be_bytes([spi_buffer[6], spi_buffer[7]]),
+        );
+        if spi_buffer[3] == SEPROXYHAL_TAG_FINGER_EVENT_TOUCH as u8 {
+            self.touch_start.get_or_insert(position);
+        } else if let Some(start) = self.touch_start.take() {
+            if let Some(hook) = self.touch_hook {
+                hook(TouchGesture::from_positions(start, position));
+            }
+        }
+    }
+
+    /// Counts one ticker period of inactivity, and calls the idle timeout
+    /// function when the inactivity delay has elapsed.
+    fn idle_tick(&mut self) {
+        if let Some((ticks, on_idle)) = self.idle_timeout {
+            self.idle_ticks += 1;
+            if self.idle_ticks >= ticks {
+                self.idle_ticks = 0;
+                on_idle();
+            }
+        }
+    }
+
+    /// Returns the communication statistics since the creation of this instance,
+    /// which can be displayed on a diagnostics screen.
+    pub fn stats(&self) -> &CommStats {
+        &self.stats
+    }
+
+    /// Resets the communication statistics.
+    pub fn reset_stats(&mut self) {
+        self.stats = CommStats::default();
+    }
+
+    /// Rejects the current command with the given status word.
+    fn reject<T: Into<Reply>>(&mut self, reply: T) {
+        self.stats.rejected = self.stats.rejected.wrapping_add(1);
+        self.reply(reply);
+    }
+
+    /// Sets the period of the [`Event::Ticker`] events returned by [`Comm::next_event`].
+    ///
+    /// The period is rounded down to a multiple of [`TICKER_PERIOD_MS`], the period of
+    /// the ticker events sent by the MCU, with a minimum of one MCU ticker period.
+    /// Ticker events are still processed internally (NBGL timers) at the MCU period,
+    /// only their delivery to the application is decimated. On Nano devices, the OS UX
+    /// is serviced on every ticker event by [`Comm::next_command`], so long periods do
+    /// not delay the PIN lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `period_ms` - Period of the ticker events, in milliseconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Receive a ticker event every second for a countdown
+    /// let mut comm = Comm::new().set_ticker_period(1000);
+    /// ```
+    pub fn set_ticker_period(mut self, period_ms: u32) -> Self {
+        self.ticker_divider = (period_ms / TICKER_PERIOD_MS).max(1);
+        self.ticker_count = 0;
+        self
+    }
+
+    /// Returns the period of the [`Event::Ticker`] events returned by [`Comm::next_event`],
+    /// in milliseconds.
+    pub fn ticker_period_ms(&self) -> u32 {
+        self.ticker_divider * TICKER_PERIOD_MS
+    }
+
+    /// Forwards a ticker event to the OS UX so that it keeps being serviced (PIN
+    /// lock, screen saver...) while the application waits for a command, and
+    /// blocks while the OS UX is displayed.
+    /// Returns the command received in the meantime, if any.
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    fn process_os_ux<T>(&mut self) -> Option<T>
+    where
+        T: TryFrom<ApduHeader>,
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        if UxEvent::Event.request() != BOLOS_UX_OK {
+            if let (_, Some(Event::Command(ins))) = UxEvent::block_and_get_event::<T>(self) {
+                return Some(ins);
+            }
+        }
+        None
+    }
+
+    /// Calls the trace hook, if any, with the command held in the APDU buffer.
+    fn trace_command(&self) {
+        if let Some(hook) = self.trace_hook {
+            let data = self.get_data().unwrap_or(&[]);
+            hook(&TraceRecord::Command {
+                header: *self.get_apdu_metadata(),
+                data_len: data.len(),
+                data: cfg!(debug_assertions).then_some(data),
+            });
+        }
+    }
+
+    /// Sends the next chunk of a chained response, in reply to a GET RESPONSE command.
+    fn get_response(&mut self) {
+        let remaining = self.chaining.len - self.chaining.offset;
+        // Honor the expected length of short APDUs, where Le = 0 means 256
+        let le = match self.rx {
+            5 if self.apdu_buffer[4] != 0 => self.apdu_buffer[4] as usize,
+            _ => CHAINING_CHUNK_LEN,
+        };
+        let len = remaining.min(le);
+        if let Some(buffer) = &self.chaining.buffer {
+            let offset = self.chaining.offset;
+            self.apdu_buffer[..len].copy_from_slice(&buffer[offset..offset + len]);
+        }
+        self.tx = len;
+        self.chaining.offset += len;
+
+        let remaining = remaining - len;
+        let sw = if remaining == 0 {
+            let sw = self.chaining.sw;
+            self.chaining.clear();
+            sw
+        } else {
+            ResponseChaining::remaining_sw(remaining)
+        };
+        self.send_with_sw(sw);
+    }
+
+    /// Defines [`Comm::expected_clas`] in order to accept several CLA values, and to reply
+    /// automatically [`StatusWords::BadCla`] when an incoming APDU has a CLA byte which is
+    /// neither one of them nor [`Comm::expected_cla`].
+    ///
+    /// Commands of each CLA can then be routed to their own instruction enumeration,
+    /// see [`ApduDispatch`].
+    ///
+    /// # Arguments
+    ///
+    /// * `clas` - Accepted values for APDUs CLA byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut comm = Comm::new().set_expected_clas(&[0xe0, 0xe1]);
+    /// ```
+    pub fn set_expected_clas(mut self, clas: &'static [u8]) -> Self {
+        self.expected_clas = clas;
+        self
+    }
+
+    /// Sets a handler receiving the commands with the given CLA and an INS in the
+    /// given range as raw bytes, bypassing the length, CLA and INS checks and the
+    /// conversion to the instruction type. This allows proprietary command formats
+    /// to coexist with the standard typed dispatch.
+    ///
+    /// The handler reads the command with [`Comm::raw_apdu`] and must reply to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cla` - CLA byte of the raw commands.
+    /// * `ins` - Range of INS bytes of the raw commands.
+    /// * `handler` - Function called for each raw command.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn handle_vendor(comm: &mut Comm) {
+    ///     let len = comm.raw_apdu().len();
+    ///     ...
+    ///     comm.reply_ok();
+    /// }
+    ///
+    /// let mut comm = Comm::new()
+    ///     .set_expected_cla(0xe0)
+    ///     .set_raw_handler(0xf0, 0x00..=0x0f, handle_vendor);
+    /// ```
+    pub fn set_raw_handler(
+        mut self,
+        cla: u8,
+        ins: RangeInclusive<u8>,
+        handler: fn(&mut Comm),
+    ) -> Self {
+        self.raw_route = Some(RawRoute { cla, ins, handler });
+        self
+    }
+
+    /// Returns the bytes of the current command, header included.
+    pub fn raw_apdu(&self) -> &[u8] {
+        &self.apdu_buffer[..self.rx]
+    }
+
+    /// Returns the raw command handler matching the current command, if any.
+    fn raw_handler(&self) -> Option<fn(&mut Comm)> {
+        match &self.raw_route {
+            Some(route)
+                if self.rx >= 2
+                    && self.apdu_buffer[0] == route.cla
+                    && route.ins.contains(&self.apdu_buffer[1]) =>
+            {
+                Some(route.handler)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether APDUs with the given CLA byte are accepted.
+    fn is_cla_accepted(&self, cla: u8) -> bool {
+        match self.expected_cla {
+            None if self.expected_clas.is_empty() => true,
+            expected => expected == Some(cla) || self.expected_clas.contains(&cla),
+        }
+    }
+
     /// Send the currently held APDU
     // This is private. Users should call reply to set the satus word and
     // transmit the response.
@@ -248,6 +1046,21 @@ impl Comm {
     /// }
     /// ```
     pub fn next_event<T>(&mut self) -> Event<T>
+    where
+        T: TryFrom<ApduHeader>,
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        loop {
+            if let Some(event) = self.next_event_or_tick() {
+                return event;
+            }
+        }
+    }
+
+    /// Waits for the next event like [`Comm::next_event`], but also returns
+    /// `None` on the ticker events which are not delivered to the application
+    /// (see [`Comm::set_ticker_period`]).
+    fn next_event_or_tick<T>(&mut self) -> Option<Event<T>>
     where
         T: TryFrom<ApduHeader>,
         Reply: From<<T as TryFrom<ApduHeader>>::Error>,
@@ -271,7 +1084,13 @@ impl Comm {
             let _rx = sys_seph::seph_recv(&mut spi_buffer, 0);
 
             if let Some(value) = self.decode_event(&mut spi_buffer) {
-                return value;
+                if let (Event::Ticker, Some(step)) = (&value, self.ux_step) {
+                    step();
+                }
+                return Some(value);
+            }
+            if let seph::Events::TickerEvent = seph::Events::from(spi_buffer[0]) {
+                return None;
             }
         }
     }
@@ -293,6 +1112,90 @@ impl Comm {
         return self.detect_apdu::<T>(&mut spi_buffer);
     }
 
+    /// Processes the next event sent by the MCU (USB, ticker, display...) without
+    /// returning it, so that the transport keeps being serviced and the user
+    /// interface keeps being updated (e.g. spinner animation) while the application
+    /// performs a long computation.
+    ///
+    /// This blocks until the next event, which takes at most one ticker period
+    /// (100 ms). Neither HID nor BLE transports allow to send an intermediate
+    /// response, so nothing is sent to the host: the host must wait for the final
+    /// response. Commands are not decoded in the meantime: the command being
+    /// processed stays pending until the application replies to it.
+    ///
+    /// As the APDU buffer may be overwritten by incoming data, the command data
+    /// must be read before calling this method, and the response appended after.
+    pub fn keepalive(&mut self) {
+        let mut spi_buffer = [0u8; 128];
+        if !sys_seph::is_status_sent() {
+            sys_seph::send_general_status();
+        }
+        sys_seph::seph_recv(&mut spi_buffer, 0);
+        // Only process the SEPH event: decoding the command in flight would
+        // deliver it again
+        let _: Option<Event<ApduHeader>> = self.process_event(&mut spi_buffer);
+    }
+
+    /// Returns a [`Busy`] helper calling [`Comm::keepalive`] every `period` steps
+    /// of a long computation.
+    ///
+    /// Each keepalive blocks until the next MCU event, for up to one ticker
+    /// period (100 ms), whatever the time spent since the previous one. `period`
+    /// should therefore be chosen so that the steps between two keepalives last
+    /// about one second, which keeps the added wait under 10% of the computation.
+    pub fn busy(&mut self, period: u32) -> Busy<'_> {
+        Busy {
+            comm: self,
+            period,
+            steps: 0,
+        }
+    }
+
+    /// Runs the long computation `f`, which calls [`Busy::step`] at each of
+    /// its steps, so that the IO is serviced every `period` steps and the
+    /// USB or BLE link does not time out. Returns the result of `f`. See
+    /// [`Comm::busy`] for the cost of each keepalive and the choice of `period`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Hashing an input takes about 1 ms: a keepalive every second
+    /// let hash = comm.with_keepalive(1024, |busy| {
+    ///     let mut hasher = Sha2_256::new();
+    ///     for input in tx.inputs() {
+    ///         hasher.update(input);
+    ///         busy.step();
+    ///     }
+    ///     hasher.finalize()
+    /// });
+    /// ```
+    pub fn with_keepalive<R>(&mut self, period: u32, f: impl FnOnce(&mut Busy<'_>) -> R) -> R {
+        f(&mut self.busy(period))
+    }
+
+    /// Runs the long computation `f` like [`Comm::with_keepalive`], while the
+    /// page of `spinner` is displayed. The spinner turns as the IO is
+    /// serviced, and stays on screen until the next page is drawn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let spinner = NbglSpinner::new().text("Signing");
+    /// // Signing an input takes about 60 ms: a keepalive every second
+    /// let signature = comm.with_spinner(&spinner, 16, |busy| sign_inputs(&psbt, busy));
+    /// NbglReviewStatus::new().show(signature.is_ok());
+    /// ```
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    pub fn with_spinner<R>(
+        &mut self,
+        spinner: &crate::nbgl::NbglSpinner,
+        period: u32,
+        f: impl FnOnce(&mut Busy<'_>) -> R,
+    ) -> R {
+        spinner.show();
+        self.with_keepalive(period, f)
+    }
+
     pub fn check_event<T>(&mut self) -> Option<Event<T>>
     where
         T: TryFrom<ApduHeader>,
@@ -300,15 +1203,38 @@ impl Comm {
     {
         if self.event_pending {
             self.event_pending = false;
+            self.activity(false, false);
+            self.stats.bytes_in = self.stats.bytes_in.wrapping_add(self.rx as u32);
+
+            if self.rx >= 4 {
+                self.trace_command();
+            }
+
+            // Send the next chunk of a chained response, or discard it if
+            // the host sends another command
+            if self.chaining.len > 0 {
+                if self.rx >= 4 && self.apdu_buffer[1] == INS_GET_RESPONSE {
+                    self.get_response();
+                    return None;
+                }
+                self.chaining.clear();
+            }
+
+            // Deliver raw commands without any check
+            if let Some(handler) = self.raw_handler() {
+                handler(self);
+                return None;
+            }
+
             // Reject incomplete APDUs
             if self.rx < 4 {
-                self.reply(StatusWords::BadLen);
+                self.reject(StatusWords::BadLen);
                 return None;
             }
 
             // Check for data length by using `get_data`
             if let Err(sw) = self.get_data() {
-                self.reply(sw);
+                self.reject(sw);
                 return None;
             }
 
@@ -322,23 +1248,22 @@ impl Comm {
             }
 
             // If CLA filtering is enabled, automatically reject APDUs with wrong CLA
-            if let Some(cla) = self.expected_cla {
-                if self.apdu_buffer[0] != cla {
-                    self.reply(StatusWords::BadCla);
-                    return None;
-                }
+            if !self.is_cla_accepted(self.apdu_buffer[0]) {
+                self.reject(StatusWords::BadCla);
+                return None;
             }
 
             let res = T::try_from(*self.get_apdu_metadata());
             match res {
                 Ok(ins) => {
+                    self.stats.commands = self.stats.commands.wrapping_add(1);
                     return Some(Event::Command(ins));
                 }
                 Err(sw) => {
                     // Invalid Ins code. Send automatically an error, mask
                     // the bad instruction to the application and just
                     // discard this event.
-                    self.reply(sw);
+                    self.reject(sw);
                 }
             }
         }
@@ -366,20 +1291,65 @@ impl Comm {
         // Treat all possible events.
         // If this is a button push, return with the associated event
         // If this is an APDU, return with the "received command" event
-        // Any other event (usb, xfer, ticker) is silently handled
+        // Any other event (usb, xfer, ticker) is silently handled, and
+        // unknown events are forwarded to the application SEPH event hook
         match seph::Events::from(tag) {
             #[cfg(not(any(target_os = "stax", target_os = "flex")))]
             seph::Events::ButtonPush => {
                 let button_info = spi_buffer[3] >> 1;
+                let ignored = self.activity(true, button_info == 0);
                 if let Some(btn_evt) = get_button_event(&mut self.buttons, button_info) {
-                    return Some(Event::Button(btn_evt));
+                    if !ignored {
+                        return Some(Event::Button(btn_evt));
+                    }
                 }
             }
             seph::Events::USBEvent => {
                 if len == 1 {
                     seph::handle_usb_event(spi_buffer[3]);
+                    match seph::Events::from(spi_buffer[3]) {
+                        seph::Events::USBEventSuspend => {
+                            return Some(Event::Usb(UsbEvent::Suspended))
+                        }
+                        seph::Events::USBEventResume => return Some(Event::Usb(UsbEvent::Resumed)),
+                        _ => (),
+                    }
+                }
+            }
+            seph::Events::StatusEvent => {
+                // Status events are also used by the OS UX and the application
+                #[cfg(any(target_os = "stax", target_os = "flex"))]
+                unsafe {
+                    ux_process_default_event();
+                }
+                seph::call_event_hook(spi_buffer);
+                if len >= 4 {
+                    let flags = u32::from_be_bytes([
+                        spi_buffer[3],
+                        spi_buffer[4],
+                        spi_buffer[5],
+                        spi_buffer[6],
+                    ]);
+                    crate::power::update_status(flags);
+                    let powered = flags & SEPROXYHAL_TAG_STATUS_EVENT_FLAG_USB_POWERED != 0;
+                    // Only report changes, the first status event gives the initial state
+                    let previous = self.usb_powered.replace(powered);
+                    if previous.is_some_and(|p| p != powered) {
+                        let usb = if powered {
+                            UsbEvent::Attached
+                        } else {
+                            UsbEvent::Detached
+                        };
+                        return Some(Event::Usb(usb));
+                    }
+                }
+                let language = Language::current();
+                let previous = self.language.replace(language);
+                if previous.is_some_and(|l| l != language) {
+                    return Some(Event::Language(language));
                 }
             }
+            seph::Events::BatteryNotification => return Some(Event::BatteryCritical),
             seph::Events::USBXFEREvent => {
                 if len >= 3 {
                     seph::handle_usb_ep_xfer_event(&mut self.apdu_buffer, spi_buffer);
@@ -395,20 +1365,38 @@ impl Comm {
                 unsafe {
                     ux_process_ticker_event();
                 }
-                return Some(Event::Ticker);
+                #[cfg(all(feature = "demo", any(target_os = "stax", target_os = "flex")))]
+                crate::nbgl::demo_tick();
+                crate::screen::keep_awake_tick();
+                crate::timers::tick();
+                crate::watchdog::tick();
+                crate::inactivity::tick();
+                self.idle_tick();
+                self.screensaver_tick();
+                self.ticker_count += 1;
+                if self.ticker_count >= self.ticker_divider {
+                    self.ticker_count = 0;
+                    return Some(Event::Ticker);
+                }
             }
 
             #[cfg(any(target_os = "stax", target_os = "flex"))]
-            seph::Events::ScreenTouch => unsafe {
-                ux_process_finger_event(spi_buffer.as_mut_ptr());
+            seph::Events::ScreenTouch => {
+                let released = spi_buffer[3] == SEPROXYHAL_TAG_FINGER_EVENT_RELEASE as u8;
+                if self.activity(true, released) {
+                    return None;
+                }
+                unsafe { ux_process_finger_event(spi_buffer.as_mut_ptr()) };
+                self.track_touch(spi_buffer);
                 return Some(Event::TouchEvent);
-            },
+            }
 
             _ => {
                 #[cfg(any(target_os = "stax", target_os = "flex"))]
                 unsafe {
                     ux_process_default_event();
                 }
+                seph::call_event_hook(spi_buffer);
             }
         }
         None
@@ -448,6 +1436,9 @@ impl Comm {
 
     /// Wait for the next Command event. Discards received button events.
     ///
+    /// On Nano devices, ticker events are forwarded to the OS UX in the meantime,
+    /// so that the PIN lock and screen saver keep working.
+    ///
     /// Like `next_event`, `T` can be any type, an enumeration, or any type
     /// which implements `TryFrom<ApduHeader>`.
     ///
@@ -484,8 +1475,45 @@ impl Comm {
         Reply: From<<T as TryFrom<ApduHeader>>::Error>,
     {
         loop {
-            if let Event::Command(ins) = self.next_event() {
-                return ins;
+            match self.next_event_or_tick() {
+                Some(Event::Command(ins)) => return ins,
+                // The OS UX is serviced on every ticker event, whatever the
+                // period of the ones delivered to the application
+                #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+                Some(Event::Ticker) | None => {
+                    if let Some(ins) = self.process_os_ux() {
+                        return ins;
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Wait for the next Command event, forwarding all user interface events
+    /// to `on_ui` in the meantime.
+    ///
+    /// This keeps UI code (buttons, touch, ticker) and protocol code (commands)
+    /// separated, instead of interleaving both in a single match on [`Event`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// loop {
+    ///     let ins: Instruction = comm.next_command_with(|ui_event| ui.process(ui_event));
+    ///     handle_apdu(&mut comm, ins);
+    /// }
+    /// ```
+    pub fn next_command_with<T, F>(&mut self, mut on_ui: F) -> T
+    where
+        T: TryFrom<ApduHeader>,
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+        F: FnMut(UiEvent),
+    {
+        loop {
+            match self.next_event().split() {
+                Ok(ins) => return ins,
+                Err(ui_event) => on_ui(ui_event),
             }
         }
     }
@@ -498,8 +1526,32 @@ impl Comm {
     /// * `sw` - Status Word to be transmitted after the Data. Can be a
     ///   StatusWords, a SyscallError, or any type which can be converted to a
     ///   Reply.
+    ///
+    /// If response chaining is enabled (see [`Comm::set_chaining_buffer`]) and
+    /// the response data exceeds 256 bytes, only the first chunk is transmitted,
+    /// and `sw` is sent along with the last one.
     pub fn reply<T: Into<Reply>>(&mut self, reply: T) {
-        let sw = reply.into().0;
+        let mut sw = reply.into().0;
+        if self.chaining.len > 0 {
+            self.chaining.offset = 0;
+            self.chaining.sw = sw;
+            sw = ResponseChaining::remaining_sw(self.chaining.len);
+        }
+        self.send_with_sw(sw);
+    }
+
+    /// Append the status word to the response data, and transmit the response.
+    fn send_with_sw(&mut self, sw: u16) {
+        self.stats.bytes_out = self.stats.bytes_out.wrapping_add(self.tx as u32 + 2);
+        self.stats.last_sw = Some(sw);
+        if let Some(hook) = self.trace_hook {
+            let data = &self.apdu_buffer[..self.tx];
+            hook(&TraceRecord::Response {
+                sw,
+                data_len: data.len(),
+                data: cfg!(debug_assertions).then_some(data),
+            });
+        }
         // Append status word
         self.apdu_buffer[self.tx] = (sw >> 8) as u8;
         self.apdu_buffer[self.tx + 1] = sw as u8;
@@ -522,42 +1574,307 @@ impl Comm {
     }
 
     pub fn get_data(&self) -> Result<&[u8], StatusWords> {
-        if self.rx == 4 {
-            Ok(&[]) // Conforming zero-data APDU
-        } else {
-            let first_len_byte = self.apdu_buffer[4] as usize;
-            let get_data_from_buffer = |len, offset| {
-                if len == 0 || len + offset > self.rx {
-                    Err(StatusWords::BadLen)
-                } else {
-                    Ok(&self.apdu_buffer[offset..offset + len])
-                }
-            };
-            match (first_len_byte, self.rx) {
-                (0, 5) => Ok(&[]), // Non-conforming zero-data APDU
-                (0, 6) => Err(StatusWords::BadLen),
-                (0, _) => {
-                    let len =
-                        u16::from_le_bytes([self.apdu_buffer[5], self.apdu_buffer[6]]) as usize;
-                    get_data_from_buffer(len, 7)
-                }
-                (len, _) => get_data_from_buffer(len, 5),
+        apdu_data(&self.apdu_buffer, self.rx)
+    }
+
+    /// Returns the number of bytes which can still be appended to the response
+    /// data, including the chaining buffer when enabled (see [`Comm::set_chaining_buffer`]).
+    pub fn remaining_capacity(&self) -> usize {
+        match &self.chaining.buffer {
+            Some(buffer) => {
+                CHAINING_CHUNK_LEN.saturating_sub(self.tx) + buffer.len() - self.chaining.len
             }
+            // Keep room for the status word
+            None => (self.apdu_buffer.len() - 2).saturating_sub(self.tx),
+        }
+    }
+
+    /// Appends `m` to the response data if it fits, see [`Comm::append`].
+    /// Nothing is appended otherwise, and [`TooLong`] is returned.
+    pub fn try_append(&mut self, m: &[u8]) -> Result<(), TooLong> {
+        if m.len() > self.remaining_capacity() {
+            return Err(TooLong);
         }
+        self.append(m);
+        Ok(())
+    }
+
+    /// Calls `handler` to process the current command, then replies exactly once:
+    /// with the response data it wrote and `0x9000` if it succeeds, or with the
+    /// status word corresponding to its error otherwise, in which case the response
+    /// data it may have written is discarded.
+    ///
+    /// The handler receives an [`Apdu`], which gives access to the command and to
+    /// the response data, but cannot send a reply itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// enum AppError {
+    ///     WrongPath,
+    ///     Io(TooLong),
+    /// }
+    ///
+    /// impl From<AppError> for Reply { ... }
+    ///
+    /// loop {
+    ///     let ins: Instruction = comm.next_command();
+    ///     comm.handle(|apdu| match ins {
+    ///         Instruction::GetVersion => {
+    ///             apdu.writer().write_all(&[1, 0, 0]).map_err(AppError::Io)
+    ///         }
+    ///         ...
+    ///     });
+    /// }
+    /// ```
+    pub fn handle<E, F>(&mut self, handler: F)
+    where
+        F: FnOnce(&mut Apdu) -> Result<(), E>,
+        Reply: From<E>,
+    {
+        let res = handler(&mut Apdu { comm: self });
+        match res {
+            Ok(()) => self.reply_ok(),
+            Err(e) => {
+                self.tx = 0;
+                self.chaining.clear();
+                self.reply(e);
+            }
+        }
+    }
+
+    /// Returns a [`ResponseWriter`] appending to the response data.
+    pub fn response_writer(&mut self) -> ResponseWriter<'_> {
+        ResponseWriter { comm: self }
     }
 
     pub fn get(&self, start: usize, end: usize) -> &[u8] {
         &self.apdu_buffer[start..end]
     }
 
+    /// Appends `m` to the response data.
+    ///
+    /// If response chaining is enabled (see [`Comm::set_chaining_buffer`]),
+    /// bytes beyond the first 256 ones are stored in the chaining buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the response data does not fit in the APDU buffer, or in the
+    /// chaining buffer when enabled.
     pub fn append(&mut self, m: &[u8]) {
         for c in m.iter() {
-            self.apdu_buffer[self.tx] = *c;
-            self.tx += 1;
+            match &mut self.chaining.buffer {
+                Some(buffer) if self.tx >= CHAINING_CHUNK_LEN => {
+                    buffer[self.chaining.len] = *c;
+                    self.chaining.len += 1;
+                }
+                _ => {
+                    self.apdu_buffer[self.tx] = *c;
+                    self.tx += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Command being processed by a handler given to [`Comm::handle`].
+///
+/// The command data and the response data share the APDU buffer, so the
+/// command data must be read before writing the response.
+pub struct Apdu<'a> {
+    comm: &'a mut Comm,
+}
+
+impl Apdu<'_> {
+    /// Returns the header of the command.
+    pub fn header(&self) -> &ApduHeader {
+        self.comm.get_apdu_metadata()
+    }
+
+    /// Returns the data of the command.
+    pub fn data(&self) -> Result<&[u8], StatusWords> {
+        self.comm.get_data()
+    }
+
+    /// Returns a [`ResponseWriter`] appending to the response data.
+    pub fn writer(&mut self) -> ResponseWriter<'_> {
+        self.comm.response_writer()
+    }
+}
+
+/// Helper signaling that the application is processing a command, by servicing
+/// the IO (see [`Comm::keepalive`]) every given number of steps of a long
+/// computation.
+///
+/// # Examples
+///
+/// ```
+/// // Hashing an input takes about 1 ms: a keepalive every second
+/// let mut busy = comm.busy(1024);
+/// for input in tx.inputs() {
+///     hash_input(input);
+///     busy.step();
+/// }
+/// ```
+pub struct Busy<'a> {
+    comm: &'a mut Comm,
+    period: u32,
+    steps: u32,
+}
+
+impl Busy<'_> {
+    /// Counts one step of the computation, and services the IO every `period` steps.
+    /// Returns true if the IO has been serviced, for instance to update a
+    /// progress bar at the same pace.
+    pub fn step(&mut self) -> bool {
+        self.steps += 1;
+        if self.steps < self.period {
+            return false;
+        }
+        self.steps = 0;
+        self.comm.keepalive();
+        true
+    }
+}
+
+/// Writer building the response data of a [`Comm`], which never overflows the
+/// response buffers. It also implements [`core::fmt::Write`] for text responses.
+///
+/// # Examples
+///
+/// ```
+/// let mut writer = comm.response_writer();
+/// writer.write_all(&pubkey)?;
+/// if writer.remaining_capacity() >= chain_code.len() {
+///     writer.write_all(&chain_code)?;
+/// }
+/// write!(writer, "{}", address).map_err(|_| TooLong)?;
+/// ```
+pub struct ResponseWriter<'a> {
+    comm: &'a mut Comm,
+}
+
+impl ResponseWriter<'_> {
+    /// Returns the number of bytes which can still be written.
+    pub fn remaining_capacity(&self) -> usize {
+        self.comm.remaining_capacity()
+    }
+
+    /// Writes as many bytes of `buf` as possible, and returns their number.
+    pub fn write(&mut self, buf: &[u8]) -> usize {
+        let len = buf.len().min(self.remaining_capacity());
+        self.comm.append(&buf[..len]);
+        len
+    }
+
+    /// Writes all of `buf`, or nothing if it does not fit.
+    pub fn write_all(&mut self, buf: &[u8]) -> Result<(), TooLong> {
+        self.comm.try_append(buf)
+    }
+}
+
+impl core::fmt::Write for ResponseWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_all(s.as_bytes()).map_err(|_| core::fmt::Error)
+    }
+}
+
+/// Returns the data of the `rx` bytes long APDU held in `apdu_buffer`.
+pub(crate) fn apdu_data(apdu_buffer: &[u8], rx: usize) -> Result<&[u8], StatusWords> {
+    if rx == 4 {
+        Ok(&[]) // Conforming zero-data APDU
+    } else {
+        let first_len_byte = apdu_buffer[4] as usize;
+        let get_data_from_buffer = |len, offset| {
+            if len == 0 || len + offset > rx {
+                Err(StatusWords::BadLen)
+            } else {
+                Ok(&apdu_buffer[offset..offset + len])
+            }
+        };
+        match (first_len_byte, rx) {
+            (0, 5) => Ok(&[]), // Non-conforming zero-data APDU
+            (0, 6) => Err(StatusWords::BadLen),
+            (0, _) => {
+                let len = u16::from_le_bytes([apdu_buffer[5], apdu_buffer[6]]) as usize;
+                get_data_from_buffer(len, 7)
+            }
+            (len, _) => get_data_from_buffer(len, 5),
         }
     }
 }
 
+/// Transport used to receive APDU commands and transmit their responses.
+///
+/// [`Comm`] is the implementation backed by the device IO. Command handlers
+/// written against this trait can also run with [`mock::MockTransport`], which
+/// replays synthetic commands and records replies, in order to unit test them.
+///
+/// # Examples
+///
+/// ```
+/// fn handle_get_version<C: ApduTransport>(comm: &mut C) -> Result<(), Reply> {
+///     comm.append(&[1, 0, 0]);
+///     Ok(())
+/// }
+///
+/// let mut comm = MockTransport::new(&[&[0xe0, 0x03, 0x00, 0x00]]);
+/// let _: ApduHeader = comm.next_command();
+/// handle_get_version(&mut comm).unwrap();
+/// comm.reply_ok();
+/// assert_eq!(comm.last_response(), &[1, 0, 0]);
+/// ```
+pub trait ApduTransport {
+    /// Waits for the next command, see [`Comm::next_command`].
+    fn next_command<T>(&mut self) -> T
+    where
+        T: TryFrom<ApduHeader>,
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>;
+
+    /// Returns the header of the current command.
+    fn get_apdu_metadata(&self) -> &ApduHeader;
+
+    /// Returns the data of the current command.
+    fn get_data(&self) -> Result<&[u8], StatusWords>;
+
+    /// Appends bytes to the response data.
+    fn append(&mut self, m: &[u8]);
+
+    /// Sets the status word of the response and transmits it.
+    fn reply<T: Into<Reply>>(&mut self, reply: T);
+
+    /// Transmits the response with the `0x9000` status word.
+    fn reply_ok(&mut self) {
+        self.reply(StatusWords::Ok);
+    }
+}
+
+impl ApduTransport for Comm {
+    fn next_command<T>(&mut self) -> T
+    where
+        T: TryFrom<ApduHeader>,
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        Comm::next_command(self)
+    }
+
+    fn get_apdu_metadata(&self) -> &ApduHeader {
+        Comm::get_apdu_metadata(self)
+    }
+
+    fn get_data(&self) -> Result<&[u8], StatusWords> {
+        Comm::get_data(self)
+    }
+
+    fn append(&mut self, m: &[u8]) {
+        Comm::append(self, m)
+    }
+
+    fn reply<T: Into<Reply>>(&mut self, reply: T) {
+        Comm::reply(self, reply)
+    }
+}
+
 // BOLOS APDU Handling (see https://developers.ledger.com/docs/connectivity/ledgerJS/open-close-info-on-apps)
 fn handle_bolos_apdu(com: &mut Comm, ins: u8) {
     match ins {
@@ -569,7 +1886,7 @@ fn handle_bolos_apdu(com: &mut Comm, ins: u8) {
                 let len = os_registry_get_current_app_tag(
                     BOLOS_TAG_APPNAME,
                     &mut com.apdu_buffer[com.tx + 1] as *mut u8,
-                    (260 - com.tx - 1) as u32,
+                    (crate::caps::APDU_BUFFER_SIZE - com.tx - 1) as u32,
                 );
                 com.apdu_buffer[com.tx] = len as u8;
                 com.tx += (1 + len) as usize;
@@ -577,7 +1894,7 @@ fn handle_bolos_apdu(com: &mut Comm, ins: u8) {
                 let len = os_registry_get_current_app_tag(
                     BOLOS_TAG_APPVERSION,
                     &mut com.apdu_buffer[com.tx + 1] as *mut u8,
-                    (260 - com.tx - 1) as u32,
+                    (crate::caps::APDU_BUFFER_SIZE - com.tx - 1) as u32,
                 );
                 com.apdu_buffer[com.tx] = len as u8;
                 com.tx += (1 + len) as usize;
@@ -587,7 +1904,7 @@ fn handle_bolos_apdu(com: &mut Comm, ins: u8) {
         // Quit Application INS
         0xa7 => {
             com.reply_ok();
-            crate::exit_app(0);
+            crate::return_to_dashboard();
         }
         _ => {
             com.reply(StatusWords::BadIns);
@@ -626,4 +1943,102 @@ mod test {
         assert_eq!(m.p1, 0);
         assert_eq!(m.p2, 0);
     }
+
+    #[test]
+    fn expected_clas() {
+        let c = Comm::new();
+        assert_eq!(c.is_cla_accepted(0x12), true);
+        let c = Comm::new()
+            .set_expected_cla(0xe0)
+            .set_expected_clas(&[0xe1]);
+        assert_eq!(c.is_cla_accepted(0xe0), true);
+        assert_eq!(c.is_cla_accepted(0xe1), true);
+        assert_eq!(c.is_cla_accepted(0xe2), false);
+    }
+
+    #[test]
+    fn ticker_period() {
+        assert_eq!(Comm::new().ticker_period_ms(), 100);
+        assert_eq!(Comm::new().set_ticker_period(1050).ticker_period_ms(), 1000);
+        assert_eq!(Comm::new().set_ticker_period(20).ticker_period_ms(), 100);
+    }
+
+    #[test]
+    fn syscall_errors() {
+        assert_eq!(SyscallError::from(4), SyscallError::Security);
+        assert_eq!(SyscallError::from(11), SyscallError::Unspecified);
+        assert_eq!(u32::from(SyscallError::NotEnoughSpace), 19);
+        assert_eq!(Reply::from(SyscallError::Security).0, 0x6804);
+        assert_eq!(Reply::from(SyscallError::Unspecified).0, 0x680b);
+    }
+
+    #[test]
+    fn try_append() {
+        let mut c = Comm::new();
+        assert_eq!(c.try_append(&[0u8; 250]), Ok(()));
+        assert_eq!(c.remaining_capacity(), 8);
+        assert_eq!(c.try_append(&[0u8; 9]), Err(TooLong));
+        assert_eq!(c.tx, 250);
+        let mut writer = c.response_writer();
+        assert_eq!(writer.write(&[0u8; 9]), 8);
+        assert_eq!(writer.remaining_capacity(), 0);
+    }
+
+    #[test]
+    fn append_chaining() {
+        static mut BUFFER: [u8; 64] = [0u8; 64];
+        let mut c =
+            Comm::new().set_chaining_buffer(unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) });
+        c.append(&[0xaa; 250]);
+        c.append(&[0xbb; 10]);
+        assert_eq!(c.tx, 256);
+        assert_eq!(c.chaining.len, 4);
+        assert_eq!(c.apdu_buffer[255], 0xbb);
+        assert_eq!(ResponseChaining::remaining_sw(c.chaining.len), 0x6104);
+        assert_eq!(ResponseChaining::remaining_sw(300), 0x6100);
+    }
+
+    #[cfg(feature = "seph_injection")]
+    #[test]
+    fn injected_events() {
+        use crate::testing::{inject_apdu, inject_seph_event, inject_seph_packet};
+        let mut c = Comm::new();
+        // Unknown tag, truncated status event, then a command too short for
+        // its header
+        assert_eq!(inject_seph_event(0x7f, &[1, 2, 3]), true);
+        assert_eq!(
+            inject_seph_packet(&[SEPROXYHAL_TAG_STATUS_EVENT as u8, 0, 4, 0]),
+            true
+        );
+        assert_eq!(inject_apdu(&[0xe0, 0x01]), true);
+        assert_eq!(inject_apdu(&[0xe0, 0x01, 0x02, 0x03, 0x00]), true);
+        assert_eq!(inject_apdu(&[0xe0]), false);
+        let event = c.next_event::<ApduHeader>();
+        assert_eq!(
+            matches!(event, Event::Command(header) if (header.ins, header.p1) == (0x01, 0x02)),
+            true
+        );
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    #[test]
+    fn touch_gestures() {
+        let tap = TouchGesture::from_positions((100, 200), (110, 195));
+        assert_eq!(tap, TouchGesture::Tap { x: 110, y: 195 });
+        let region = TouchRegion {
+            x: 100,
+            y: 150,
+            width: 50,
+            height: 50,
+        };
+        assert_eq!(tap.is_tap_in(&region), true);
+        assert_eq!(
+            TouchGesture::from_positions((300, 200), (100, 220)),
+            TouchGesture::SwipeLeft
+        );
+        assert_eq!(
+            TouchGesture::from_positions((100, 100), (110, 300)),
+            TouchGesture::SwipeDown
+        );
+    }
 }
@@ -0,0 +1,125 @@
+//! [`ApduTransport`] over TCP, to run an application natively with the
+//! `emulation` feature
+//!
+//! [`TcpTransport`] exchanges APDUs with the framing of the APDU port of
+//! Speculos: each command is preceded by its length on 4 big-endian bytes,
+//! and each response by the length of its data, status word excluded. Host
+//! clients talking to Speculos can thus drive the application running
+//! natively.
+//!
+//! # Examples
+//!
+//! ```
+//! fn main() {
+//!     let mut comm = TcpTransport::listen("127.0.0.1:9999").unwrap();
+//!     loop {
+//!         let ins: Instruction = comm.next_command();
+//!         let status = match handle_apdu(&mut comm, ins) {
+//!             Ok(()) => StatusWords::Ok.into(),
+//!             Err(sw) => sw,
+//!         };
+//!         comm.reply(status);
+//!     }
+//! }
+//! ```
+
+extern crate std;
+
+use super::{apdu_data, ApduHeader, ApduTransport, Reply, StatusWords};
+use crate::caps::APDU_BUFFER_SIZE;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+/// Transport receiving commands from a TCP client.
+pub struct TcpTransport {
+    stream: TcpStream,
+    apdu_buffer: [u8; APDU_BUFFER_SIZE],
+    rx: usize,
+    tx: usize,
+}
+
+impl TcpTransport {
+    /// Waits for a client to connect on `address`, such as
+    /// `"127.0.0.1:9999"`.
+    pub fn listen(address: &str) -> std::io::Result<Self> {
+        let (stream, _) = TcpListener::bind(address)?.accept()?;
+        Ok(TcpTransport {
+            stream,
+            apdu_buffer: [0u8; APDU_BUFFER_SIZE],
+            rx: 0,
+            tx: 0,
+        })
+    }
+
+    /// Receives the next command into the APDU buffer. The application exits
+    /// when the client disconnects, as when the user quits it.
+    fn receive(&mut self) {
+        let mut len = [0u8; 4];
+        let received = self.stream.read_exact(&mut len).and_then(|_| {
+            let len = u32::from_be_bytes(len) as usize;
+            if len > self.apdu_buffer.len() {
+                return Err(ErrorKind::InvalidData.into());
+            }
+            self.rx = len;
+            self.stream.read_exact(&mut self.apdu_buffer[..len])
+        });
+        match received {
+            Ok(()) => self.tx = 0,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => crate::return_to_dashboard(),
+            Err(e) => panic!("APDU reception failed: {e}"),
+        }
+    }
+}
+
+impl ApduTransport for TcpTransport {
+    /// Waits for the next command. Commands which are rejected by the
+    /// conversion to `T` are replied to with the corresponding status word
+    /// and skipped.
+    fn next_command<T>(&mut self) -> T
+    where
+        T: TryFrom<ApduHeader>,
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        loop {
+            self.receive();
+            if self.rx < 4 {
+                self.reply(StatusWords::BadLen);
+                continue;
+            }
+            if let Err(sw) = self.get_data() {
+                self.reply(sw);
+                continue;
+            }
+            match T::try_from(*self.get_apdu_metadata()) {
+                Ok(ins) => return ins,
+                Err(sw) => self.reply(sw),
+            }
+        }
+    }
+
+    fn get_apdu_metadata(&self) -> &ApduHeader {
+        let ptr = &self.apdu_buffer[0] as &u8 as *const u8 as *const ApduHeader;
+        unsafe { &*ptr }
+    }
+
+    fn get_data(&self) -> Result<&[u8], StatusWords> {
+        apdu_data(&self.apdu_buffer, self.rx)
+    }
+
+    fn append(&mut self, m: &[u8]) {
+        self.apdu_buffer[self.tx..self.tx + m.len()].copy_from_slice(m);
+        self.tx += m.len();
+    }
+
+    fn reply<T: Into<Reply>>(&mut self, reply: T) {
+        let sw = reply.into().0;
+        let mut response = [0u8; 4 + APDU_BUFFER_SIZE + 2];
+        response[..4].copy_from_slice(&(self.tx as u32).to_be_bytes());
+        response[4..4 + self.tx].copy_from_slice(&self.apdu_buffer[..self.tx]);
+        response[4 + self.tx..6 + self.tx].copy_from_slice(&sw.to_be_bytes());
+        if let Err(e) = self.stream.write_all(&response[..6 + self.tx]) {
+            panic!("APDU transmission failed: {e}");
+        }
+        self.tx = 0;
+    }
+}
@@ -0,0 +1,156 @@
+//! In-memory [`ApduTransport`] used to unit test command handlers
+//!
+//! [`MockTransport`] replays a list of synthetic commands, and records the
+//! response to the last one, so that handlers can be tested without the
+//! device IO.
+
+use super::{apdu_data, ApduHeader, ApduTransport, Reply, StatusWords};
+use crate::caps::APDU_BUFFER_SIZE;
+
+/// Transport replaying synthetic commands and recording their responses.
+pub struct MockTransport<'a> {
+    commands: &'a [&'a [u8]],
+    next: usize,
+    apdu_buffer: [u8; APDU_BUFFER_SIZE],
+    rx: usize,
+    tx: usize,
+    response: [u8; APDU_BUFFER_SIZE],
+    response_len: usize,
+    sw: Option<u16>,
+}
+
+impl<'a> MockTransport<'a> {
+    /// Creates a transport delivering `commands` (complete APDUs, header included)
+    /// in order to [`ApduTransport::next_command`].
+    pub fn new(commands: &'a [&'a [u8]]) -> Self {
+        Self {
+            commands,
+            next: 0,
+            apdu_buffer: [0u8; APDU_BUFFER_SIZE],
+            rx: 0,
+            tx: 0,
+            response: [0u8; APDU_BUFFER_SIZE],
+            response_len: 0,
+            sw: None,
+        }
+    }
+
+    /// Number of commands not delivered yet.
+    pub fn remaining_commands(&self) -> usize {
+        self.commands.len() - self.next
+    }
+
+    /// Data of the last transmitted response, without the status word.
+    pub fn last_response(&self) -> &[u8] {
+        &self.response[..self.response_len]
+    }
+
+    /// Status word of the last transmitted response, if any.
+    pub fn last_sw(&self) -> Option<u16> {
+        self.sw
+    }
+}
+
+impl ApduTransport for MockTransport<'_> {
+    /// Delivers the next command. Commands which are rejected by the conversion
+    /// to `T` are replied to with the corresponding status word and skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics when all commands have been delivered.
+    fn next_command<T>(&mut self) -> T
+    where
+        T: TryFrom<ApduHeader>,
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        loop {
+            let command = self.commands.get(self.next).expect("no more commands");
+            self.next += 1;
+            self.apdu_buffer[..command.len()].copy_from_slice(command);
+            self.rx = command.len();
+            self.tx = 0;
+
+            if self.rx < 4 {
+                self.reply(StatusWords::BadLen);
+                continue;
+            }
+            if let Err(sw) = self.get_data() {
+                self.reply(sw);
+                continue;
+            }
+            match T::try_from(*self.get_apdu_metadata()) {
+                Ok(ins) => return ins,
+                Err(sw) => self.reply(sw),
+            }
+        }
+    }
+
+    fn get_apdu_metadata(&self) -> &ApduHeader {
+        let ptr = &self.apdu_buffer[0] as &u8 as *const u8 as *const ApduHeader;
+        unsafe { &*ptr }
+    }
+
+    fn get_data(&self) -> Result<&[u8], StatusWords> {
+        apdu_data(&self.apdu_buffer, self.rx)
+    }
+
+    fn append(&mut self, m: &[u8]) {
+        self.apdu_buffer[self.tx..self.tx + m.len()].copy_from_slice(m);
+        self.tx += m.len();
+    }
+
+    fn reply<T: Into<Reply>>(&mut self, reply: T) {
+        self.response[..self.tx].copy_from_slice(&self.apdu_buffer[..self.tx]);
+        self.response_len = self.tx;
+        self.sw = Some(reply.into().0);
+        self.tx = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    struct Echo;
+
+    impl TryFrom<ApduHeader> for Echo {
+        type Error = StatusWords;
+
+        fn try_from(h: ApduHeader) -> Result<Self, Self::Error> {
+            match h.ins {
+                0x01 => Ok(Echo),
+                _ => Err(StatusWords::BadIns),
+            }
+        }
+    }
+
+    fn handle_echo<C: ApduTransport>(comm: &mut C) -> Result<(), Reply> {
+        let mut data = [0u8; 16];
+        let len = {
+            let src = comm.get_data()?;
+            data[..src.len()].copy_from_slice(src);
+            src.len()
+        };
+        comm.append(&data[..len]);
+        Ok(())
+    }
+
+    #[test]
+    fn mock_transport() {
+        let mut comm = MockTransport::new(&[
+            &[0xe0, 0x02, 0x00, 0x00],
+            &[0xe0, 0x01, 0x00, 0x00, 0x02, 0xab, 0xcd],
+        ]);
+        let _: Echo = comm.next_command();
+        // The first command has been rejected
+        assert_eq!(comm.last_sw(), Some(0x6e01));
+        assert_eq!(comm.remaining_commands(), 0);
+        assert_eq!(handle_echo(&mut comm).is_ok(), true);
+        comm.reply_ok();
+        assert_eq!(comm.last_sw(), Some(0x9000));
+        assert_eq!(comm.last_response(), &[0xab, 0xcd]);
+    }
+}
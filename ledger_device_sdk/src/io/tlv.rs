@@ -0,0 +1,300 @@
+//! TLV (tag, length, value) encoding of APDU payloads
+//!
+//! Several Ledger protocols (trusted names, token information, swap payloads...)
+//! encode their payloads as a sequence of TLV fields, where both the tag and the
+//! length are DER encoded integers: a single byte below `0x80`, or `0x80 | n`
+//! followed by `n` big-endian bytes otherwise.
+//!
+//! # Examples
+//!
+//! ```
+//! const TAG_VERSION: u32 = 0x00;
+//! const TAG_NAME: u32 = 0x20;
+//!
+//! let mut version = None;
+//! let mut name = None;
+//! for field in TlvReader::new(comm.get_data()?) {
+//!     let field = field?;
+//!     match field.tag {
+//!         TAG_VERSION => version = Some(field.as_u8()?),
+//!         TAG_NAME => name = Some(field.as_str()?),
+//!         _ => (),
+//!     }
+//! }
+//! ```
+
+use super::{Reply, StatusWords, TooLong};
+
+/// Maximum number of bytes of a DER encoded integer, after the first byte.
+const MAX_DER_INT_LEN: usize = 4;
+
+/// Errors returned when decoding TLV payloads.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TlvError {
+    /// The payload ends in the middle of a field
+    Truncated,
+    /// A tag or a length is not a valid DER encoded integer
+    InvalidEncoding,
+    /// A value does not have the expected length or format
+    InvalidValue,
+}
+
+impl From<TlvError> for Reply {
+    fn from(_: TlvError) -> Reply {
+        StatusWords::BadLen.into()
+    }
+}
+
+/// A TLV field, borrowing its value from the payload.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Tlv<'a> {
+    pub tag: u32,
+    pub value: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// Decodes a big-endian unsigned integer value of at most `N` bytes.
+    fn as_uint<const N: usize>(&self) -> Result<[u8; N], TlvError> {
+        if self.value.is_empty() || self.value.len() > N {
+            return Err(TlvError::InvalidValue);
+        }
+        let mut bytes = [0u8; N];
+        bytes[N - self.value.len()..].copy_from_slice(self.value);
+        Ok(bytes)
+    }
+
+    /// Decodes the value as a `u8`.
+    pub fn as_u8(&self) -> Result<u8, TlvError> {
+        self.as_uint::<1>().map(u8::from_be_bytes)
+    }
+
+    /// Decodes the value as a big-endian `u16`, encoded on 1 or 2 bytes.
+    pub fn as_u16(&self) -> Result<u16, TlvError> {
+        self.as_uint::<2>().map(u16::from_be_bytes)
+    }
+
+    /// Decodes the value as a big-endian `u32`, encoded on 1 to 4 bytes.
+    pub fn as_u32(&self) -> Result<u32, TlvError> {
+        self.as_uint::<4>().map(u32::from_be_bytes)
+    }
+
+    /// Decodes the value as a big-endian `u64`, encoded on 1 to 8 bytes.
+    pub fn as_u64(&self) -> Result<u64, TlvError> {
+        self.as_uint::<8>().map(u64::from_be_bytes)
+    }
+
+    /// Decodes a one byte boolean value, which must be `0` or `1`.
+    pub fn as_bool(&self) -> Result<bool, TlvError> {
+        match self.value {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(TlvError::InvalidValue),
+        }
+    }
+
+    /// Decodes the value as an UTF-8 string.
+    pub fn as_str(&self) -> Result<&'a str, TlvError> {
+        core::str::from_utf8(self.value).map_err(|_| TlvError::InvalidValue)
+    }
+
+    /// Returns the value, which must be at most `max_len` bytes long.
+    pub fn as_bytes(&self, max_len: usize) -> Result<&'a [u8], TlvError> {
+        if self.value.len() > max_len {
+            return Err(TlvError::InvalidValue);
+        }
+        Ok(self.value)
+    }
+}
+
+/// Iterator over the TLV fields of a payload.
+///
+/// Iteration stops after the first error.
+pub struct TlvReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    failed: bool,
+}
+
+impl<'a> TlvReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            offset: 0,
+            failed: false,
+        }
+    }
+
+    /// Returns the first field with the given tag, if any.
+    pub fn find(self, tag: u32) -> Result<Option<Tlv<'a>>, TlvError> {
+        for field in self {
+            let field = field?;
+            if field.tag == tag {
+                return Ok(Some(field));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the offset of the next field in the payload.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Decodes a DER encoded integer at the current offset.
+    fn read_der_int(&mut self) -> Result<u32, TlvError> {
+        let first = *self.data.get(self.offset).ok_or(TlvError::Truncated)?;
+        self.offset += 1;
+        if first < 0x80 {
+            return Ok(first as u32);
+        }
+        let len = (first & 0x7f) as usize;
+        if len == 0 || len > MAX_DER_INT_LEN {
+            return Err(TlvError::InvalidEncoding);
+        }
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or(TlvError::Truncated)?;
+        self.offset += len;
+        Ok(bytes.iter().fold(0, |acc, b| (acc << 8) | *b as u32))
+    }
+
+    fn read_field(&mut self) -> Result<Tlv<'a>, TlvError> {
+        let tag = self.read_der_int()?;
+        let len = self.read_der_int()? as usize;
+        let end = self.offset.checked_add(len).ok_or(TlvError::Truncated)?;
+        let value = self.data.get(self.offset..end).ok_or(TlvError::Truncated)?;
+        self.offset = end;
+        Ok(Tlv { tag, value })
+    }
+}
+
+impl<'a> Iterator for TlvReader<'a> {
+    type Item = Result<Tlv<'a>, TlvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset >= self.data.len() {
+            return None;
+        }
+        let res = self.read_field();
+        self.failed = res.is_err();
+        Some(res)
+    }
+}
+
+/// Serializes TLV fields into a buffer.
+pub struct TlvWriter<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> TlvWriter<'a> {
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self { buffer, len: 0 }
+    }
+
+    /// Returns the serialized fields.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// DER encodes `value` into `out`, and returns the encoded length.
+    fn encode_der_int(value: u32, out: &mut [u8; MAX_DER_INT_LEN + 1]) -> usize {
+        if value < 0x80 {
+            out[0] = value as u8;
+            return 1;
+        }
+        let bytes = value.to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        let len = MAX_DER_INT_LEN - skip;
+        out[0] = 0x80 | len as u8;
+        out[1..1 + len].copy_from_slice(&bytes[skip..]);
+        1 + len
+    }
+
+    /// Appends a field, or nothing if it does not fit in the buffer.
+    pub fn write(&mut self, tag: u32, value: &[u8]) -> Result<(), TooLong> {
+        let mut tag_bytes = [0u8; MAX_DER_INT_LEN + 1];
+        let tag_len = Self::encode_der_int(tag, &mut tag_bytes);
+        let mut len_bytes = [0u8; MAX_DER_INT_LEN + 1];
+        let len_len = Self::encode_der_int(value.len() as u32, &mut len_bytes);
+
+        let total = tag_len + len_len + value.len();
+        if total > self.buffer.len() - self.len {
+            return Err(TooLong);
+        }
+        let mut offset = self.len;
+        for part in [&tag_bytes[..tag_len], &len_bytes[..len_len], value] {
+            self.buffer[offset..offset + part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+        self.len = offset;
+        Ok(())
+    }
+
+    /// Appends a field with a one byte value.
+    pub fn write_u8(&mut self, tag: u32, value: u8) -> Result<(), TooLong> {
+        self.write(tag, &[value])
+    }
+
+    /// Appends a field with a big-endian `u16` value.
+    pub fn write_u16(&mut self, tag: u32, value: u16) -> Result<(), TooLong> {
+        self.write(tag, &value.to_be_bytes())
+    }
+
+    /// Appends a field with a big-endian `u32` value.
+    pub fn write_u32(&mut self, tag: u32, value: u32) -> Result<(), TooLong> {
+        self.write(tag, &value.to_be_bytes())
+    }
+
+    /// Appends a field with a big-endian `u64` value.
+    pub fn write_u64(&mut self, tag: u32, value: u64) -> Result<(), TooLong> {
+        self.write(tag, &value.to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn tlv_roundtrip() {
+        let mut buffer = [0u8; 300];
+        let mut writer = TlvWriter::new(&mut buffer);
+        assert_eq!(writer.write_u8(0x01, 0x2a), Ok(()));
+        assert_eq!(writer.write(0x1234, &[0xab; 200]), Ok(()));
+        assert_eq!(writer.write(0x02, b"name"), Ok(()));
+        // tag (3 bytes) + length (2 bytes)
+        assert_eq!(writer.as_bytes().len(), 3 + 205 + 6);
+
+        let mut reader = TlvReader::new(writer.as_bytes());
+        assert_eq!(reader.next().unwrap().unwrap().as_u8(), Ok(0x2a));
+        let field = reader.next().unwrap().unwrap();
+        assert_eq!(field.tag, 0x1234);
+        assert_eq!(field.value.len(), 200);
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), Ok("name"));
+        assert_eq!(reader.next().is_none(), true);
+    }
+
+    #[test]
+    fn tlv_errors() {
+        let mut reader = TlvReader::new(&[0x01, 0x03, 0xaa]);
+        assert_eq!(reader.next(), Some(Err(TlvError::Truncated)));
+        assert_eq!(reader.next(), None);
+        assert_eq!(
+            TlvReader::new(&[0x01, 0x85, 0, 0, 0, 0, 1]).next(),
+            Some(Err(TlvError::InvalidEncoding))
+        );
+        let field = Tlv {
+            tag: 1,
+            value: &[1, 2, 3],
+        };
+        assert_eq!(field.as_u16(), Err(TlvError::InvalidValue));
+        assert_eq!(field.as_u32(), Ok(0x010203));
+        let mut buffer = [0u8; 4];
+        assert_eq!(TlvWriter::new(&mut buffer).write(1, &[0; 3]), Err(TooLong));
+    }
+}
@@ -7,18 +7,52 @@
 #![feature(generic_const_exprs)]
 #![feature(cfg_version)]
 
+pub mod amount;
+pub mod app_metadata;
+#[cfg(not(target_os = "nanos"))]
+pub mod attestation;
 #[cfg(target_os = "nanox")]
 pub mod ble;
+pub mod cal;
+pub mod caps;
 
 #[cfg(feature = "ccid")]
 pub mod ccid;
+pub mod collections;
+#[cfg(feature = "debug_serial")]
+pub mod debug_serial;
+pub mod device_info;
+pub mod display;
 pub mod ecc;
+pub mod encoding;
+pub mod error;
+pub mod error_context;
 pub mod hash;
+#[cfg(any(feature = "heap", not(target_os = "nanos")))]
+pub mod heap;
+pub mod i18n;
+pub mod inactivity;
 pub mod io;
+pub mod libcall;
+#[cfg(feature = "log")]
+pub mod logger;
+#[cfg(not(feature = "host"))]
+pub mod memory;
 pub mod nvm;
+mod panic_diagnostics;
+pub mod power;
 pub mod random;
+pub mod registry;
 pub mod screen;
+pub mod secret;
+#[cfg(not(target_os = "nanos"))]
+pub mod secure_channel;
+pub mod security;
 pub mod seph;
+#[cfg(not(feature = "host"))]
+pub mod stack;
+pub mod time;
+pub mod timers;
 
 pub mod testing;
 
@@ -26,23 +60,80 @@ pub mod testing;
 pub mod nbgl;
 #[cfg(not(any(target_os = "stax", target_os = "flex")))]
 pub mod ui;
+pub mod ui_flows;
+#[cfg(feature = "ui_recording")]
+pub mod ui_recording;
+pub mod update;
 
 pub mod uxapp;
+pub mod watchdog;
+
+// The demo mode approves reviews without the user, and injected events can
+// do the same: keep both out of release builds for devices.
+#[cfg(all(feature = "demo", not(any(debug_assertions, feature = "speculos"))))]
+compile_error!(
+    "the `demo` feature approves reviews, it requires a debug build or the `speculos` feature"
+);
+#[cfg(all(
+    feature = "seph_injection",
+    not(any(debug_assertions, feature = "speculos"))
+))]
+compile_error!(
+    "the `seph_injection` feature fakes user inputs, it requires a debug build or the `speculos` feature"
+);
 
 use core::panic::PanicInfo;
 
-/// In case of runtime problems, return an internal error and exit the app
+/// In case of runtime problems, return an internal error and exit the app.
+///
+/// In debug builds, once the error is replied, so that automated clients do
+/// not wait for the user, the panic message and location are displayed on
+/// screen until the user dismisses them, and written to the Speculos console
+/// and the debug serial interface when the `speculos` and `debug_serial`
+/// features are enabled.
 #[inline]
-pub fn exiting_panic(_info: &PanicInfo) -> ! {
+pub fn exiting_panic(info: &PanicInfo) -> ! {
     let mut comm = io::Comm::new();
     comm.reply(io::StatusWords::Panic);
-    ledger_secure_sdk_sys::exit_app(0);
+    if cfg!(debug_assertions) {
+        panic_diagnostics::show(info);
+    }
+    exit(ExitCode::Failure);
+}
+
+/// Status of the application when it exits, reported to the OS.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ExitCode {
+    /// The application has completed normally, for instance when the user
+    /// quits it or a library call has ended
+    Success = 0,
+    /// The application has been stopped by an error, such as a panic
+    Failure = 1,
 }
 
-// re-export exit_app
+/// Exits the application with `code`, returning to the dashboard, or to the
+/// caller of a library call.
+pub fn exit(code: ExitCode) -> ! {
+    ledger_secure_sdk_sys::exit_app(code as u8)
+}
+
+/// Exits the application and returns to the dashboard, as when the user
+/// quits the application.
+pub fn return_to_dashboard() -> ! {
+    exit(ExitCode::Success)
+}
+
+// re-export exit_app, prefer `exit` with an `ExitCode`
 pub use ledger_secure_sdk_sys::buttons;
 pub use ledger_secure_sdk_sys::exit_app;
 
+pub use error::Error;
+
+// re-export include_gif, used by the flow_glyph macro
+#[doc(hidden)]
+pub use include_gif::include_gif;
+
 /// Helper macro that sets an external panic handler
 /// as the project's current panic handler
 #[macro_export]
@@ -56,16 +147,36 @@ macro_rules! set_panic {
     };
 }
 
+/// Writes formatted data to the USB CDC-ACM debug interface.
+/// Does nothing unless the `debug_serial` feature is enabled.
+#[cfg(not(feature = "debug_serial"))]
+#[macro_export]
+macro_rules! debug_write {
+    ($($arg:tt)*) => {{}};
+}
+
+/// Writes formatted data followed by a newline to the USB CDC-ACM debug interface.
+/// Does nothing unless the `debug_serial` feature is enabled.
+#[cfg(not(feature = "debug_serial"))]
+#[macro_export]
+macro_rules! debug_writeln {
+    ($($arg:tt)*) => {{}};
+}
+
+#[cfg(not(feature = "host"))]
 extern "C" {
-    fn c_main();
+    fn c_main(arg0: u32);
 }
 
+#[cfg(not(feature = "host"))]
 #[link_section = ".boot"]
 #[no_mangle]
-pub extern "C" fn _start() -> ! {
-    // Main is in C until the try_context can be set properly from Rust
-    unsafe { c_main() };
-    ledger_secure_sdk_sys::exit_app(1);
+pub extern "C" fn _start(arg0: u32) -> ! {
+    // Main is in C until the try_context can be set properly from Rust.
+    // `arg0` is non-zero when the application is started as a library
+    // through `os_lib_call`, and is forwarded to `sample_main`.
+    unsafe { c_main(arg0) };
+    exit(ExitCode::Failure);
 }
 
 /// Data wrapper to force access through address translation with [`pic_rs`] or
@@ -133,7 +244,7 @@ impl<T> NVMData<T> {
     /// This is necessary when using the `rwpi` relocation model,
     /// because a static mutable will be assumed to be located in
     /// RAM, and be accessed through the static base (r9)
-    #[cfg(not(target_os = "nanos"))]
+    #[cfg(all(not(target_os = "nanos"), not(feature = "host")))]
     pub fn get_mut(&mut self) -> &mut T {
         use core::arch::asm;
         unsafe {
@@ -149,6 +260,12 @@ impl<T> NVMData<T> {
             &mut *pic_addr.cast()
         }
     }
+
+    /// NVM is emulated in RAM when building for the host.
+    #[cfg(feature = "host")]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
 }
 
 #[cfg(test)]
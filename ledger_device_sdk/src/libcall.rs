@@ -0,0 +1,275 @@
+//! Support for applications started as a library by another application
+//!
+//! When an application is started through `os_lib_call` (for instance by the
+//! Exchange application during a swap), `sample_main` receives a non-zero
+//! argument which is a pointer to a `libargs_t` structure owned by the caller.
+//!
+//! # Examples
+//!
+//! ```
+//! #[no_mangle]
+//! extern "C" fn sample_main(arg0: u32) {
+//!     if arg0 != 0 {
+//!         match libcall::get_command(arg0) {
+//!             LibCallCommand::SwapCheckAddress => { ... }
+//!             LibCallCommand::SwapGetPrintableAmount => { ... }
+//!             LibCallCommand::SwapSignTransaction => {
+//!                 libcall::init_io();
+//!                 ...
+//!             }
+//!         }
+//!     }
+//!     ...
+//! }
+//! ```
+//!
+//! Coin applications supporting swaps can instead implement
+//! [`swap::SwapHandler`] and let [`swap::handle`] decode the call and return
+//! the result. Applications exposing their own commands use [`LibraryCall`],
+//! and other applications call them with [`call`]. Main applications
+//! delegate the parsing of payloads to [`plugin`]s, which are built on these.
+
+pub mod plugin;
+pub mod swap;
+
+use crate::collections::ArrayVec;
+
+/// Identifier expected in the `id` field of the library call arguments.
+const LIBCALL_ID: u32 = 0x100;
+
+const SIGN_TRANSACTION: u32 = 2;
+const CHECK_ADDRESS: u32 = 3;
+const GET_PRINTABLE_AMOUNT: u32 = 4;
+
+/// Library call commands an application can be started with.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LibCallCommand {
+    SwapSignTransaction,
+    SwapGetPrintableAmount,
+    SwapCheckAddress,
+}
+
+impl TryFrom<u32> for LibCallCommand {
+    type Error = ();
+    fn try_from(command: u32) -> Result<LibCallCommand, ()> {
+        match command {
+            SIGN_TRANSACTION => Ok(LibCallCommand::SwapSignTransaction),
+            GET_PRINTABLE_AMOUNT => Ok(LibCallCommand::SwapGetPrintableAmount),
+            CHECK_ADDRESS => Ok(LibCallCommand::SwapCheckAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+/// FFI layout of the C SDK `libargs_t` structure. The last field is an union
+/// of pointers to the command-specific parameters.
+#[repr(C)]
+pub(crate) struct LibArgs {
+    pub id: u32,
+    pub command: u32,
+    pub unused: u32,
+    pub parameters: *mut core::ffi::c_void,
+}
+
+/// Returns a reference to the library call arguments pointed to by `arg0`.
+///
+/// # Panics
+///
+/// Panics if `arg0` is null or does not point to valid library call arguments.
+pub(crate) fn libargs(arg0: u32) -> &'static LibArgs {
+    assert!(arg0 != 0, "not started as a library");
+    let args = unsafe { &*(arg0 as *const LibArgs) };
+    assert!(args.id == LIBCALL_ID, "invalid library call arguments");
+    args
+}
+
+/// Decodes the command the application has been started with.
+///
+/// # Arguments
+///
+/// * `arg0` - Argument received by `sample_main`.
+///
+/// # Panics
+///
+/// Panics if `arg0` does not point to valid library call arguments, or if the
+/// command is unknown.
+pub fn get_command(arg0: u32) -> LibCallCommand {
+    LibCallCommand::try_from(libargs(arg0).command).expect("unknown library call command")
+}
+
+#[cfg(not(feature = "host"))]
+extern "C" {
+    fn c_reset_io();
+}
+
+/// Initializes the IO of an application started as a library, before it
+/// exchanges APDUs with the host, as when signing a swap transaction.
+///
+/// The IO is not initialized at startup in that case, since the caller may
+/// still be using the USB or BLE link, for instance while the Exchange
+/// application checks an address or formats an amount.
+pub fn init_io() {
+    #[cfg(not(feature = "host"))]
+    unsafe {
+        c_reset_io();
+    }
+}
+
+/// Error returned when entering or issuing a library call.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LibCallError {
+    /// The application was not started as a library
+    NotLibraryCall,
+    /// The arguments do not have the expected identifier
+    InvalidArguments,
+    /// A library call is already being handled
+    AlreadyEntered,
+    /// A parameter is longer than the buffer it is copied to
+    ParameterTooLong,
+}
+
+/// Set while a [`LibraryCall`] is alive.
+static mut LIBRARY_CALL_ENTERED: bool = false;
+
+/// Library call being handled by the application, for applications exposing
+/// their own commands to other applications.
+///
+/// Only one library call can be handled at a time. The call ends, returning
+/// control to the caller, with [`LibraryCall::end`].
+///
+/// # Examples
+///
+/// ```
+/// #[no_mangle]
+/// extern "C" fn sample_main(arg0: u32) {
+///     if let Ok(mut call) = LibraryCall::enter(arg0) {
+///         if call.command() == DERIVE_KEY {
+///             let params = unsafe { call.parameters::<DeriveKeyParams>() }.unwrap();
+///             params.result = derive_key(params.path);
+///         }
+///         call.end();
+///     }
+///     ...
+/// }
+/// ```
+pub struct LibraryCall {
+    args: &'static LibArgs,
+}
+
+impl LibraryCall {
+    /// Decodes the library call arguments pointed to by `arg0`, the argument
+    /// received by `sample_main`.
+    pub fn enter(arg0: u32) -> Result<LibraryCall, LibCallError> {
+        if arg0 == 0 {
+            return Err(LibCallError::NotLibraryCall);
+        }
+        let args = unsafe { &*(arg0 as *const LibArgs) };
+        if args.id != LIBCALL_ID {
+            return Err(LibCallError::InvalidArguments);
+        }
+        unsafe {
+            if LIBRARY_CALL_ENTERED {
+                return Err(LibCallError::AlreadyEntered);
+            }
+            LIBRARY_CALL_ENTERED = true;
+        }
+        Ok(LibraryCall { args })
+    }
+
+    /// Command requested by the caller.
+    pub fn command(&self) -> u32 {
+        self.args.command
+    }
+
+    /// Returns the command parameters, or `None` if the caller passed none.
+    ///
+    /// # Safety
+    ///
+    /// `T` must match the layout of the parameters the caller passes with
+    /// [`Self::command`]. The parameters live in the caller memory, which
+    /// remains valid until the call ends.
+    pub unsafe fn parameters<T>(&mut self) -> Option<&mut T> {
+        (self.args.parameters as *mut T).as_mut()
+    }
+
+    /// Ends the library call, returning control to the caller. Results must
+    /// have been written into the parameters beforehand.
+    pub fn end(self) -> ! {
+        unsafe {
+            LIBRARY_CALL_ENTERED = false;
+            ledger_secure_sdk_sys::os_lib_end();
+        }
+        crate::exit(crate::ExitCode::Success);
+    }
+}
+
+/// Allows later library calls when the call is dropped without being ended,
+/// for instance on an early return.
+impl Drop for LibraryCall {
+    fn drop(&mut self) {
+        unsafe {
+            LIBRARY_CALL_ENTERED = false;
+        }
+    }
+}
+
+/// Starts the installed application `app_name` as a library with `command`
+/// and `parameters`, and returns once it has ended the call. Results are
+/// read from `parameters`, whose layout is defined by the called application.
+///
+/// Library calls cannot be issued while handling one.
+///
+/// # Safety
+///
+/// `parameters` must point to parameters the called application expects
+/// with `command`, and remain valid until the call returns.
+pub unsafe fn call(
+    app_name: &core::ffi::CStr,
+    command: u32,
+    parameters: *mut core::ffi::c_void,
+) -> Result<(), LibCallError> {
+    if LIBRARY_CALL_ENTERED {
+        return Err(LibCallError::AlreadyEntered);
+    }
+    // The OS passes the arguments following the name to the called
+    // application, with the `libargs_t` layout.
+    let mut call_parameters: [u32; 5] = [
+        app_name.as_ptr() as u32,
+        LIBCALL_ID,
+        command,
+        0,
+        parameters as u32,
+    ];
+    ledger_secure_sdk_sys::os_lib_call(call_parameters.as_mut_ptr());
+    Ok(())
+}
+
+/// Copies the NUL-terminated C string pointed to by `src`, which must not be
+/// longer than `N` bytes.
+pub(crate) unsafe fn copy_c_str<const N: usize>(
+    src: *const u8,
+) -> Result<ArrayVec<u8, N>, LibCallError> {
+    let mut dst = ArrayVec::new();
+    if !src.is_null() {
+        let mut i = 0;
+        while *src.add(i) != 0 {
+            dst.push(*src.add(i))
+                .map_err(|_| LibCallError::ParameterTooLong)?;
+            i += 1;
+        }
+    }
+    Ok(dst)
+}
+
+/// Copies `len` bytes pointed to by `src`, which must not be more than `N`.
+pub(crate) unsafe fn copy_bytes<const N: usize>(
+    src: *const u8,
+    len: usize,
+) -> Result<ArrayVec<u8, N>, LibCallError> {
+    let mut dst = ArrayVec::new();
+    if !src.is_null() {
+        dst.extend_from_slice(core::slice::from_raw_parts(src, len))
+            .map_err(|_| LibCallError::ParameterTooLong)?;
+    }
+    Ok(dst)
+}
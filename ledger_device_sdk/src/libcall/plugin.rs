@@ -0,0 +1,433 @@
+//! Plugins parsing payloads on behalf of another application
+//!
+//! In the way of the plugins of the Ethereum application, a main application
+//! delegates the parsing of the payloads it does not know, such as the calls
+//! to a specific smart contract, to a plugin: an application installed
+//! separately and started as a library. The plugin parses the payload as it
+//! is received, and generates the screens of its review, which the main
+//! application displays.
+//!
+//! The main application drives the plugin with a [`Plugin`], which issues a
+//! library call for each step:
+//!
+//! 1. `init` with the header of the payload, such as a method selector: the
+//!    plugin tells whether it handles the payload, and initializes its
+//!    context,
+//! 2. `provide_data` with each chunk of the payload,
+//! 3. `finalize`: the plugin returns the number of screens of the review,
+//! 4. `screen` for each screen, which the plugin formats into a title and a
+//!    value.
+//!
+//! The plugin keeps its state between the calls in a context of at most
+//! [`CONTEXT_SIZE`] bytes, which is stored by the main application. The
+//! plugin implements [`PluginHandler`] and runs it with [`handle`]. Both sides
+//! share the layout of the parameters of the library calls, versioned with
+//! [`INTERFACE_VERSION`].
+//!
+//! # Examples
+//!
+//! In the main application:
+//!
+//! ```
+//! let mut plugin = Plugin::new(c"Crab Plugin");
+//! plugin.init(&payload[..4])?;
+//! for (i, chunk) in payload[4..].chunks(32).enumerate() {
+//!     plugin.provide_data(i * 32, chunk)?;
+//! }
+//! let screens = plugin.finalize()?;
+//! for index in 0..screens {
+//!     let screen = plugin.screen(index)?;
+//!     fields.push(screen)?;
+//! }
+//! ```
+//!
+//! In the plugin:
+//!
+//! ```
+//! #[no_mangle]
+//! extern "C" fn sample_main(arg0: u32) {
+//!     if let Ok(call) = LibraryCall::enter(arg0) {
+//!         plugin::handle(call, &mut CrabPlugin);
+//!     }
+//!     ...
+//! }
+//! ```
+
+use super::{copy_c_str, LibCallError, LibraryCall};
+use crate::collections::ArrayString;
+use crate::display::Field;
+use core::ffi::CStr;
+
+/// Version of the layout of the library call parameters. Plugins reply
+/// [`PluginError::Unsupported`] to main applications using another version.
+pub const INTERFACE_VERSION: u32 = 1;
+
+/// Maximum size of the context of a plugin
+pub const CONTEXT_SIZE: usize = 160;
+/// Maximum length of the title of a screen
+pub const MAX_TITLE_LEN: usize = 32;
+/// Maximum length of the value of a screen
+pub const MAX_VALUE_LEN: usize = 128;
+
+const PLUGIN_INIT: u32 = 0x0101;
+const PLUGIN_PROVIDE_DATA: u32 = 0x0102;
+const PLUGIN_FINALIZE: u32 = 0x0103;
+const PLUGIN_QUERY_SCREEN: u32 = 0x0104;
+
+/// Result codes written by the plugin. The main application initializes the
+/// result to `RESULT_UNAVAILABLE`, which is left as is if the called
+/// application is not a plugin.
+const RESULT_UNAVAILABLE: u32 = 0;
+const RESULT_OK: u32 = 1;
+const RESULT_UNSUPPORTED: u32 = 2;
+const RESULT_INVALID_DATA: u32 = 3;
+
+/// Error of a plugin, or of the library call to a plugin.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PluginError {
+    /// The plugin does not handle the payload, or uses another version of
+    /// the interface
+    Unsupported,
+    /// The payload is invalid, or a screen does not exist
+    InvalidData,
+    /// The called application did not answer as a plugin
+    NotAnswered,
+    /// The plugin could not be called
+    LibCall(LibCallError),
+}
+
+impl From<LibCallError> for PluginError {
+    fn from(e: LibCallError) -> PluginError {
+        PluginError::LibCall(e)
+    }
+}
+
+/// Context of a plugin, stored by the main application.
+#[repr(C, align(8))]
+#[derive(Copy, Clone)]
+struct Context([u8; CONTEXT_SIZE]);
+
+/// FFI layout of the parameters of the plugin library calls, shared by the
+/// main application and the plugin.
+#[repr(C)]
+struct PluginParams {
+    version: u32,
+    result: u32,
+    context: *mut Context,
+    data: *const u8,
+    data_len: u32,
+    /// Offset of the data in the payload, for `PLUGIN_PROVIDE_DATA`
+    offset: u32,
+    /// Index of the requested screen for `PLUGIN_QUERY_SCREEN`, or number of
+    /// screens returned by `PLUGIN_FINALIZE`
+    screen: u32,
+    title: [u8; MAX_TITLE_LEN + 1],
+    value: [u8; MAX_VALUE_LEN + 1],
+}
+
+impl PluginParams {
+    fn new(context: &mut Context) -> PluginParams {
+        PluginParams {
+            version: INTERFACE_VERSION,
+            result: RESULT_UNAVAILABLE,
+            context,
+            data: core::ptr::null(),
+            data_len: 0,
+            offset: 0,
+            screen: 0,
+            title: [0; MAX_TITLE_LEN + 1],
+            value: [0; MAX_VALUE_LEN + 1],
+        }
+    }
+
+    fn result(&self) -> Result<(), PluginError> {
+        match self.result {
+            RESULT_OK => Ok(()),
+            RESULT_UNSUPPORTED => Err(PluginError::Unsupported),
+            RESULT_INVALID_DATA => Err(PluginError::InvalidData),
+            _ => Err(PluginError::NotAnswered),
+        }
+    }
+
+    fn set_result(&mut self, result: Result<(), PluginError>) {
+        self.result = match result {
+            Ok(()) => RESULT_OK,
+            Err(PluginError::Unsupported) => RESULT_UNSUPPORTED,
+            Err(_) => RESULT_INVALID_DATA,
+        }
+    }
+}
+
+/// Title and value of a screen generated by a plugin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Screen {
+    pub title: ArrayString<MAX_TITLE_LEN>,
+    pub value: ArrayString<MAX_VALUE_LEN>,
+}
+
+impl Screen {
+    /// Returns the screen as a review field.
+    pub fn field(&self) -> Field<'_> {
+        Field {
+            name: self.title.as_str(),
+            value: self.value.as_str(),
+        }
+    }
+}
+
+/// Reads the NUL-terminated UTF-8 string of `bytes`.
+fn read_str<const N: usize>(bytes: &[u8]) -> Result<ArrayString<N>, PluginError> {
+    let bytes = unsafe { copy_c_str::<N>(bytes.as_ptr()) }.map_err(|_| PluginError::InvalidData)?;
+    let text = core::str::from_utf8(&bytes).map_err(|_| PluginError::InvalidData)?;
+    ArrayString::try_from(text).map_err(|_| PluginError::InvalidData)
+}
+
+/// Plugin called by the main application, see the [module](self)
+/// documentation.
+pub struct Plugin<'a> {
+    name: &'a CStr,
+    context: Context,
+}
+
+impl<'a> Plugin<'a> {
+    /// Creates a plugin calling the installed application `name`.
+    pub fn new(name: &'a CStr) -> Plugin<'a> {
+        Plugin {
+            name,
+            context: Context([0; CONTEXT_SIZE]),
+        }
+    }
+
+    fn call(&self, command: u32, params: &mut PluginParams) -> Result<(), PluginError> {
+        unsafe {
+            super::call(
+                self.name,
+                command,
+                params as *mut PluginParams as *mut core::ffi::c_void,
+            )?;
+        }
+        params.result()
+    }
+
+    /// Starts the parsing of a payload, whose `header` tells the plugin
+    /// whether it handles it.
+    pub fn init(&mut self, header: &[u8]) -> Result<(), PluginError> {
+        let mut params = PluginParams::new(&mut self.context);
+        params.data = header.as_ptr();
+        params.data_len = header.len() as u32;
+        self.call(PLUGIN_INIT, &mut params)
+    }
+
+    /// Provides the plugin with `data`, at `offset` in the payload.
+    pub fn provide_data(&mut self, offset: usize, data: &[u8]) -> Result<(), PluginError> {
+        let mut params = PluginParams::new(&mut self.context);
+        params.data = data.as_ptr();
+        params.data_len = data.len() as u32;
+        params.offset = offset as u32;
+        self.call(PLUGIN_PROVIDE_DATA, &mut params)
+    }
+
+    /// Ends the parsing of the payload, and returns the number of screens
+    /// of its review.
+    pub fn finalize(&mut self) -> Result<u8, PluginError> {
+        let mut params = PluginParams::new(&mut self.context);
+        self.call(PLUGIN_FINALIZE, &mut params)?;
+        u8::try_from(params.screen).map_err(|_| PluginError::InvalidData)
+    }
+
+    /// Returns the screen `index` of the review, generated by the plugin.
+    pub fn screen(&mut self, index: u8) -> Result<Screen, PluginError> {
+        let mut params = PluginParams::new(&mut self.context);
+        params.screen = index as u32;
+        self.call(PLUGIN_QUERY_SCREEN, &mut params)?;
+        Ok(Screen {
+            title: read_str(&params.title)?,
+            value: read_str(&params.value)?,
+        })
+    }
+}
+
+/// Parsing of payloads, implemented by plugins and run by [`handle`].
+pub trait PluginHandler {
+    /// State of the parsing, kept by the main application between the calls.
+    /// It must fit in [`CONTEXT_SIZE`] bytes, be aligned on at most 8 bytes,
+    /// and hold no pointer, since the plugin memory is not kept between the
+    /// calls.
+    type Context: Copy;
+
+    /// Returns the initial context if the plugin handles the payload with
+    /// `header`, or [`PluginError::Unsupported`] otherwise.
+    fn init(&mut self, header: &[u8]) -> Result<Self::Context, PluginError>;
+
+    /// Parses `data`, found at `offset` in the payload.
+    fn provide_data(
+        &mut self,
+        context: &mut Self::Context,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<(), PluginError>;
+
+    /// Checks that the payload is complete, and returns the number of screens
+    /// of its review.
+    fn finalize(&mut self, context: &mut Self::Context) -> Result<u8, PluginError>;
+
+    /// Formats the screen `index` of the review into `title` and `value`.
+    fn screen(
+        &mut self,
+        context: &Self::Context,
+        index: u8,
+        title: &mut ArrayString<MAX_TITLE_LEN>,
+        value: &mut ArrayString<MAX_VALUE_LEN>,
+    ) -> Result<(), PluginError>;
+}
+
+/// Copies `text` into `out` with a NUL terminator.
+fn write_str(text: &str, out: &mut [u8]) {
+    out[..text.len()].copy_from_slice(text.as_bytes());
+    out[text.len()] = 0;
+}
+
+/// Runs the step `command` of `handler` with `params`, and writes its result.
+fn dispatch<H: PluginHandler>(command: u32, params: &mut PluginParams, handler: &mut H) {
+    const {
+        assert!(core::mem::size_of::<H::Context>() <= CONTEXT_SIZE);
+        assert!(core::mem::align_of::<H::Context>() <= core::mem::align_of::<Context>());
+    }
+
+    if params.version != INTERFACE_VERSION {
+        params.set_result(Err(PluginError::Unsupported));
+        return;
+    }
+    let context = params.context as *mut H::Context;
+    let data = if params.data.is_null() {
+        &[][..]
+    } else {
+        unsafe { core::slice::from_raw_parts(params.data, params.data_len as usize) }
+    };
+    let result = match command {
+        PLUGIN_INIT => handler
+            .init(data)
+            .map(|initial| unsafe { context.write(initial) }),
+        PLUGIN_PROVIDE_DATA => {
+            handler.provide_data(unsafe { &mut *context }, params.offset as usize, data)
+        }
+        PLUGIN_FINALIZE => handler
+            .finalize(unsafe { &mut *context })
+            .map(|screens| params.screen = screens as u32),
+        PLUGIN_QUERY_SCREEN => {
+            let mut title = ArrayString::new();
+            let mut value = ArrayString::new();
+            let index = u8::try_from(params.screen).map_err(|_| PluginError::InvalidData);
+            index
+                .and_then(|index| {
+                    handler.screen(unsafe { &*context }, index, &mut title, &mut value)
+                })
+                .map(|()| {
+                    write_str(&title, &mut params.title);
+                    write_str(&value, &mut params.value);
+                })
+        }
+        _ => Err(PluginError::Unsupported),
+    };
+    params.set_result(result);
+}
+
+/// Runs the step of the plugin requested by the main application with
+/// `handler`, and ends the library call.
+///
+/// Commands which are not plugin commands are replied as unsupported if
+/// the caller passed parameters.
+pub fn handle<H: PluginHandler>(mut call: LibraryCall, handler: &mut H) -> ! {
+    let command = call.command();
+    if let Some(params) = unsafe { call.parameters::<PluginParams>() } {
+        dispatch(command, params, handler);
+    }
+    call.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    struct SumPlugin;
+
+    impl PluginHandler for SumPlugin {
+        type Context = u32;
+
+        fn init(&mut self, header: &[u8]) -> Result<u32, PluginError> {
+            match header {
+                b"SUM" => Ok(0),
+                _ => Err(PluginError::Unsupported),
+            }
+        }
+
+        fn provide_data(
+            &mut self,
+            context: &mut u32,
+            _offset: usize,
+            data: &[u8],
+        ) -> Result<(), PluginError> {
+            *context += data.iter().map(|&b| b as u32).sum::<u32>();
+            Ok(())
+        }
+
+        fn finalize(&mut self, _context: &mut u32) -> Result<u8, PluginError> {
+            Ok(1)
+        }
+
+        fn screen(
+            &mut self,
+            context: &u32,
+            index: u8,
+            title: &mut ArrayString<MAX_TITLE_LEN>,
+            value: &mut ArrayString<MAX_VALUE_LEN>,
+        ) -> Result<(), PluginError> {
+            if index > 0 {
+                return Err(PluginError::InvalidData);
+            }
+            title
+                .push_str("Sum")
+                .map_err(|_| PluginError::InvalidData)?;
+            let mut number = crate::display::number::Buffer::new();
+            value
+                .push_str(number.format(*context))
+                .map_err(|_| PluginError::InvalidData)
+        }
+    }
+
+    fn step(context: &mut Context, command: u32, data: &[u8], screen: u32) -> PluginParams {
+        let mut params = PluginParams::new(context);
+        params.data = data.as_ptr();
+        params.data_len = data.len() as u32;
+        params.screen = screen;
+        dispatch(command, &mut params, &mut SumPlugin);
+        params
+    }
+
+    #[test]
+    fn plugin_steps() {
+        let mut context = Context([0; CONTEXT_SIZE]);
+        let params = step(&mut context, PLUGIN_INIT, b"MUL", 0);
+        assert_eq!(params.result(), Err(PluginError::Unsupported));
+        assert_eq!(step(&mut context, PLUGIN_INIT, b"SUM", 0).result(), Ok(()));
+        let params = step(&mut context, PLUGIN_PROVIDE_DATA, &[40, 2], 0);
+        assert_eq!(params.result(), Ok(()));
+        assert_eq!(step(&mut context, PLUGIN_FINALIZE, &[], 0).screen, 1);
+
+        let params = step(&mut context, PLUGIN_QUERY_SCREEN, &[], 0);
+        assert_eq!(params.result(), Ok(()));
+        assert_eq!(
+            read_str::<MAX_TITLE_LEN>(&params.title).unwrap().as_str(),
+            "Sum"
+        );
+        assert_eq!(
+            read_str::<MAX_VALUE_LEN>(&params.value).unwrap().as_str(),
+            "42"
+        );
+        let params = step(&mut context, PLUGIN_QUERY_SCREEN, &[], 1);
+        assert_eq!(params.result(), Err(PluginError::InvalidData));
+    }
+}
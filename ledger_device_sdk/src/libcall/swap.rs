@@ -0,0 +1,456 @@
+//! Parameters of the library calls issued by the Exchange application
+//!
+//! The Exchange application calls the coin application to check an address
+//! (`CHECK_ADDRESS`), to format an amount (`GET_PRINTABLE_AMOUNT`) and finally
+//! to sign the swap transaction (`SIGN_TRANSACTION`). The parameters are read
+//! from the caller memory and copied into owned buffers, and the results are
+//! written back with the `*_return` functions, which end the library call.
+//! Parameters which do not fit in these buffers are rejected rather than
+//! truncated, as the transaction is checked against them: such calls must be
+//! ended with [`reject`].
+
+use super::{copy_bytes, copy_c_str, libargs, LibCallCommand, LibCallError};
+use crate::collections::ArrayVec;
+use core::ffi::c_char;
+use ledger_secure_sdk_sys::os_lib_end;
+
+/// Maximum size of the coin configuration sent by the Exchange application.
+pub const MAX_COIN_CONFIG_LEN: usize = 16;
+/// Maximum size of a serialized derivation path (length byte + 10 components).
+pub const MAX_DPATH_LEN: usize = 41;
+/// Maximum size of an address or of an extra identifier (memo, tag...).
+pub const MAX_ADDRESS_LEN: usize = 64;
+/// Maximum size of an amount, big-endian encoded, for 256-bit amounts.
+pub const MAX_AMOUNT_LEN: usize = 32;
+/// Size of the printable amount buffer of the Exchange application,
+/// including the NUL terminator.
+pub const MAX_PRINTABLE_AMOUNT_SIZE: usize = 50;
+
+/// FFI layout of `check_address_parameters_t`
+#[repr(C)]
+struct CheckAddressParamsInternal {
+    coin_configuration: *const u8,
+    coin_configuration_length: u8,
+    address_parameters: *const u8,
+    address_parameters_length: u8,
+    address_to_check: *const c_char,
+    extra_id_to_check: *const c_char,
+    result: i32,
+}
+
+/// FFI layout of `get_printable_amount_parameters_t`
+#[repr(C)]
+struct PrintableAmountParamsInternal {
+    coin_configuration: *const u8,
+    coin_configuration_length: u8,
+    amount: *const u8,
+    amount_length: u8,
+    is_fee: bool,
+    printable_amount: [c_char; MAX_PRINTABLE_AMOUNT_SIZE],
+}
+
+/// FFI layout of `create_transaction_parameters_t`
+#[repr(C)]
+struct CreateTxParamsInternal {
+    coin_configuration: *const u8,
+    coin_configuration_length: u8,
+    amount: *const u8,
+    amount_length: u8,
+    fee_amount: *const u8,
+    fee_amount_length: u8,
+    destination_address: *const c_char,
+    destination_address_extra_id: *const c_char,
+    result: u8,
+}
+
+/// Parameters of a `CHECK_ADDRESS` library call.
+pub struct CheckAddressParams {
+    coin_config: ArrayVec<u8, MAX_COIN_CONFIG_LEN>,
+    dpath: ArrayVec<u8, MAX_DPATH_LEN>,
+    ref_address: ArrayVec<u8, MAX_ADDRESS_LEN>,
+    result: *mut i32,
+}
+
+impl CheckAddressParams {
+    /// Coin configuration, as defined by the coin application.
+    pub fn coin_config(&self) -> &[u8] {
+        &self.coin_config
+    }
+
+    /// Serialized derivation path: number of components followed by the
+    /// big-endian encoded components.
+    pub fn dpath(&self) -> &[u8] {
+        &self.dpath
+    }
+
+    /// Address the application must compare with the one it derives.
+    pub fn ref_address(&self) -> &[u8] {
+        &self.ref_address
+    }
+}
+
+/// Parameters of a `GET_PRINTABLE_AMOUNT` library call.
+pub struct PrintableAmountParams {
+    coin_config: ArrayVec<u8, MAX_COIN_CONFIG_LEN>,
+    amount: ArrayVec<u8, MAX_AMOUNT_LEN>,
+    /// Whether the amount is the transaction fee rather than the swapped amount.
+    pub is_fee: bool,
+    printable_amount: *mut c_char,
+}
+
+impl PrintableAmountParams {
+    /// Coin configuration, as defined by the coin application.
+    pub fn coin_config(&self) -> &[u8] {
+        &self.coin_config
+    }
+
+    /// Big-endian encoded amount.
+    pub fn amount(&self) -> &[u8] {
+        &self.amount
+    }
+}
+
+/// Parameters of a `SIGN_TRANSACTION` library call.
+pub struct CreateTxParams {
+    coin_config: ArrayVec<u8, MAX_COIN_CONFIG_LEN>,
+    amount: ArrayVec<u8, MAX_AMOUNT_LEN>,
+    fee_amount: ArrayVec<u8, MAX_AMOUNT_LEN>,
+    dest_address: ArrayVec<u8, MAX_ADDRESS_LEN>,
+    dest_extra_id: ArrayVec<u8, MAX_ADDRESS_LEN>,
+    result: *mut u8,
+}
+
+impl CreateTxParams {
+    /// Coin configuration, as defined by the coin application.
+    pub fn coin_config(&self) -> &[u8] {
+        &self.coin_config
+    }
+
+    /// Big-endian encoded amount the transaction must send.
+    pub fn amount(&self) -> &[u8] {
+        &self.amount
+    }
+
+    /// Big-endian encoded fees the transaction must pay.
+    pub fn fee_amount(&self) -> &[u8] {
+        &self.fee_amount
+    }
+
+    /// Address the transaction must send funds to.
+    pub fn dest_address(&self) -> &[u8] {
+        &self.dest_address
+    }
+
+    /// Extra identifier (memo, destination tag...) the transaction must carry.
+    /// Empty if not applicable.
+    pub fn dest_extra_id(&self) -> &[u8] {
+        &self.dest_extra_id
+    }
+}
+
+/// Decodes the parameters of a `CHECK_ADDRESS` library call.
+///
+/// # Arguments
+///
+/// * `arg0` - Argument received by `sample_main`.
+pub fn get_check_address_params(arg0: u32) -> Result<CheckAddressParams, LibCallError> {
+    let params = libargs(arg0).parameters as *mut CheckAddressParamsInternal;
+    unsafe {
+        let p = &mut *params;
+        Ok(CheckAddressParams {
+            coin_config: copy_bytes(p.coin_configuration, p.coin_configuration_length as usize)?,
+            dpath: copy_bytes(p.address_parameters, p.address_parameters_length as usize)?,
+            ref_address: copy_c_str(p.address_to_check as *const u8)?,
+            result: &mut p.result as *mut i32,
+        })
+    }
+}
+
+/// Decodes the parameters of a `GET_PRINTABLE_AMOUNT` library call.
+///
+/// # Arguments
+///
+/// * `arg0` - Argument received by `sample_main`.
+pub fn get_printable_amount_params(arg0: u32) -> Result<PrintableAmountParams, LibCallError> {
+    let params = libargs(arg0).parameters as *mut PrintableAmountParamsInternal;
+    unsafe {
+        let p = &mut *params;
+        Ok(PrintableAmountParams {
+            coin_config: copy_bytes(p.coin_configuration, p.coin_configuration_length as usize)?,
+            amount: copy_bytes(p.amount, p.amount_length as usize)?,
+            is_fee: p.is_fee,
+            printable_amount: p.printable_amount.as_mut_ptr(),
+        })
+    }
+}
+
+/// Decodes the parameters of a `SIGN_TRANSACTION` library call.
+///
+/// # Arguments
+///
+/// * `arg0` - Argument received by `sample_main`.
+pub fn sign_tx_params(arg0: u32) -> Result<CreateTxParams, LibCallError> {
+    let params = libargs(arg0).parameters as *mut CreateTxParamsInternal;
+    unsafe {
+        let p = &mut *params;
+        Ok(CreateTxParams {
+            coin_config: copy_bytes(p.coin_configuration, p.coin_configuration_length as usize)?,
+            amount: copy_bytes(p.amount, p.amount_length as usize)?,
+            fee_amount: copy_bytes(p.fee_amount, p.fee_amount_length as usize)?,
+            dest_address: copy_c_str(p.destination_address as *const u8)?,
+            dest_extra_id: copy_c_str(p.destination_address_extra_id as *const u8)?,
+            result: &mut p.result as *mut u8,
+        })
+    }
+}
+
+/// Returns a failure to the Exchange application and ends the library call,
+/// whose parameters could not be decoded.
+///
+/// # Arguments
+///
+/// * `arg0` - Argument received by `sample_main`.
+pub fn reject(arg0: u32) -> ! {
+    let parameters = libargs(arg0).parameters;
+    unsafe {
+        match super::get_command(arg0) {
+            LibCallCommand::SwapCheckAddress => {
+                (*(parameters as *mut CheckAddressParamsInternal)).result = 0;
+            }
+            LibCallCommand::SwapGetPrintableAmount => {
+                (*(parameters as *mut PrintableAmountParamsInternal)).printable_amount[0] = 0;
+            }
+            LibCallCommand::SwapSignTransaction => {
+                (*(parameters as *mut CreateTxParamsInternal)).result = 0;
+            }
+        }
+        os_lib_end();
+    }
+    crate::exit(crate::ExitCode::Success);
+}
+
+/// Returns the result of a `CHECK_ADDRESS` library call to the caller and
+/// ends the library call.
+pub fn check_address_return(params: &CheckAddressParams, matches: bool) -> ! {
+    unsafe {
+        *params.result = matches as i32;
+        os_lib_end();
+    }
+    crate::exit(crate::ExitCode::Success);
+}
+
+/// Returns the formatted amount of a `GET_PRINTABLE_AMOUNT` library call to
+/// the caller and ends the library call. An amount which does not fit in the
+/// caller buffer, of [`MAX_PRINTABLE_AMOUNT_SIZE`] bytes with the NUL
+/// terminator, is never truncated, as it is displayed to the user: an empty
+/// amount is returned instead, which the Exchange application handles as a
+/// failure.
+pub fn printable_amount_return(params: &PrintableAmountParams, amount: &str) -> ! {
+    let len = if amount.len() < MAX_PRINTABLE_AMOUNT_SIZE {
+        amount.len()
+    } else {
+        0
+    };
+    unsafe {
+        let dst = params.printable_amount as *mut u8;
+        core::ptr::copy_nonoverlapping(amount.as_ptr(), dst, len);
+        *dst.add(len) = 0;
+        os_lib_end();
+    }
+    crate::exit(crate::ExitCode::Success);
+}
+
+/// Returns the result of a `SIGN_TRANSACTION` library call to the caller and
+/// ends the library call. This must be called once the signature APDU has
+/// been replied to.
+pub fn sign_tx_return(params: &CreateTxParams, success: bool) -> ! {
+    unsafe {
+        *params.result = success as u8;
+        os_lib_end();
+    }
+    crate::exit(crate::ExitCode::Success);
+}
+
+/// Mismatch between a transaction and the `SIGN_TRANSACTION` parameters,
+/// returned by [`CreateTxParams::check_transaction`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SwapError {
+    /// The transaction does not send the expected amount
+    Amount,
+    /// The transaction does not pay the expected fees
+    Fee,
+    /// The transaction does not send funds to the expected address
+    DestinationAddress,
+    /// The transaction does not carry the expected extra identifier
+    ExtraId,
+}
+
+/// Compares two big-endian encoded amounts, ignoring leading zeros.
+fn amounts_eq(a: &[u8], b: &[u8]) -> bool {
+    fn trim(amount: &[u8]) -> &[u8] {
+        let start = amount.iter().position(|&b| b != 0).unwrap_or(amount.len());
+        &amount[start..]
+    }
+    trim(a) == trim(b)
+}
+
+impl CreateTxParams {
+    /// Checks that the transaction to sign matches the swap accepted by the
+    /// user in the Exchange application. Amounts are big-endian encoded, with
+    /// any number of leading zeros, and `extra_id` is empty if the
+    /// transaction carries no extra identifier.
+    pub fn check_transaction(
+        &self,
+        amount: &[u8],
+        fee_amount: &[u8],
+        dest_address: &[u8],
+        dest_extra_id: &[u8],
+    ) -> Result<(), SwapError> {
+        if !amounts_eq(amount, self.amount()) {
+            return Err(SwapError::Amount);
+        }
+        if !amounts_eq(fee_amount, self.fee_amount()) {
+            return Err(SwapError::Fee);
+        }
+        if dest_address != self.dest_address() {
+            return Err(SwapError::DestinationAddress);
+        }
+        if dest_extra_id != self.dest_extra_id() {
+            return Err(SwapError::ExtraId);
+        }
+        Ok(())
+    }
+}
+
+/// Handlers of the library calls issued by the Exchange application,
+/// implemented by coin applications and run by [`handle`].
+pub trait SwapHandler {
+    /// Returns whether the address derived with the path and the coin
+    /// configuration of `params` is [`CheckAddressParams::ref_address`].
+    fn check_address(&mut self, params: &CheckAddressParams) -> bool;
+
+    /// Formats the amount of `params` with its ticker into `buf`, of
+    /// `MAX_PRINTABLE_AMOUNT_SIZE - 1` bytes, and returns the formatted
+    /// amount. Longer amounts fail the call rather than being truncated, see
+    /// [`printable_amount_return`].
+    fn get_printable_amount<'a>(
+        &mut self,
+        params: &PrintableAmountParams,
+        buf: &'a mut [u8],
+    ) -> &'a str;
+
+    /// Runs the application until the host has sent the swap transaction,
+    /// and returns whether it was signed. The IO has been initialized with
+    /// [`super::init_io`] beforehand. The transaction must be checked
+    /// with [`CreateTxParams::check_transaction`], and the signature APDU
+    /// replied to, before returning.
+    fn sign_transaction(&mut self, params: &CreateTxParams) -> bool;
+}
+
+/// Decodes the library call the application has been started with, runs the
+/// matching handler of `handler`, and returns its result to the Exchange
+/// application, which ends the library call. Calls with parameters too long
+/// to be decoded fail without running the handler.
+///
+/// # Arguments
+///
+/// * `arg0` - Argument received by `sample_main`.
+///
+/// # Examples
+///
+/// ```
+/// #[no_mangle]
+/// extern "C" fn sample_main(arg0: u32) {
+///     if arg0 != 0 {
+///         swap::handle(arg0, &mut CoinSwap);
+///     }
+///     ...
+/// }
+/// ```
+pub fn handle<H: SwapHandler>(arg0: u32, handler: &mut H) -> ! {
+    match super::get_command(arg0) {
+        LibCallCommand::SwapCheckAddress => {
+            let Ok(params) = get_check_address_params(arg0) else {
+                reject(arg0)
+            };
+            let matches = handler.check_address(&params);
+            check_address_return(&params, matches)
+        }
+        LibCallCommand::SwapGetPrintableAmount => {
+            let Ok(params) = get_printable_amount_params(arg0) else {
+                reject(arg0)
+            };
+            let mut buf = [0u8; MAX_PRINTABLE_AMOUNT_SIZE - 1];
+            let amount = handler.get_printable_amount(&params, &mut buf);
+            printable_amount_return(&params, amount)
+        }
+        LibCallCommand::SwapSignTransaction => {
+            let Ok(params) = sign_tx_params(arg0) else {
+                reject(arg0)
+            };
+            super::init_io();
+            let success = handler.sign_transaction(&params);
+            sign_tx_return(&params, success)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn check_transaction() {
+        let params = CreateTxParams {
+            coin_config: ArrayVec::new(),
+            amount: ArrayVec::try_from(&[0x00, 0x01, 0x00][..]).unwrap(),
+            fee_amount: ArrayVec::try_from(&[0x10][..]).unwrap(),
+            dest_address: ArrayVec::try_from(&b"addr"[..]).unwrap(),
+            dest_extra_id: ArrayVec::new(),
+            result: core::ptr::null_mut(),
+        };
+
+        let amount = 256u64.to_be_bytes();
+        assert_eq!(
+            params.check_transaction(&amount, &[0x10], b"addr", &[]),
+            Ok(())
+        );
+        assert_eq!(
+            params.check_transaction(&[0x01, 0x01], &[0x10], b"addr", &[]),
+            Err(SwapError::Amount)
+        );
+        assert_eq!(
+            params.check_transaction(&amount, &[], b"addr", &[]),
+            Err(SwapError::Fee)
+        );
+        assert_eq!(
+            params.check_transaction(&amount, &[0x10], b"addr2", &[]),
+            Err(SwapError::DestinationAddress)
+        );
+        assert_eq!(
+            params.check_transaction(&amount, &[0x10], b"addr", b"memo"),
+            Err(SwapError::ExtraId)
+        );
+    }
+
+    #[test]
+    fn copy_parameters() {
+        let amount = [0xffu8; MAX_AMOUNT_LEN + 1];
+        let copied = unsafe { copy_bytes::<MAX_AMOUNT_LEN>(amount.as_ptr(), MAX_AMOUNT_LEN) };
+        assert_eq!(copied.map(|a| a.len()), Ok(MAX_AMOUNT_LEN));
+        let copied = unsafe { copy_bytes::<MAX_AMOUNT_LEN>(amount.as_ptr(), amount.len()) };
+        assert_eq!(copied.err(), Some(LibCallError::ParameterTooLong));
+
+        let mut address = [b'a'; MAX_ADDRESS_LEN + 2];
+        address[MAX_ADDRESS_LEN] = 0;
+        let copied = unsafe { copy_c_str::<MAX_ADDRESS_LEN>(address.as_ptr()) };
+        assert_eq!(copied.map(|a| a.len()), Ok(MAX_ADDRESS_LEN));
+        address[MAX_ADDRESS_LEN] = b'a';
+        address[MAX_ADDRESS_LEN + 1] = 0;
+        let copied = unsafe { copy_c_str::<MAX_ADDRESS_LEN>(address.as_ptr()) };
+        assert_eq!(copied.err(), Some(LibCallError::ParameterTooLong));
+    }
+}
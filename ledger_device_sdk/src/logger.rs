@@ -0,0 +1,68 @@
+//! Logger for the [`log`] crate
+//!
+//! Once [`init`] has been called, the `log::error!` to `log::trace!` macros,
+//! used by the application and the libraries it depends on, write their
+//! records to the Speculos console when the `speculos` feature is enabled,
+//! and to the USB debug interface when the `debug_serial` feature is enabled.
+//!
+//! Records are compiled out of release builds, so that logging has no cost
+//! in released applications.
+//!
+//! # Examples
+//!
+//! ```
+//! ledger_device_sdk::logger::init(log::LevelFilter::Debug);
+//! log::debug!("signing {} bytes", data.len());
+//! ```
+
+#[cfg(any(feature = "speculos", feature = "debug_serial"))]
+use core::fmt::Write;
+use log::{LevelFilter, Log, Metadata, Record};
+
+struct SdkLogger;
+
+static LOGGER: SdkLogger = SdkLogger;
+
+impl Log for SdkLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        #[cfg(feature = "speculos")]
+        let _ = writeln!(
+            crate::testing::SpeculosConsole,
+            "[{}] {}: {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        #[cfg(feature = "debug_serial")]
+        let _ = write!(
+            crate::debug_serial::DebugSerial,
+            "[{}] {}: {}\r\n",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger, keeping the records up to `level`.
+///
+/// Must be called once, at the start of the application, since the logger is
+/// installed without synchronization.
+pub fn init(level: LevelFilter) {
+    // Devices have no atomic compare-and-swap, required by `log::set_logger`
+    unsafe {
+        let _ = log::set_logger_racy(&LOGGER);
+    }
+    log::set_max_level(level);
+}
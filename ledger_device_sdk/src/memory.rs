@@ -0,0 +1,132 @@
+//! RAM usage of the application
+//!
+//! [`usage`] gathers the figures of the [`stack`](crate::stack) module and,
+//! when the global allocator is available, of the [`heap`](crate::heap), and
+//! [`show_usage`] displays them on a debug screen, to tune the size of
+//! buffers against the RAM of each device.
+//!
+//! # Examples
+//!
+//! ```
+//! #[no_mangle]
+//! extern "C" fn sample_main() {
+//!     stack::paint();
+//!     ...
+//!     sign_transaction(&tx);
+//!     if cfg!(debug_assertions) {
+//!         memory::show_usage();
+//!     }
+//! }
+//! ```
+
+use crate::collections::ArrayString;
+use crate::display::number;
+use crate::stack;
+
+/// RAM usage, in bytes, returned by [`usage`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub stack_size: usize,
+    /// Stack used by the caller of [`usage`]
+    pub stack_used: usize,
+    /// Stack left below the caller of [`usage`]
+    pub stack_headroom: usize,
+    /// Maximum stack usage since [`stack::paint`] was called
+    pub stack_high_water_mark: usize,
+    /// Heap allocated, `None` without the global allocator
+    pub heap_used: Option<usize>,
+    /// Heap left, `None` without the global allocator
+    pub heap_free: Option<usize>,
+}
+
+/// Returns the current RAM usage.
+pub fn usage() -> MemoryUsage {
+    #[cfg(any(feature = "heap", not(target_os = "nanos")))]
+    let (heap_used, heap_free) = (
+        Some(crate::heap::heap_used()),
+        Some(crate::heap::heap_free()),
+    );
+    #[cfg(not(any(feature = "heap", not(target_os = "nanos"))))]
+    let (heap_used, heap_free) = (None, None);
+
+    MemoryUsage {
+        stack_size: stack::size(),
+        stack_used: stack::used(),
+        stack_headroom: stack::headroom(),
+        stack_high_water_mark: stack::high_water_mark(),
+        heap_used,
+        heap_free,
+    }
+}
+
+/// Writes "`used` / `total` bytes" into `text`.
+fn write_bytes(text: &mut ArrayString<32>, used: usize, total: usize) {
+    let mut number = number::Buffer::new();
+    let _ = text.push_str(number.format(used));
+    let _ = text.push_str(" / ");
+    let _ = text.push_str(number.format(total));
+    let _ = text.push_str(" bytes");
+}
+
+/// Texts displayed by [`show_usage`]: the stack, its peak and the heap.
+fn usage_texts(usage: &MemoryUsage) -> [ArrayString<32>; 3] {
+    let mut texts: [ArrayString<32>; 3] = Default::default();
+    write_bytes(&mut texts[0], usage.stack_used, usage.stack_size);
+    write_bytes(&mut texts[1], usage.stack_high_water_mark, usage.stack_size);
+    match (usage.heap_used, usage.heap_free) {
+        (Some(used), Some(free)) => write_bytes(&mut texts[2], used, used + free),
+        _ => {
+            let _ = texts[2].push_str("No allocator");
+        }
+    }
+    texts
+}
+
+/// Displays the current RAM usage on a debug screen, until the user leaves
+/// it. APDUs received in the meantime are ignored.
+pub fn show_usage() {
+    let texts = usage_texts(&usage());
+    let titles = ["Stack", "Stack peak", "Heap"];
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        let mut page = crate::nbgl::NbglPage::new();
+        for (title, text) in titles.iter().zip(texts.iter()) {
+            page = page.text(title, text.as_str());
+        }
+        page.back(()).show();
+    }
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        use crate::ui::gadgets::{clear_screen, Page, PageStyle};
+        for (title, text) in titles.iter().zip(texts.iter()) {
+            clear_screen();
+            Page::new(PageStyle::BoldNormal, [*title, text.as_str()], None).place_and_wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn texts() {
+        let usage = MemoryUsage {
+            stack_size: 8192,
+            stack_used: 1024,
+            stack_headroom: 7168,
+            stack_high_water_mark: 2048,
+            heap_used: None,
+            heap_free: None,
+        };
+        let texts = usage_texts(&usage);
+        assert_eq!(texts[0].as_str(), "1024 / 8192 bytes");
+        assert_eq!(texts[1].as_str(), "2048 / 8192 bytes");
+        assert_eq!(texts[2].as_str(), "No allocator");
+    }
+}
@@ -1,3 +1,4 @@
+pub use crate::display::Field;
 use crate::io::{ApduHeader, Comm, Event, Reply};
 use crate::nvm::*;
 use const_zero::const_zero;
@@ -8,6 +9,42 @@ use core::ffi::{c_char, c_int};
 use core::mem::transmute;
 use include_gif::include_gif;
 use ledger_secure_sdk_sys::*;
+use numtoa::NumToA;
+
+mod nbgl_address_book;
+mod nbgl_batch_review;
+mod nbgl_blind_signing;
+mod nbgl_closures;
+#[cfg(feature = "demo")]
+mod nbgl_demo;
+mod nbgl_exchange_review;
+mod nbgl_font;
+mod nbgl_keyboard;
+mod nbgl_keypad;
+mod nbgl_lazy_review;
+mod nbgl_long_value;
+mod nbgl_page;
+mod nbgl_progress;
+mod nbgl_ui;
+mod nbgl_warning;
+pub use nbgl_address_book::*;
+pub use nbgl_batch_review::*;
+pub use nbgl_blind_signing::*;
+pub use nbgl_closures::*;
+#[cfg(feature = "demo")]
+pub use nbgl_demo::*;
+pub use nbgl_exchange_review::*;
+pub use nbgl_font::*;
+pub use nbgl_keyboard::*;
+pub use nbgl_keypad::*;
+pub use nbgl_lazy_review::*;
+pub use nbgl_long_value::*;
+pub use nbgl_page::*;
+pub use nbgl_progress::*;
+pub use nbgl_ui::*;
+pub use nbgl_warning::*;
+
+pub use crate::ui_flows::{ReviewResult, ReviewStatus, ReviewUi, StatusType};
 
 #[no_mangle]
 pub static mut G_ux_params: bolos_ux_params_t = unsafe { const_zero!(bolos_ux_params_t) };
@@ -15,13 +52,19 @@ pub static mut G_ux_params: bolos_ux_params_t = unsafe { const_zero!(bolos_ux_pa
 static mut COMM_REF: Option<&mut Comm> = None;
 const SETTINGS_SIZE: usize = 10;
 static mut NVM_REF: Option<&mut AtomicStorage<[u8; SETTINGS_SIZE]>> = None;
+/// Storages bound to individual setting switches with [`NbglHomeAndSettings::switch`].
+static mut SWITCH_REFS: [Option<&mut AtomicStorage<bool>>; SETTINGS_SIZE] =
+    [NO_SWITCH_REF; SETTINGS_SIZE];
+const NO_SWITCH_REF: Option<&mut AtomicStorage<bool>> = None;
 static mut SWITCH_ARRAY: [nbgl_contentSwitch_t; SETTINGS_SIZE] =
     [unsafe { const_zero!(nbgl_contentSwitch_t) }; SETTINGS_SIZE];
-
-pub struct Field<'a> {
-    pub name: &'a str,
-    pub value: &'a str,
-}
+/// Set by [`NbglHomeAndSettings::show_with_events`] to return from the home
+/// screen when a setting switch is toggled.
+static mut SETTINGS_EVENTS: bool = false;
+/// Last setting switch toggled by the user, as (index, new state).
+static mut SETTING_CHANGED: Option<(u8, bool)> = None;
+/// Set when the action button of the home screen is touched.
+static mut HOME_ACTION_TRIGGERED: bool = false;
 
 struct CField {
     pub name: CString,
@@ -61,6 +104,27 @@ impl<'a> NbglGlyph<'a> {
             bitmap: packed.0,
         }
     }
+
+    /// Selects, at compile time, the variant of a glyph matching the screen of
+    /// the target device: `large` (typically 64x64 4bpp) on Stax and Flex, and
+    /// `small` (typically 32x32 1bpp) on devices with smaller screens. This
+    /// allows one codebase to bundle all the variants of an icon.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// const APP_ICON: NbglGlyph = NbglGlyph::for_device(
+    ///     NbglGlyph::from_include(include_gif!("icons/app_64x64.gif", NBGL)),
+    ///     NbglGlyph::from_include(include_gif!("icons/app_32x32.gif", NBGL)),
+    /// );
+    /// ```
+    pub const fn for_device(large: NbglGlyph<'a>, small: NbglGlyph<'a>) -> NbglGlyph<'a> {
+        if cfg!(any(target_os = "stax", target_os = "flex")) {
+            large
+        } else {
+            small
+        }
+    }
 }
 
 impl<'a> Into<nbgl_icon_details_t> for &NbglGlyph<'a> {
@@ -81,19 +145,13 @@ impl<'a> Into<nbgl_icon_details_t> for &NbglGlyph<'a> {
     }
 }
 
+#[derive(Copy, Clone)]
 pub enum TransactionType {
     Transaction,
     Message,
     Operation,
 }
 
-pub enum StatusType {
-    Transaction,
-    Message,
-    Operation,
-    Address,
-}
-
 impl StatusType {
     fn transaction_type(&self) -> Option<TransactionType> {
         match self {
@@ -190,12 +248,50 @@ unsafe extern "C" fn settings_callback(token: c_int, _index: u8, _page: c_int) {
         panic!("Invalid token.");
     }
 
-    if let Some(data) = NVM_REF.as_mut() {
-        let setting_idx: usize = idx as usize;
-        let mut switch_values: [u8; SETTINGS_SIZE] = data.get_ref().clone();
-        switch_values[setting_idx] = !switch_values[setting_idx];
-        data.update(&switch_values);
-        SWITCH_ARRAY[setting_idx].initState = switch_values[setting_idx] as nbgl_state_t;
+    let setting_idx: usize = idx as usize;
+    if let Some(state) = setting_value(setting_idx) {
+        let state = !state;
+        if let Some(storage) = SWITCH_REFS[setting_idx].as_mut() {
+            storage.update(&state);
+        } else if let Some(data) = NVM_REF.as_mut() {
+            let mut switch_values: [u8; SETTINGS_SIZE] = data.get_ref().clone();
+            switch_values[setting_idx] = state as u8;
+            data.update(&switch_values);
+        }
+        SWITCH_ARRAY[setting_idx].initState = switch_state(state);
+
+        if SETTINGS_EVENTS {
+            SETTING_CHANGED = Some((setting_idx as u8, state));
+            ux_sync_setReturnCode(UX_SYNC_RET_APPROVED);
+            ux_sync_setEnded(true);
+        }
+    }
+}
+
+/// Callback triggered by the NBGL API when the action button of the home
+/// screen is touched. The home screen is left so that the action runs outside
+/// of the NBGL callback.
+unsafe extern "C" fn home_action_callback() {
+    HOME_ACTION_TRIGGERED = true;
+    ux_sync_setReturnCode(UX_SYNC_RET_APPROVED);
+    ux_sync_setEnded(true);
+}
+
+/// Returns the stored value of the setting switch at `idx`, if it is bound to a storage.
+unsafe fn setting_value(idx: usize) -> Option<bool> {
+    if let Some(storage) = SWITCH_REFS[idx].as_ref() {
+        Some(*storage.get_ref())
+    } else {
+        NVM_REF.as_ref().map(|data| data.get_ref()[idx] != 0)
+    }
+}
+
+/// Converts a setting value to the state of its switch.
+fn switch_state(on: bool) -> nbgl_state_t {
+    if on {
+        ON_STATE
+    } else {
+        OFF_STATE
     }
 }
 
@@ -215,27 +311,102 @@ const INFO_FIELDS: [*const c_char; 2] = [
     "Developer\0".as_ptr() as *const c_char,
 ];
 
+/// Events returned by [`NbglHomeAndSettings::show_with_events`].
+pub enum HomeAndSettingsEvent<T> {
+    /// An APDU command has been received
+    Command(T),
+    /// The user toggled the setting switch at `index`, whose new value has
+    /// already been stored in NVM.
+    SettingChanged { index: u8, state: bool },
+}
+
+/// Action button of the home screen.
+struct HomeAction<'a> {
+    text: CString,
+    glyph: Option<&'a NbglGlyph<'a>>,
+    callback: fn(),
+}
+
 /// A wrapper around the synchronous NBGL ux_sync_homeAndSettings C API binding.
 /// Used to display the home screen of the application, with an optional glyph,
-/// information fields, and settings switches.  
+/// information fields, and settings switches.
+///
+/// Setting values are stored in the [`AtomicStorage`] passed to
+/// [`settings`](NbglHomeAndSettings::settings), one byte per switch (`0` for
+/// off, `1` for on), or in the `AtomicStorage<bool>` bound to each switch with
+/// [`switch`](NbglHomeAndSettings::switch). Stored values initialize the
+/// switches, and toggling a switch updates its storage.
 pub struct NbglHomeAndSettings<'a> {
     glyph: Option<&'a NbglGlyph<'a>>,
+    tagline: Option<CString>,
     // app_name, version, author
     info_contents: Vec<CString>,
+    // Additional (name, value) entries of the info page
+    extra_infos: Vec<[CString; 2]>,
     setting_contents: Vec<[CString; 2]>,
     nb_settings: u8,
+    action: Option<HomeAction<'a>>,
+    // Page displayed by the next call to show
+    init_page: u8,
 }
 
 impl<'a> NbglHomeAndSettings<'a> {
     pub fn new() -> NbglHomeAndSettings<'a> {
         NbglHomeAndSettings {
             glyph: None,
+            tagline: None,
             info_contents: Vec::default(),
+            extra_infos: Vec::default(),
             setting_contents: Vec::default(),
             nb_settings: 0,
+            action: None,
+            init_page: INIT_HOME_PAGE as u8,
         }
     }
 
+    /// Adds a setting switch bound to `storage`, after the switches already
+    /// added with [`settings`](NbglHomeAndSettings::settings) or `switch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[link_section = ".nvm_data"]
+    /// static mut BLIND_SIGNING: NVMData<AtomicStorage<bool>> =
+    ///     NVMData::new(AtomicStorage::new(&false));
+    ///
+    /// let mut home = NbglHomeAndSettings::new()
+    ///     .infos("Example App", env!("CARGO_PKG_VERSION"), env!("CARGO_PKG_AUTHORS"))
+    ///     .switch(
+    ///         unsafe { BLIND_SIGNING.get_mut() },
+    ///         "Blind signing",
+    ///         "Enable transaction blind signing",
+    ///     );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are already `SETTINGS_SIZE` (10) switches.
+    pub fn switch(
+        mut self,
+        storage: &'a mut AtomicStorage<bool>,
+        title: &str,
+        subtitle: &str,
+    ) -> NbglHomeAndSettings<'a> {
+        let idx = self.setting_contents.len();
+        if idx >= SETTINGS_SIZE {
+            panic!("Too many settings.");
+        }
+        unsafe {
+            SWITCH_REFS[idx] = Some(transmute(storage));
+        }
+        self.setting_contents.push([
+            CString::new(title).unwrap(),
+            CString::new(subtitle).unwrap(),
+        ]);
+        self.nb_settings += 1;
+        self
+    }
+
     pub fn glyph(self, glyph: &'a NbglGlyph) -> NbglHomeAndSettings<'a> {
         NbglHomeAndSettings {
             glyph: Some(glyph),
@@ -243,6 +414,64 @@ impl<'a> NbglHomeAndSettings<'a> {
         }
     }
 
+    /// Sets the text displayed below the application name, instead of the
+    /// default "This app enables signing transactions on its network."
+    pub fn tagline(self, tagline: &str) -> NbglHomeAndSettings<'a> {
+        NbglHomeAndSettings {
+            tagline: Some(CString::new(tagline).unwrap()),
+            ..self
+        }
+    }
+
+    /// Adds an entry to the info page, after the version and the developer
+    /// set with [`infos`](NbglHomeAndSettings::infos).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut home = NbglHomeAndSettings::new()
+    ///     .infos("Example App", env!("CARGO_PKG_VERSION"), env!("CARGO_PKG_AUTHORS"))
+    ///     .info("Copyright", "(c) 2024 Example")
+    ///     .info("Contact", "support@example.com");
+    /// ```
+    pub fn info(mut self, name: &str, value: &str) -> NbglHomeAndSettings<'a> {
+        self.extra_infos
+            .push([CString::new(name).unwrap(), CString::new(value).unwrap()]);
+        self
+    }
+
+    /// Adds an action button to the home page, with an optional icon.
+    /// `callback` is called when the button is touched, after leaving the home
+    /// screen: it can display its own screens, and the home screen is
+    /// displayed again when it returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn show_address() {
+    ///     NbglAddressReview::new().show(&get_address());
+    /// }
+    ///
+    /// let mut home = NbglHomeAndSettings::new()
+    ///     .infos("Example App", env!("CARGO_PKG_VERSION"), env!("CARGO_PKG_AUTHORS"))
+    ///     .action("Show address", None, show_address);
+    /// ```
+    pub fn action(
+        self,
+        text: &str,
+        glyph: Option<&'a NbglGlyph>,
+        callback: fn(),
+    ) -> NbglHomeAndSettings<'a> {
+        NbglHomeAndSettings {
+            action: Some(HomeAction {
+                text: CString::new(text).unwrap(),
+                glyph,
+                callback,
+            }),
+            ..self
+        }
+    }
+
     pub fn infos(
         self,
         app_name: &'a str,
@@ -259,47 +488,97 @@ impl<'a> NbglHomeAndSettings<'a> {
         }
     }
 
+    /// Adds setting switches stored in `nvm_data`, after the switches already
+    /// added with [`switch`](NbglHomeAndSettings::switch). Each switch is
+    /// stored in the byte of `nvm_data` at its index among all the switches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `SETTINGS_SIZE` (10) switches.
     pub fn settings(
-        self,
+        mut self,
         nvm_data: &'a mut AtomicStorage<[u8; SETTINGS_SIZE]>,
         settings_strings: &[[&'a str; 2]],
     ) -> NbglHomeAndSettings<'a> {
+        let first = self.setting_contents.len();
+        if first + settings_strings.len() > SETTINGS_SIZE {
+            panic!("Too many settings.");
+        }
+
         unsafe {
             NVM_REF = Some(transmute(nvm_data));
+            // Keep the bindings of the switches already added, and unbind the
+            // new ones from the storages of a previous home screen
+            for switch_ref in SWITCH_REFS[first..first + settings_strings.len()].iter_mut() {
+                *switch_ref = None;
+            }
         }
 
-        if settings_strings.len() > SETTINGS_SIZE {
-            panic!("Too many settings.");
-        }
+        self.setting_contents.extend(
+            settings_strings
+                .iter()
+                .map(|s| [CString::new(s[0]).unwrap(), CString::new(s[1]).unwrap()]),
+        );
+        self.nb_settings = self.setting_contents.len() as u8;
+        self
+    }
 
-        let v: Vec<[CString; 2]> = settings_strings
-            .iter()
-            .map(|s| [CString::new(s[0]).unwrap(), CString::new(s[1]).unwrap()])
-            .collect();
+    /// Displays the first settings page instead of the home page on the next
+    /// call to [`show`](NbglHomeAndSettings::show), for instance when the user
+    /// asked to enable a setting from another flow.
+    pub fn open_settings(&mut self) {
+        self.init_page = 0;
+    }
 
-        NbglHomeAndSettings {
-            nb_settings: settings_strings.len() as u8,
-            setting_contents: v,
-            ..self
+    /// Displays the home screen until an APDU command is received.
+    /// Setting switches are updated in NVM without notifying the application.
+    pub fn show<T: TryFrom<ApduHeader>>(&mut self) -> Event<T>
+    where
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        match self.show_home(false) {
+            HomeAndSettingsEvent::Command(event) => event,
+            HomeAndSettingsEvent::SettingChanged { .. } => unreachable!(),
         }
     }
 
-    pub fn show<T: TryFrom<ApduHeader>>(&mut self) -> Event<T>
+    /// Displays the home screen until an APDU command is received or a setting
+    /// switch is toggled. After a setting change, the next call displays the
+    /// settings page again, so that the user does not notice the interruption.
+    pub fn show_with_events<T: TryFrom<ApduHeader>>(&mut self) -> HomeAndSettingsEvent<Event<T>>
+    where
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        self.show_home(true)
+    }
+
+    fn show_home<T: TryFrom<ApduHeader>>(
+        &mut self,
+        settings_events: bool,
+    ) -> HomeAndSettingsEvent<Event<T>>
     where
         Reply: From<<T as TryFrom<ApduHeader>>::Error>,
     {
         unsafe {
+            SETTINGS_EVENTS = settings_events;
+            SETTING_CHANGED = None;
             loop {
-                let info_contents: Vec<*const c_char> = self
+                let mut info_contents: Vec<*const c_char> = self
                     .info_contents
                     .iter()
                     .map(|s| s.as_ptr())
                     .collect::<Vec<_>>();
 
+                let mut info_types: Vec<*const c_char> = INFO_FIELDS.to_vec();
+                for [name, value] in self.extra_infos.iter() {
+                    info_types.push(name.as_ptr());
+                    info_contents.push(value.as_ptr());
+                }
+
                 let info_list: nbgl_contentInfoList_t = nbgl_contentInfoList_t {
-                    infoTypes: INFO_FIELDS.as_ptr() as *const *const c_char,
+                    infoTypes: info_types.as_ptr() as *const *const c_char,
                     infoContents: info_contents[1..].as_ptr() as *const *const c_char,
-                    nbInfos: INFO_FIELDS.len() as u8,
+                    nbInfos: info_types.len() as u8,
                 };
 
                 let icon: nbgl_icon_details_t = match self.glyph {
@@ -310,8 +589,7 @@ impl<'a> NbglHomeAndSettings<'a> {
                 for (i, setting) in self.setting_contents.iter().enumerate() {
                     SWITCH_ARRAY[i].text = setting[0].as_ptr();
                     SWITCH_ARRAY[i].subText = setting[1].as_ptr();
-                    SWITCH_ARRAY[i].initState =
-                        NVM_REF.as_mut().unwrap().get_ref()[i] as nbgl_state_t;
+                    SWITCH_ARRAY[i].initState = switch_state(setting_value(i).unwrap_or(false));
                     SWITCH_ARRAY[i].token = (FIRST_USER_TOKEN + i as u32) as u8;
                     SWITCH_ARRAY[i].tuneId = TuneIndex::TapCasual as u8;
                 }
@@ -335,22 +613,57 @@ impl<'a> NbglHomeAndSettings<'a> {
                     nbContents: if self.nb_settings > 0 { 1 } else { 0 },
                 };
 
+                let action_icon: Option<nbgl_icon_details_t> = self
+                    .action
+                    .as_ref()
+                    .and_then(|action| action.glyph)
+                    .map(|g| g.into());
+                let home_action = self.action.as_ref().map(|action| nbgl_homeAction_t {
+                    text: action.text.as_ptr() as *const c_char,
+                    icon: action_icon
+                        .as_ref()
+                        .map_or(core::ptr::null(), |icon| icon as *const nbgl_icon_details_t),
+                    callback: Some(home_action_callback),
+                    ..Default::default()
+                });
+
+                let init_page = self.init_page;
+                self.init_page = INIT_HOME_PAGE as u8;
+                HOME_ACTION_TRIGGERED = false;
                 match ux_sync_homeAndSettings(
                     info_contents[0],
                     &icon as *const nbgl_icon_details_t,
-                    core::ptr::null(),
-                    INIT_HOME_PAGE as u8,
+                    self.tagline
+                        .as_ref()
+                        .map_or(core::ptr::null(), |tagline| tagline.as_ptr()),
+                    init_page,
                     &generic_contents as *const nbgl_genericContents_t,
                     &info_list as *const nbgl_contentInfoList_t,
-                    core::ptr::null(),
+                    home_action.as_ref().map_or(core::ptr::null(), |action| {
+                        action as *const nbgl_homeAction_t
+                    }),
                 ) {
                     UX_SYNC_RET_APDU_RECEIVED => {
                         if let Some(comm) = COMM_REF.as_mut() {
                             if let Some(value) = comm.check_event() {
-                                return value;
+                                SETTINGS_EVENTS = false;
+                                return HomeAndSettingsEvent::Command(value);
                             }
                         }
                     }
+                    UX_SYNC_RET_APPROVED if HOME_ACTION_TRIGGERED => {
+                        HOME_ACTION_TRIGGERED = false;
+                        if let Some(action) = self.action.as_ref() {
+                            (action.callback)();
+                        }
+                    }
+                    UX_SYNC_RET_APPROVED if SETTING_CHANGED.is_some() => {
+                        let (index, state) = SETTING_CHANGED.take().unwrap();
+                        SETTINGS_EVENTS = false;
+                        // Settings are displayed on the first settings page
+                        self.init_page = 0;
+                        return HomeAndSettingsEvent::SettingChanged { index, state };
+                    }
                     _ => {
                         panic!("Unexpected return value from ux_sync_homeAndSettings");
                     }
@@ -391,6 +704,26 @@ fn show_blind_warning() -> bool {
 
 /// A wrapper around the synchronous NBGL ux_sync_review C API binding.
 /// Used to display transaction review screens.
+///
+/// The review is made of a first page with the glyph, title and subtitle, the
+/// given fields as tag/value pages, and a last page with the finish title
+/// where the user approves or rejects the transaction.
+///
+/// # Examples
+///
+/// ```
+/// let fields = [
+///     Field { name: "Amount", value: amount.as_str() },
+///     Field { name: "To", value: destination.as_str() },
+/// ];
+/// let result = NbglReview::new()
+///     .titles("Review transaction", "to send CRAB", "Sign transaction\nto send CRAB")
+///     .glyph(&FERRIS)
+///     .review(&fields);
+/// if result == ReviewResult::Rejected {
+///     return Err(StatusWords::UserCancelled);
+/// }
+/// ```
 pub struct NbglReview<'a> {
     title: CString,
     subtitle: CString,
@@ -398,6 +731,9 @@ pub struct NbglReview<'a> {
     glyph: Option<&'a NbglGlyph<'a>>,
     tx_type: TransactionType,
     blind: bool,
+    skippable: bool,
+    long_value_len: usize,
+    long_values: LongValues,
 }
 
 impl<'a> NbglReview<'a> {
@@ -409,6 +745,9 @@ impl<'a> NbglReview<'a> {
             glyph: None,
             tx_type: TransactionType::Transaction,
             blind: false,
+            skippable: false,
+            long_value_len: LONG_VALUE_LEN,
+            long_values: LongValues::More,
         }
     }
 
@@ -423,6 +762,35 @@ impl<'a> NbglReview<'a> {
         }
     }
 
+    /// Displays a "Skip" button on the fields pages, allowing the user to jump
+    /// directly to the final approval page. Meant for reviews with many fields.
+    pub fn skippable(self) -> NbglReview<'a> {
+        NbglReview {
+            skippable: true,
+            ..self
+        }
+    }
+
+    /// Sets the maximum length of the values displayed inline, in bytes.
+    /// Longer values are displayed according to
+    /// [`long_values`](NbglReview::long_values).
+    /// Defaults to [`LONG_VALUE_LEN`].
+    pub fn long_value_len(self, long_value_len: usize) -> NbglReview<'a> {
+        NbglReview {
+            long_value_len,
+            ..self
+        }
+    }
+
+    /// Sets how values longer than [`long_value_len`](NbglReview::long_value_len)
+    /// are displayed. Defaults to [`LongValues::More`].
+    pub fn long_values(self, long_values: LongValues) -> NbglReview<'a> {
+        NbglReview {
+            long_values,
+            ..self
+        }
+    }
+
     pub fn titles(
         self,
         title: &'a str,
@@ -444,34 +812,29 @@ impl<'a> NbglReview<'a> {
         }
     }
 
+    /// Displays the review of `fields`, and returns true if the user approved it.
     pub fn show(&mut self, fields: &[Field]) -> bool {
-        unsafe {
-            let v: Vec<CField> = fields
-                .iter()
-                .map(|f| CField {
-                    name: CString::new(f.name).unwrap(),
-                    value: CString::new(f.value).unwrap(),
-                })
-                .collect();
-
-            // Fill the tag_value_array with the fields converted to nbgl_contentTagValue_t
-            let mut tag_value_array: Vec<nbgl_contentTagValue_t> = Vec::new();
-            for field in v.iter() {
-                let val = nbgl_contentTagValue_t {
-                    item: field.name.as_ptr() as *const i8,
-                    value: field.value.as_ptr() as *const i8,
-                    ..Default::default()
-                };
-                tag_value_array.push(val);
-            }
+        self.review(fields).is_approved()
+    }
 
-            // Create the tag_value_list with the tag_value_array.
-            let tag_value_list = nbgl_contentTagValueList_t {
-                pairs: tag_value_array.as_ptr() as *const nbgl_contentTagValue_t,
-                nbPairs: fields.len() as u8,
-                ..Default::default()
-            };
+    /// Displays the review of `fields`, and returns whether the user approved
+    /// or rejected it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than 255 fields, including the parts of the
+    /// values split with [`LongValues::Split`].
+    pub fn review(&mut self, fields: &[Field]) -> ReviewResult {
+        let tag_values = CTagValues::new(fields, self.long_value_len, self.long_values);
+        if tag_values.len() > u8::MAX as usize {
+            panic!("Too many fields.");
+        }
+        self.review_list(&tag_values.lists().next().unwrap_or_default())
+    }
 
+    /// Displays the review of the fields described by `tag_value_list`.
+    fn review_list(&mut self, tag_value_list: &nbgl_contentTagValueList_t) -> ReviewResult {
+        unsafe {
             let icon: nbgl_icon_details_t = match self.glyph {
                 Some(g) => g.into(),
                 None => nbgl_icon_details_t::default(),
@@ -479,28 +842,29 @@ impl<'a> NbglReview<'a> {
 
             if self.blind {
                 if !show_blind_warning() {
-                    return false;
+                    return ReviewResult::Rejected;
                 }
             }
 
+            // Intro page, fields and approval page
+            #[cfg(feature = "demo")]
+            demo_arm(tag_value_list.nbPairs as usize + 2);
+
             // Show the review on the device.
-            let sync_ret = ux_sync_review(
-                self.tx_type.to_c_type(self.blind, false),
-                &tag_value_list as *const nbgl_contentTagValueList_t,
-                &icon as *const nbgl_icon_details_t,
-                self.title.as_ptr() as *const c_char,
-                self.subtitle.as_ptr() as *const c_char,
-                self.finish_title.as_ptr() as *const c_char,
-            );
+            let sync_ret = crate::inactivity::review(|| {
+                ux_sync_review(
+                    self.tx_type.to_c_type(self.blind, self.skippable),
+                    tag_value_list as *const nbgl_contentTagValueList_t,
+                    &icon as *const nbgl_icon_details_t,
+                    self.title.as_ptr() as *const c_char,
+                    self.subtitle.as_ptr() as *const c_char,
+                    self.finish_title.as_ptr() as *const c_char,
+                )
+            });
 
-            // Return true if the user approved the transaction, false otherwise.
             match sync_ret {
-                UX_SYNC_RET_APPROVED => {
-                    return true;
-                }
-                _ => {
-                    return false;
-                }
+                UX_SYNC_RET_APPROVED => ReviewResult::Approved,
+                _ => ReviewResult::Rejected,
             }
         }
     }
@@ -922,10 +1286,12 @@ impl NbglGenericReview {
 
             let reject_button_cstring = CString::new(reject_button_str).unwrap();
 
-            let sync_ret = ux_sync_genericReview(
-                &content_struct as *const nbgl_genericContents_t,
-                reject_button_cstring.as_ptr() as *const c_char,
-            );
+            let sync_ret = crate::inactivity::review(|| {
+                ux_sync_genericReview(
+                    &content_struct as *const nbgl_genericContents_t,
+                    reject_button_cstring.as_ptr() as *const c_char,
+                )
+            });
 
             // Return true if the user approved the transaction, false otherwise.
             match sync_ret {
@@ -942,10 +1308,54 @@ impl NbglGenericReview {
 
 /// A wrapper around the synchronous NBGL ux_sync_reviewStreaming (start, continue and finish)
 /// C API binding. Used to display streamed transaction review screens.
+///
+/// Streaming allows a transaction to be reviewed while it is being received:
+/// the fields decoded from each APDU chunk are displayed with
+/// [`continue_review`](NbglStreamingReview::continue_review), then dropped
+/// before the next chunk is requested, so neither the whole transaction nor all
+/// of its display strings need to be kept in RAM.
+///
+/// Once the user rejects the review, all the following calls return `false`
+/// without displaying anything.
+///
+/// When the review is [`skippable`](NbglStreamingReview::skippable), the user
+/// can skip the remaining fields to jump to the approval page. The following
+/// calls to [`continue_review`](NbglStreamingReview::continue_review) then
+/// return `true` without displaying anything, and the application can check
+/// [`is_skipped`](NbglStreamingReview::is_skipped) to stop formatting fields
+/// and call [`finish`](NbglStreamingReview::finish) as soon as the whole
+/// transaction has been received.
+///
+/// # Examples
+///
+/// ```
+/// let mut review = NbglStreamingReview::new().glyph(&FERRIS);
+/// if !review.start("Review transaction", "") {
+///     return Err(StatusWords::UserCancelled);
+/// }
+/// loop {
+///     // Reply to the current chunk and wait for the next one
+///     comm.reply_ok();
+///     let ins: Instruction = comm.next_command();
+///     let (fields, last) = decode_chunk(comm.get_data()?)?;
+///     if !review.continue_review(&fields) {
+///         return Err(StatusWords::UserCancelled);
+///     }
+///     if last {
+///         break;
+///     }
+/// }
+/// let approved = review.finish("Sign transaction?");
+/// ```
 pub struct NbglStreamingReview {
     icon: nbgl_icon_details_t,
     tx_type: TransactionType,
     blind: bool,
+    skippable: bool,
+    rejected: bool,
+    skipped: bool,
+    long_value_len: usize,
+    long_values: LongValues,
 }
 
 impl NbglStreamingReview {
@@ -954,6 +1364,11 @@ impl NbglStreamingReview {
             icon: nbgl_icon_details_t::default(),
             tx_type: TransactionType::Transaction,
             blind: false,
+            skippable: false,
+            rejected: false,
+            skipped: false,
+            long_value_len: LONG_VALUE_LEN,
+            long_values: LongValues::More,
         }
     }
 
@@ -975,103 +1390,163 @@ impl NbglStreamingReview {
         }
     }
 
+    /// Displays a "Skip" button on the fields pages, allowing the user to jump
+    /// directly to the final approval page.
+    pub fn skippable(self) -> NbglStreamingReview {
+        NbglStreamingReview {
+            skippable: true,
+            ..self
+        }
+    }
+
+    /// Sets the maximum length of the values displayed inline, see
+    /// [`NbglReview::long_value_len`].
+    pub fn long_value_len(self, long_value_len: usize) -> NbglStreamingReview {
+        NbglStreamingReview {
+            long_value_len,
+            ..self
+        }
+    }
+
+    /// Sets how long values are displayed, see [`NbglReview::long_values`].
+    pub fn long_values(self, long_values: LongValues) -> NbglStreamingReview {
+        NbglStreamingReview {
+            long_values,
+            ..self
+        }
+    }
+
+    /// Returns whether the user has rejected the review.
+    pub fn is_rejected(&self) -> bool {
+        self.rejected
+    }
+
+    /// Returns whether the user has skipped the remaining fields.
+    pub fn is_skipped(&self) -> bool {
+        self.skipped
+    }
+
+    /// Records the result of a streaming step, and returns true if the user
+    /// approved or skipped it.
+    fn step_result(&mut self, sync_ret: ux_sync_ret_t) -> bool {
+        match sync_ret {
+            UX_SYNC_RET_APPROVED => true,
+            UX_SYNC_RET_SKIPPED => {
+                self.skipped = true;
+                true
+            }
+            _ => {
+                self.rejected = true;
+                false
+            }
+        }
+    }
+
+    /// Displays the first page of the review, preceded by the blind signing
+    /// warning if [`blind`](NbglStreamingReview::blind) has been set.
+    /// Returns true if the user chose to continue the review.
     pub fn start(&mut self, title: &str, subtitle: &str) -> bool {
+        if self.rejected {
+            return false;
+        }
         unsafe {
             let title = CString::new(title).unwrap();
             let subtitle = CString::new(subtitle).unwrap();
 
             if self.blind {
                 if !show_blind_warning() {
+                    self.rejected = true;
                     return false;
                 }
             }
 
-            let sync_ret = ux_sync_reviewStreamingStart(
-                self.tx_type.to_c_type(self.blind, false),
-                &self.icon as *const nbgl_icon_details_t,
-                title.as_ptr() as *const c_char,
-                subtitle.as_ptr() as *const c_char,
-            );
+            #[cfg(feature = "demo")]
+            demo_arm(1);
+            let sync_ret = crate::inactivity::review(|| {
+                ux_sync_reviewStreamingStart(
+                    self.tx_type.to_c_type(self.blind, self.skippable),
+                    &self.icon as *const nbgl_icon_details_t,
+                    title.as_ptr() as *const c_char,
+                    subtitle.as_ptr() as *const c_char,
+                )
+            });
 
-            // Return true if the user approved the transaction, false otherwise.
-            match sync_ret {
-                UX_SYNC_RET_APPROVED => {
-                    return true;
-                }
-                _ => {
-                    return false;
-                }
-            }
+            self.step_result(sync_ret)
         }
     }
 
+    /// Displays the next fields of the review. It can be called as many times
+    /// as needed between [`start`](NbglStreamingReview::start) and
+    /// [`finish`](NbglStreamingReview::finish), typically once per received
+    /// chunk. The fields are not retained after this call returns.
+    /// Returns true if the user chose to continue the review.
     pub fn continue_review(&mut self, fields: &[Field]) -> bool {
-        unsafe {
-            let v: Vec<CField> = fields
-                .iter()
-                .map(|f| CField {
-                    name: CString::new(f.name).unwrap(),
-                    value: CString::new(f.value).unwrap(),
-                })
-                .collect();
-
-            // Fill the tag_value_array with the fields converted to nbgl_contentTagValue_t
-            let mut tag_value_array: Vec<nbgl_contentTagValue_t> = Vec::new();
-            for field in v.iter() {
-                let val = nbgl_contentTagValue_t {
-                    item: field.name.as_ptr() as *const i8,
-                    value: field.value.as_ptr() as *const i8,
-                    ..Default::default()
-                };
-                tag_value_array.push(val);
+        let tag_values = CTagValues::new(fields, self.long_value_len, self.long_values);
+        // The number of pairs of a tag/value list is stored on a u8
+        for tag_value_list in tag_values.lists() {
+            if self.rejected || self.skipped {
+                break;
             }
-
-            // Create the tag_value_list with the tag_value_array.
-            let tag_value_list = nbgl_contentTagValueList_t {
-                pairs: tag_value_array.as_ptr() as *const nbgl_contentTagValue_t,
-                nbPairs: fields.len() as u8,
-                ..Default::default()
-            };
-
-            let sync_ret = ux_sync_reviewStreamingContinue(
-                &tag_value_list as *const nbgl_contentTagValueList_t,
-            );
-
-            // Return true if the user approved the transaction, false otherwise.
-            match sync_ret {
-                UX_SYNC_RET_APPROVED => {
-                    return true;
-                }
-                _ => {
-                    return false;
-                }
+            unsafe {
+                #[cfg(feature = "demo")]
+                demo_arm(tag_value_list.nbPairs as usize);
+                let sync_ret = crate::inactivity::review(|| {
+                    ux_sync_reviewStreamingContinue(
+                        &tag_value_list as *const nbgl_contentTagValueList_t,
+                    )
+                });
+                self.step_result(sync_ret);
             }
         }
+        !self.rejected
     }
 
+    /// Displays a single field of the review, see
+    /// [`continue_review`](NbglStreamingReview::continue_review).
+    pub fn continue_field(&mut self, name: &str, value: &str) -> bool {
+        self.continue_review(&[Field { name, value }])
+    }
+
+    /// Displays the final page of the review, and returns true if the user
+    /// approved the transaction.
     pub fn finish(&mut self, finish_title: &str) -> bool {
+        if self.rejected {
+            return false;
+        }
         unsafe {
             let finish_title = CString::new(finish_title).unwrap();
-            let sync_ret = ux_sync_reviewStreamingFinish(finish_title.as_ptr() as *const c_char);
+            #[cfg(feature = "demo")]
+            demo_arm(1);
+            let sync_ret = crate::inactivity::review(|| {
+                ux_sync_reviewStreamingFinish(finish_title.as_ptr() as *const c_char)
+            });
 
-            // Return true if the user approved the transaction, false otherwise.
-            match sync_ret {
-                UX_SYNC_RET_APPROVED => {
-                    return true;
-                }
-                _ => {
-                    return false;
-                }
-            }
+            self.step_result(sync_ret)
         }
     }
 }
 
 /// A wrapper around the synchronous NBGL ux_sync_addressReview C API binding.
 /// Used to display address confirmation screens.
+///
+/// The derivation path, account index and extra details are displayed on a
+/// details page next to the address, so that users can tell which account's
+/// address they are verifying.
+///
+/// # Examples
+///
+/// ```
+/// let approved = NbglAddressReview::new()
+///     .glyph(&FERRIS)
+///     .verify_str("Verify CRAB address")
+///     .derivation_path("m/44'/535348'/0'/0/0")
+///     .account(0)
+///     .show(&address);
+/// ```
 pub struct NbglAddressReview<'a> {
     glyph: Option<&'a NbglGlyph<'a>>,
     verify_str: CString,
+    details: Vec<CField>,
 }
 
 impl<'a> NbglAddressReview<'a> {
@@ -1079,9 +1554,37 @@ impl<'a> NbglAddressReview<'a> {
         NbglAddressReview {
             verify_str: CString::new("").unwrap(),
             glyph: None,
+            details: Vec::new(),
         }
     }
 
+    /// Adds a detail field, displayed after the previously added ones.
+    fn detail(mut self, name: &str, value: &str) -> NbglAddressReview<'a> {
+        self.details.push(CField {
+            name: CString::new(name).unwrap(),
+            value: CString::new(value).unwrap(),
+        });
+        self
+    }
+
+    /// Displays the derivation path of the address, such as `m/44'/0'/0'/0/0`.
+    pub fn derivation_path(self, path: &str) -> NbglAddressReview<'a> {
+        self.detail("Derivation path", path)
+    }
+
+    /// Displays the index of the account the address belongs to.
+    pub fn account(self, index: u32) -> NbglAddressReview<'a> {
+        let mut buf = [0u8; 10];
+        self.detail("Account", index.numtoa_str(10, &mut buf))
+    }
+
+    /// Displays additional fields, after the derivation path and account.
+    pub fn details(self, fields: &[Field]) -> NbglAddressReview<'a> {
+        fields
+            .iter()
+            .fold(self, |review, f| review.detail(f.name, f.value))
+    }
+
     pub fn glyph(self, glyph: &'a NbglGlyph) -> NbglAddressReview<'a> {
         NbglAddressReview {
             glyph: Some(glyph),
@@ -1105,14 +1608,38 @@ impl<'a> NbglAddressReview<'a> {
 
             let address = CString::new(address).unwrap();
 
+            let tag_value_array: Vec<nbgl_contentTagValue_t> = self
+                .details
+                .iter()
+                .map(|field| nbgl_contentTagValue_t {
+                    item: field.name.as_ptr() as *const i8,
+                    value: field.value.as_ptr() as *const i8,
+                    ..Default::default()
+                })
+                .collect();
+            let tag_value_list = nbgl_contentTagValueList_t {
+                pairs: tag_value_array.as_ptr() as *const nbgl_contentTagValue_t,
+                nbPairs: tag_value_array.len() as u8,
+                ..Default::default()
+            };
+            let details = if tag_value_array.is_empty() {
+                core::ptr::null()
+            } else {
+                &tag_value_list as *const nbgl_contentTagValueList_t
+            };
+
             // Show the address confirmation on the device.
-            let sync_ret = ux_sync_addressReview(
-                address.as_ptr(),
-                core::ptr::null(),
-                &icon as *const nbgl_icon_details_t,
-                self.verify_str.as_ptr(),
-                core::ptr::null(),
-            );
+            #[cfg(feature = "demo")]
+            demo_arm(1);
+            let sync_ret = crate::inactivity::review(|| {
+                ux_sync_addressReview(
+                    address.as_ptr(),
+                    details,
+                    &icon as *const nbgl_icon_details_t,
+                    self.verify_str.as_ptr(),
+                    core::ptr::null(),
+                )
+            });
 
             // Return true if the user approved the address, false otherwise.
             match sync_ret {
@@ -1130,9 +1657,20 @@ impl<'a> NbglAddressReview<'a> {
     }
 }
 
-/// A wrapper around the synchronous NBGL ux_sync_status C API binding.
+/// A wrapper around the synchronous NBGL ux_sync_choice C API binding.
 /// Draws a generic choice page, described in a centered info (with configurable icon),
 /// thanks to a button and a footer at the bottom of the page.
+///
+/// # Examples
+///
+/// ```
+/// let reset = NbglChoice::new().glyph(&WARNING).show(
+///     "Reset app data?",
+///     "All the registered accounts will be removed.",
+///     "Reset",
+///     "Cancel",
+/// );
+/// ```
 pub struct NbglChoice<'a> {
     glyph: Option<&'a NbglGlyph<'a>>,
 }
@@ -1149,6 +1687,8 @@ impl<'a> NbglChoice<'a> {
         }
     }
 
+    /// Displays the choice page, and returns true if the user pressed the
+    /// `confirm_text` button, false if they pressed the `cancel_text` footer.
     pub fn show(
         self,
         message: &str,
@@ -1174,7 +1714,7 @@ impl<'a> NbglChoice<'a> {
                 cancel_text.as_ptr() as *const c_char,
             );
 
-            // Return true if the user approved the transaction, false otherwise.
+            // Return true if the user confirmed, false otherwise.
             match sync_ret {
                 UX_SYNC_RET_APPROVED => {
                     return true;
@@ -1187,8 +1727,32 @@ impl<'a> NbglChoice<'a> {
     }
 }
 
+impl From<ReviewStatus> for nbgl_reviewStatusType_t {
+    fn from(status: ReviewStatus) -> nbgl_reviewStatusType_t {
+        match status {
+            ReviewStatus::TransactionSigned => STATUS_TYPE_TRANSACTION_SIGNED,
+            ReviewStatus::TransactionRejected => STATUS_TYPE_TRANSACTION_REJECTED,
+            ReviewStatus::MessageSigned => STATUS_TYPE_MESSAGE_SIGNED,
+            ReviewStatus::MessageRejected => STATUS_TYPE_MESSAGE_REJECTED,
+            ReviewStatus::OperationSigned => STATUS_TYPE_OPERATION_SIGNED,
+            ReviewStatus::OperationRejected => STATUS_TYPE_OPERATION_REJECTED,
+            ReviewStatus::AddressVerified => STATUS_TYPE_ADDRESS_VERIFIED,
+            ReviewStatus::AddressRejected => STATUS_TYPE_ADDRESS_REJECTED,
+        }
+    }
+}
+
 /// A wrapper around the synchronous NBGL ux_sync_reviewStatus C API binding.
 /// Draws a transient (3s) status page of the chosen type.
+///
+/// # Examples
+///
+/// ```
+/// let result = NbglReview::new().glyph(&FERRIS).review(&fields);
+/// NbglReviewStatus::new().show_result(result);
+///
+/// NbglReviewStatus::show_status(ReviewStatus::AddressVerified);
+/// ```
 pub struct NbglReviewStatus {
     status_type: StatusType,
 }
@@ -1209,10 +1773,23 @@ impl NbglReviewStatus {
             ux_sync_reviewStatus(self.status_type.to_message(success));
         }
     }
+
+    /// Displays the status page closing a review with the given result.
+    pub fn show_result(&self, result: ReviewResult) {
+        NbglReviewStatus::show_status(ReviewStatus::new(self.status_type, result));
+    }
+
+    /// Displays the given status page.
+    pub fn show_status(status: ReviewStatus) {
+        unsafe {
+            ux_sync_reviewStatus(status.into());
+        }
+    }
 }
 
 /// A wrapper around the synchronous NBGL ux_sync_status C API binding.
-/// Draws a transient (3s) status page, either of success or failure, with the given message
+/// Draws a transient (3s) status page, either of success or failure, with the given message.
+/// The page is dismissed automatically after the delay, or when the user taps it.
 pub struct NbglStatus {
     text: CString,
 }
@@ -1230,38 +1807,184 @@ impl NbglStatus {
         }
     }
 
+    /// Displays the status page, and returns once it has been dismissed.
     pub fn show(&self, success: bool) {
         unsafe {
             ux_sync_status(self.text.as_ptr() as *const c_char, success);
         }
     }
+
+    /// Displays a success status page with the given message.
+    pub fn success(text: &str) {
+        NbglStatus::new().text(text).show(true);
+    }
+
+    /// Displays a failure status page with the given message.
+    pub fn failure(text: &str) {
+        NbglStatus::new().text(text).show(false);
+    }
 }
 
 /// A wrapper around the asynchronous NBGL nbgl_useCaseSpinner C API binding.
 /// Draws a spinner page with the given parameters. The spinner will "turn" automatically every
 /// 800 ms, provided the IO event loop is running to process TickerEvents.
+///
+/// The spinner page stays on screen until another page is drawn, or until
+/// [`dismiss`](NbglSpinner::dismiss) is called. The `NbglSpinner` must be kept
+/// alive while its page is displayed, as the page refers to its text.
+///
+/// # Examples
+///
+/// ```
+/// let spinner = NbglSpinner::new().text("Processing");
+/// spinner.show();
+/// let signature = sign_transaction(&tx);
+/// NbglReviewStatus::new().show(signature.is_ok());
+/// ```
 pub struct NbglSpinner {
     text: CString,
+    cancel_text: CString,
+    on_cancel: Option<fn()>,
+    layout: *mut nbgl_layout_t,
+}
+
+/// Token of the cancel button of a cancellable spinner.
+const SPINNER_CANCEL_TOKEN: u8 = FIRST_USER_TOKEN as u8;
+/// Set when the cancel button of the displayed spinner is touched.
+static mut SPINNER_CANCELLED: bool = false;
+static mut SPINNER_ON_CANCEL: Option<fn()> = None;
+
+/// Callback triggered by the NBGL API when the cancel button of a
+/// cancellable spinner is touched.
+unsafe extern "C" fn spinner_action_callback(token: c_int, _index: u8) {
+    if token == SPINNER_CANCEL_TOKEN as c_int && !SPINNER_CANCELLED {
+        SPINNER_CANCELLED = true;
+        if let Some(on_cancel) = SPINNER_ON_CANCEL {
+            on_cancel();
+        }
+    }
 }
 
 impl NbglSpinner {
     pub fn new() -> NbglSpinner {
         NbglSpinner {
             text: CString::new("").unwrap(),
+            cancel_text: CString::new("Cancel").unwrap(),
+            on_cancel: None,
+            layout: core::ptr::null_mut(),
         }
     }
 
     pub fn text(self, text: &str) -> NbglSpinner {
         NbglSpinner {
             text: CString::new(text).unwrap(),
+            ..self
+        }
+    }
+
+    /// Sets the text of the cancel button of
+    /// [`show_cancellable`](NbglSpinner::show_cancellable), "Cancel" by default.
+    pub fn cancel_text(self, cancel_text: &str) -> NbglSpinner {
+        NbglSpinner {
+            cancel_text: CString::new(cancel_text).unwrap(),
+            ..self
         }
     }
 
+    /// Sets a function called when the user touches the cancel button of
+    /// [`show_cancellable`](NbglSpinner::show_cancellable). It is called while
+    /// the IO events are processed, and must not display other pages.
+    pub fn on_cancel(self, on_cancel: fn()) -> NbglSpinner {
+        NbglSpinner {
+            on_cancel: Some(on_cancel),
+            ..self
+        }
+    }
+
+    /// Draws the spinner page, and returns immediately.
     pub fn show(&self) {
         unsafe {
             nbgl_useCaseSpinner(self.text.as_ptr() as *const c_char);
         }
     }
+
+    /// Draws a spinner page with a cancel button, and returns immediately.
+    ///
+    /// The touch on the cancel button is only detected while the IO events are
+    /// processed, so the computation must service the IO regularly, for
+    /// instance with [`Comm::busy`], and poll
+    /// [`is_cancelled`](NbglSpinner::is_cancelled).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut spinner = NbglSpinner::new().text("Processing inputs");
+    /// spinner.show_cancellable();
+    /// // Processing an input takes about 60 ms: a keepalive every second
+    /// let mut busy = comm.busy(16);
+    /// for input in psbt.inputs() {
+    ///     if spinner.is_cancelled() {
+    ///         return Err(AppError::Cancelled);
+    ///     }
+    ///     process_input(input);
+    ///     busy.step();
+    /// }
+    /// ```
+    pub fn show_cancellable(&mut self) {
+        unsafe {
+            self.release();
+            SPINNER_CANCELLED = false;
+            SPINNER_ON_CANCEL = self.on_cancel;
+            let layout_description = nbgl_layoutDescription_t {
+                modal: false,
+                onActionCallback: Some(spinner_action_callback),
+                ..Default::default()
+            };
+            self.layout = nbgl_layoutGet(&layout_description as *const nbgl_layoutDescription_t);
+            nbgl_layoutAddSpinner(self.layout, self.text.as_ptr() as *const c_char, false);
+            nbgl_layoutAddFooter(
+                self.layout,
+                self.cancel_text.as_ptr() as *const c_char,
+                SPINNER_CANCEL_TOKEN,
+                TuneIndex::TapCasual as tune_index_e,
+            );
+            nbgl_layoutDraw(self.layout);
+            nbgl_refresh();
+        }
+    }
+
+    /// Returns whether the user touched the cancel button of the page drawn
+    /// by [`show_cancellable`](NbglSpinner::show_cancellable).
+    pub fn is_cancelled(&self) -> bool {
+        !self.layout.is_null() && unsafe { SPINNER_CANCELLED }
+    }
+
+    /// Removes the spinner page, leaving a blank screen until the next page is
+    /// drawn. Not needed when the spinner is directly followed by another page.
+    pub fn dismiss(&self) {
+        unsafe {
+            nbgl_screenReset();
+            nbgl_refresh();
+        }
+    }
+
+    fn release(&mut self) {
+        if !self.layout.is_null() {
+            unsafe {
+                nbgl_layoutRelease(self.layout);
+                SPINNER_ON_CANCEL = None;
+            }
+            self.layout = core::ptr::null_mut();
+        }
+    }
+}
+
+/// The page stays on screen until another page is drawn, only the resources
+/// of a cancellable spinner are released.
+impl Drop for NbglSpinner {
+    fn drop(&mut self) {
+        self.release();
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -0,0 +1,188 @@
+use super::*;
+
+/// Maximum length of an address book entry name, in bytes
+pub const ADDRESS_BOOK_NAME_LEN: usize = 32;
+/// Maximum length of an address book entry address, in bytes
+pub const ADDRESS_BOOK_ADDRESS_LEN: usize = 64;
+
+/// Named address stored in an [`AddressBook`].
+#[derive(Copy, Clone)]
+pub struct AddressBookEntry {
+    name: [u8; ADDRESS_BOOK_NAME_LEN],
+    name_len: u8,
+    address: [u8; ADDRESS_BOOK_ADDRESS_LEN],
+    address_len: u8,
+}
+
+impl AddressBookEntry {
+    /// Empty entry, used to initialize the NVM [`Collection`] of an [`AddressBook`].
+    pub const EMPTY: AddressBookEntry = AddressBookEntry {
+        name: [0u8; ADDRESS_BOOK_NAME_LEN],
+        name_len: 0,
+        address: [0u8; ADDRESS_BOOK_ADDRESS_LEN],
+        address_len: 0,
+    };
+
+    /// Creates an entry, or returns `None` if the name or the address is too long.
+    pub fn new(name: &str, address: &str) -> Option<AddressBookEntry> {
+        if name.len() > ADDRESS_BOOK_NAME_LEN || address.len() > ADDRESS_BOOK_ADDRESS_LEN {
+            return None;
+        }
+        let mut entry = AddressBookEntry::EMPTY;
+        entry.name[..name.len()].copy_from_slice(name.as_bytes());
+        entry.name_len = name.len() as u8;
+        entry.address[..address.len()].copy_from_slice(address.as_bytes());
+        entry.address_len = address.len() as u8;
+        Some(entry)
+    }
+
+    pub fn name(&self) -> &str {
+        core::str::from_utf8(&self.name[..self.name_len as usize]).unwrap_or("")
+    }
+
+    pub fn address(&self) -> &str {
+        core::str::from_utf8(&self.address[..self.address_len as usize]).unwrap_or("")
+    }
+}
+
+/// Errors returned when adding an entry to an [`AddressBook`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AddressBookError {
+    /// The name or the address is too long
+    TooLong,
+    /// The address book is full
+    Full,
+    /// The address is already registered
+    Duplicate,
+}
+
+/// Actions of the address book pages.
+#[derive(Copy, Clone)]
+enum AddressBookAction {
+    Close,
+    Next,
+    Delete,
+}
+
+/// Address book storing named addresses in an NVM [`Collection`], with the
+/// NBGL screens to add, list and delete entries. It allows wallet applications
+/// to offer address whitelisting or favorites.
+///
+/// # Examples
+///
+/// ```
+/// #[link_section = ".nvm_data"]
+/// static mut ADDRESSES: NVMData<Collection<AddressBookEntry, 16>> =
+///     NVMData::new(Collection::new(AddressBookEntry::EMPTY));
+///
+/// let mut book = AddressBook::new(unsafe { ADDRESSES.get_mut() });
+/// // Ask the user to confirm the new entry
+/// book.add("Savings", &address)?;
+/// // Browse and delete entries
+/// book.show();
+/// ```
+pub struct AddressBook<'a, const N: usize> {
+    entries: &'a mut Collection<AddressBookEntry, N>,
+}
+
+impl<'a, const N: usize> AddressBook<'a, N> {
+    pub fn new(entries: &'a mut Collection<AddressBookEntry, N>) -> AddressBook<'a, N> {
+        AddressBook { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&AddressBookEntry> {
+        self.entries.get(index)
+    }
+
+    /// Returns the name registered for `address`, if any.
+    pub fn find(&self, address: &str) -> Option<&str> {
+        self.entries
+            .into_iter()
+            .find(|e| e.address() == address)
+            .map(|e| e.name())
+    }
+
+    /// Asks the user to confirm the registration of `address` under `name`,
+    /// and stores it if approved. Returns whether the entry has been added.
+    pub fn add(&mut self, name: &str, address: &str) -> Result<bool, AddressBookError> {
+        let entry = AddressBookEntry::new(name, address).ok_or(AddressBookError::TooLong)?;
+        if self.find(address).is_some() {
+            return Err(AddressBookError::Duplicate);
+        }
+        if self.entries.remaining() == 0 {
+            return Err(AddressBookError::Full);
+        }
+        let approved = NbglAddressReview::new()
+            .verify_str("Save address?")
+            .details(&[Field {
+                name: "Name",
+                value: name,
+            }])
+            .show(address);
+        if approved {
+            self.entries
+                .add(&entry)
+                .map_err(|_| AddressBookError::Full)?;
+        }
+        NbglReviewStatus::new()
+            .status_type(StatusType::Address)
+            .show(approved);
+        Ok(approved)
+    }
+
+    /// Displays the entries one by one, allowing the user to delete them,
+    /// until the user closes the address book.
+    pub fn show(&mut self) {
+        let mut index = 0;
+        loop {
+            let len = self.entries.len();
+            if len == 0 {
+                NbglPage::new()
+                    .text("Address book", "No saved addresses")
+                    .back(())
+                    .show();
+                return;
+            }
+            index %= len;
+            let entry = *self.entries.get(index).unwrap();
+
+            let mut page = NbglPage::new()
+                .text(entry.name(), entry.address())
+                .back(AddressBookAction::Close);
+            if len > 1 {
+                page = page.button("Next", None, ButtonStyle::Black, AddressBookAction::Next);
+            }
+            page = page.button(
+                "Delete",
+                None,
+                ButtonStyle::White,
+                AddressBookAction::Delete,
+            );
+
+            match page.show() {
+                AddressBookAction::Close => return,
+                AddressBookAction::Next => index += 1,
+                AddressBookAction::Delete => {
+                    let confirmed = NbglChoice::new().show(
+                        "Delete this address?",
+                        entry.name(),
+                        "Delete",
+                        "Cancel",
+                    );
+                    if confirmed {
+                        self.entries.remove(index);
+                        NbglStatus::success("Address deleted");
+                    }
+                }
+            }
+        }
+    }
+}
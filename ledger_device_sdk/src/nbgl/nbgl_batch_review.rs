@@ -0,0 +1,127 @@
+use super::*;
+use alloc::format;
+use alloc::string::String;
+
+/// Operation of a batch transaction reviewed with [`NbglReview::review_batch`].
+pub struct BatchItem<'a> {
+    /// Short description, such as "0.5 BTC to bc1q...xyz"
+    pub summary: &'a str,
+    /// Fields displayed when the user opens the details of the operation
+    pub details: &'a [Field<'a>],
+}
+
+impl<'a> NbglReview<'a> {
+    /// Displays the review of a transaction made of several operations, such
+    /// as a multi-send or a batch withdrawal.
+    ///
+    /// Each operation is displayed as a field named "`item_name` i/n" with its
+    /// summary, and a "More" button opening its details. The `totals` fields,
+    /// such as the total amount and fees, are displayed after the operations,
+    /// before the approval page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let result = NbglReview::new()
+    ///     .titles("Review multi-send", "", "Sign transaction")
+    ///     .review_batch(
+    ///         "Output",
+    ///         &[
+    ///             BatchItem {
+    ///                 summary: "1 CRAB to crab1qx...7ze",
+    ///                 details: &[
+    ///                     Field { name: "Recipient", value: "crab1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx7ze" },
+    ///                     Field { name: "Amount", value: "1 CRAB" },
+    ///                 ],
+    ///             },
+    ///             BatchItem {
+    ///                 summary: "2 CRAB to crab1pz...k3m",
+    ///                 details: &[
+    ///                     Field { name: "Recipient", value: "crab1pzry9x0s0muk3m" },
+    ///                     Field { name: "Amount", value: "2 CRAB" },
+    ///                 ],
+    ///             },
+    ///         ],
+    ///         &[
+    ///             Field { name: "Total", value: "3 CRAB" },
+    ///             Field { name: "Fees", value: "0.01 CRAB" },
+    ///         ],
+    ///     );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than 255 operations and totals.
+    pub fn review_batch(
+        &mut self,
+        item_name: &str,
+        items: &[BatchItem],
+        totals: &[Field],
+    ) -> ReviewResult {
+        if items.len() + totals.len() > u8::MAX as usize {
+            panic!("Too many fields.");
+        }
+
+        let names: Vec<CString> = items
+            .iter()
+            .enumerate()
+            .map(|(i, _)| CString::new(format!("{} {}/{}", item_name, i + 1, items.len())).unwrap())
+            .chain(totals.iter().map(|f| CString::new(f.name).unwrap()))
+            .collect();
+        let values: Vec<CString> = items
+            .iter()
+            .map(|item| CString::new(item.summary).unwrap())
+            .chain(totals.iter().map(|f| CString::new(f.value).unwrap()))
+            .collect();
+        let details: Vec<CString> = items
+            .iter()
+            .map(|item| CString::new(format_details(item.details)).unwrap())
+            .collect();
+        // Built before the pairs pointing to them, so that they are never moved
+        let extensions: Vec<nbgl_contentValueExt_t> = details
+            .iter()
+            .map(|d| nbgl_contentValueExt_t {
+                fullValue: d.as_ptr() as *const c_char,
+                ..Default::default()
+            })
+            .collect();
+
+        let pairs: Vec<nbgl_contentTagValue_t> = names
+            .iter()
+            .zip(values.iter())
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let mut pair = nbgl_contentTagValue_t {
+                    item: name.as_ptr() as *const c_char,
+                    value: value.as_ptr() as *const c_char,
+                    ..Default::default()
+                };
+                if let Some(extension) = extensions.get(i) {
+                    pair.__bindgen_anon_1.extension = extension as *const nbgl_contentValueExt_t;
+                    pair.set_aliasValue(1);
+                }
+                pair
+            })
+            .collect();
+
+        let tag_value_list = nbgl_contentTagValueList_t {
+            pairs: pairs.as_ptr() as *const nbgl_contentTagValue_t,
+            nbPairs: pairs.len() as u8,
+            ..Default::default()
+        };
+        self.review_list(&tag_value_list)
+    }
+}
+
+/// Formats the details of an operation as "name" and "value" lines, separated
+/// by empty lines.
+fn format_details(details: &[Field]) -> String {
+    let mut text = String::new();
+    for (i, field) in details.iter().enumerate() {
+        if i > 0 {
+            text.push_str("\n\n");
+        }
+        text.push_str(&format!("{}\n{}", field.name, field.value));
+    }
+    text
+}
@@ -0,0 +1,128 @@
+use super::*;
+
+/// Outcome of an [`NbglBlindSigningReview`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlindSigningResult {
+    /// The user accepted the risk and approved the transaction
+    Approved,
+    /// The user rejected the transaction, either on a warning page or on the review
+    Rejected,
+    /// Blind signing is disabled, and the user asked to go to the settings to
+    /// enable it. The transaction must be rejected, see
+    /// [`NbglHomeAndSettings::open_settings`].
+    GoToSettings,
+}
+
+/// A prebuilt flow for transactions which cannot be decoded, following the
+/// blind signing UX guidelines:
+///
+/// - when blind signing is disabled in the application settings, the user is
+///   informed and offered to go to the settings, the transaction being rejected,
+/// - otherwise, the risk is explained on warning pages where rejecting is the
+///   default choice, then the hash of the transaction is reviewed.
+///
+/// # Examples
+///
+/// ```
+/// let result = NbglBlindSigningReview::new()
+///     .glyph(&FERRIS)
+///     .enabled(settings.blind_signing())
+///     .show(&hash_hex);
+/// match result {
+///     BlindSigningResult::Approved => sign(comm),
+///     BlindSigningResult::GoToSettings => {
+///         comm.reply(StatusWords::UserCancelled);
+///         home.open_settings();
+///     }
+///     BlindSigningResult::Rejected => comm.reply(StatusWords::UserCancelled),
+/// }
+/// ```
+pub struct NbglBlindSigningReview<'a> {
+    glyph: Option<&'a NbglGlyph<'a>>,
+    tx_type: TransactionType,
+    enabled: bool,
+    title: &'a str,
+    finish_title: &'a str,
+    hash_name: &'a str,
+}
+
+impl<'a> NbglBlindSigningReview<'a> {
+    pub fn new() -> NbglBlindSigningReview<'a> {
+        NbglBlindSigningReview {
+            glyph: None,
+            tx_type: TransactionType::Transaction,
+            enabled: false,
+            title: "Review transaction",
+            finish_title: "Sign transaction",
+            hash_name: "Transaction hash",
+        }
+    }
+
+    pub fn glyph(self, glyph: &'a NbglGlyph) -> NbglBlindSigningReview<'a> {
+        NbglBlindSigningReview {
+            glyph: Some(glyph),
+            ..self
+        }
+    }
+
+    pub fn tx_type(self, tx_type: TransactionType) -> NbglBlindSigningReview<'a> {
+        NbglBlindSigningReview { tx_type, ..self }
+    }
+
+    /// Sets whether blind signing is enabled in the application settings.
+    pub fn enabled(self, enabled: bool) -> NbglBlindSigningReview<'a> {
+        NbglBlindSigningReview { enabled, ..self }
+    }
+
+    /// Sets the titles of the first and last review pages, and the name of the
+    /// hash field.
+    pub fn titles(
+        self,
+        title: &'a str,
+        finish_title: &'a str,
+        hash_name: &'a str,
+    ) -> NbglBlindSigningReview<'a> {
+        NbglBlindSigningReview {
+            title,
+            finish_title,
+            hash_name,
+            ..self
+        }
+    }
+
+    /// Runs the flow, displaying `hash` as the only reviewed field.
+    pub fn show(&self, hash: &str) -> BlindSigningResult {
+        if !self.enabled {
+            const WARNING: NbglGlyph =
+                NbglGlyph::from_include(include_gif!("icons/Warning_64px.gif", NBGL));
+            let go_to_settings = NbglChoice::new().glyph(&WARNING).show(
+                "This transaction cannot be clear-signed",
+                "Enable blind signing in the settings to sign this transaction.",
+                "Go to settings",
+                "Reject transaction",
+            );
+            return if go_to_settings {
+                BlindSigningResult::GoToSettings
+            } else {
+                BlindSigningResult::Rejected
+            };
+        }
+
+        let mut review = NbglReview::new().tx_type(self.tx_type).blind().titles(
+            self.title,
+            "",
+            self.finish_title,
+        );
+        if let Some(glyph) = self.glyph {
+            review = review.glyph(glyph);
+        }
+        let fields = [Field {
+            name: self.hash_name,
+            value: hash,
+        }];
+        match review.review(&fields) {
+            ReviewResult::Approved => BlindSigningResult::Approved,
+            ReviewResult::Rejected => BlindSigningResult::Rejected,
+        }
+    }
+}
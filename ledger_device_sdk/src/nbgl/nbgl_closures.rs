@@ -0,0 +1,143 @@
+use super::*;
+use alloc::boxed::Box;
+
+/// Number of closures of each kind which can be registered at the same time.
+pub const CALLBACK_SLOTS: usize = 4;
+
+/// This macro generates an adapter turning Rust closures into NBGL callbacks.
+///
+/// C callbacks do not carry any user data, so each closure is stored in one of
+/// `CALLBACK_SLOTS` static slots, and called by a trampoline function dedicated
+/// to this slot. The slot is freed when the adapter is dropped, after which
+/// the callback does nothing.
+macro_rules! impl_closure_callback {
+    ($(#[$doc:meta])* $typename:ident, $slots:ident, $trampoline:ident, $ctype:ty, ($($arg:ident: $argtype:ty),*)) => {
+        static mut $slots: [Option<Box<dyn FnMut($($argtype),*)>>; CALLBACK_SLOTS] = {
+            const EMPTY_SLOT: Option<Box<dyn FnMut($($argtype),*)>> = None;
+            [EMPTY_SLOT; CALLBACK_SLOTS]
+        };
+
+        unsafe extern "C" fn $trampoline<const SLOT: usize>($($arg: $argtype),*) {
+            if let Some(closure) = $slots[SLOT].as_mut() {
+                closure($($arg),*);
+            }
+        }
+
+        $(#[$doc])*
+        pub struct $typename {
+            slot: usize,
+        }
+
+        impl $typename {
+            /// Registers `closure`, or returns `None` if all the slots are
+            /// already in use.
+            pub fn new<F: FnMut($($argtype),*) + 'static>(closure: F) -> Option<$typename> {
+                unsafe {
+                    let slot = $slots.iter().position(|s| s.is_none())?;
+                    $slots[slot] = Some(Box::new(closure));
+                    Some($typename { slot })
+                }
+            }
+
+            /// Returns the C callback calling the closure, to be passed to
+            /// the NBGL API. It must not be used after `self` is dropped.
+            pub fn c_callback(&self) -> $ctype {
+                // One trampoline per slot, which does not build if the number
+                // of slots changes without this list
+                const TRAMPOLINES: [unsafe extern "C" fn($($argtype),*); CALLBACK_SLOTS] = [
+                    $trampoline::<0>,
+                    $trampoline::<1>,
+                    $trampoline::<2>,
+                    $trampoline::<3>,
+                ];
+                Some(TRAMPOLINES[self.slot])
+            }
+        }
+
+        impl Drop for $typename {
+            fn drop(&mut self) {
+                unsafe {
+                    $slots[self.slot] = None;
+                }
+            }
+        }
+    };
+}
+
+impl_closure_callback!(
+    /// A closure called with the choice of the user, `true` if they approved
+    /// or confirmed, as an `nbgl_choiceCallback_t`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let callback = ChoiceCallback::new(|confirmed| {
+    ///     if confirmed {
+    ///         sign_and_reply();
+    ///     }
+    /// })
+    /// .unwrap();
+    /// unsafe {
+    ///     nbgl_useCaseChoice(
+    ///         &icon,
+    ///         message.as_ptr(),
+    ///         sub_message.as_ptr(),
+    ///         confirm_text.as_ptr(),
+    ///         cancel_text.as_ptr(),
+    ///         callback.c_callback(),
+    ///     );
+    /// }
+    /// ```
+    ChoiceCallback,
+    CHOICE_SLOTS,
+    choice_trampoline,
+    nbgl_choiceCallback_t,
+    (confirm: bool)
+);
+
+impl_closure_callback!(
+    /// A closure called when a button is touched or a screen is left, as an
+    /// `nbgl_callback_t`.
+    ActionCallback,
+    ACTION_SLOTS,
+    action_trampoline,
+    nbgl_callback_t,
+    ()
+);
+
+impl_closure_callback!(
+    /// A closure called with the token, index and page of the touched control
+    /// of a content, such as a setting switch, as an
+    /// `nbgl_contentActionCallback_t`.
+    ContentActionCallback,
+    CONTENT_ACTION_SLOTS,
+    content_action_trampoline,
+    nbgl_contentActionCallback_t,
+    (token: c_int, index: u8, page: c_int)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    static mut CONFIRMED: Option<bool> = None;
+
+    #[test]
+    fn closure_callbacks() {
+        let callback = ChoiceCallback::new(|confirm| unsafe { CONFIRMED = Some(confirm) }).unwrap();
+        unsafe {
+            callback.c_callback().unwrap()(true);
+            assert_eq!(CONFIRMED, Some(true));
+        }
+
+        let others: Vec<ChoiceCallback> = (1..CALLBACK_SLOTS)
+            .map(|_| ChoiceCallback::new(|_| ()).unwrap())
+            .collect();
+        assert_eq!(ChoiceCallback::new(|_| ()).is_none(), true);
+        drop(others);
+        assert_eq!(ChoiceCallback::new(|_| ()).is_some(), true);
+    }
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+/// Screen size, used to position the simulated swipes.
+const SCREEN_SIZE: (u16, u16) = (
+    crate::caps::SCREEN_WIDTH as u16,
+    crate::caps::SCREEN_HEIGHT as u16,
+);
+
+/// Number of ticker events between two pages, 0 when the demo mode is disabled.
+static mut DEMO_PAGE_TICKS: u32 = 0;
+static mut DEMO_TICKS: u32 = 0;
+/// Pages left to display before approving the current flow, if any.
+static mut DEMO_PAGES_LEFT: Option<usize> = None;
+
+/// Enables the demo mode, only available with the `demo` feature.
+///
+/// Review flows then advance to their next page every `page_ticks` ticker
+/// events (100 ms each), and are approved once their last page has been
+/// displayed. It allows to capture the screenshots of complete flows, for the
+/// application store submission or the documentation, without touching the
+/// screen.
+///
+/// As it approves transactions without user consent, the `demo` feature only
+/// builds in debug builds or with the `speculos` feature.
+pub fn enable_demo_mode(page_ticks: u32) {
+    unsafe {
+        DEMO_PAGE_TICKS = page_ticks;
+        DEMO_TICKS = 0;
+    }
+}
+
+pub fn disable_demo_mode() {
+    unsafe {
+        DEMO_PAGE_TICKS = 0;
+        DEMO_PAGES_LEFT = None;
+    }
+}
+
+/// Called before displaying a flow of `pages` pages, after which the demo
+/// mode approves it.
+pub(crate) fn demo_arm(pages: usize) {
+    unsafe {
+        if DEMO_PAGE_TICKS > 0 {
+            DEMO_TICKS = 0;
+            DEMO_PAGES_LEFT = Some(pages);
+        }
+    }
+}
+
+/// Called on each ticker event, to advance or approve the displayed flow.
+pub(crate) fn demo_tick() {
+    unsafe {
+        let Some(pages_left) = DEMO_PAGES_LEFT else {
+            return;
+        };
+        DEMO_TICKS += 1;
+        if DEMO_TICKS < DEMO_PAGE_TICKS {
+            return;
+        }
+        DEMO_TICKS = 0;
+        if pages_left > 1 {
+            DEMO_PAGES_LEFT = Some(pages_left - 1);
+            swipe_left();
+        } else {
+            DEMO_PAGES_LEFT = None;
+            ux_sync_setReturnCode(UX_SYNC_RET_APPROVED);
+            ux_sync_setEnded(true);
+        }
+    }
+}
+
+/// Simulates a swipe from right to left in the middle of the screen, which
+/// displays the next page of a review.
+fn swipe_left() {
+    let (width, height) = SCREEN_SIZE;
+    let y = height / 2;
+    for (state, x) in [
+        (SEPROXYHAL_TAG_FINGER_EVENT_TOUCH, width * 3 / 4),
+        (SEPROXYHAL_TAG_FINGER_EVENT_TOUCH, width / 4),
+        (SEPROXYHAL_TAG_FINGER_EVENT_RELEASE, width / 4),
+    ] {
+        // [ tag, len_hi, len_lo, state, x_hi, x_lo, y_hi, y_lo ]
+        let mut event = [0u8; 128];
+        event[0] = SEPROXYHAL_TAG_FINGER_EVENT as u8;
+        event[2] = 5;
+        event[3] = state as u8;
+        event[4..6].copy_from_slice(&x.to_be_bytes());
+        event[6..8].copy_from_slice(&y.to_be_bytes());
+        unsafe {
+            ux_process_finger_event(event.as_mut_ptr());
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use super::*;
+use alloc::format;
+
+/// Kinds of exchange transactions, started by the Exchange application.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExchangeKind {
+    /// Exchange of crypto assets
+    Swap,
+    /// Sale of crypto assets for fiat
+    Sell,
+    /// Funding of an account with crypto assets
+    Fund,
+}
+
+/// Review of an exchange transaction (swap, sell or fund), with the standard
+/// titles and fields of these flows: amount sent, amount received, fees and
+/// exchange provider.
+///
+/// # Examples
+///
+/// ```
+/// let result = NbglExchangeReview::new(ExchangeKind::Swap, "ChangeNOW")
+///     .glyph(&FERRIS)
+///     .send("1.5 CRAB")
+///     .receive("0.02 BTC")
+///     .fees("0.001 CRAB")
+///     .show();
+/// ```
+pub struct NbglExchangeReview<'a> {
+    kind: ExchangeKind,
+    provider: &'a str,
+    glyph: Option<&'a NbglGlyph<'a>>,
+    send: Option<&'a str>,
+    receive: Option<&'a str>,
+    fees: Option<&'a str>,
+    account: Option<&'a str>,
+}
+
+impl<'a> NbglExchangeReview<'a> {
+    pub fn new(kind: ExchangeKind, provider: &'a str) -> NbglExchangeReview<'a> {
+        NbglExchangeReview {
+            kind,
+            provider,
+            glyph: None,
+            send: None,
+            receive: None,
+            fees: None,
+            account: None,
+        }
+    }
+
+    pub fn glyph(self, glyph: &'a NbglGlyph) -> NbglExchangeReview<'a> {
+        NbglExchangeReview {
+            glyph: Some(glyph),
+            ..self
+        }
+    }
+
+    /// Sets the formatted amount sent by the user.
+    pub fn send(self, amount: &'a str) -> NbglExchangeReview<'a> {
+        NbglExchangeReview {
+            send: Some(amount),
+            ..self
+        }
+    }
+
+    /// Sets the formatted amount received by the user (swap and sell).
+    pub fn receive(self, amount: &'a str) -> NbglExchangeReview<'a> {
+        NbglExchangeReview {
+            receive: Some(amount),
+            ..self
+        }
+    }
+
+    /// Sets the formatted fees of the transaction.
+    pub fn fees(self, fees: &'a str) -> NbglExchangeReview<'a> {
+        NbglExchangeReview {
+            fees: Some(fees),
+            ..self
+        }
+    }
+
+    /// Sets the account which is funded (fund).
+    pub fn account(self, account: &'a str) -> NbglExchangeReview<'a> {
+        NbglExchangeReview {
+            account: Some(account),
+            ..self
+        }
+    }
+
+    /// Displays the review, and returns whether the user approved it.
+    pub fn show(&self) -> ReviewResult {
+        let (title, finish_title) = match self.kind {
+            ExchangeKind::Swap => (
+                format!("Review transaction\nto swap with {}", self.provider),
+                "Sign transaction\nto swap",
+            ),
+            ExchangeKind::Sell => (
+                format!("Review transaction\nto sell with {}", self.provider),
+                "Sign transaction\nto sell",
+            ),
+            ExchangeKind::Fund => (
+                format!("Review transaction\nto fund with {}", self.provider),
+                "Sign transaction\nto fund account",
+            ),
+        };
+
+        let fields: Vec<Field> = [
+            ("Send", self.send),
+            ("Get", self.receive),
+            ("Account", self.account),
+            ("Provider", Some(self.provider)),
+            ("Fees", self.fees),
+        ]
+        .iter()
+        .filter_map(|(name, value)| value.map(|value| Field { name, value }))
+        .collect();
+
+        let mut review = NbglReview::new().titles(&title, "", finish_title);
+        if let Some(glyph) = self.glyph {
+            review = review.glyph(glyph);
+        }
+        review.review(&fields)
+    }
+}
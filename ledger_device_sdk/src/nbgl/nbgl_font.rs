@@ -0,0 +1,190 @@
+use super::*;
+
+/// Maximum number of fonts registered with [`register_font`]
+pub const MAX_FONTS: usize = 4;
+
+/// Fonts used by [`render_text`], in registration order.
+static mut FONTS: [Option<&'static NbglFont>; MAX_FONTS] = [None; MAX_FONTS];
+
+/// Error returned when a text cannot be rendered with custom fonts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FontError {
+    /// No font has a glyph for this character
+    MissingGlyph(char),
+    /// The rendered text does not fit in the output buffer
+    BufferTooSmall,
+    /// The rendered text is wider than 65535 pixels
+    TooWide,
+}
+
+/// Bitmap font converted at compile time with
+/// [`include_font!`](include_gif::include_font), to render text which the
+/// fonts of the OS cannot display, such as non-Latin scripts or symbols.
+///
+/// NBGL flows display their texts with the fonts of the OS: a text rendered
+/// with a custom font is an image, which can be used wherever the flows
+/// accept an [`NbglGlyph`].
+///
+/// # Examples
+///
+/// ```
+/// static SYMBOLS: NbglFont = NbglFont::from_include(include_font!("fonts/symbols.bdf"));
+///
+/// register_font(&SYMBOLS);
+/// let mut buffer = [0u8; 512];
+/// let token_name = render_text("◎ SOL", &mut buffer)?;
+/// NbglPage::new()
+///     .centered_info(CenteredInfo::new(
+///         "Token", "", "", Some(&token_name), true,
+///         CenteredInfoStyle::LargeCaseBoldInfo, 0,
+///     ))
+///     .back(())
+///     .show();
+/// ```
+pub struct NbglFont {
+    ascent: u16,
+    descent: u16,
+    /// Code point, advance width and rows of each glyph, sorted by code point
+    glyphs: &'static [(u32, u8, &'static [u8])],
+}
+
+impl NbglFont {
+    pub const fn from_include(font: (u16, u16, &'static [(u32, u8, &'static [u8])])) -> NbglFont {
+        NbglFont {
+            ascent: font.0,
+            descent: font.1,
+            glyphs: font.2,
+        }
+    }
+
+    /// Height of the glyphs, in pixels.
+    pub fn height(&self) -> u16 {
+        self.ascent + self.descent
+    }
+
+    /// Returns the advance width and rows of the glyph of `c`, if any.
+    fn glyph(&self, c: char) -> Option<(u8, &'static [u8])> {
+        let i = self
+            .glyphs
+            .binary_search_by_key(&(c as u32), |glyph| glyph.0)
+            .ok()?;
+        Some((self.glyphs[i].1, self.glyphs[i].2))
+    }
+
+    /// Returns true if the font has a glyph for each character of `text`.
+    pub fn can_render(&self, text: &str) -> bool {
+        text.chars().all(|c| self.glyph(c).is_some())
+    }
+
+    /// Renders `text` with this font into `buffer`, see [`render_text`].
+    pub fn render<'a>(&self, text: &str, buffer: &'a mut [u8]) -> Result<NbglGlyph<'a>, FontError> {
+        render(&[Some(self)], text, buffer)
+    }
+}
+
+/// Registers `font` for [`render_text`]. Fonts are looked up in registration
+/// order for each character.
+///
+/// # Panics
+///
+/// Panics if there are already [`MAX_FONTS`] registered fonts.
+pub fn register_font(font: &'static NbglFont) {
+    let fonts = unsafe { &mut *core::ptr::addr_of_mut!(FONTS) };
+    match fonts.iter_mut().find(|slot| slot.is_none()) {
+        Some(slot) => *slot = Some(font),
+        None => panic!("Too many registered fonts."),
+    }
+}
+
+/// Renders `text` on a single line with the registered fonts into `buffer`,
+/// and returns it as a 1 bpp glyph. The glyphs of different fonts are aligned
+/// on their baseline, and the height is rounded up to a multiple of 4 pixels
+/// as required by NBGL.
+///
+/// `buffer` needs one bit per pixel: `width * height / 8` bytes, rounded up.
+pub fn render_text<'a>(text: &str, buffer: &'a mut [u8]) -> Result<NbglGlyph<'a>, FontError> {
+    render(unsafe { &*core::ptr::addr_of!(FONTS) }, text, buffer)
+}
+
+/// Returns the first font of `fonts` with a glyph for `c`, and the glyph.
+fn lookup<'f>(
+    fonts: &[Option<&'f NbglFont>],
+    c: char,
+) -> Result<(&'f NbglFont, u8, &'static [u8]), FontError> {
+    fonts
+        .iter()
+        .flatten()
+        .find_map(|font| font.glyph(c).map(|(width, rows)| (*font, width, rows)))
+        .ok_or(FontError::MissingGlyph(c))
+}
+
+fn render<'a>(
+    fonts: &[Option<&NbglFont>],
+    text: &str,
+    buffer: &'a mut [u8],
+) -> Result<NbglGlyph<'a>, FontError> {
+    let mut width: u32 = 0;
+    let mut ascent = 0;
+    let mut descent = 0;
+    for c in text.chars() {
+        let (font, advance, _) = lookup(fonts, c)?;
+        width += advance as u32;
+        ascent = ascent.max(font.ascent);
+        descent = descent.max(font.descent);
+    }
+    let width = u16::try_from(width).map_err(|_| FontError::TooWide)?;
+    let height = (ascent + descent).next_multiple_of(4);
+    let len = (width as usize * height as usize).div_ceil(8);
+    if buffer.len() < len {
+        return Err(FontError::BufferTooSmall);
+    }
+
+    // NBGL bitmaps are stored column by column, from the rightmost one, with
+    // the background pixels set.
+    let bitmap = &mut buffer[..len];
+    bitmap.fill(0xff);
+    let mut x0 = 0;
+    for c in text.chars() {
+        let (font, advance, rows) = lookup(fonts, c)?;
+        let bytes_per_row = (advance as usize).div_ceil(8);
+        let y0 = (ascent - font.ascent) as usize;
+        for y in 0..font.height() as usize {
+            for x in 0..advance as usize {
+                if rows[y * bytes_per_row + x / 8] & (0x80 >> (x % 8)) == 0 {
+                    continue;
+                }
+                let column = width as usize - 1 - (x0 + x);
+                let bit = column * height as usize + y0 + y;
+                bitmap[bit / 8] &= !(0x80 >> (bit % 8));
+            }
+        }
+        x0 += advance as usize;
+    }
+    Ok(NbglGlyph::new(bitmap, width, height, 1, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    static DIAGONAL: NbglFont = NbglFont::from_include((2, 0, &[(0x61, 2, &[0x80, 0x40])]));
+
+    #[test]
+    fn render_glyph() {
+        let mut buffer = [0u8; 4];
+        let glyph = DIAGONAL.render("a", &mut buffer).unwrap();
+        assert_eq!((glyph.width, glyph.height), (2, 4));
+        assert_eq!(glyph.bitmap, &[0xb7]);
+        assert_eq!(
+            DIAGONAL.render("ab", &mut buffer).err(),
+            Some(FontError::MissingGlyph('b'))
+        );
+        assert_eq!(
+            DIAGONAL.render("aaaaa", &mut buffer).err(),
+            Some(FontError::BufferTooSmall)
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use super::*;
+use zeroize::Zeroize;
+
+/// Maximum length of a keyboard entry, in bytes
+pub const KEYBOARD_MAX_LEN: usize = 64;
+
+const KEYBOARD_BACK_TOKEN: c_int = FIRST_USER_TOKEN as c_int;
+const KEYBOARD_CONFIRM_TOKEN: c_int = FIRST_USER_TOKEN as c_int + 1;
+
+/// Key codes sent by the keyboard besides characters, from `nbgl_obj.h`
+const BACKSPACE: u8 = BACKSPACE_KEY as u8;
+const VALIDATE: u8 = VALIDATE_KEY as u8;
+
+/// Restriction of the characters which can be entered, given the text already
+/// entered and a candidate character.
+pub type KeyboardFilter = fn(&str, char) -> bool;
+
+/// State of the keyboard page being displayed, shared with the NBGL callbacks.
+struct KeyboardState {
+    layout: *mut nbgl_layout_t,
+    keyboard_index: u8,
+    text_index: u8,
+    button_index: u8,
+    // Text entered so far, NUL terminated
+    text: [u8; KEYBOARD_MAX_LEN + 1],
+    len: usize,
+    max_len: usize,
+    placeholder: *const c_char,
+    confirm_text: *const c_char,
+    filter: Option<KeyboardFilter>,
+    validated: bool,
+    ended: bool,
+}
+
+static mut KEYBOARD_STATE: Option<KeyboardState> = None;
+
+impl KeyboardState {
+    fn as_str(&self) -> &str {
+        // Only ASCII characters are entered
+        unsafe { core::str::from_utf8_unchecked(&self.text[..self.len]) }
+    }
+
+    fn accepts(&self, c: char) -> bool {
+        self.len < self.max_len && self.filter.map_or(true, |f| f(self.as_str(), c))
+    }
+
+    /// Mask of the letter keys to disable, bit `i` being the `i`-th letter.
+    fn key_mask(&self) -> u32 {
+        (b'a'..=b'z')
+            .enumerate()
+            .filter(|(_, c)| !self.accepts(*c as char))
+            .fold(0, |mask, (i, _)| mask | (1 << i))
+    }
+
+    /// Refreshes the entered text, the keyboard and the confirmation button.
+    unsafe fn update(&mut self) {
+        let empty = self.len == 0;
+        nbgl_layoutUpdateEnteredText(
+            self.layout,
+            self.text_index,
+            false,
+            0,
+            if empty {
+                self.placeholder
+            } else {
+                self.text.as_ptr() as *const c_char
+            },
+            empty,
+        );
+        nbgl_layoutUpdateKeyboard(
+            self.layout,
+            self.keyboard_index,
+            self.key_mask(),
+            false,
+            LOWER_CASE,
+        );
+        nbgl_layoutUpdateConfirmationButton(
+            self.layout,
+            self.button_index,
+            !empty,
+            self.confirm_text,
+        );
+        nbgl_refresh();
+    }
+}
+
+/// Callback triggered by the NBGL API when a key is touched.
+unsafe extern "C" fn keyboard_callback(key: c_char) {
+    let Some(state) = KEYBOARD_STATE.as_mut() else {
+        return;
+    };
+    match key as u8 {
+        BACKSPACE => {
+            if state.len > 0 {
+                state.len -= 1;
+                state.text[state.len] = 0;
+            }
+        }
+        VALIDATE => {
+            if state.len > 0 {
+                state.validated = true;
+                state.ended = true;
+            }
+            return;
+        }
+        c if c.is_ascii() && state.accepts(c as char) => {
+            state.text[state.len] = c;
+            state.len += 1;
+            state.text[state.len] = 0;
+        }
+        _ => return,
+    }
+    state.update();
+}
+
+/// Callback triggered by the NBGL API when the back or confirmation button is touched.
+unsafe extern "C" fn keyboard_action_callback(token: c_int, _index: u8) {
+    let Some(state) = KEYBOARD_STATE.as_mut() else {
+        return;
+    };
+    if token == KEYBOARD_BACK_TOKEN {
+        state.ended = true;
+    } else if token == KEYBOARD_CONFIRM_TOKEN && state.len > 0 {
+        state.validated = true;
+        state.ended = true;
+    }
+}
+
+/// Text entered on an [`NbglKeyboard`].
+/// The text is erased from memory when the entry is dropped.
+pub struct KeyboardEntry {
+    text: [u8; KEYBOARD_MAX_LEN],
+    len: usize,
+}
+
+impl KeyboardEntry {
+    pub fn as_str(&self) -> &str {
+        // Only ASCII characters are entered
+        unsafe { core::str::from_utf8_unchecked(&self.text[..self.len]) }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.text[..self.len]
+    }
+}
+
+/// Cleanup text from memory when dropping this structure.
+impl Drop for KeyboardEntry {
+    #[inline(never)]
+    fn drop(&mut self) {
+        self.text.zeroize();
+        self.len = 0;
+    }
+}
+
+/// A wrapper around the NBGL keyboard layout.
+/// Draws a page with a keyboard, the entered text and a confirmation button,
+/// and waits for the user to confirm the text or to go back.
+///
+/// The characters which can be entered are restricted with
+/// [`filter`](NbglKeyboard::filter), which is called with the text entered so
+/// far and a candidate character. Disallowed letters are greyed out on the
+/// keyboard.
+///
+/// # Examples
+///
+/// ```
+/// // Only allow prefixes of BIP39 words
+/// fn is_bip39_prefix(text: &str, c: char) -> bool {
+///     BIP39_WORDS
+///         .iter()
+///         .any(|w| w.starts_with(text) && w[text.len()..].starts_with(c))
+/// }
+///
+/// let word = NbglKeyboard::new()
+///     .placeholder("Enter word #1")
+///     .letters_only()
+///     .max_len(8)
+///     .filter(is_bip39_prefix)
+///     .show();
+/// ```
+pub struct NbglKeyboard {
+    placeholder: CString,
+    confirm_text: CString,
+    max_len: usize,
+    letters_only: bool,
+    filter: Option<KeyboardFilter>,
+}
+
+impl NbglKeyboard {
+    pub fn new() -> NbglKeyboard {
+        NbglKeyboard {
+            placeholder: CString::new("").unwrap(),
+            confirm_text: CString::new("Confirm").unwrap(),
+            max_len: KEYBOARD_MAX_LEN,
+            letters_only: false,
+            filter: None,
+        }
+    }
+
+    /// Sets the text displayed greyed out while nothing has been entered.
+    pub fn placeholder(self, placeholder: &str) -> NbglKeyboard {
+        NbglKeyboard {
+            placeholder: CString::new(placeholder).unwrap(),
+            ..self
+        }
+    }
+
+    pub fn confirm_text(self, confirm_text: &str) -> NbglKeyboard {
+        NbglKeyboard {
+            confirm_text: CString::new(confirm_text).unwrap(),
+            ..self
+        }
+    }
+
+    /// Sets the maximum length of the text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is greater than [`KEYBOARD_MAX_LEN`].
+    pub fn max_len(self, max_len: usize) -> NbglKeyboard {
+        if max_len > KEYBOARD_MAX_LEN {
+            panic!("Invalid keyboard length.");
+        }
+        NbglKeyboard { max_len, ..self }
+    }
+
+    /// Only displays the letter keys.
+    pub fn letters_only(self) -> NbglKeyboard {
+        NbglKeyboard {
+            letters_only: true,
+            ..self
+        }
+    }
+
+    pub fn filter(self, filter: KeyboardFilter) -> NbglKeyboard {
+        NbglKeyboard {
+            filter: Some(filter),
+            ..self
+        }
+    }
+
+    /// Displays the keyboard, and returns the entered text once confirmed, or
+    /// `None` if the user pressed the back button. APDUs received while the
+    /// keyboard is displayed are ignored.
+    pub fn show(&self) -> Option<KeyboardEntry> {
+        unsafe {
+            let layout_description = nbgl_layoutDescription_t {
+                modal: false,
+                onActionCallback: Some(keyboard_action_callback),
+                ..Default::default()
+            };
+            let layout = nbgl_layoutGet(&layout_description as *const nbgl_layoutDescription_t);
+
+            KEYBOARD_STATE = Some(KeyboardState {
+                layout,
+                keyboard_index: 0,
+                text_index: 0,
+                button_index: 0,
+                text: [0u8; KEYBOARD_MAX_LEN + 1],
+                len: 0,
+                max_len: self.max_len,
+                placeholder: self.placeholder.as_ptr() as *const c_char,
+                confirm_text: self.confirm_text.as_ptr() as *const c_char,
+                filter: self.filter,
+                validated: false,
+                ended: false,
+            });
+            let state = KEYBOARD_STATE.as_mut().unwrap();
+
+            nbgl_layoutAddProgressIndicator(
+                layout,
+                0,
+                0,
+                true,
+                KEYBOARD_BACK_TOKEN as u8,
+                TuneIndex::TapCasual as tune_index_e,
+            );
+            let keyboard = nbgl_layoutKbd_t {
+                callback: Some(keyboard_callback),
+                keyMask: state.key_mask(),
+                lettersOnly: self.letters_only,
+                mode: MODE_LETTERS,
+                casing: LOWER_CASE,
+                ..Default::default()
+            };
+            state.keyboard_index =
+                nbgl_layoutAddKeyboard(layout, &keyboard as *const nbgl_layoutKbd_t) as u8;
+            state.text_index =
+                nbgl_layoutAddEnteredText(layout, false, 0, state.placeholder, true, 0, 0) as u8;
+            state.button_index = nbgl_layoutAddConfirmationButton(
+                layout,
+                false,
+                state.confirm_text,
+                KEYBOARD_CONFIRM_TOKEN,
+                TuneIndex::TapCasual as tune_index_e,
+            ) as u8;
+            nbgl_layoutDraw(layout);
+            nbgl_refresh();
+
+            while !KEYBOARD_STATE.as_ref().unwrap().ended {
+                io_recv_and_process_event();
+            }
+
+            let state = KEYBOARD_STATE.as_mut().unwrap();
+            nbgl_layoutRelease(state.layout);
+
+            let entry = if state.validated {
+                let mut entry = KeyboardEntry {
+                    text: [0u8; KEYBOARD_MAX_LEN],
+                    len: state.len,
+                };
+                entry.text[..state.len].copy_from_slice(&state.text[..state.len]);
+                Some(entry)
+            } else {
+                None
+            };
+            // Zeroize the text in the static before it is released, as taking
+            // it out would only clear a moved copy
+            state.text.zeroize();
+            KEYBOARD_STATE = None;
+            entry
+        }
+    }
+}
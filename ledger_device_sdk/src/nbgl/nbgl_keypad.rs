@@ -0,0 +1,195 @@
+use super::*;
+use zeroize::Zeroize;
+
+/// Maximum number of digits of a keypad entry
+pub const KEYPAD_MAX_DIGITS: usize = 12;
+
+/// Token returned by the back button of the keypad page.
+const KEYPAD_BACK_TOKEN: u8 = FIRST_USER_TOKEN as u8;
+
+static mut KEYPAD_ENTRY: KeypadEntry = KeypadEntry {
+    digits: [0u8; KEYPAD_MAX_DIGITS],
+    len: 0,
+};
+static mut KEYPAD_VALIDATED: bool = false;
+static mut KEYPAD_ENDED: bool = false;
+
+/// Digits entered on an [`NbglKeypad`], as ASCII characters.
+/// The digits are erased from memory when the entry is dropped.
+pub struct KeypadEntry {
+    digits: [u8; KEYPAD_MAX_DIGITS],
+    len: usize,
+}
+
+impl KeypadEntry {
+    /// Returns the entered digits, as ASCII characters (`b'0'` to `b'9'`).
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.digits[..self.len]
+    }
+
+    /// Returns the entered digits as a string.
+    pub fn as_str(&self) -> &str {
+        // Keypad keys are all ASCII digits
+        unsafe { core::str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Returns the value of each digit, from 0 to 9.
+    pub fn digits(&self) -> impl Iterator<Item = u8> + '_ {
+        self.as_bytes().iter().map(|d| d - b'0')
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Cleanup digits from memory when dropping this structure.
+impl Drop for KeypadEntry {
+    #[inline(never)]
+    fn drop(&mut self) {
+        self.digits.zeroize();
+        self.len = 0;
+    }
+}
+
+/// Callback triggered by the NBGL API when the entry is validated.
+unsafe extern "C" fn keypad_validate_callback(content: *const u8, len: u8) {
+    let len = (len as usize).min(KEYPAD_MAX_DIGITS);
+    let content = core::slice::from_raw_parts(content, len);
+    KEYPAD_ENTRY.digits[..len].copy_from_slice(content);
+    KEYPAD_ENTRY.len = len;
+    KEYPAD_VALIDATED = true;
+    KEYPAD_ENDED = true;
+}
+
+/// Callback triggered by the NBGL API when the back button is pressed.
+unsafe extern "C" fn keypad_action_callback(token: c_int, _index: u8) {
+    if token == KEYPAD_BACK_TOKEN as c_int {
+        KEYPAD_VALIDATED = false;
+        KEYPAD_ENDED = true;
+    }
+}
+
+/// A wrapper around the asynchronous NBGL nbgl_useCaseKeypadPIN and
+/// nbgl_useCaseKeypadDigits C API bindings.
+/// Draws a keypad page to enter a numeric code, such as a PIN or an OTP,
+/// and waits for the user to validate it or to go back.
+///
+/// # Examples
+///
+/// ```
+/// let pin = NbglKeypad::new()
+///     .title("Enter your PIN")
+///     .digits(4, 8)
+///     .shuffled()
+///     .show();
+/// match pin {
+///     Some(pin) => check_pin(pin.as_bytes()),
+///     None => return Err(StatusWords::UserCancelled),
+/// }
+/// ```
+pub struct NbglKeypad {
+    title: CString,
+    min_digits: u8,
+    max_digits: u8,
+    shuffled: bool,
+    masked: bool,
+}
+
+impl NbglKeypad {
+    pub fn new() -> NbglKeypad {
+        NbglKeypad {
+            title: CString::new("").unwrap(),
+            min_digits: 4,
+            max_digits: 8,
+            shuffled: false,
+            masked: true,
+        }
+    }
+
+    pub fn title(self, title: &str) -> NbglKeypad {
+        NbglKeypad {
+            title: CString::new(title).unwrap(),
+            ..self
+        }
+    }
+
+    /// Sets the minimum and maximum number of digits of the entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min > max`, or if `max` is greater than [`KEYPAD_MAX_DIGITS`].
+    pub fn digits(self, min: u8, max: u8) -> NbglKeypad {
+        if min > max || max as usize > KEYPAD_MAX_DIGITS {
+            panic!("Invalid keypad length.");
+        }
+        NbglKeypad {
+            min_digits: min,
+            max_digits: max,
+            ..self
+        }
+    }
+
+    /// Shuffles the keys of the keypad, so that the position of the touches
+    /// does not reveal the entered digits.
+    pub fn shuffled(self) -> NbglKeypad {
+        NbglKeypad {
+            shuffled: true,
+            ..self
+        }
+    }
+
+    /// Displays the entered digits instead of masking them.
+    pub fn unmasked(self) -> NbglKeypad {
+        NbglKeypad {
+            masked: false,
+            ..self
+        }
+    }
+
+    /// Displays the keypad, and returns the entered digits once validated, or
+    /// `None` if the user pressed the back button. APDUs received while the
+    /// keypad is displayed are ignored.
+    pub fn show(&self) -> Option<KeypadEntry> {
+        unsafe {
+            KEYPAD_VALIDATED = false;
+            KEYPAD_ENDED = false;
+
+            let use_case = if self.masked {
+                nbgl_useCaseKeypadPIN
+            } else {
+                nbgl_useCaseKeypadDigits
+            };
+            use_case(
+                self.title.as_ptr() as *const c_char,
+                self.min_digits,
+                self.max_digits,
+                KEYPAD_BACK_TOKEN,
+                self.shuffled,
+                TuneIndex::TapCasual as tune_index_e,
+                Some(keypad_validate_callback),
+                Some(keypad_action_callback),
+            );
+
+            while !KEYPAD_ENDED {
+                io_recv_and_process_event();
+            }
+
+            if !KEYPAD_VALIDATED {
+                return None;
+            }
+            // Move the digits out of the static buffer, which is erased
+            let entry = KeypadEntry {
+                digits: KEYPAD_ENTRY.digits,
+                len: KEYPAD_ENTRY.len,
+            };
+            KEYPAD_ENTRY.digits.zeroize();
+            KEYPAD_ENTRY.len = 0;
+            Some(entry)
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use super::*;
+
+/// Maximum length of a lazily provided field name, in bytes
+pub const LAZY_NAME_LEN: usize = 32;
+/// Maximum length of a lazily provided field value, in bytes
+pub const LAZY_VALUE_LEN: usize = 128;
+/// Number of fields kept in memory at once. NBGL requests the fields of a
+/// page one after the other before drawing it, so this must be greater than
+/// the number of fields displayed on a page.
+const LAZY_SLOTS: usize = 8;
+
+/// Scratch buffers receiving a field formatted by a [`NbglReview::review_lazy`]
+/// provider. The name and value are truncated to [`LAZY_NAME_LEN`] and
+/// [`LAZY_VALUE_LEN`] bytes.
+pub struct LazyField {
+    // NUL terminated strings
+    name: [u8; LAZY_NAME_LEN + 1],
+    value: [u8; LAZY_VALUE_LEN + 1],
+    value_len: usize,
+}
+
+impl LazyField {
+    const fn new() -> LazyField {
+        LazyField {
+            name: [0u8; LAZY_NAME_LEN + 1],
+            value: [0u8; LAZY_VALUE_LEN + 1],
+            value_len: 0,
+        }
+    }
+
+    /// Copies as much of `s` as fits in `buffer` (keeping the terminating NUL
+    /// byte), on a character boundary, and returns the number of copied bytes.
+    fn copy_str(buffer: &mut [u8], s: &str) -> usize {
+        let mut len = s.len().min(buffer.len() - 1);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        buffer[..len].copy_from_slice(&s.as_bytes()[..len]);
+        buffer[len] = 0;
+        len
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        Self::copy_str(&mut self.name, name);
+    }
+
+    /// Sets the value. It can also be formatted with `write!`, which appends
+    /// to the current value.
+    pub fn set_value(&mut self, value: &str) {
+        self.value_len = Self::copy_str(&mut self.value, value);
+    }
+}
+
+impl core::fmt::Write for LazyField {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let len = Self::copy_str(&mut self.value[self.value_len..], s);
+        self.value_len += len;
+        Ok(())
+    }
+}
+
+type LazyProvider = dyn FnMut(u8, &mut LazyField);
+
+static mut LAZY_PROVIDER: Option<*mut LazyProvider> = None;
+const EMPTY_LAZY_FIELD: LazyField = LazyField::new();
+static mut LAZY_FIELDS: [LazyField; LAZY_SLOTS] = [EMPTY_LAZY_FIELD; LAZY_SLOTS];
+static mut LAZY_PAIRS: [nbgl_contentTagValue_t; LAZY_SLOTS] =
+    [unsafe { const_zero!(nbgl_contentTagValue_t) }; LAZY_SLOTS];
+
+/// Callback triggered by the NBGL API to get the field at `index`.
+unsafe extern "C" fn lazy_field_callback(index: u8) -> *mut nbgl_contentTagValue_t {
+    let slot = index as usize % LAZY_SLOTS;
+    let field = &mut LAZY_FIELDS[slot];
+    field.set_name("");
+    field.set_value("");
+    if let Some(provider) = LAZY_PROVIDER {
+        (*provider)(index, field);
+    }
+    LAZY_PAIRS[slot] = nbgl_contentTagValue_t {
+        item: field.name.as_ptr() as *const c_char,
+        value: field.value.as_ptr() as *const c_char,
+        ..Default::default()
+    };
+    &mut LAZY_PAIRS[slot]
+}
+
+impl<'a> NbglReview<'a> {
+    /// Displays the review of `nb_fields` fields, which are formatted on demand
+    /// by `provider` when their page is displayed, instead of being all kept
+    /// in memory during the review. The provider is called with the index of
+    /// the field, possibly several times for the same field as the user
+    /// navigates back and forth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let result = NbglReview::new()
+    ///     .titles("Review transaction", "", "Sign transaction")
+    ///     .review_lazy(tx.outputs.len() as u8, |index, field| {
+    ///         let output = &tx.outputs[index as usize];
+    ///         field.set_name("Output");
+    ///         let _ = write!(field, "{} CRAB to {}", output.amount, output.address);
+    ///     });
+    /// ```
+    pub fn review_lazy<F>(&mut self, nb_fields: u8, mut provider: F) -> ReviewResult
+    where
+        F: FnMut(u8, &mut LazyField),
+    {
+        unsafe {
+            let provider: *mut (dyn FnMut(u8, &mut LazyField) + '_) = &mut provider;
+            // The provider is only called during the review, and unregistered before
+            // it goes out of scope.
+            LAZY_PROVIDER = Some(transmute::<
+                *mut (dyn FnMut(u8, &mut LazyField) + '_),
+                *mut LazyProvider,
+            >(provider));
+
+            let tag_value_list = nbgl_contentTagValueList_t {
+                pairs: core::ptr::null(),
+                callback: Some(lazy_field_callback),
+                nbPairs: nb_fields,
+                ..Default::default()
+            };
+            let result = self.review_list(&tag_value_list);
+
+            LAZY_PROVIDER = None;
+            result
+        }
+    }
+}
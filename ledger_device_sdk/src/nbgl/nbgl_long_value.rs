@@ -0,0 +1,146 @@
+use super::*;
+use alloc::format;
+use alloc::string::String;
+
+/// Default maximum length of a value displayed inline in a review, in bytes.
+/// Longer values are truncated, and can be displayed entirely on sub-pages.
+pub const LONG_VALUE_LEN: usize = 128;
+
+/// Display of the values longer than the maximum length of a review.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LongValues {
+    /// The value is truncated, with a "More" button opening pages which
+    /// display the full value, after which the review resumes.
+    More,
+    /// The value is split across consecutive fields, named "Name (1/3)",
+    /// "Name (2/3)"... so that it is entirely part of the review.
+    Split,
+}
+
+/// Tag/value pairs of a review, with the C strings they point to.
+///
+/// Values longer than the given threshold are displayed according to
+/// [`LongValues`]: either truncated, as an alias for which NBGL displays a
+/// "More" button, or split across several pairs.
+pub(crate) struct CTagValues {
+    pairs: Vec<nbgl_contentTagValue_t>,
+    _names: Vec<CString>,
+    _values: Vec<CString>,
+    _full_values: Vec<CString>,
+    _extensions: Vec<nbgl_contentValueExt_t>,
+}
+
+impl CTagValues {
+    pub(crate) fn new(fields: &[Field], long_value_len: usize, mode: LongValues) -> CTagValues {
+        let mut names = Vec::with_capacity(fields.len());
+        let mut values = Vec::with_capacity(fields.len());
+        let mut full_values = Vec::new();
+        // Extensions are pointed to by the pairs: the capacity is reserved
+        // so that they are never moved.
+        let mut extensions = Vec::with_capacity(fields.len());
+        let mut pairs = Vec::with_capacity(fields.len());
+
+        for field in fields {
+            if field.value.len() > long_value_len && mode == LongValues::Split {
+                let parts = split(field.value, long_value_len);
+                for (i, part) in parts.iter().enumerate() {
+                    let name = CString::new(format!("{} ({}/{})", field.name, i + 1, parts.len()))
+                        .unwrap();
+                    let value = CString::new(*part).unwrap();
+                    pairs.push(nbgl_contentTagValue_t {
+                        item: name.as_ptr() as *const c_char,
+                        value: value.as_ptr() as *const c_char,
+                        ..Default::default()
+                    });
+                    names.push(name);
+                    values.push(value);
+                }
+                continue;
+            }
+
+            let name = CString::new(field.name).unwrap();
+            let mut pair = nbgl_contentTagValue_t {
+                item: name.as_ptr() as *const c_char,
+                ..Default::default()
+            };
+            let value = if field.value.len() > long_value_len {
+                let full_value = CString::new(field.value).unwrap();
+                extensions.push(nbgl_contentValueExt_t {
+                    fullValue: full_value.as_ptr() as *const c_char,
+                    ..Default::default()
+                });
+                pair.__bindgen_anon_1.extension =
+                    extensions.last().unwrap() as *const nbgl_contentValueExt_t;
+                pair.set_aliasValue(1);
+                full_values.push(full_value);
+                CString::new(truncate(field.value, long_value_len)).unwrap()
+            } else {
+                CString::new(field.value).unwrap()
+            };
+            pair.value = value.as_ptr() as *const c_char;
+            pairs.push(pair);
+            names.push(name);
+            values.push(value);
+        }
+
+        CTagValues {
+            pairs,
+            _names: names,
+            _values: values,
+            _full_values: full_values,
+            _extensions: extensions,
+        }
+    }
+
+    /// Number of pairs, which may be greater than the number of fields when
+    /// long values are split.
+    pub(crate) fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns the tag/value lists pointing to the pairs, by chunks of at
+    /// most 255 pairs. They must not outlive `self`.
+    pub(crate) fn lists(&self) -> impl Iterator<Item = nbgl_contentTagValueList_t> + '_ {
+        self.pairs
+            .chunks(u8::MAX as usize)
+            .map(|chunk| nbgl_contentTagValueList_t {
+                pairs: chunk.as_ptr() as *const nbgl_contentTagValue_t,
+                nbPairs: chunk.len() as u8,
+                ..Default::default()
+            })
+    }
+}
+
+/// Returns the index of the last character boundary of `value` before `len`.
+fn floor_char_boundary(value: &str, len: usize) -> usize {
+    let mut end = len.min(value.len());
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+/// Returns the first `len` bytes of `value`, cut on a character boundary,
+/// followed by an ellipsis.
+fn truncate(value: &str, len: usize) -> String {
+    let mut truncated = String::from(&value[..floor_char_boundary(value, len)]);
+    truncated.push_str("...");
+    truncated
+}
+
+/// Splits `value` in parts of at most `len` bytes, cut on character boundaries.
+fn split(value: &str, len: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = value;
+    while !rest.is_empty() {
+        // Always progress, even if `len` is shorter than a character
+        let end = match floor_char_boundary(rest, len) {
+            0 => rest.chars().next().map_or(rest.len(), char::len_utf8),
+            end => end,
+        };
+        let (part, tail) = rest.split_at(end);
+        parts.push(part);
+        rest = tail;
+    }
+    parts
+}
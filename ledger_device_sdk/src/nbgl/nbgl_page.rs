@@ -0,0 +1,233 @@
+use super::*;
+
+/// Token of the back button, action tokens follow it.
+const PAGE_BACK_TOKEN: c_int = FIRST_USER_TOKEN as c_int;
+
+/// Token of the last touched control of the displayed page, if any.
+static mut PAGE_TOKEN: Option<c_int> = None;
+
+/// Callback triggered by the NBGL API when a control of the page is touched.
+unsafe extern "C" fn page_action_callback(token: c_int, _index: u8) {
+    PAGE_TOKEN = Some(token);
+}
+
+#[derive(Copy, Clone)]
+pub enum ButtonStyle {
+    Black,
+    White,
+    NoBorder,
+}
+
+impl From<ButtonStyle> for nbgl_layoutButtonStyle_t {
+    fn from(style: ButtonStyle) -> nbgl_layoutButtonStyle_t {
+        match style {
+            ButtonStyle::Black => BLACK_BACKGROUND,
+            ButtonStyle::White => WHITE_BACKGROUND,
+            ButtonStyle::NoBorder => NO_BORDER,
+        }
+    }
+}
+
+enum PageItem {
+    CenteredInfo(CenteredInfo),
+    Text {
+        text: CString,
+        sub_text: CString,
+    },
+    Button {
+        text: CString,
+        icon: Option<nbgl_icon_details_t>,
+        style: ButtonStyle,
+        token: c_int,
+    },
+}
+
+/// A builder over the NBGL layout API, to compose custom pages from centered
+/// infos, texts and buttons.
+///
+/// Each button is associated with a value of type `T`, which is returned by
+/// [`show`](NbglPage::show) when the button is touched.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Copy, Clone)]
+/// enum Action {
+///     Back,
+///     Export,
+///     Delete,
+/// }
+///
+/// let action = NbglPage::new()
+///     .centered_info(CenteredInfo::new(
+///         "Account 1",
+///         "",
+///         "",
+///         Some(&FERRIS),
+///         true,
+///         CenteredInfoStyle::LargeCaseBoldInfo,
+///         0,
+///     ))
+///     .text("Registered on", "2024-03-12")
+///     .button("Export", None, ButtonStyle::Black, Action::Export)
+///     .button("Delete", None, ButtonStyle::White, Action::Delete)
+///     .back(Action::Back)
+///     .show();
+/// ```
+pub struct NbglPage<T: Copy> {
+    items: Vec<PageItem>,
+    actions: Vec<T>,
+    back: Option<T>,
+}
+
+impl<T: Copy> NbglPage<T> {
+    pub fn new() -> NbglPage<T> {
+        NbglPage {
+            items: Vec::new(),
+            actions: Vec::new(),
+            back: None,
+        }
+    }
+
+    pub fn centered_info(mut self, info: CenteredInfo) -> NbglPage<T> {
+        self.items.push(PageItem::CenteredInfo(info));
+        self
+    }
+
+    /// Adds a text, with an optional sub text below it.
+    pub fn text(mut self, text: &str, sub_text: &str) -> NbglPage<T> {
+        self.items.push(PageItem::Text {
+            text: CString::new(text).unwrap(),
+            sub_text: CString::new(sub_text).unwrap(),
+        });
+        self
+    }
+
+    /// Adds a button, returning `action` when touched.
+    pub fn button(
+        mut self,
+        text: &str,
+        icon: Option<&NbglGlyph>,
+        style: ButtonStyle,
+        action: T,
+    ) -> NbglPage<T> {
+        self.actions.push(action);
+        self.items.push(PageItem::Button {
+            text: CString::new(text).unwrap(),
+            icon: icon.map(|g| g.into()),
+            style,
+            token: PAGE_BACK_TOKEN + self.actions.len() as c_int,
+        });
+        self
+    }
+
+    /// Adds a back button at the top of the page, returning `action` when touched.
+    pub fn back(self, action: T) -> NbglPage<T> {
+        NbglPage {
+            back: Some(action),
+            ..self
+        }
+    }
+
+    /// Draws the page, and returns the action of the touched button. APDUs
+    /// received while the page is displayed are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the page has no buttons, as it could not be left.
+    pub fn show(&self) -> T {
+        if self.actions.is_empty() && self.back.is_none() {
+            panic!("Page without buttons.");
+        }
+        unsafe {
+            let layout_description = nbgl_layoutDescription_t {
+                modal: false,
+                onActionCallback: Some(page_action_callback),
+                ..Default::default()
+            };
+            let layout = nbgl_layoutGet(&layout_description as *const nbgl_layoutDescription_t);
+
+            if self.back.is_some() {
+                nbgl_layoutAddProgressIndicator(
+                    layout,
+                    0,
+                    0,
+                    true,
+                    PAGE_BACK_TOKEN as u8,
+                    TuneIndex::TapCasual as tune_index_e,
+                );
+            }
+            for item in self.items.iter() {
+                match item {
+                    PageItem::CenteredInfo(data) => {
+                        let centered_info = nbgl_layoutCenteredInfo_t {
+                            text1: data.text1.as_ptr() as *const c_char,
+                            text2: data.text2.as_ptr() as *const c_char,
+                            text3: data.text3.as_ptr() as *const c_char,
+                            icon: data.icon.as_ref().map_or(core::ptr::null(), |icon| {
+                                icon as *const nbgl_icon_details_t
+                            }),
+                            onTop: data.on_top,
+                            style: data.style.into(),
+                            offsetY: data.offset_y,
+                            ..Default::default()
+                        };
+                        nbgl_layoutAddCenteredInfo(
+                            layout,
+                            &centered_info as *const nbgl_layoutCenteredInfo_t,
+                        );
+                    }
+                    PageItem::Text { text, sub_text } => {
+                        nbgl_layoutAddText(
+                            layout,
+                            text.as_ptr() as *const c_char,
+                            sub_text.as_ptr() as *const c_char,
+                        );
+                    }
+                    PageItem::Button {
+                        text,
+                        icon,
+                        style,
+                        token,
+                    } => {
+                        let button = nbgl_layoutButton_t {
+                            text: text.as_ptr() as *const c_char,
+                            icon: icon.as_ref().map_or(core::ptr::null(), |icon| {
+                                icon as *const nbgl_icon_details_t
+                            }),
+                            token: *token as u8,
+                            style: (*style).into(),
+                            tuneId: TuneIndex::TapCasual as tune_index_e,
+                            ..Default::default()
+                        };
+                        nbgl_layoutAddButton(layout, &button as *const nbgl_layoutButton_t);
+                    }
+                }
+            }
+
+            PAGE_TOKEN = None;
+            nbgl_layoutDraw(layout);
+            nbgl_refresh();
+
+            let action = loop {
+                io_recv_and_process_event();
+                match PAGE_TOKEN.take() {
+                    Some(PAGE_BACK_TOKEN) => {
+                        if let Some(back) = self.back {
+                            break back;
+                        }
+                    }
+                    Some(token) => {
+                        let index = (token - PAGE_BACK_TOKEN - 1) as usize;
+                        if let Some(action) = self.actions.get(index) {
+                            break *action;
+                        }
+                    }
+                    None => (),
+                }
+            };
+            nbgl_layoutRelease(layout);
+            action
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::*;
+
+/// A wrapper around the NBGL progress bar layout.
+/// Draws a page with a progress bar and a text, which can be updated from
+/// inside a long computation so that the UI does not appear frozen.
+///
+/// Updating the page only redraws the screen: events are not processed, so
+/// APDUs and touches are handled once the computation is over.
+///
+/// # Examples
+///
+/// ```
+/// let mut progress = NbglProgress::new().text("Signing inputs");
+/// progress.show();
+/// for (i, input) in psbt.inputs.iter().enumerate() {
+///     sign_input(input);
+///     progress.set_percent(((i + 1) * 100 / psbt.inputs.len()) as u8);
+/// }
+/// ```
+pub struct NbglProgress {
+    text: CString,
+    sub_text: CString,
+    layout: *mut nbgl_layout_t,
+    percent: u8,
+}
+
+impl NbglProgress {
+    pub fn new() -> NbglProgress {
+        NbglProgress {
+            text: CString::new("").unwrap(),
+            sub_text: CString::new("").unwrap(),
+            layout: core::ptr::null_mut(),
+            percent: 0,
+        }
+    }
+
+    pub fn text(self, text: &str) -> NbglProgress {
+        NbglProgress {
+            text: CString::new(text).unwrap(),
+            ..self
+        }
+    }
+
+    /// Sets the text displayed below the progress bar.
+    pub fn sub_text(self, sub_text: &str) -> NbglProgress {
+        NbglProgress {
+            sub_text: CString::new(sub_text).unwrap(),
+            ..self
+        }
+    }
+
+    /// Draws the page, with the current percentage.
+    pub fn show(&mut self) {
+        unsafe {
+            self.release();
+            let layout_description = nbgl_layoutDescription_t {
+                modal: false,
+                ..Default::default()
+            };
+            self.layout = nbgl_layoutGet(&layout_description as *const nbgl_layoutDescription_t);
+            let bar = nbgl_layoutProgressBar_t {
+                percentage: self.percent,
+                text: self.text.as_ptr() as *const c_char,
+                subText: self.sub_text.as_ptr() as *const c_char,
+                ..Default::default()
+            };
+            nbgl_layoutAddProgressBar(self.layout, &bar as *const nbgl_layoutProgressBar_t);
+            nbgl_layoutDraw(self.layout);
+            nbgl_refresh();
+        }
+    }
+
+    /// Updates the percentage, from 0 to 100, and redraws the page if it is
+    /// displayed and the percentage changed.
+    pub fn set_percent(&mut self, percent: u8) {
+        let percent = percent.min(100);
+        if percent == self.percent {
+            return;
+        }
+        self.percent = percent;
+        if !self.layout.is_null() {
+            self.show();
+        }
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    fn release(&mut self) {
+        if !self.layout.is_null() {
+            unsafe {
+                nbgl_layoutRelease(self.layout);
+            }
+            self.layout = core::ptr::null_mut();
+        }
+    }
+}
+
+/// The page stays on screen until another page is drawn, only its resources
+/// are released.
+impl Drop for NbglProgress {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
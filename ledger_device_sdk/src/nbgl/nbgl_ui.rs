@@ -0,0 +1,45 @@
+use super::*;
+
+/// [`ReviewUi`] implementation displaying the screens with NBGL.
+pub struct NbglUi;
+
+impl ReviewUi for NbglUi {
+    fn review(
+        &mut self,
+        glyph: Option<&NbglGlyph>,
+        title: &str,
+        subtitle: &str,
+        finish_title: &str,
+        fields: &[Field],
+    ) -> ReviewResult {
+        let mut review = NbglReview::new().titles(title, subtitle, finish_title);
+        if let Some(glyph) = glyph {
+            review = review.glyph(glyph);
+        }
+        review.review(fields)
+    }
+
+    fn review_address(&mut self, glyph: Option<&NbglGlyph>, title: &str, address: &str) -> bool {
+        let mut review = NbglAddressReview::new().verify_str(title);
+        if let Some(glyph) = glyph {
+            review = review.glyph(glyph);
+        }
+        review.show(address)
+    }
+
+    fn choice(&mut self, message: &str, sub_message: &str, confirm: &str, cancel: &str) -> bool {
+        NbglChoice::new().show(message, sub_message, confirm, cancel)
+    }
+
+    fn status(&mut self, message: &str, success: bool) {
+        if success {
+            NbglStatus::success(message);
+        } else {
+            NbglStatus::failure(message);
+        }
+    }
+
+    fn review_status(&mut self, status: ReviewStatus) {
+        NbglReviewStatus::show_status(status);
+    }
+}
@@ -0,0 +1,140 @@
+use super::*;
+
+const WARNING: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/Warning_64px.gif", NBGL));
+
+/// Actions of the warning details pages.
+#[derive(Copy, Clone)]
+enum WarningAction {
+    Previous,
+    Next,
+    Reject,
+    Accept,
+}
+
+/// Warning displayed before a high-risk operation, such as signing a
+/// transaction which cannot be decoded.
+///
+/// Rejecting is always the default, highlighted choice. To accept the risk,
+/// the user must review the risk, scroll through all the details pages, and
+/// confirm their choice on a last page.
+///
+/// # Examples
+///
+/// ```
+/// let accepted = NbglWarning::new("Security risk detected")
+///     .summary("This contract is not verified. It may drain your wallet.")
+///     .page("Unverified contract", "The contract code could not be verified by the app.")
+///     .page("What to do", "Only continue if you wrote or audited this contract.")
+///     .show();
+/// if !accepted {
+///     return Err(AppError::Rejected);
+/// }
+/// ```
+pub struct NbglWarning<'a> {
+    glyph: &'a NbglGlyph<'a>,
+    title: &'a str,
+    summary: &'a str,
+    pages: Vec<(&'a str, &'a str)>,
+    reject_text: &'a str,
+    accept_text: &'a str,
+}
+
+impl<'a> NbglWarning<'a> {
+    pub fn new(title: &'a str) -> NbglWarning<'a> {
+        NbglWarning {
+            glyph: &WARNING,
+            title,
+            summary: "",
+            pages: Vec::new(),
+            reject_text: "Reject transaction",
+            accept_text: "I accept the risk",
+        }
+    }
+
+    /// Sets the glyph of the first page, a warning sign by default.
+    pub fn glyph(self, glyph: &'a NbglGlyph) -> NbglWarning<'a> {
+        NbglWarning { glyph, ..self }
+    }
+
+    /// Sets the text displayed below the title on the first page.
+    pub fn summary(self, summary: &'a str) -> NbglWarning<'a> {
+        NbglWarning { summary, ..self }
+    }
+
+    /// Adds a details page, which the user must go through before being
+    /// able to accept the risk.
+    pub fn page(mut self, title: &'a str, text: &'a str) -> NbglWarning<'a> {
+        self.pages.push((title, text));
+        self
+    }
+
+    /// Sets the texts of the reject and accept buttons, "Reject transaction"
+    /// and "I accept the risk" by default.
+    pub fn buttons(self, reject_text: &'a str, accept_text: &'a str) -> NbglWarning<'a> {
+        NbglWarning {
+            reject_text,
+            accept_text,
+            ..self
+        }
+    }
+
+    /// Displays the warning, and returns true if the user accepted the risk.
+    pub fn show(&self) -> bool {
+        let back_to_safety = NbglChoice::new().glyph(self.glyph).show(
+            self.title,
+            self.summary,
+            "Back to safety",
+            "Review risk",
+        );
+        if back_to_safety {
+            return false;
+        }
+
+        let mut index = 0;
+        while index < self.pages.len() {
+            let (title, text) = self.pages[index];
+            let last = index + 1 == self.pages.len();
+            let mut page = NbglPage::new().text(title, text);
+            if index > 0 {
+                page = page.back(WarningAction::Previous);
+            }
+            page = if last {
+                page.button(
+                    self.reject_text,
+                    None,
+                    ButtonStyle::Black,
+                    WarningAction::Reject,
+                )
+                .button(
+                    self.accept_text,
+                    None,
+                    ButtonStyle::NoBorder,
+                    WarningAction::Accept,
+                )
+            } else {
+                page.button("Continue", None, ButtonStyle::Black, WarningAction::Next)
+                    .button(
+                        self.reject_text,
+                        None,
+                        ButtonStyle::NoBorder,
+                        WarningAction::Reject,
+                    )
+            };
+            match page.show() {
+                WarningAction::Previous => index -= 1,
+                WarningAction::Next => index += 1,
+                WarningAction::Reject => return false,
+                WarningAction::Accept => break,
+            }
+        }
+
+        // Rejecting remains the default on the confirmation page
+        let reject = NbglChoice::new().glyph(self.glyph).show(
+            "Accept the risk?",
+            "You are about to continue despite the warning. Only accept if you fully trust this operation.",
+            self.reject_text,
+            self.accept_text,
+        );
+        !reject
+    }
+}
@@ -188,9 +188,11 @@ macro_rules! atomic_storage {
 atomic_storage!(64);
 #[cfg(target_os = "nanox")]
 atomic_storage!(256);
-#[cfg(any(target_os = "nanosplus", target_os = "stax", target_os = "flex"))]
+#[cfg(not(any(target_os = "nanos", target_os = "nanox")))]
 atomic_storage!(512);
 
+const _: () = assert!(core::mem::align_of::<AtomicStorage<u8>>() == crate::caps::NVM_PAGE_SIZE);
+
 pub enum AtomicStorageElem {
     StorageA,
     StorageB,
@@ -252,6 +254,226 @@ where
         }
     }
 }
+
+/// Returned when a [`MonotonicCounter`] cannot be advanced
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CounterError {
+    /// The counter has reached its maximum value
+    Overflow,
+    /// The requested value is not above the current one
+    NotIncreasing,
+}
+
+/// Non-Volatile counter which can only increase, for sign-operation counters
+/// and anti-replay nonces.
+///
+/// The counter is stored in an [`AtomicStorage`] and a new value is written
+/// before being returned: if the update is interrupted by tearing, the
+/// counter keeps its previous value, which has already been used, and the
+/// interrupted value has never been returned. A value is therefore never
+/// returned twice, even across power losses.
+///
+/// # Examples
+///
+/// ```
+/// #[link_section = ".nvm_data"]
+/// static mut NONCE: NVMData<MonotonicCounter> = NVMData::new(MonotonicCounter::new(0));
+///
+/// let nonce = unsafe { NONCE.get_mut() }.increment()?;
+/// ```
+pub struct MonotonicCounter {
+    value: AtomicStorage<u64>,
+}
+
+impl MonotonicCounter {
+    /// Create a counter starting at `initial`. The first value returned by
+    /// [`increment`](MonotonicCounter::increment) is `initial + 1`.
+    pub const fn new(initial: u64) -> MonotonicCounter {
+        MonotonicCounter {
+            value: AtomicStorage::new(&initial),
+        }
+    }
+
+    /// Returns the last value of the counter.
+    pub fn get(&self) -> u64 {
+        *self.value.get_ref()
+    }
+
+    /// Increments the counter and returns its new value, once stored.
+    pub fn increment(&mut self) -> Result<u64, CounterError> {
+        let value = self.get().checked_add(1).ok_or(CounterError::Overflow)?;
+        self.value.update(&value);
+        Ok(value)
+    }
+
+    /// Sets the counter to `value`, which must be strictly above the current
+    /// value, for instance to resynchronize with a nonce received from the
+    /// host.
+    pub fn advance_to(&mut self, value: u64) -> Result<(), CounterError> {
+        if value <= self.get() {
+            return Err(CounterError::NotIncreasing);
+        }
+        self.value.update(&value);
+        Ok(())
+    }
+}
+
+/// Byte region of `N` bytes in NVM, read and written at any offset, for
+/// data of variable length such as serialized structures.
+///
+/// With the `embedded-io` feature, [`NvmRegion::cursor`] implements the
+/// `Read`, `Write` and `Seek` traits of `embedded_io`, so that libraries
+/// working on streams can persist data to flash.
+///
+/// Warning: writes are not atomic.
+///
+/// # Examples
+///
+/// ```
+/// #[link_section = ".nvm_data"]
+/// static mut SETTINGS: NVMData<NvmRegion<1024>> = NVMData::new(NvmRegion::new());
+///
+/// let mut cursor = unsafe { SETTINGS.get_mut() }.cursor();
+/// cursor.write_all(&encoded_settings)?;
+/// ```
+#[repr(align(64))]
+pub struct NvmRegion<const N: usize> {
+    bytes: [u8; N],
+}
+
+impl<const N: usize> NvmRegion<N> {
+    /// Create a region filled with zeros.
+    pub const fn new() -> NvmRegion<N> {
+        NvmRegion { bytes: [0; N] }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Writes `data` at `offset`, truncated to the end of the region, and
+    /// returns the number of bytes written.
+    pub fn write_at(&mut self, offset: usize, data: &[u8]) -> usize {
+        let len = data.len().min(N.saturating_sub(offset));
+        if len > 0 {
+            unsafe {
+                nvm_write(
+                    self.bytes[offset..].as_mut_ptr() as *mut core::ffi::c_void,
+                    data.as_ptr() as *mut core::ffi::c_void,
+                    len as u32,
+                );
+            }
+        }
+        len
+    }
+
+    /// Returns a cursor reading and writing the region from its start.
+    pub fn cursor(&mut self) -> NvmCursor<'_, N> {
+        NvmCursor {
+            region: self,
+            position: 0,
+        }
+    }
+}
+
+impl<const N: usize> Default for NvmRegion<N> {
+    fn default() -> NvmRegion<N> {
+        NvmRegion::new()
+    }
+}
+
+/// Position in an [`NvmRegion`], returned by [`NvmRegion::cursor`].
+pub struct NvmCursor<'a, const N: usize> {
+    region: &'a mut NvmRegion<N>,
+    position: usize,
+}
+
+impl<const N: usize> NvmCursor<'_, N> {
+    /// Returns the offset of the next byte read or written.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Error of the `embedded_io` traits implemented by [`NvmCursor`].
+#[cfg(feature = "embedded-io")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NvmIoError {
+    /// The end of the region is reached while writing
+    EndOfRegion,
+    /// Seeking before the start or after the end of the region
+    InvalidSeek,
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Error for NvmIoError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            NvmIoError::EndOfRegion => embedded_io::ErrorKind::OutOfMemory,
+            NvmIoError::InvalidSeek => embedded_io::ErrorKind::InvalidInput,
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<const N: usize> embedded_io::ErrorType for NvmCursor<'_, N> {
+    type Error = NvmIoError;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<const N: usize> embedded_io::Read for NvmCursor<'_, N> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, NvmIoError> {
+        let remaining = &self.region.as_bytes()[self.position..];
+        let len = buf.len().min(remaining.len());
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<const N: usize> embedded_io::Write for NvmCursor<'_, N> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, NvmIoError> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.region.write_at(self.position, buf) {
+            0 => Err(NvmIoError::EndOfRegion),
+            len => {
+                self.position += len;
+                Ok(len)
+            }
+        }
+    }
+
+    /// Writes are done in NVM immediately.
+    fn flush(&mut self) -> Result<(), NvmIoError> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<const N: usize> embedded_io::Seek for NvmCursor<'_, N> {
+    fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, NvmIoError> {
+        let position = match pos {
+            embedded_io::SeekFrom::Start(offset) => i64::try_from(offset).ok(),
+            embedded_io::SeekFrom::End(offset) => (N as i64).checked_add(offset),
+            embedded_io::SeekFrom::Current(offset) => (self.position as i64).checked_add(offset),
+        };
+        match position {
+            Some(position) if (0..=N as i64).contains(&position) => {
+                self.position = position as usize;
+                Ok(position as u64)
+            }
+            _ => Err(NvmIoError::InvalidSeek),
+        }
+    }
+}
+
 pub struct KeyOutOfRange;
 
 /// A Non-Volatile fixed-size collection of fixed-size items.
@@ -452,3 +674,36 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    // Static, as an `AtomicStorage` is aligned on a page, which does not fit
+    // well on the stack. The errors are returned before any write.
+    static mut MAX_COUNTER: MonotonicCounter = MonotonicCounter::new(u64::MAX);
+    static mut COUNTER: MonotonicCounter = MonotonicCounter::new(5);
+
+    #[test]
+    fn counter_overflow() {
+        let counter = unsafe { &mut *core::ptr::addr_of_mut!(MAX_COUNTER) };
+        assert_eq!(counter.increment(), Err(CounterError::Overflow));
+        assert_eq!(counter.get(), u64::MAX);
+        assert_eq!(
+            counter.advance_to(u64::MAX),
+            Err(CounterError::NotIncreasing)
+        );
+    }
+
+    #[test]
+    fn counter_not_increasing() {
+        let counter = unsafe { &mut *core::ptr::addr_of_mut!(COUNTER) };
+        assert_eq!(counter.advance_to(5), Err(CounterError::NotIncreasing));
+        assert_eq!(counter.advance_to(4), Err(CounterError::NotIncreasing));
+        assert_eq!(counter.advance_to(0), Err(CounterError::NotIncreasing));
+        assert_eq!(counter.get(), 5);
+    }
+}
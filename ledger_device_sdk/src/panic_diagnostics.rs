@@ -0,0 +1,119 @@
+//! Diagnostics displayed by [`exiting_panic`](crate::exiting_panic) in debug
+//! builds, and when the [`watchdog`](crate::watchdog) expires.
+//!
+//! The panic message and location are formatted into a fixed buffer, since the
+//! panic may be due to an exhausted heap, then written to the debug channels
+//! enabled by the `speculos` and `debug_serial` features, and displayed until
+//! the user dismisses them.
+
+use core::fmt::Write;
+use core::panic::PanicInfo;
+
+/// Maximum length of the displayed diagnostics, longer ones are truncated
+const DIAGNOSTICS_MAX_LEN: usize = 160;
+
+/// Truncating writer of the diagnostics, with room for a null terminator
+struct DiagnosticsBuffer {
+    buf: [u8; DIAGNOSTICS_MAX_LEN + 1],
+    len: usize,
+}
+
+impl DiagnosticsBuffer {
+    const fn new() -> Self {
+        DiagnosticsBuffer {
+            buf: [0; DIAGNOSTICS_MAX_LEN + 1],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        // Truncation may have split a multi-byte character
+        match core::str::from_utf8(&self.buf[..self.len]) {
+            Ok(s) => s,
+            Err(e) => unsafe { core::str::from_utf8_unchecked(&self.buf[..e.valid_up_to()]) },
+        }
+    }
+}
+
+impl Write for DiagnosticsBuffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &b in s.as_bytes() {
+            if self.len == DIAGNOSTICS_MAX_LEN {
+                break;
+            }
+            // Displayed on a single line, and null terminated for NBGL
+            self.buf[self.len] = match b {
+                b'\n' => b' ',
+                0 => b'?',
+                b => b,
+            };
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Outputs the message and location of the panic on the debug channels and
+/// the screen, and returns once the user has dismissed it.
+pub(crate) fn show(info: &PanicInfo) {
+    show_fatal("Panic\0", format_args!("{}", info));
+}
+
+/// Outputs `message` on the debug channels and the screen, below `title`
+/// which must be null terminated, and returns once the user has dismissed it.
+pub(crate) fn show_fatal(title: &str, message: core::fmt::Arguments) {
+    let mut diagnostics = DiagnosticsBuffer::new();
+    let _ = diagnostics.write_fmt(message);
+    let text = diagnostics.as_str();
+
+    #[cfg(feature = "speculos")]
+    {
+        crate::testing::debug_print(text);
+        crate::testing::debug_print("\n");
+    }
+    crate::debug_writeln!("{}", text);
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    unsafe {
+        use core::ffi::c_char;
+        use ledger_secure_sdk_sys::*;
+
+        let icon = nbgl_icon_details_t::default();
+        ux_sync_choice(
+            &icon as *const nbgl_icon_details_t,
+            title.as_ptr() as *const c_char,
+            diagnostics.buf.as_ptr() as *const c_char,
+            "Quit app\0".as_ptr() as *const c_char,
+            "Quit app\0".as_ptr() as *const c_char,
+        );
+    }
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        // Only the message is scrolled on Nano devices
+        let _ = title;
+        crate::ui::gadgets::MessageScroller::new(text).event_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn diagnostics_buffer() {
+        let mut diagnostics = DiagnosticsBuffer::new();
+        let _ = write!(diagnostics, "panicked at src/main.rs:1:1:\n{}", "oops");
+        assert_eq!(diagnostics.as_str(), "panicked at src/main.rs:1:1: oops");
+
+        let mut diagnostics = DiagnosticsBuffer::new();
+        for _ in 0..DIAGNOSTICS_MAX_LEN {
+            let _ = diagnostics.write_str("é");
+        }
+        assert_eq!(diagnostics.as_str().len(), DIAGNOSTICS_MAX_LEN);
+        assert_eq!(diagnostics.buf[DIAGNOSTICS_MAX_LEN], 0);
+    }
+}
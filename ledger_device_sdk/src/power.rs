@@ -0,0 +1,94 @@
+//! Power and connectivity status of the device
+//!
+//! The status is reported by the MCU with status events, which are received
+//! while [`Comm`](crate::io::Comm) processes the device events. Changes of
+//! the USB power are also returned as [`UsbEvent`](crate::io::UsbEvent)s, so
+//! that screens showing the status can be redrawn.
+//!
+//! The OS does not expose the battery percentage to applications: only
+//! whether the device is charging is known.
+//!
+//! When the battery level becomes critical, the MCU notifies the application
+//! before powering the device off. The notification is returned as
+//! [`Event::BatteryCritical`](crate::io::Event::BatteryCritical), or handled
+//! by the handler registered with [`set_shutdown_handler`], which runs
+//! whichever loop is processing the events, including the ones of the NBGL
+//! review flows.
+
+use ledger_secure_sdk_sys::*;
+
+/// Flags of the last status event, `None` until the first one is received
+static mut STATUS_FLAGS: Option<u32> = None;
+
+/// Handler called before the device is powered off, see
+/// [`set_shutdown_handler`].
+pub type ShutdownHandler = fn();
+
+static mut SHUTDOWN_HANDLER: Option<ShutdownHandler> = None;
+
+/// Power and connectivity status, see [`device_status`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeviceStatus {
+    /// The device is powered through USB
+    pub usb_powered: bool,
+    /// The battery is charging
+    pub charging: bool,
+    /// Bluetooth is enabled
+    pub ble_enabled: bool,
+}
+
+impl DeviceStatus {
+    fn from_flags(flags: u32) -> Self {
+        DeviceStatus {
+            usb_powered: flags & SEPROXYHAL_TAG_STATUS_EVENT_FLAG_USB_POWERED != 0,
+            charging: flags & SEPROXYHAL_TAG_STATUS_EVENT_FLAG_CHARGING != 0,
+            ble_enabled: flags & SEPROXYHAL_TAG_STATUS_EVENT_FLAG_BLE_ON != 0,
+        }
+    }
+}
+
+/// Returns the last status reported by the MCU, or `None` if no status event
+/// has been received yet.
+pub fn device_status() -> Option<DeviceStatus> {
+    unsafe { STATUS_FLAGS.map(DeviceStatus::from_flags) }
+}
+
+/// Called on each status event with its flags.
+pub(crate) fn update_status(flags: u32) {
+    unsafe {
+        STATUS_FLAGS = Some(flags);
+    }
+}
+
+/// Registers `handler` to be called when the battery level is critical, so
+/// that state kept in RAM can be written to NVM before the device is powered
+/// off. The application then exits, which aborts any review in progress
+/// instead of leaving it half-processed. Passing `None` removes the handler,
+/// and [`Event::BatteryCritical`](crate::io::Event::BatteryCritical) is
+/// returned instead.
+///
+/// The handler runs while an event is being received: it must be short and
+/// must not wait for events or display anything.
+///
+/// # Examples
+///
+/// ```
+/// power::set_shutdown_handler(Some(|| {
+///     let settings = unsafe { SETTINGS.get_mut() };
+///     settings.update(&pending_settings());
+/// }));
+/// ```
+pub fn set_shutdown_handler(handler: Option<ShutdownHandler>) {
+    unsafe { SHUTDOWN_HANDLER = handler };
+    ledger_secure_sdk_sys::seph::set_battery_critical_hook(
+        handler.map(|_| battery_critical as fn()),
+    );
+}
+
+/// Called on battery notifications when a shutdown handler is registered.
+fn battery_critical() {
+    if let Some(handler) = unsafe { (*core::ptr::addr_of_mut!(SHUTDOWN_HANDLER)).take() } {
+        handler();
+    }
+    crate::exit(crate::ExitCode::Failure);
+}
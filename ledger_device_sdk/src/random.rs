@@ -17,6 +17,42 @@ pub fn rand_bytes(out: &mut [u8]) {
     }
 }
 
+/// Fills `out` with random bytes, same as [`rand_bytes`].
+#[inline]
+pub fn fill(out: &mut [u8]) {
+    rand_bytes(out)
+}
+
+/// Returns a uniformly distributed random number in `0..n`.
+///
+/// Random values from the biased tail of the `u32` range are rejected and
+/// drawn again, so that all the results have the same probability, unlike
+/// `u32::random() % n`.
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+pub fn rand_u32_below(n: u32) -> u32 {
+    u32::random_from_range(0..n)
+}
+
+/// Shuffles `items` in place with the Fisher-Yates algorithm, all the
+/// permutations having the same probability.
+///
+/// # Example
+///
+/// ```
+/// // Random order of the words of a recovery phrase to check
+/// let mut indexes: [u8; 24] = core::array::from_fn(|i| i as u8);
+/// shuffle(&mut indexes);
+/// ```
+pub fn shuffle<T>(items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        let j = rand_u32_below(i as u32 + 1) as usize;
+        items.swap(i, j);
+    }
+}
+
 /// In-house random trait for generating random numbers.
 pub trait Random
 where
@@ -126,4 +162,20 @@ mod tests {
         let r: [u8; 16] = core::array::from_fn(|_| u8::random());
         assert_eq!(u128::from_be_bytes(r) != 0, true);
     }
+
+    #[test]
+    fn below() {
+        assert_eq!(rand_u32_below(1), 0);
+        for _ in 0..32 {
+            assert_eq!(rand_u32_below(10) < 10, true);
+        }
+    }
+
+    #[test]
+    fn shuffled() {
+        let mut items: [u8; 16] = core::array::from_fn(|i| i as u8);
+        shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, core::array::from_fn::<u8, 16, _>(|i| i as u8));
+    }
 }
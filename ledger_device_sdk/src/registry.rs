@@ -0,0 +1,288 @@
+//! Install parameters of the application and OS settings
+//!
+//! Safe getters over the `os_registry_get_current_app_tag`, `os_flags` and
+//! `os_setting_get` syscalls, returning typed values instead of raw buffers
+//! and integers.
+//!
+//! # Examples
+//!
+//! ```
+//! let mut buf = [0u8; 32];
+//! let version = registry::app_version(&mut buf).unwrap_or("unknown");
+//!
+//! if !registry::check_path_allowed(&path) {
+//!     return Err(StatusWords::BadP1P2);
+//! }
+//!
+//! if !registry::os_flags().pin_validated {
+//!     return Err(SyscallError::Security.into());
+//! }
+//! ```
+
+use crate::i18n::Language;
+use ledger_secure_sdk_sys::*;
+
+/// Install parameters of the application, declared with `cargo ledger` and
+/// stored by the OS when the application is installed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AppTag {
+    /// Name of the application
+    Name,
+    /// Version of the application
+    Version,
+    /// Icon displayed in the dashboard
+    Icon,
+    /// Derivation path prefixes and curves the application is allowed to use
+    DerivationPath,
+    /// Application the current one depends on, such as the Ethereum
+    /// application for its plugins
+    Dependency,
+}
+
+impl AppTag {
+    fn bolos_tag(self) -> u32 {
+        match self {
+            AppTag::Name => BOLOS_TAG_APPNAME,
+            AppTag::Version => BOLOS_TAG_APPVERSION,
+            AppTag::Icon => BOLOS_TAG_ICON,
+            AppTag::DerivationPath => BOLOS_TAG_DERIVEPATH,
+            AppTag::Dependency => BOLOS_TAG_DEPENDENCY,
+        }
+    }
+}
+
+/// Reads the install parameter `tag` of the running application into `buf`,
+/// and returns the part of `buf` it was written to. The value is truncated if
+/// `buf` is too small, and empty if the parameter was not set at install.
+pub fn app_tag(tag: AppTag, buf: &mut [u8]) -> &[u8] {
+    let len = unsafe {
+        os_registry_get_current_app_tag(tag.bolos_tag(), buf.as_mut_ptr(), buf.len() as u32)
+    };
+    &buf[..(len as usize).min(buf.len())]
+}
+
+/// Returns the name of the running application, read into `buf`, or `None`
+/// if it is not valid UTF-8.
+pub fn app_name(buf: &mut [u8]) -> Option<&str> {
+    core::str::from_utf8(app_tag(AppTag::Name, buf)).ok()
+}
+
+/// Returns the version of the running application, read into `buf`, or
+/// `None` if it is not valid UTF-8.
+pub fn app_version(buf: &mut [u8]) -> Option<&str> {
+    core::str::from_utf8(app_tag(AppTag::Version, buf)).ok()
+}
+
+/// Maximum size of the derivation path install parameter read by
+/// [`check_path_allowed`]
+const DERIVATION_PARAMS_SIZE: usize = 256;
+
+/// Flag set on the length of SLIP-21 labels in the derivation path install
+/// parameter
+const SLIP21_LABEL: u8 = 0x80;
+
+/// Curves the application is allowed to derive keys on, declared in its
+/// install parameters.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Curves {
+    pub secp256k1: bool,
+    pub secp256r1: bool,
+    pub ed25519: bool,
+    pub slip21: bool,
+    pub bls12381g1: bool,
+}
+
+impl Curves {
+    pub const SECP256K1: u8 = 0x01;
+    pub const SECP256R1: u8 = 0x02;
+    pub const ED25519: u8 = 0x04;
+    pub const SLIP21: u8 = 0x08;
+    pub const BLS12381G1: u8 = 0x10;
+
+    fn from_bits(bits: u8) -> Self {
+        Curves {
+            secp256k1: bits & Self::SECP256K1 != 0,
+            secp256r1: bits & Self::SECP256R1 != 0,
+            ed25519: bits & Self::ED25519 != 0,
+            slip21: bits & Self::SLIP21 != 0,
+            bls12381g1: bits & Self::BLS12381G1 != 0,
+        }
+    }
+}
+
+/// Derivation path install parameter of the application: the allowed curves
+/// followed by the allowed BIP32 path prefixes and SLIP-21 labels, see
+/// [`derivation_params`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DerivationParams<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> DerivationParams<'a> {
+    /// Parses the raw install parameter. Returns `None` if it is empty, in
+    /// which case the OS does not restrict the derivations of the application.
+    pub fn parse(raw: &'a [u8]) -> Option<Self> {
+        match raw {
+            [] => None,
+            _ => Some(DerivationParams { raw }),
+        }
+    }
+
+    /// Curves the application is allowed to use.
+    pub fn curves(&self) -> Curves {
+        Curves::from_bits(self.raw[0])
+    }
+
+    /// Allowed BIP32 path prefixes, each one as big-endian components on 4
+    /// bytes. SLIP-21 labels and truncated entries are skipped.
+    pub fn path_prefixes(&self) -> impl Iterator<Item = &'a [u8]> {
+        let mut entries = &self.raw[1..];
+        core::iter::from_fn(move || loop {
+            let (&header, rest) = entries.split_first()?;
+            let len = match header & SLIP21_LABEL {
+                0 => header as usize * 4,
+                _ => (header & !SLIP21_LABEL) as usize,
+            };
+            if rest.len() < len {
+                entries = &[];
+                return None;
+            }
+            let (entry, rest) = rest.split_at(len);
+            entries = rest;
+            if header & SLIP21_LABEL == 0 {
+                return Some(entry);
+            }
+        })
+    }
+
+    /// Returns true if `path` starts with one of the allowed prefixes.
+    pub fn allows(&self, path: &[u32]) -> bool {
+        self.path_prefixes().any(|prefix| {
+            prefix.len() / 4 <= path.len()
+                && prefix
+                    .chunks_exact(4)
+                    .zip(path)
+                    .all(|(c, &p)| c == p.to_be_bytes())
+        })
+    }
+}
+
+/// Reads the derivation path install parameter of the application into
+/// `buf`, see [`DerivationParams::parse`].
+pub fn derivation_params(buf: &mut [u8]) -> Option<DerivationParams<'_>> {
+    DerivationParams::parse(app_tag(AppTag::DerivationPath, buf))
+}
+
+/// Returns true if the install parameters of the application allow it to
+/// derive keys on `path`, as enforced by the OS. Checking it before the
+/// derivation allows to reply with an error status instead of having the
+/// application killed by the OS.
+pub fn check_path_allowed(path: &[u32]) -> bool {
+    let mut buf = [0u8; DERIVATION_PARAMS_SIZE];
+    match derivation_params(&mut buf) {
+        Some(params) => params.allows(path),
+        None => true,
+    }
+}
+
+/// Global state of the OS, see [`os_flags`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OsFlags {
+    /// The device was started in recovery mode
+    pub recovery: bool,
+    /// The MCU firmware is signed
+    pub signed_mcu_code: bool,
+    /// The device has been set up, with a PIN and a seed
+    pub onboarded: bool,
+    /// The PIN has been entered since the device was last locked
+    pub pin_validated: bool,
+}
+
+impl OsFlags {
+    fn from_bits(flags: u32) -> Self {
+        OsFlags {
+            recovery: flags & OS_FLAG_RECOVERY != 0,
+            signed_mcu_code: flags & OS_FLAG_SIGNED_MCU_CODE != 0,
+            onboarded: flags & OS_FLAG_ONBOARDED != 0,
+            pin_validated: flags & OS_FLAG_PIN_VALIDATED != 0,
+        }
+    }
+}
+
+/// Returns the global state of the OS.
+pub fn os_flags() -> OsFlags {
+    OsFlags::from_bits(unsafe { ledger_secure_sdk_sys::os_flags() } as u32)
+}
+
+fn setting(setting: u32) -> u32 {
+    unsafe { os_setting_get(setting, core::ptr::null_mut(), 0) as u32 }
+}
+
+/// Returns the delay of inactivity after which the device locks itself, as
+/// stored in the OS settings, or `None` if the automatic lock is disabled.
+pub fn auto_lock_delay() -> Option<u32> {
+    match setting(OS_SETTING_AUTO_LOCK_DELAY as u32) {
+        0 => None,
+        delay => Some(delay),
+    }
+}
+
+/// Returns the language selected in the OS settings. Changes are reported
+/// by [`Comm::next_event`](crate::io::Comm::next_event) as
+/// [`Event::Language`](crate::io::Event::Language).
+pub fn language() -> Language {
+    Language::current()
+}
+
+/// Returns true if the airplane mode, disabling Bluetooth, is enabled in the
+/// OS settings.
+pub fn airplane_mode() -> bool {
+    setting(OS_SETTING_PLANEMODE as u32) != 0
+}
+
+/// Returns true if the screen colors are inverted in the OS settings.
+pub fn screen_inverted() -> bool {
+    setting(OS_SETTING_INVERT as u32) != 0
+}
+
+/// Returns true if the screen is rotated upside down in the OS settings.
+pub fn screen_rotated() -> bool {
+    setting(OS_SETTING_ROTATION as u32) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn os_flags_bits() {
+        let flags = OsFlags::from_bits(OS_FLAG_ONBOARDED | OS_FLAG_PIN_VALIDATED);
+        assert_eq!(flags.onboarded, true);
+        assert_eq!(flags.pin_validated, true);
+        assert_eq!(flags.recovery, false);
+        assert_eq!(OsFlags::from_bits(0), OsFlags::default());
+    }
+
+    #[test]
+    fn derivation_path_prefixes() {
+        // secp256k1 and ed25519, m/44'/535348', a SLIP-21 label and m/5
+        let raw = [
+            0x05, 0x02, 0x80, 0x00, 0x00, 0x2c, 0x80, 0x08, 0x2b, 0x34, 0x82, b'L', b'B', 0x01,
+            0x00, 0x00, 0x00, 0x05,
+        ];
+        let params = DerivationParams::parse(&raw).unwrap();
+        assert_eq!(params.curves().secp256k1, true);
+        assert_eq!(params.curves().ed25519, true);
+        assert_eq!(params.curves().secp256r1, false);
+        assert_eq!(params.path_prefixes().count(), 2);
+        let path: [u32; 3] = crate::ecc::make_bip32_path(b"m/44'/535348'/0'");
+        assert_eq!(params.allows(&path), true);
+        assert_eq!(params.allows(&path[..1]), false);
+        assert_eq!(params.allows(&[5, 0]), true);
+        assert_eq!(params.allows(&[6]), false);
+        assert_eq!(DerivationParams::parse(&[]), None);
+    }
+}
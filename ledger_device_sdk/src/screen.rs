@@ -63,3 +63,82 @@ pub fn sdk_bagl_hal_draw_rect(color: u32, x: i32, y: i32, width: u32, height: u3
         bagl_hal_draw_rect(color, x, y, width, height);
     }
 }
+
+/// Returns the screen brightness level configured in the OS settings.
+///
+/// Applications can not change it: the brightness can only be set by the user
+/// in the OS settings.
+pub fn brightness() -> u8 {
+    unsafe {
+        ledger_secure_sdk_sys::os_setting_get(
+            ledger_secure_sdk_sys::OS_SETTING_BRIGHTNESS as u32,
+            core::ptr::null_mut(),
+            0,
+        ) as u8
+    }
+}
+
+/// Number of ticker events (100 ms each) between two wake up requests while
+/// the screen is kept awake.
+const KEEP_AWAKE_PERIOD: u32 = 50;
+
+/// Number of alive [`KeepAwake`] guards.
+static mut KEEP_AWAKE_GUARDS: u32 = 0;
+static mut KEEP_AWAKE_TICKS: u32 = 0;
+
+/// Guard preventing the screen from dimming, and the device from locking,
+/// because of inactivity while it is alive, for instance while the user
+/// verifies a long address. The OS inactivity timer is reset periodically, as
+/// long as the IO events are processed. The usual behavior is restored when
+/// the guard is dropped.
+///
+/// # Examples
+///
+/// ```
+/// let _awake = KeepAwake::new();
+/// NbglAddressReview::new().show(&address);
+/// ```
+pub struct KeepAwake {
+    _private: (),
+}
+
+impl KeepAwake {
+    pub fn new() -> KeepAwake {
+        unsafe {
+            if KEEP_AWAKE_GUARDS == 0 {
+                KEEP_AWAKE_TICKS = 0;
+            }
+            KEEP_AWAKE_GUARDS += 1;
+        }
+        KeepAwake { _private: () }
+    }
+}
+
+impl Default for KeepAwake {
+    fn default() -> KeepAwake {
+        KeepAwake::new()
+    }
+}
+
+impl Drop for KeepAwake {
+    fn drop(&mut self) {
+        unsafe {
+            KEEP_AWAKE_GUARDS -= 1;
+        }
+    }
+}
+
+/// Called on each ticker event, to reset the OS inactivity timer while a
+/// [`KeepAwake`] guard is alive.
+pub(crate) fn keep_awake_tick() {
+    unsafe {
+        if KEEP_AWAKE_GUARDS == 0 {
+            return;
+        }
+        KEEP_AWAKE_TICKS += 1;
+        if KEEP_AWAKE_TICKS >= KEEP_AWAKE_PERIOD {
+            KEEP_AWAKE_TICKS = 0;
+            crate::uxapp::UxEvent::WakeUp.request();
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Wiping of sensitive temporaries
+//!
+//! Seeds, derived keys and entered PINs must not remain in memory once used.
+//! [`Zeroizing`] wraps a value which is wiped when it is dropped, including
+//! on early returns with `?`, and [`with_secret`] lends a byte buffer which
+//! is wiped when the closure returns.
+//!
+//! # Examples
+//!
+//! ```
+//! let signature = with_secret(|seed: &mut [u8; 64]| {
+//!     derive_seed(seed)?;
+//!     sign_with_seed(seed, &hash)
+//! })?;
+//!
+//! let mut pin = Zeroizing::new([0u8; 8]);
+//! let len = read_pin(&mut pin)?;
+//! check_pin(&pin[..len])?;
+//! ```
+
+pub use zeroize::{Zeroize, Zeroizing};
+
+/// Calls `f` with a zero-initialized buffer of `N` bytes, which is wiped
+/// when `f` returns, and returns the result of `f`.
+///
+/// Results computed from the buffer should not be copies of it, as they are
+/// not wiped.
+#[inline(never)]
+pub fn with_secret<const N: usize, R>(f: impl FnOnce(&mut [u8; N]) -> R) -> R {
+    let mut buffer = Zeroizing::new([0u8; N]);
+    f(&mut buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn zeroizing() {
+        let sum = with_secret(|buffer: &mut [u8; 4]| {
+            buffer.copy_from_slice(&[1, 2, 3, 4]);
+            buffer.iter().map(|&b| b as u32).sum::<u32>()
+        });
+        assert_eq!(sum, 10);
+
+        let mut secret = Zeroizing::new([0x55u8; 4]);
+        secret.zeroize();
+        assert_eq!(*secret, [0u8; 4]);
+    }
+}
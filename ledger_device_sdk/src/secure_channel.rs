@@ -0,0 +1,478 @@
+//! Encrypted channel between the application and its host
+//!
+//! The channel is established by an ECDH handshake between an ephemeral
+//! secp256k1 key pair of the host and one generated by the device, which
+//! signs the handshake with its endorsement key 2 (see the
+//! [`attestation`](crate::attestation) module). The host thus knows it talks
+//! to the application running on a genuine device. The host signs its
+//! ephemeral key with a long-term identity key, which the application
+//! embeds: channels are only established with hosts holding this key, so
+//! that other programs on the USB or BLE link cannot read the responses.
+//!
+//! APDU payloads are then encrypted with ChaCha20-Poly1305, so that
+//! addresses or extended public keys are not sent in plaintext over USB or
+//! BLE.
+//!
+//! # Protocol
+//!
+//! - The host sends its ephemeral public key `host_key`, in uncompressed
+//!   form, and the DER encoded ECDSA signature of `SHA-256(host_key)` by its
+//!   secp256k1 identity key. The device rejects the handshake if the
+//!   signature does not match the identity key it trusts.
+//! - The device replies with its ephemeral public key `device_key` and the
+//!   signature of `host_key || device_key` by its endorsement key 2.
+//! - Both sides compute the X coordinate `secret` of the ECDH shared point,
+//!   and the keys `SHA-256(label || secret || host_key || device_key)`, with
+//!   the labels `"host->device"` and `"device->host"` for each direction.
+//! - Each message is encrypted with the key of its direction, and the nonce
+//!   made of 4 zero bytes followed by the big-endian number of messages
+//!   previously sent in this direction, on 8 bytes. The 16-byte tag follows
+//!   the ciphertext.
+//!
+//! # Examples
+//!
+//! ```
+//! const HOST_IDENTITY_KEY: [u8; PUBLIC_KEY_LEN] = [0x04, ...];
+//!
+//! let (mut channel, handshake) =
+//!     SecureChannel::establish(&HOST_IDENTITY_KEY, &host_key, host_signature)?;
+//! comm.append(handshake.device_key());
+//! comm.append(handshake.signature());
+//! ...
+//! let mut plaintext = [0u8; 255];
+//! let request = channel.decrypt(comm.get_data()?, &mut plaintext)?;
+//! ...
+//! let mut ciphertext = [0u8; 255];
+//! comm.append(channel.encrypt(xpub, &mut ciphertext)?);
+//! ```
+
+use crate::attestation::{self, AttestationError, MAX_SIGNATURE_LEN, PUBLIC_KEY_LEN};
+use crate::ecc::{CurvesId, CxError, ECPublicKey, Secp256k1};
+use crate::hash::{sha2::Sha2_256, HashError, HashInit};
+use crate::secret::{Zeroize, Zeroizing};
+use ledger_secure_sdk_sys::*;
+
+/// Length of the encryption keys
+const KEY_LEN: usize = 32;
+/// Length of the ChaCha20-Poly1305 nonces
+const NONCE_LEN: usize = 12;
+/// Length of the authentication tag appended to each message
+pub const TAG_LEN: usize = 16;
+
+const HOST_TO_DEVICE: &[u8] = b"host->device";
+const DEVICE_TO_HOST: &[u8] = b"device->host";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SecureChannelError {
+    /// Error of a cryptographic primitive, for instance on an invalid host
+    /// key
+    Crypto(CxError),
+    /// The host key is not signed by the trusted host identity key
+    HostAuthentication,
+    /// The handshake could not be signed with the endorsement key
+    Attestation(AttestationError),
+    /// The output buffer is too small for the message
+    BufferTooSmall,
+    /// The message was not encrypted by the host with the expected key and
+    /// nonce, or has been modified
+    Authentication,
+    /// The maximum number of messages has been exchanged, a new channel must
+    /// be established
+    CounterExhausted,
+}
+
+impl From<CxError> for SecureChannelError {
+    fn from(e: CxError) -> SecureChannelError {
+        SecureChannelError::Crypto(e)
+    }
+}
+
+impl From<HashError> for SecureChannelError {
+    fn from(_: HashError) -> SecureChannelError {
+        SecureChannelError::Crypto(CxError::InternalError)
+    }
+}
+
+impl From<AttestationError> for SecureChannelError {
+    fn from(e: AttestationError) -> SecureChannelError {
+        SecureChannelError::Attestation(e)
+    }
+}
+
+/// Reply of the device to the host, returned by
+/// [`SecureChannel::establish`].
+pub struct Handshake {
+    device_key: [u8; PUBLIC_KEY_LEN],
+    signature: [u8; MAX_SIGNATURE_LEN],
+    signature_len: usize,
+}
+
+impl Handshake {
+    /// Ephemeral public key of the device, in uncompressed form
+    pub fn device_key(&self) -> &[u8] {
+        &self.device_key
+    }
+
+    /// DER encoded signature of `host_key || device_key` by the endorsement
+    /// key 2
+    pub fn signature(&self) -> &[u8] {
+        &self.signature[..self.signature_len]
+    }
+}
+
+/// Keys and message counters of an established channel. The keys are wiped
+/// when the channel is dropped.
+pub struct SecureChannel {
+    receive_key: Zeroizing<[u8; KEY_LEN]>,
+    send_key: Zeroizing<[u8; KEY_LEN]>,
+    received: u64,
+    sent: u64,
+}
+
+/// Returns `SHA-256(label || secret || transcript)`.
+fn derive_key(
+    label: &[u8],
+    secret: &[u8],
+    transcript: &[u8],
+) -> Result<Zeroizing<[u8; KEY_LEN]>, SecureChannelError> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    let mut sha = Sha2_256::new();
+    sha.update(label)?;
+    sha.update(secret)?;
+    sha.update(transcript)?;
+    sha.finalize(&mut key[..])?;
+    Ok(key)
+}
+
+fn nonce(counter: u64) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[4..].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+/// Runs `f` with a ChaCha20-Poly1305 context set up with `key`, and wipes
+/// the context afterwards.
+fn with_chachapoly(
+    key: &[u8; KEY_LEN],
+    f: impl FnOnce(&mut cx_chachapoly_context_t) -> cx_err_t,
+) -> Result<(), CxError> {
+    let mut ctx = cx_chachapoly_context_t::default();
+    let err = unsafe {
+        cx_chachapoly_init(&mut ctx);
+        match cx_chachapoly_set_key(&mut ctx, key.as_ptr(), KEY_LEN) {
+            CX_OK => f(&mut ctx),
+            err => err,
+        }
+    };
+    unsafe { core::ptr::write_volatile(&mut ctx, cx_chachapoly_context_t::default()) };
+    match err {
+        CX_OK => Ok(()),
+        err => Err(err.into()),
+    }
+}
+
+impl SecureChannel {
+    /// Establishes a channel with the host ephemeral public key `host_key`,
+    /// in uncompressed form, once `host_signature` is verified to be the
+    /// signature of `SHA-256(host_key)` by `identity_key`, the uncompressed
+    /// secp256k1 public key of the trusted host. Returns the channel and the
+    /// handshake to send back to the host.
+    pub fn establish(
+        identity_key: &[u8; PUBLIC_KEY_LEN],
+        host_key: &[u8; PUBLIC_KEY_LEN],
+        host_signature: &[u8],
+    ) -> Result<(SecureChannel, Handshake), SecureChannelError> {
+        let mut hash = [0u8; 32];
+        Sha2_256::new().hash(host_key, &mut hash)?;
+        let mut public_key = ECPublicKey::<PUBLIC_KEY_LEN, 'W'>::new(CurvesId::Secp256k1);
+        public_key.pubkey = *identity_key;
+        if host_signature.len() > MAX_SIGNATURE_LEN
+            || !public_key.verify((host_signature, host_signature.len() as u32), &hash)
+        {
+            return Err(SecureChannelError::HostAuthentication);
+        }
+
+        let mut seed = Zeroizing::new([0u8; 32]);
+        crate::random::rand_bytes(&mut seed[..]);
+        let private_key = Secp256k1::from(&seed[..]);
+        let device_key: [u8; PUBLIC_KEY_LEN] = private_key.public_key()?.into();
+        let secret = Zeroizing::new(private_key.ecdh(host_key)?);
+
+        let mut transcript = [0u8; 2 * PUBLIC_KEY_LEN];
+        transcript[..PUBLIC_KEY_LEN].copy_from_slice(host_key);
+        transcript[PUBLIC_KEY_LEN..].copy_from_slice(&device_key);
+        let receive_key = derive_key(HOST_TO_DEVICE, &secret[..], &transcript)?;
+        let send_key = derive_key(DEVICE_TO_HOST, &secret[..], &transcript)?;
+
+        let (signature, signature_len) = attestation::sign_with_key2(&transcript)?;
+        let channel = SecureChannel {
+            receive_key,
+            send_key,
+            received: 0,
+            sent: 0,
+        };
+        let handshake = Handshake {
+            device_key,
+            signature,
+            signature_len,
+        };
+        Ok((channel, handshake))
+    }
+
+    /// Encrypts `plaintext` into `out`, and returns the ciphertext followed
+    /// by its tag. `out` must be [`TAG_LEN`] bytes longer than `plaintext`.
+    pub fn encrypt<'a>(
+        &mut self,
+        plaintext: &[u8],
+        out: &'a mut [u8],
+    ) -> Result<&'a [u8], SecureChannelError> {
+        let len = plaintext.len() + TAG_LEN;
+        if out.len() < len {
+            return Err(SecureChannelError::BufferTooSmall);
+        }
+        let counter = self.sent;
+        self.sent = counter
+            .checked_add(1)
+            .ok_or(SecureChannelError::CounterExhausted)?;
+        let nonce = nonce(counter);
+        let (ciphertext, tag) = out[..len].split_at_mut(plaintext.len());
+        with_chachapoly(&self.send_key, |ctx| unsafe {
+            cx_chachapoly_encrypt_and_tag(
+                ctx,
+                nonce.as_ptr(),
+                NONCE_LEN,
+                core::ptr::null(),
+                0,
+                plaintext.as_ptr(),
+                plaintext.len(),
+                ciphertext.as_mut_ptr(),
+                tag.as_mut_ptr(),
+            )
+        })?;
+        Ok(&out[..len])
+    }
+
+    /// Decrypts `message`, a ciphertext followed by its tag, into `out` and
+    /// returns the plaintext.
+    ///
+    /// Messages must be decrypted in the order they were sent by the host. A
+    /// message which fails to authenticate is rejected, and the next one is
+    /// still expected to use the same nonce.
+    pub fn decrypt<'a>(
+        &mut self,
+        message: &[u8],
+        out: &'a mut [u8],
+    ) -> Result<&'a [u8], SecureChannelError> {
+        let Some(len) = message.len().checked_sub(TAG_LEN) else {
+            return Err(SecureChannelError::Authentication);
+        };
+        if out.len() < len {
+            return Err(SecureChannelError::BufferTooSmall);
+        }
+        let counter = self.received;
+        let next = counter
+            .checked_add(1)
+            .ok_or(SecureChannelError::CounterExhausted)?;
+        let nonce = nonce(counter);
+        let (ciphertext, tag) = message.split_at(len);
+        with_chachapoly(&self.receive_key, |ctx| unsafe {
+            cx_chachapoly_decrypt_and_auth(
+                ctx,
+                nonce.as_ptr(),
+                NONCE_LEN,
+                core::ptr::null(),
+                0,
+                ciphertext.as_ptr(),
+                len,
+                out.as_mut_ptr(),
+                tag.as_ptr(),
+            )
+        })
+        .map_err(|_| {
+            out[..len].zeroize();
+            SecureChannelError::Authentication
+        })?;
+        self.received = next;
+        Ok(&out[..len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::ecc::{make_bip32_path, SeedDerive};
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    /// Returns the channel of the device and the one of the host, with the
+    /// keys of each direction fixed.
+    fn channels() -> (SecureChannel, SecureChannel) {
+        let host_to_device: [u8; KEY_LEN] = core::array::from_fn(|i| i as u8);
+        let device_to_host = [0x42u8; KEY_LEN];
+        let device = SecureChannel {
+            receive_key: Zeroizing::new(host_to_device),
+            send_key: Zeroizing::new(device_to_host),
+            received: 0,
+            sent: 0,
+        };
+        let host = SecureChannel {
+            receive_key: Zeroizing::new(device_to_host),
+            send_key: Zeroizing::new(host_to_device),
+            received: 0,
+            sent: 0,
+        };
+        (device, host)
+    }
+
+    #[test]
+    fn host_authentication() {
+        let identity = Secp256k1::derive_from_path(&make_bip32_path(b"m/44'/535348'/0'"));
+        let identity_key = identity.public_key().unwrap().pubkey;
+        let host = Secp256k1::derive_from_path(&make_bip32_path(b"m/44'/535348'/1'"));
+        let host_key = host.public_key().unwrap().pubkey;
+        let mut hash = [0u8; 32];
+        Sha2_256::new().hash(&host_key, &mut hash).unwrap();
+        let (signature, len, _) = identity.deterministic_sign(&hash).unwrap();
+        let signature = &signature[..len as usize];
+
+        let mut other_key = identity_key;
+        other_key[PUBLIC_KEY_LEN - 1] ^= 1;
+        assert_eq!(
+            SecureChannel::establish(&other_key, &host_key, signature).err(),
+            Some(SecureChannelError::HostAuthentication)
+        );
+        // Signature of another ephemeral key
+        assert_eq!(
+            SecureChannel::establish(&identity_key, &identity_key, signature).err(),
+            Some(SecureChannelError::HostAuthentication)
+        );
+        assert_eq!(
+            SecureChannel::establish(&identity_key, &host_key, &signature[..len as usize - 1])
+                .err(),
+            Some(SecureChannelError::HostAuthentication)
+        );
+    }
+
+    #[test]
+    fn nonce_counter() {
+        assert_eq!(nonce(0), [0; NONCE_LEN]);
+        assert_eq!(nonce(0x0102), [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let (mut device, mut host) = channels();
+        let mut message = [0u8; 64];
+        let mut plaintext = [0u8; 64];
+
+        // ChaCha20-Poly1305 of "xpub" with the key 00 01 .. 1f and a zero nonce
+        let request = host.encrypt(b"xpub", &mut message).unwrap();
+        assert_eq!(
+            request,
+            [
+                0x60, 0xc8, 0x37, 0x53, 0x36, 0x0c, 0x2d, 0x17, 0x44, 0x41, 0x00, 0x8f, 0xc5, 0xd0,
+                0xcd, 0x6f, 0x4a, 0xb6, 0xb9, 0xfd
+            ]
+        );
+        assert_eq!(device.decrypt(request, &mut plaintext), Ok(&b"xpub"[..]));
+
+        let response = device.encrypt(b"xpub6C...", &mut message).unwrap();
+        assert_eq!(response.len(), 9 + TAG_LEN);
+        assert_eq!(
+            host.decrypt(response, &mut plaintext),
+            Ok(&b"xpub6C..."[..])
+        );
+        assert_eq!((device.sent, device.received), (1, 1));
+    }
+
+    #[test]
+    fn tampered_message() {
+        let (mut device, mut host) = channels();
+        let mut message = [0u8; 64];
+        let mut plaintext = [0u8; 64];
+        let len = host.encrypt(b"xpub", &mut message).unwrap().len();
+
+        message[len - 1] ^= 1;
+        assert_eq!(
+            device.decrypt(&message[..len], &mut plaintext),
+            Err(SecureChannelError::Authentication)
+        );
+        assert_eq!(plaintext[..4], [0; 4]);
+        message[len - 1] ^= 1;
+        message[0] ^= 1;
+        assert_eq!(
+            device.decrypt(&message[..len], &mut plaintext),
+            Err(SecureChannelError::Authentication)
+        );
+        // The rejected messages do not consume the nonce
+        message[0] ^= 1;
+        assert_eq!(
+            device.decrypt(&message[..len], &mut plaintext),
+            Ok(&b"xpub"[..])
+        );
+    }
+
+    #[test]
+    fn replayed_message() {
+        let (mut device, mut host) = channels();
+        let mut first = [0u8; 64];
+        let mut second = [0u8; 64];
+        let mut plaintext = [0u8; 64];
+        let first_len = host.encrypt(b"first", &mut first).unwrap().len();
+        let second_len = host.encrypt(b"second", &mut second).unwrap().len();
+
+        // Out of order
+        assert_eq!(
+            device.decrypt(&second[..second_len], &mut plaintext),
+            Err(SecureChannelError::Authentication)
+        );
+        assert_eq!(
+            device.decrypt(&first[..first_len], &mut plaintext),
+            Ok(&b"first"[..])
+        );
+        // Replayed
+        assert_eq!(
+            device.decrypt(&first[..first_len], &mut plaintext),
+            Err(SecureChannelError::Authentication)
+        );
+        assert_eq!(
+            device.decrypt(&second[..second_len], &mut plaintext),
+            Ok(&b"second"[..])
+        );
+        // Sent in the other direction
+        let mut response = [0u8; 64];
+        let len = device.encrypt(b"third", &mut response).unwrap().len();
+        assert_eq!(
+            device.decrypt(&response[..len], &mut plaintext),
+            Err(SecureChannelError::Authentication)
+        );
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let (mut device, mut host) = channels();
+        let mut message = [0u8; 64];
+        let mut plaintext = [0u8; 64];
+        assert_eq!(
+            device.decrypt(&message[..TAG_LEN - 1], &mut plaintext),
+            Err(SecureChannelError::Authentication)
+        );
+        assert_eq!(
+            device.encrypt(b"xpub", &mut message[..4 + TAG_LEN - 1]),
+            Err(SecureChannelError::BufferTooSmall)
+        );
+        let len = host.encrypt(b"xpub", &mut message).unwrap().len();
+        assert_eq!(
+            device.decrypt(&message[..len], &mut plaintext[..3]),
+            Err(SecureChannelError::BufferTooSmall)
+        );
+        assert_eq!((device.sent, device.received), (0, 0));
+
+        device.sent = u64::MAX;
+        assert_eq!(
+            device.encrypt(b"xpub", &mut message),
+            Err(SecureChannelError::CounterExhausted)
+        );
+    }
+}
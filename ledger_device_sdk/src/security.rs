@@ -0,0 +1,38 @@
+//! Fresh user authentication
+//!
+//! Especially sensitive commands, such as exporting a private key or resetting
+//! the application settings, can require the user to enter their PIN again,
+//! even though the device is already unlocked.
+//!
+//! # Examples
+//!
+//! ```
+//! Instruction::ExportKey => {
+//!     if !security::request_pin_validation() {
+//!         return Err(SyscallError::Security.into());
+//!     }
+//!     export_key(comm)?;
+//! }
+//! ```
+
+use crate::uxapp::{UxEvent, BOLOS_UX_OK};
+use ledger_secure_sdk_sys::*;
+
+/// Returns true if the PIN has been entered since the device was last locked.
+pub fn is_pin_validated() -> bool {
+    unsafe { os_global_pin_is_validated() as u32 == BOLOS_TRUE as u32 }
+}
+
+/// Locks the device and displays the OS PIN entry screen, then returns true
+/// once the user has entered the correct PIN, or false if the PIN was not
+/// validated.
+///
+/// The OS screen replaces the application one, which must be displayed again
+/// afterwards.
+pub fn request_pin_validation() -> bool {
+    unsafe {
+        os_global_pin_invalidate();
+    }
+    let status = UxEvent::ValidatePIN.request();
+    status == BOLOS_UX_OK && is_pin_validated()
+}
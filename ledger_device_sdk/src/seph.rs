@@ -19,6 +19,8 @@ pub enum Events {
     DisplayProcessed = SEPROXYHAL_TAG_DISPLAY_PROCESSED_EVENT as u8,
     BleReceive = SEPROXYHAL_TAG_BLE_RECV_EVENT as u8,
     ScreenTouch = SEPROXYHAL_TAG_FINGER_EVENT as u8,
+    StatusEvent = SEPROXYHAL_TAG_STATUS_EVENT as u8,
+    BatteryNotification = SEPROXYHAL_TAG_BATTERY_NOTIFICATION_EVENT as u8,
     Unknown = 0xff,
 }
 #[repr(u8)]
@@ -46,6 +48,8 @@ impl From<u8> for Events {
             SEPROXYHAL_TAG_DISPLAY_PROCESSED_EVENT => Events::DisplayProcessed,
             SEPROXYHAL_TAG_BLE_RECV_EVENT => Events::BleReceive,
             SEPROXYHAL_TAG_FINGER_EVENT => Events::ScreenTouch,
+            SEPROXYHAL_TAG_STATUS_EVENT => Events::StatusEvent,
+            SEPROXYHAL_TAG_BATTERY_NOTIFICATION_EVENT => Events::BatteryNotification,
             _ => Events::Unknown,
         }
     }
@@ -64,6 +68,40 @@ impl From<u8> for UsbEp {
     }
 }
 
+/// Signature of a hook receiving the SEPH events which are not handled by the
+/// SDK. The first argument is the event tag, the second one the event payload
+/// (without the tag and length header).
+pub type EventHook = fn(tag: u8, payload: &[u8]);
+
+static mut EVENT_HOOK: Option<EventHook> = None;
+
+/// Registers a hook called for every SEPH event the SDK does not handle
+/// itself (status events, custom tags...). Passing `None` removes the hook.
+///
+/// # Examples
+///
+/// ```
+/// fn on_seph_event(tag: u8, payload: &[u8]) {
+///     if tag == SEPROXYHAL_TAG_STATUS_EVENT as u8 { ... }
+/// }
+///
+/// seph::set_event_hook(Some(on_seph_event));
+/// ```
+pub fn set_event_hook(hook: Option<EventHook>) {
+    unsafe {
+        EVENT_HOOK = hook;
+    }
+}
+
+/// Forwards an unhandled SEPH event to the registered hook, if any.
+pub(crate) fn call_event_hook(spi_buffer: &[u8]) {
+    if let Some(hook) = unsafe { EVENT_HOOK } {
+        let len = u16::from_be_bytes([spi_buffer[1], spi_buffer[2]]) as usize;
+        let end = (3 + len).min(spi_buffer.len());
+        hook(spi_buffer[0], &spi_buffer[3..end]);
+    }
+}
+
 /// FFI bindings to USBD functions inlined here for clarity
 /// and also because some of the generated ones are incorrectly
 /// assuming mutable pointers when they are not
@@ -200,6 +238,6 @@ pub fn handle_event(apdu_buffer: &mut [u8], spi_buffer: &[u8]) {
         Events::BleReceive => ble::receive(apdu_buffer, spi_buffer),
         Events::CAPDUEvent => handle_capdu_event(apdu_buffer, spi_buffer),
         Events::TickerEvent => { /* unsafe{ G_io_app.ms += 100; } */ }
-        _ => (),
+        _ => call_event_hook(spi_buffer),
     }
 }
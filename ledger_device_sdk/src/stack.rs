@@ -0,0 +1,135 @@
+//! Measurement of the stack usage
+//!
+//! [`paint`] fills the unused part of the stack with a known pattern, and
+//! [`high_water_mark`] later finds how deep the stack has grown by looking
+//! for the lowest overwritten word. This measures the real consumption of a
+//! flow, such as signing a large transaction, instead of finding out about a
+//! too small stack through a crash.
+//!
+//! # Examples
+//!
+//! ```
+//! #[no_mangle]
+//! extern "C" fn sample_main() {
+//!     stack::paint();
+//!     ...
+//!     sign_transaction(&tx);
+//!     debug_println!("stack: {} / {} bytes", stack::high_water_mark(), stack::size());
+//! }
+//! ```
+
+use core::arch::asm;
+
+/// Pattern written by [`paint`] into the unused stack
+const PAINT_PATTERN: u32 = 0xa5a5_a5a5;
+
+/// Space left unpainted below the stack pointer, for the frame of [`paint`]
+/// and interrupts occurring while painting
+const PAINT_MARGIN: usize = 64;
+
+extern "C" {
+    // Linker script symbols, at the lowest and highest addresses of the
+    // stack.
+    static _stack_validation: u32;
+    static _estack: u32;
+}
+
+/// Returns the lowest and highest addresses of the stack.
+fn bounds() -> (usize, usize) {
+    // `_estack` is an absolute symbol, whose address is not relocated as a
+    // RAM address. The top of the stack is found from its distance to the
+    // bottom, which is relocated.
+    let bottom = unsafe { core::ptr::addr_of!(_stack_validation) } as usize;
+    let link_bottom: usize;
+    let link_top: usize;
+    unsafe {
+        asm!(
+            "ldr {0}, ={1}",
+            "ldr {2}, ={3}",
+            out(reg) link_bottom,
+            sym _stack_validation,
+            out(reg) link_top,
+            sym _estack,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+    (bottom, bottom + (link_top - link_bottom))
+}
+
+fn stack_pointer() -> usize {
+    let sp: usize;
+    unsafe {
+        asm!("mov {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+    }
+    sp
+}
+
+/// Size of the stack, in bytes.
+pub fn size() -> usize {
+    let (bottom, top) = bounds();
+    top - bottom
+}
+
+/// Current stack usage, in bytes.
+pub fn used() -> usize {
+    bounds().1 - stack_pointer()
+}
+
+/// Space left below the current stack pointer, in bytes.
+pub fn headroom() -> usize {
+    stack_pointer() - bounds().0
+}
+
+/// Fills the unused part of the stack with a pattern, for
+/// [`high_water_mark`]. Should be called once, at the start of the
+/// application.
+#[inline(never)]
+pub fn paint() {
+    let (bottom, _) = bounds();
+    let end = stack_pointer() - PAINT_MARGIN;
+    let mut word = bottom as *mut u32;
+    while (word as usize) < end {
+        unsafe {
+            word.write_volatile(PAINT_PATTERN);
+            word = word.add(1);
+        }
+    }
+}
+
+/// Maximum stack usage since [`paint`] was called, in bytes.
+///
+/// Without a call to [`paint`], the whole stack size is usually returned.
+pub fn high_water_mark() -> usize {
+    let (bottom, top) = bounds();
+    let mut word = bottom as *const u32;
+    while (word as usize) < top && unsafe { word.read_volatile() } == PAINT_PATTERN {
+        word = unsafe { word.add(1) };
+    }
+    top - word as usize
+}
+
+/// Returns true if the stack has grown to its lowest word since [`paint`] was
+/// called, in which case it has most likely overflowed and corrupted the
+/// memory below it.
+pub fn exhausted() -> bool {
+    let (bottom, _) = bounds();
+    unsafe { (bottom as *const u32).read_volatile() != PAINT_PATTERN }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn high_water_mark_bounds() {
+        paint();
+        assert_eq!(exhausted(), false);
+        let mark = high_water_mark();
+        assert_eq!(mark >= used(), true);
+        assert_eq!(mark < size(), true);
+        assert_eq!(headroom() > 0 && headroom() < size(), true);
+    }
+}
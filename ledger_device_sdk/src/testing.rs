@@ -1,8 +1,16 @@
+//! Runner of the unit tests executed on the device under Speculos
+//!
+//! Integration tests driving a whole application from the host, through APDUs
+//! and the buttons or touch screen, use the `speculos_harness` crate instead.
+
+#[cfg(not(feature = "host"))]
 use core::arch::asm;
 use core::panic::PanicInfo;
 
 /// Debug 'print' function that uses ARM semihosting
-/// Prints only strings with no formatting
+/// Prints only strings with no formatting, see [`debug_print!`](crate::debug_print)
+/// for formatted output
+#[cfg(not(feature = "host"))]
 pub fn debug_print(s: &str) {
     let p = s.as_bytes().as_ptr();
     for i in 0..s.len() {
@@ -17,6 +25,394 @@ pub fn debug_print(s: &str) {
     }
 }
 
+/// Debug 'print' function writing to the standard error when building for
+/// the host.
+#[cfg(feature = "host")]
+pub fn debug_print(s: &str) {
+    ledger_secure_sdk_sys::host::print(s);
+}
+
+/// Writer to the Speculos console, through ARM semihosting, used by
+/// [`debug_print!`](crate::debug_print) and
+/// [`debug_println!`](crate::debug_println).
+pub struct SpeculosConsole;
+
+impl core::fmt::Write for SpeculosConsole {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        debug_print(s);
+        Ok(())
+    }
+}
+
+/// Formats a byte slice as lowercase hexadecimal.
+///
+/// # Examples
+///
+/// ```
+/// debug_println!("hash: {}", Hex(&hash));
+/// ```
+pub struct Hex<'a>(pub &'a [u8]);
+
+impl core::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes formatted data to the Speculos console.
+///
+/// Does nothing unless the `speculos` feature is enabled, and in release
+/// builds of the calling crate.
+///
+/// # Examples
+///
+/// ```
+/// debug_print!("amount: {} ", amount);
+/// ```
+#[cfg(feature = "speculos")]
+#[macro_export]
+macro_rules! debug_print {
+    ($($arg:tt)*) => {{
+        if cfg!(debug_assertions) {
+            let _ = core::fmt::Write::write_fmt(
+                &mut $crate::testing::SpeculosConsole,
+                format_args!($($arg)*),
+            );
+        }
+    }};
+}
+
+/// Writes formatted data followed by a newline to the Speculos console.
+///
+/// Does nothing unless the `speculos` feature is enabled, and in release
+/// builds of the calling crate.
+///
+/// # Examples
+///
+/// ```
+/// debug_println!("path: {:?}, key: {}", path, Hex(&key));
+/// ```
+#[cfg(feature = "speculos")]
+#[macro_export]
+macro_rules! debug_println {
+    () => {
+        $crate::debug_print!("\n")
+    };
+    ($($arg:tt)*) => {{
+        $crate::debug_print!($($arg)*);
+        $crate::debug_print!("\n");
+    }};
+}
+
+/// Writes formatted data to the Speculos console.
+/// Does nothing unless the `speculos` feature is enabled.
+#[cfg(not(feature = "speculos"))]
+#[macro_export]
+macro_rules! debug_print {
+    ($($arg:tt)*) => {{}};
+}
+
+/// Writes formatted data followed by a newline to the Speculos console.
+/// Does nothing unless the `speculos` feature is enabled.
+#[cfg(not(feature = "speculos"))]
+#[macro_export]
+macro_rules! debug_println {
+    ($($arg:tt)*) => {{}};
+}
+
+/// Written on the Speculos console before the name of a screenshot requested
+/// with [`screenshot`].
+#[cfg(feature = "speculos")]
+pub const SCREENSHOT_MARKER: &str = "[speculos_harness:screenshot] ";
+
+/// Requests a screenshot of the current screen, saved as `name.png` by the
+/// `speculos_harness` crate running the application, for instance to compare
+/// it with a reference screenshot in a UI regression test.
+///
+/// The screenshot is taken shortly after the request, which must be made
+/// while the screen is stable, such as before waiting for a button press.
+/// `name` may only contain ASCII letters, digits, `-` and `_`.
+///
+/// # Examples
+///
+/// ```
+/// review.show(&fields);
+/// testing::screenshot("review_first_page");
+/// ```
+#[cfg(feature = "speculos")]
+pub fn screenshot(name: &str) {
+    debug_print(SCREENSHOT_MARKER);
+    debug_print(name);
+    debug_print("\n");
+}
+
+/// Queues a raw SEPH packet, which may be malformed or truncated, to be
+/// processed as the next event sent by the MCU. Returns false if too many
+/// events are pending or the packet is longer than 128 bytes.
+///
+/// Requires the `seph_injection` feature, which only builds in debug builds
+/// or with the `speculos` feature, as injected packets can stand for user
+/// inputs.
+///
+/// # Examples
+///
+/// ```
+/// // Status event announcing 4 bytes of flags but carrying only one
+/// testing::inject_seph_packet(&[0x01, 0x00, 0x04, 0xff]);
+/// let _ = comm.next_event::<Instruction>();
+/// ```
+#[cfg(feature = "seph_injection")]
+pub fn inject_seph_packet(packet: &[u8]) -> bool {
+    ledger_secure_sdk_sys::seph::inject(packet)
+}
+
+/// Queues a SEPH event with tag `tag` and `payload`, preceded by its length,
+/// see [`inject_seph_packet`].
+#[cfg(feature = "seph_injection")]
+pub fn inject_seph_event(tag: u8, payload: &[u8]) -> bool {
+    let mut packet = [0u8; 128];
+    if payload.len() > packet.len() - 3 {
+        return false;
+    }
+    packet[0] = tag;
+    packet[1..3].copy_from_slice(&(payload.len() as u16).to_be_bytes());
+    packet[3..3 + payload.len()].copy_from_slice(payload);
+    inject_seph_packet(&packet[..3 + payload.len()])
+}
+
+/// Queues `apdu`, which may be malformed, as a command received from the
+/// host, see [`inject_seph_packet`].
+#[cfg(feature = "seph_injection")]
+pub fn inject_apdu(apdu: &[u8]) -> bool {
+    inject_seph_event(
+        ledger_secure_sdk_sys::SEPROXYHAL_TAG_CAPDU_EVENT as u8,
+        apdu,
+    )
+}
+
+/// Deterministic generator of test inputs for [`check_property`], an
+/// xorshift64* generator: a failing case is replayed from its seed.
+///
+/// It is not cryptographically secure, and must only be used in tests.
+#[derive(Clone)]
+pub struct Gen {
+    state: u64,
+}
+
+impl Gen {
+    /// Creates a generator from `seed`. A zero seed is replaced, as xorshift
+    /// generators would only produce zeros.
+    pub fn from_seed(seed: u64) -> Self {
+        Gen {
+            state: if seed == 0 {
+                0x9e37_79b9_7f4a_7c15
+            } else {
+                seed
+            },
+        }
+    }
+
+    /// Returns a value in `0..n`. The result is slightly biased towards low
+    /// values, which does not matter for tests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn below(&mut self, n: u32) -> u32 {
+        assert!(n > 0, "Invalid range");
+        self.next_u32() % n
+    }
+
+    /// Returns true with a probability of one half.
+    pub fn bool(&mut self) -> bool {
+        self.next_u32() & 1 == 1
+    }
+
+    /// Returns a random element of `items`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u32) as usize]
+    }
+}
+
+impl rand_core::RngCore for Gen {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Runs `property` on `cases` inputs produced by generators seeded from the
+/// device RNG, and stops at the first failure, printing its seed so that it
+/// can be replayed with [`check_property_with_seed`].
+///
+/// # Examples
+///
+/// ```
+/// #[test]
+/// fn push_pop() {
+///     testing::check_property(100, |gen| {
+///         let mut vec = ArrayVec::<u32, 8>::new();
+///         let items = gen.below(8);
+///         for i in 0..items {
+///             assert_eq!(vec.push(i), Ok(()));
+///         }
+///         assert_eq!(vec.len() as u32, items);
+///         Ok(())
+///     })?;
+/// }
+/// ```
+pub fn check_property(
+    cases: u32,
+    mut property: impl FnMut(&mut Gen) -> Result<(), ()>,
+) -> Result<(), ()> {
+    for _ in 0..cases {
+        let mut seed = [0u8; 8];
+        crate::random::rand_bytes(&mut seed);
+        check_property_with_seed(u64::from_be_bytes(seed), &mut property)?;
+    }
+    Ok(())
+}
+
+/// Runs `property` on the input produced by the generator seeded with
+/// `seed`, and prints the seed if it fails.
+pub fn check_property_with_seed(
+    seed: u64,
+    mut property: impl FnMut(&mut Gen) -> Result<(), ()>,
+) -> Result<(), ()> {
+    let result = property(&mut Gen::from_seed(seed));
+    if result.is_err() {
+        debug_print("property failed with seed 0x");
+        debug_print(core::str::from_utf8(&to_hex((seed >> 32) as u32)).unwrap());
+        debug_print(core::str::from_utf8(&to_hex(seed as u32)).unwrap());
+        debug_print("\n");
+    }
+    result
+}
+
+/// Duration of the measures of [`Bencher::iter`]
+const BENCH_DURATION_MS: u32 = 1000;
+
+/// Measure of [`bench`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BenchResult {
+    pub iterations: u32,
+    pub elapsed_ms: u32,
+}
+
+impl BenchResult {
+    /// Average duration of an iteration, in microseconds.
+    pub fn us_per_iteration(&self) -> u32 {
+        (self.elapsed_ms as u64 * 1000 / self.iterations.max(1) as u64) as u32
+    }
+}
+
+/// Runs `f` repeatedly for at least `duration_ms` milliseconds, and returns
+/// the number of iterations and the time they took.
+///
+/// The time is counted in ticker periods of 100 ms: the device events are
+/// processed between batches of iterations, whose size is doubled until a
+/// batch lasts at least one period. Results are thus only meaningful for
+/// durations of one second or more. Under Speculos, they measure the
+/// emulator rather than the device.
+pub fn bench(duration_ms: u32, mut f: impl FnMut()) -> BenchResult {
+    use ledger_secure_sdk_sys::seph::ticker_events;
+    let mut comm = crate::io::Comm::new();
+    // Start on a ticker event
+    let start = ticker_events();
+    while ticker_events() == start {
+        comm.keepalive();
+    }
+    let start = ticker_events();
+    let mut iterations: u32 = 0;
+    let mut batch: u32 = 1;
+    loop {
+        let before = ticker_events();
+        for _ in 0..batch {
+            f();
+        }
+        iterations = iterations.saturating_add(batch);
+        comm.keepalive();
+        let elapsed_ms = ticker_events().wrapping_sub(start) * crate::io::TICKER_PERIOD_MS;
+        if elapsed_ms >= duration_ms {
+            return BenchResult {
+                iterations,
+                elapsed_ms,
+            };
+        }
+        if ticker_events().wrapping_sub(before) <= 1 {
+            batch = batch.saturating_mul(2);
+        }
+    }
+}
+
+/// Measures an operation in a benchmark declared with the `bench_item`
+/// attribute of the `testmacro` crate.
+///
+/// # Examples
+///
+/// ```
+/// use testmacro::bench_item as bench;
+///
+/// #[bench]
+/// fn sha256(b: &mut Bencher) {
+///     let data = [0u8; 64];
+///     b.iter(|| Sha2_256::new().hash(&data, &mut [0u8; 32]));
+/// }
+/// ```
+#[derive(Default)]
+pub struct Bencher {
+    result: Option<BenchResult>,
+}
+
+impl Bencher {
+    /// Measures the throughput of `f`, see [`bench`].
+    pub fn iter<R>(&mut self, mut f: impl FnMut() -> R) {
+        self.result = Some(bench(BENCH_DURATION_MS, || {
+            core::hint::black_box(f());
+        }));
+    }
+
+    /// Returns the measure of the last call to [`Bencher::iter`].
+    pub fn result(&self) -> Option<BenchResult> {
+        self.result
+    }
+
+    /// Prints the measure to the Speculos console.
+    pub fn report(&self) {
+        use core::fmt::Write;
+        if let Some(result) = self.result {
+            let _ = write!(
+                SpeculosConsole,
+                "{:>10} us/iter ({} iterations) ",
+                result.us_per_iteration(),
+                result.iterations
+            );
+        }
+    }
+}
+
 pub fn to_hex(m: u32) -> [u8; 8] {
     let mut hex = [0u8; 8];
     let mut i = 0;
@@ -38,7 +434,7 @@ pub fn test_panic(info: &PanicInfo) -> ! {
     debug_print("\n");
     debug_print(core::str::from_utf8(&to_hex(loc.line())).unwrap());
     debug_print("\n");
-    ledger_secure_sdk_sys::exit_app(1);
+    crate::exit(crate::ExitCode::Failure);
 }
 
 /// Custom type used to implement tests
@@ -89,9 +485,9 @@ pub fn sdk_test_runner(tests: &[&TestType]) {
         debug_print("\n");
     }
     if failures > 0 {
-        ledger_secure_sdk_sys::exit_app(1);
+        crate::exit(crate::ExitCode::Failure);
     }
-    ledger_secure_sdk_sys::exit_app(0);
+    crate::exit(crate::ExitCode::Success);
 }
 
 /// This variant of `assert_eq!()` returns an error
@@ -111,3 +507,28 @@ macro_rules! assert_eq_err {
         }
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use rand_core::RngCore;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn property_replay() {
+        let mut first = Gen::from_seed(42);
+        let mut second = first.clone();
+        assert_eq!(first.next_u64(), second.next_u64());
+        assert_eq!(Gen::from_seed(0).next_u64() != 0, true);
+        let mut cases = 0;
+        check_property(10, |gen| {
+            cases += 1;
+            assert_eq!(gen.below(3) < 3, true);
+            Ok(())
+        })?;
+        assert_eq!(cases, 10);
+        assert_eq!(check_property_with_seed(1, |_| Err(())), Err(()));
+    }
+}
@@ -0,0 +1,111 @@
+//! Uptime and elapsed time measurement
+//!
+//! Devices have no clock available to applications. Time is measured by
+//! counting the ticker events sent by the MCU every
+//! [`TICKER_PERIOD_MS`](crate::io::TICKER_PERIOD_MS), so it only advances
+//! while the application processes the device events, and its resolution is
+//! one ticker period.
+//!
+//! # Examples
+//!
+//! ```
+//! let start = Instant::now();
+//! loop {
+//!     match comm.next_event::<Instruction>() {
+//!         Event::Command(ins) => { ... }
+//!         _ => {
+//!             if start.elapsed() > Duration::from_secs(30) {
+//!                 return Err(AppError::Timeout);
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::io::TICKER_PERIOD_MS;
+pub use core::time::Duration;
+
+/// Returns the time elapsed since the application started, in milliseconds.
+pub fn uptime_ms() -> u64 {
+    ledger_secure_sdk_sys::seph::ticker_events() as u64 * TICKER_PERIOD_MS as u64
+}
+
+/// Returns the time elapsed since the application started.
+pub fn uptime() -> Duration {
+    Duration::from_millis(uptime_ms())
+}
+
+/// Point in time, measured from the start of the application.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant {
+    ms: u64,
+}
+
+impl Instant {
+    /// Returns the current time.
+    pub fn now() -> Instant {
+        Instant { ms: uptime_ms() }
+    }
+
+    /// Returns the time elapsed since `self`.
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+
+    /// Returns the time elapsed from `earlier` to `self`, or zero if
+    /// `earlier` is later than `self`.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_millis(self.ms.saturating_sub(earlier.ms))
+    }
+
+    /// Returns the instant `duration` after `self`, or `None` on overflow.
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        let ms = u64::try_from(duration.as_millis()).ok()?;
+        Some(Instant {
+            ms: self.ms.checked_add(ms)?,
+        })
+    }
+
+    /// Milliseconds elapsed from the start of the application to `self`.
+    pub fn as_millis(&self) -> u64 {
+        self.ms
+    }
+}
+
+impl core::ops::Add<Duration> for Instant {
+    type Output = Instant;
+
+    /// # Panics
+    ///
+    /// Panics on overflow, see [`Instant::checked_add`].
+    fn add(self, duration: Duration) -> Instant {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+impl core::ops::Sub for Instant {
+    type Output = Duration;
+
+    fn sub(self, earlier: Instant) -> Duration {
+        self.duration_since(earlier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn instant() {
+        let start = Instant { ms: 1000 };
+        let later = start + Duration::from_millis(2500);
+        assert_eq!(later.as_millis(), 3500);
+        assert_eq!(later - start, Duration::from_millis(2500));
+        assert_eq!(start - later, Duration::ZERO);
+        assert_eq!(start.checked_add(Duration::MAX), None);
+    }
+}
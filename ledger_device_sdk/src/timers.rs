@@ -0,0 +1,169 @@
+//! One-shot and periodic timers
+//!
+//! Timers count the ticker events sent by the MCU every
+//! [`TICKER_PERIOD_MS`](crate::io::TICKER_PERIOD_MS), whatever the period
+//! set with [`Comm::set_ticker_period`](crate::io::Comm::set_ticker_period),
+//! and their callbacks are called from [`Comm`](crate::io::Comm) while it
+//! processes the device events. They are meant for timeouts, animations and
+//! pages dismissed automatically, without counting ticker events in the
+//! application event loop.
+//!
+//! # Examples
+//!
+//! ```
+//! fn blink() {
+//!     LED.toggle();
+//! }
+//!
+//! fn timeout() {
+//!     status("Timeout", false);
+//! }
+//!
+//! let blinking = timers::start_periodic(5, blink).unwrap();
+//! let deadline = timers::start_oneshot(300, timeout).unwrap();
+//! ...
+//! timers::cancel(blinking);
+//! timers::cancel(deadline);
+//! ```
+
+/// Maximum number of timers running at the same time
+pub const MAX_TIMERS: usize = 8;
+
+/// Identifier of a running timer, returned when it is started.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TimerId(u32);
+
+#[derive(Copy, Clone)]
+struct Timer {
+    id: TimerId,
+    /// Ticker events before the next expiry
+    remaining: u32,
+    /// Period of a periodic timer, 0 for a one-shot timer
+    period: u32,
+    callback: fn(),
+}
+
+static mut TIMERS: [Option<Timer>; MAX_TIMERS] = [None; MAX_TIMERS];
+static mut NEXT_ID: u32 = 0;
+
+fn start(ticks: u32, period: u32, callback: fn()) -> Option<TimerId> {
+    unsafe {
+        let slot = (0..MAX_TIMERS).find(|&i| TIMERS[i].is_none())?;
+        let id = TimerId(NEXT_ID);
+        NEXT_ID = NEXT_ID.wrapping_add(1);
+        TIMERS[slot] = Some(Timer {
+            id,
+            remaining: ticks.max(1),
+            period,
+            callback,
+        });
+        Some(id)
+    }
+}
+
+/// Starts a timer calling `callback` once, after `ticks` ticker events.
+///
+/// Returns `None` if [`MAX_TIMERS`] timers are already running.
+pub fn start_oneshot(ticks: u32, callback: fn()) -> Option<TimerId> {
+    start(ticks, 0, callback)
+}
+
+/// Starts a timer calling `callback` every `period` ticker events, until it
+/// is cancelled.
+///
+/// Returns `None` if [`MAX_TIMERS`] timers are already running.
+pub fn start_periodic(period: u32, callback: fn()) -> Option<TimerId> {
+    start(period, period.max(1), callback)
+}
+
+/// Stops the timer `id`. Returns false if it was not running anymore, such
+/// as a one-shot timer which already expired.
+pub fn cancel(id: TimerId) -> bool {
+    unsafe {
+        for i in 0..MAX_TIMERS {
+            if TIMERS[i].is_some_and(|timer| timer.id == id) {
+                TIMERS[i] = None;
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Returns true if the timer `id` is running.
+pub fn is_running(id: TimerId) -> bool {
+    unsafe { (0..MAX_TIMERS).any(|i| TIMERS[i].is_some_and(|timer| timer.id == id)) }
+}
+
+/// Stops all the timers.
+pub fn cancel_all() {
+    unsafe {
+        TIMERS = [None; MAX_TIMERS];
+    }
+}
+
+/// Called on each ticker event, to call the callbacks of the expired timers.
+///
+/// The timers are updated before their callback is called, so that the
+/// callbacks can start and cancel timers, including their own.
+pub(crate) fn tick() {
+    for i in 0..MAX_TIMERS {
+        let callback = unsafe {
+            let Some(timer) = TIMERS[i].as_mut() else {
+                continue;
+            };
+            timer.remaining -= 1;
+            if timer.remaining > 0 {
+                continue;
+            }
+            let callback = timer.callback;
+            if timer.period > 0 {
+                timer.remaining = timer.period;
+            } else {
+                TIMERS[i] = None;
+            }
+            callback
+        };
+        callback();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    static mut ONESHOT_CALLS: u32 = 0;
+    static mut PERIODIC_CALLS: u32 = 0;
+
+    fn oneshot() {
+        unsafe { ONESHOT_CALLS += 1 }
+    }
+
+    fn periodic() {
+        unsafe { PERIODIC_CALLS += 1 }
+    }
+
+    #[test]
+    fn timers() {
+        cancel_all();
+        let oneshot_id = start_oneshot(2, oneshot).unwrap();
+        let periodic_id = start_periodic(3, periodic).unwrap();
+        for _ in 0..7 {
+            tick();
+        }
+        assert_eq!(unsafe { ONESHOT_CALLS }, 1);
+        assert_eq!(unsafe { PERIODIC_CALLS }, 2);
+        assert_eq!(is_running(oneshot_id), false);
+        assert_eq!(cancel(periodic_id), true);
+        assert_eq!(cancel(periodic_id), false);
+
+        for _ in 0..MAX_TIMERS {
+            assert_eq!(start_oneshot(1, oneshot).is_some(), true);
+        }
+        assert_eq!(start_oneshot(1, oneshot), None);
+        cancel_all();
+    }
+}
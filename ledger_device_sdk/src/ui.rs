@@ -13,14 +13,10 @@ pub mod fonts;
 pub mod layout;
 
 pub mod gadgets;
+pub mod gestures;
 pub mod screen_util;
 
 pub const PADDING: usize = 2;
 pub const Y_PADDING: usize = 3;
-pub const SCREEN_WIDTH: usize = 128;
-
-#[cfg(target_os = "nanos")]
-pub const SCREEN_HEIGHT: usize = 32;
-
-#[cfg(not(target_os = "nanos"))]
-pub const SCREEN_HEIGHT: usize = 64;
+pub const SCREEN_WIDTH: usize = crate::caps::SCREEN_WIDTH;
+pub const SCREEN_HEIGHT: usize = crate::caps::SCREEN_HEIGHT;
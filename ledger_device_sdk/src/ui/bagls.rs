@@ -8,6 +8,7 @@ pub mod se;
 #[cfg(not(target_os = "nanos"))]
 pub use self::se::*;
 
+use crate::ui::layout::Draw;
 use bitmaps::Glyph;
 
 pub struct RectFull {
@@ -38,6 +39,128 @@ impl RectFull {
     }
 }
 
+/// Horizontal progress bar: an outline filled from the left
+/// according to the progress of a long operation.
+///
+/// # Examples
+///
+/// ```
+/// let mut bar = ProgressBar::new().pos(14, 40);
+/// "Signing...".place(Location::Top, Layout::Centered, false);
+/// bar.instant_display();
+/// for (i, chunk) in chunks.iter().enumerate() {
+///     process(chunk);
+///     bar.update((100 * (i + 1) / chunks.len()) as u8);
+/// }
+/// ```
+pub struct ProgressBar {
+    pos: (i32, i32),
+    width: u32,
+    height: u32,
+    percent: u8,
+}
+
+impl ProgressBar {
+    pub const fn new() -> ProgressBar {
+        ProgressBar {
+            pos: (14, (crate::ui::SCREEN_HEIGHT as i32 - 8) / 2),
+            width: 100,
+            height: 8,
+            percent: 0,
+        }
+    }
+    pub const fn pos(self, x: i32, y: i32) -> ProgressBar {
+        ProgressBar {
+            pos: (x, y),
+            ..self
+        }
+    }
+    /// Sets the outer width of the bar, at least 5 pixels
+    pub const fn width(self, width: u32) -> ProgressBar {
+        ProgressBar {
+            width: if width < 5 { 5 } else { width },
+            ..self
+        }
+    }
+    /// Sets the outer height of the bar, at least 5 pixels
+    pub const fn height(self, height: u32) -> ProgressBar {
+        ProgressBar {
+            height: if height < 5 { 5 } else { height },
+            ..self
+        }
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    /// Area filled when the progress is complete, inside
+    /// the outline and a 1 pixel margin
+    fn inner(&self) -> RectFull {
+        RectFull::new()
+            .pos(self.pos.0 + 2, self.pos.1 + 2)
+            .width(self.width - 4)
+            .height(self.height - 4)
+    }
+
+    fn filled_width(&self, percent: u8) -> u32 {
+        (self.width - 4) * percent as u32 / 100
+    }
+
+    /// Sets the progress, capped to 100%, and redraws the
+    /// part of the bar which changed, without clearing the
+    /// rest of the screen
+    pub fn update(&mut self, percent: u8) {
+        let percent = percent.min(100);
+        let old = self.filled_width(self.percent);
+        let new = self.filled_width(percent);
+        self.percent = percent;
+        let inner = self.inner();
+        let changed = RectFull::new()
+            .pos(inner.pos.0 + old.min(new) as i32, inner.pos.1)
+            .width(old.abs_diff(new))
+            .height(inner.height);
+        if new > old {
+            changed.instant_display();
+        } else if new < old {
+            changed.instant_erase();
+        }
+    }
+}
+
+impl Draw for ProgressBar {
+    fn display(&self) {
+        let (x, y) = self.pos;
+        let (w, h) = (self.width, self.height);
+        // Outline
+        RectFull::new().pos(x, y).width(w).height(1).display();
+        RectFull::new()
+            .pos(x, y + h as i32 - 1)
+            .width(w)
+            .height(1)
+            .display();
+        RectFull::new().pos(x, y).width(1).height(h).display();
+        RectFull::new()
+            .pos(x + w as i32 - 1, y)
+            .width(1)
+            .height(h)
+            .display();
+        // Progress
+        let filled = self.filled_width(self.percent);
+        if filled > 0 {
+            self.inner().width(filled).display();
+        }
+    }
+
+    fn erase(&self) {
+        RectFull::new()
+            .pos(self.pos.0, self.pos.1)
+            .width(self.width)
+            .height(self.height)
+            .erase();
+    }
+}
+
 const fn middle_y(glyph: &Glyph) -> i16 {
     ((crate::ui::SCREEN_HEIGHT as u32 - glyph.height) / 2) as i16
 }
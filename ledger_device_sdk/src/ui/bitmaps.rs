@@ -1,6 +1,29 @@
 use crate::ui::screen_util::draw;
 use ledger_secure_sdk_sys;
 
+/// 1bpp bitmap, displayed with [`Glyph::draw`] or as an [`Icon`](crate::ui::bagls::Icon).
+///
+/// Pixels are stored row by row, 8 pixels per byte starting from the least
+/// significant bit, as generated by `include_gif!`.
+///
+/// The size of the bitmap is checked when the glyph is created: declaring
+/// glyphs as constants turns an inconsistent size into a compilation error.
+///
+/// # Examples
+///
+/// ```
+/// const COIN: Glyph = Glyph::from_include(include_gif!("coin.gif"));
+/// // 8x8 square outline
+/// const SQUARE: Glyph = Glyph::from_array(
+///     &[0xff, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0xff],
+///     8,
+///     8,
+/// );
+///
+/// // Logo on the left of an amount
+/// COIN.draw(2, 24);
+/// "1.5 COIN".place(Location::Middle, Layout::Custom(22), false);
+/// ```
 pub struct Glyph<'a> {
     pub bitmap: &'a [u8],
     pub width: u32,
@@ -8,8 +31,24 @@ pub struct Glyph<'a> {
     pub inverted: bool,
 }
 
+/// Panics if a `len` bytes bitmap does not hold a `width`x`height` glyph,
+/// or if the glyph does not fit on the screen.
+const fn check_size(len: usize, width: u32, height: u32) {
+    if width as usize > crate::ui::SCREEN_WIDTH || height as usize > crate::ui::SCREEN_HEIGHT {
+        panic!("Glyph larger than the screen");
+    }
+    if len < (width * height).div_ceil(8) as usize {
+        panic!("Glyph bitmap smaller than its size");
+    }
+}
+
 impl<'a> Glyph<'a> {
+    /// # Panics
+    ///
+    /// Panics if `bitmap` is smaller than `width`x`height` pixels, or if the
+    /// glyph is larger than the screen.
     pub const fn new(bitmap: &'a [u8], width: u32, height: u32) -> Glyph<'a> {
+        check_size(bitmap.len(), width, height);
         Glyph {
             bitmap,
             width,
@@ -17,13 +56,21 @@ impl<'a> Glyph<'a> {
             inverted: false,
         }
     }
+    /// Creates a glyph from a bitmap generated by `include_gif!`.
     pub const fn from_include(packed: (&'a [u8], u32, u32)) -> Glyph<'a> {
-        Glyph {
-            bitmap: packed.0,
-            width: packed.1,
-            height: packed.2,
-            inverted: false,
+        Glyph::new(packed.0, packed.1, packed.2)
+    }
+    /// Creates a glyph from a raw bitmap, whose size must be exactly the
+    /// number of bytes needed by `width`x`height` pixels.
+    pub const fn from_array<const N: usize>(
+        bitmap: &'a [u8; N],
+        width: u32,
+        height: u32,
+    ) -> Glyph<'a> {
+        if N != (width * height).div_ceil(8) as usize {
+            panic!("Glyph bitmap size does not match its dimensions");
         }
+        Glyph::new(bitmap, width, height)
     }
     pub const fn invert(self) -> Glyph<'a> {
         Glyph {
@@ -73,3 +120,8 @@ pub const EYE: Glyph = Glyph::from_include(include_gif!("icons/icon_eye.gif"));
 pub const PROCESSING: Glyph = Glyph::from_include(include_gif!("icons/icon_processing.gif"));
 pub const VALIDATE_14: Glyph = Glyph::from_include(include_gif!("icons/icon_validate_14.gif"));
 pub const WARNING: Glyph = Glyph::from_include(include_gif!("icons/icon_warning.gif"));
+/// Bluetooth rune, displayed by [`draw_status_icons`](crate::ui::gadgets::draw_status_icons)
+pub const BLE: Glyph = Glyph::from_array(&[0x08, 0x18, 0x2a, 0x1c, 0x1c, 0x2a, 0x18, 0x08], 8, 8);
+/// Lightning bolt, displayed by [`draw_status_icons`](crate::ui::gadgets::draw_status_icons)
+pub const CHARGING: Glyph =
+    Glyph::from_array(&[0x20, 0x10, 0x08, 0x3c, 0x10, 0x08, 0x04, 0x00], 8, 8);
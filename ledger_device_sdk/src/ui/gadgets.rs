@@ -1,21 +1,26 @@
 use crate::{
     buttons::ButtonEvent::*,
     io::{self, ApduHeader, Comm, Event, Reply},
+    screen::KeepAwake,
     uxapp::{UxEvent, BOLOS_UX_OK},
 };
 use ledger_secure_sdk_sys::{
     buttons::{get_button_event, ButtonEvent, ButtonsState},
-    seph,
+    seph, SEPROXYHAL_TAG_BUTTON_PUSH_EVENT, SEPROXYHAL_TAG_TICKER_EVENT,
 };
 
-use crate::ui::bitmaps::{Glyph, WARNING};
+use crate::ui::bitmaps::{
+    Glyph, BACK, BLE, CHARGING, CROSSMARK, EYE, PROCESSING, VALIDATE_14, WARNING,
+};
 
 use crate::ui::{bagls::*, fonts::OPEN_SANS};
 
 use crate::ui::layout;
 use crate::ui::layout::{Draw, Location, StringPlace};
 
+use crate::display::HexFormat;
 use numtoa::NumToA;
+use zeroize::Zeroize;
 
 const MAX_CHAR_PER_LINE: usize = 17;
 
@@ -34,9 +39,45 @@ pub fn get_event(buttons: &mut ButtonsState) -> Option<ButtonEvent> {
 
         // button push event
         if tag == 0x05 {
+            crate::inactivity::activity();
             let button_info = buttons.cmd_buffer[3] >> 1;
             return get_button_event(buttons, button_info);
         }
+        if tag as u32 == SEPROXYHAL_TAG_TICKER_EVENT {
+            crate::inactivity::tick();
+            if crate::inactivity::dismissing() {
+                return None;
+            }
+        }
+    }
+    None
+}
+
+/// Event returned by [`get_timed_event`]
+#[derive(Eq, PartialEq)]
+pub enum TimedEvent {
+    Button(ButtonEvent),
+    /// Ticker event, received every [`io::TICKER_PERIOD_MS`] milliseconds
+    Ticker,
+}
+
+/// Same as [`get_event`], but also reports the ticker
+/// events, to animate the screen or measure durations
+pub fn get_timed_event(buttons: &mut ButtonsState) -> Option<TimedEvent> {
+    if !seph::is_status_sent() {
+        seph::send_general_status();
+    }
+
+    while seph::is_status_sent() {
+        seph::seph_recv(&mut buttons.cmd_buffer, 0);
+        match buttons.cmd_buffer[0] as u32 {
+            SEPROXYHAL_TAG_BUTTON_PUSH_EVENT => {
+                let button_info = buttons.cmd_buffer[3] >> 1;
+                return get_button_event(buttons, button_info).map(TimedEvent::Button);
+            }
+            SEPROXYHAL_TAG_TICKER_EVENT => return Some(TimedEvent::Ticker),
+            _ => (),
+        }
     }
     None
 }
@@ -63,6 +104,24 @@ pub fn clear_screen() {
     BLANK.paint();
 }
 
+/// Draws the charging and Bluetooth icons in the top right
+/// corner of the screen, according to the last reported
+/// [`device_status`](crate::power::device_status), as the
+/// dashboard does. The screen is not updated.
+pub fn draw_status_icons() {
+    let Some(status) = crate::power::device_status() else {
+        return;
+    };
+    let mut x = crate::ui::SCREEN_WIDTH as i32 - 2;
+    for (shown, glyph) in [(status.charging, &CHARGING), (status.ble_enabled, &BLE)] {
+        if shown {
+            x -= glyph.width as i32;
+            glyph.draw(x, 1);
+            x -= 2;
+        }
+    }
+}
+
 /// Display a developer mode / pending review popup, cleared with user interaction.
 ///
 /// This method must be called by an application at the very beginning until it has been reviewed
@@ -119,6 +178,52 @@ pub fn popup(message: &str) {
     SingleMessage::new(message).show_and_wait();
 }
 
+/// Guard keeping the screen awake while the processing screen is displayed
+static mut PROCESSING_AWAKE: Option<KeepAwake> = None;
+
+/// Displays a "Processing..." screen, to be called before a
+/// long computation such as signing, so that the last review
+/// page does not remain visible meanwhile.
+///
+/// The OS inactivity timer is reset, and kept reset as long
+/// as IO events are processed, until [`hide_processing`] is
+/// called.
+///
+/// # Examples
+///
+/// ```
+/// if Review::new().show(&fields) {
+///     show_processing();
+///     let signature = sign(&tx);
+///     hide_processing();
+///     comm.append(&signature);
+/// }
+/// ```
+pub fn show_processing() {
+    clear_screen();
+    Page::new(
+        PageStyle::PictureBold,
+        ["Processing...", ""],
+        Some(&PROCESSING),
+    )
+    .place();
+    crate::ui::screen_util::screen_update();
+    UxEvent::WakeUp.request();
+    unsafe {
+        PROCESSING_AWAKE = Some(KeepAwake::new());
+    }
+}
+
+/// Clears the screen displayed by [`show_processing`], and
+/// restores the usual inactivity behavior.
+pub fn hide_processing() {
+    unsafe {
+        PROCESSING_AWAKE = None;
+    }
+    clear_screen();
+    crate::ui::screen_util::screen_update();
+}
+
 /// Display a single screen with a message,
 /// and exit the function with 'true'
 /// if the user validated 'message'
@@ -503,6 +608,7 @@ impl<'a> Page<'a> {
                 Some(ButtonEvent::LeftButtonRelease)
                 | Some(ButtonEvent::RightButtonRelease)
                 | Some(ButtonEvent::BothButtonsRelease) => return,
+                None if crate::inactivity::dismissing() => return,
                 _ => (),
             }
         }
@@ -589,6 +695,196 @@ impl<'a> MultiPageMenu<'a> {
                         return EventOrPageIndex::Event(io::Event::Ticker);
                     }
                 }
+                io::Event::Usb(_) | io::Event::Language(_) | io::Event::BatteryCritical => (),
+            };
+        }
+    }
+}
+
+/// Maximum number of nested levels of a [`NestedMenu`], root menu included.
+pub const MAX_MENU_DEPTH: usize = 4;
+
+/// Entry of a [`NestedMenu`].
+#[derive(Copy, Clone)]
+pub enum MenuItem<'a> {
+    /// Runs the callback when selected, then displays the menu again.
+    Action(Page<'a>, fn()),
+    /// Opens a submenu when selected. A "Back" entry, returning to the parent
+    /// menu, is added at the end of the submenu.
+    Submenu(Page<'a>, &'a [MenuItem<'a>]),
+}
+
+impl<'a> MenuItem<'a> {
+    fn page(&self) -> &Page<'a> {
+        match self {
+            MenuItem::Action(page, _) | MenuItem::Submenu(page, _) => page,
+        }
+    }
+}
+
+/// Menu made of nested submenus, such as the home menu of an application
+/// with "Settings > Display > ..." entries.
+///
+/// The menu tree is declared once, and navigation between the submenus is
+/// handled by the menu itself: apps only provide the callbacks of the entries.
+///
+/// # Examples
+///
+/// ```
+/// static DISPLAY: [MenuItem; 2] = [
+///     MenuItem::Action(Page::new(PageStyle::Normal, ["Contrast", ""], None), set_contrast),
+///     MenuItem::Action(Page::new(PageStyle::Normal, ["Flip screen", ""], None), flip),
+/// ];
+/// static SETTINGS: [MenuItem; 1] = [MenuItem::Submenu(
+///     Page::new(PageStyle::Normal, ["Display", ""], None),
+///     &DISPLAY,
+/// )];
+/// static HOME: [MenuItem; 2] = [
+///     MenuItem::Submenu(Page::new(PageStyle::PictureBold, ["Settings", ""], Some(&COGGLE)), &SETTINGS),
+///     MenuItem::Action(Page::new(PageStyle::PictureBold, ["Quit", ""], Some(&DASHBOARD)), quit),
+/// ];
+///
+/// let mut menu = NestedMenu::new(&mut comm, &HOME);
+/// loop {
+///     if let io::Event::Command(ins) = menu.show::<Instruction>() {
+///         // handle the APDU, the menu resumes where it was on the next call
+///     }
+/// }
+/// ```
+pub struct NestedMenu<'a> {
+    comm: &'a mut io::Comm,
+    root: &'a [MenuItem<'a>],
+    back: Page<'a>,
+    status_icons: bool,
+    /// Selected index in each open menu, from the root menu
+    path: [usize; MAX_MENU_DEPTH],
+    depth: usize,
+}
+
+impl<'a> NestedMenu<'a> {
+    pub fn new(comm: &'a mut io::Comm, root: &'a [MenuItem<'a>]) -> Self {
+        NestedMenu {
+            comm,
+            root,
+            back: Page::new(PageStyle::PictureBold, ["Back", ""], Some(&BACK)),
+            status_icons: false,
+            path: [0; MAX_MENU_DEPTH],
+            depth: 0,
+        }
+    }
+
+    /// Sets the page of the "Back" entries added to the submenus.
+    pub fn back_page(self, back: Page<'a>) -> Self {
+        NestedMenu { back, ..self }
+    }
+
+    /// Displays the charging and Bluetooth icons on all the
+    /// pages, see [`draw_status_icons`].
+    pub fn status_icons(self) -> Self {
+        NestedMenu {
+            status_icons: true,
+            ..self
+        }
+    }
+
+    /// Entries of the displayed menu.
+    fn items(&self) -> &'a [MenuItem<'a>] {
+        let mut items = self.root;
+        for &index in &self.path[..self.depth] {
+            if let MenuItem::Submenu(_, submenu) = items[index] {
+                items = submenu;
+            }
+        }
+        items
+    }
+
+    /// Number of pages of the displayed menu, including its "Back" entry.
+    fn page_count(&self) -> usize {
+        self.items().len() + if self.depth > 0 { 1 } else { 0 }
+    }
+
+    fn draw(&self) {
+        let items = self.items();
+        let index = self.path[self.depth];
+        clear_screen();
+        match items.get(index) {
+            Some(item) => item.page().place(),
+            None => self.back.place(),
+        }
+        LEFT_ARROW.display();
+        RIGHT_ARROW.display();
+        if self.status_icons {
+            draw_status_icons();
+        }
+        crate::ui::screen_util::screen_update();
+    }
+
+    /// Handles the selection of the displayed entry.
+    fn select(&mut self) {
+        let items = self.items();
+        match items.get(self.path[self.depth]) {
+            Some(MenuItem::Action(_, action)) => action(),
+            Some(MenuItem::Submenu(..)) => {
+                if self.depth + 1 >= MAX_MENU_DEPTH {
+                    panic!("Menu nested too deeply.");
+                }
+                self.depth += 1;
+                self.path[self.depth] = 0;
+            }
+            // "Back" entry
+            None => self.depth -= 1,
+        }
+    }
+
+    /// Displays the menu and handles the navigation and the callbacks of the
+    /// entries, until an APDU is received or the screen must be redisplayed
+    /// after a pin lock.
+    ///
+    /// The menu keeps its position: calling `show` again displays the same
+    /// entry of the same submenu.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the root menu is empty, or if the menu has more than
+    /// [`MAX_MENU_DEPTH`] nested levels.
+    pub fn show<T: TryFrom<ApduHeader>>(&mut self) -> io::Event<T>
+    where
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        self.draw();
+
+        loop {
+            match self.comm.next_event() {
+                io::Event::Button(button) => {
+                    let page_count = self.page_count();
+                    let index = &mut self.path[self.depth];
+                    match button {
+                        LeftButtonRelease => *index = (*index + page_count - 1) % page_count,
+                        RightButtonRelease => *index = (*index + 1) % page_count,
+                        BothButtonsRelease => self.select(),
+                        _ => continue,
+                    }
+                    self.draw();
+                }
+                io::Event::Command(ins) => return io::Event::Command(ins),
+                io::Event::Ticker => {
+                    if UxEvent::Event.request() != BOLOS_UX_OK {
+                        // pin lock management
+                        let (_res, ins) = UxEvent::block_and_get_event::<Temp>(self.comm);
+                        if let Some(_e) = ins {
+                            self.comm.reply::<io::StatusWords>(io::StatusWords::Unknown);
+                        }
+                        // notify Ticker event only when redisplay is required
+                        return io::Event::Ticker;
+                    }
+                }
+                // Redraw the status icons when the USB power changes
+                io::Event::Usb(_) => {
+                    if self.status_icons {
+                        self.draw();
+                    }
+                }
+                io::Event::Language(_) | io::Event::BatteryCritical => (),
             };
         }
     }
@@ -635,33 +931,107 @@ impl<'a> SingleMessage<'a> {
 /// over several panes in chunks
 /// of MAX_CHAR_PER_LINE characters.
 /// Press both buttons to exit.
+///
+/// With [`MessageScroller::speed`], the message scrolls
+/// automatically, one character at a time, pausing at
+/// both ends. Pressing the left or right button then
+/// pauses or resumes the scrolling.
+///
+/// # Examples
+///
+/// ```
+/// // Scroll an address one character every 300 ms,
+/// // pausing 2 s at both ends
+/// MessageScroller::new(address)
+///     .speed(3)
+///     .pause_at_ends(20)
+///     .event_loop();
+/// ```
 pub struct MessageScroller<'a> {
     message: &'a str,
+    chunk_width: usize,
+    speed: u32,
+    pause_at_ends: u32,
 }
 
 impl<'a> MessageScroller<'a> {
     pub fn new(message: &'a str) -> Self {
-        MessageScroller { message }
+        MessageScroller {
+            message,
+            chunk_width: MAX_CHAR_PER_LINE,
+            speed: 0,
+            pause_at_ends: 10,
+        }
+    }
+
+    /// Sets the number of characters displayed at once,
+    /// MAX_CHAR_PER_LINE by default.
+    pub fn chunk_width(self, chunk_width: usize) -> Self {
+        MessageScroller {
+            chunk_width: chunk_width.clamp(1, MAX_CHAR_PER_LINE),
+            ..self
+        }
+    }
+
+    /// Scrolls the message automatically, by one character
+    /// every `ticks` ticker events (100 ms each).
+    /// 0, the default, lets the user scroll pane by pane.
+    pub fn speed(self, ticks: u32) -> Self {
+        MessageScroller {
+            speed: ticks,
+            ..self
+        }
+    }
+
+    /// Sets the number of ticker events (100 ms each) the
+    /// automatic scrolling pauses at both ends of the
+    /// message, 10 by default.
+    pub fn pause_at_ends(self, ticks: u32) -> Self {
+        MessageScroller {
+            pause_at_ends: ticks,
+            ..self
+        }
     }
 
     pub fn event_loop(&self) {
-        clear_screen();
-        let mut buttons = ButtonsState::new();
-        let page_count = (self.message.len() - 1) / MAX_CHAR_PER_LINE + 1;
-        if page_count == 0 {
+        if self.message.is_empty() {
             return;
         }
+        clear_screen();
+        if self.speed > 0 {
+            self.auto_scroll_loop();
+        } else {
+            self.manual_scroll_loop();
+        }
+    }
+
+    /// Returns the `chunk_width` characters of the message starting at the
+    /// character `start`, cut on character boundaries so that non-ASCII
+    /// messages are displayed too.
+    fn chunk(&self, start: usize) -> &'a str {
+        let mut bounds = self
+            .message
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(Some(self.message.len()));
+        let begin = bounds.nth(start).unwrap_or(self.message.len());
+        let end = bounds
+            .nth(self.chunk_width - 1)
+            .unwrap_or(self.message.len());
+        &self.message[begin..end]
+    }
+
+    fn manual_scroll_loop(&self) {
+        let mut buttons = ButtonsState::new();
+        let page_count = self.message.chars().count().div_ceil(self.chunk_width);
         let mut label = Label::from("");
         let mut cur_page = 0;
 
         // A closure to draw common elements of the screen
         // cur_page passed as parameter to prevent borrowing
         let mut draw = |page: usize| {
-            let start = page * MAX_CHAR_PER_LINE;
-            let end = (start + MAX_CHAR_PER_LINE).min(self.message.len());
-            let chunk = &self.message[start..end];
             label.erase();
-            label.text = chunk;
+            label.text = self.chunk(page * self.chunk_width);
             LEFT_ARROW.erase();
             RIGHT_ARROW.erase();
             if page > 0 {
@@ -702,13 +1072,345 @@ impl<'a> MessageScroller<'a> {
             }
         }
     }
+
+    fn auto_scroll_loop(&self) {
+        let mut buttons = ButtonsState::new();
+        let last_offset = self
+            .message
+            .chars()
+            .count()
+            .saturating_sub(self.chunk_width);
+        let mut label = Label::from(self.chunk(0));
+        let mut offset = 0;
+        let mut ticks = 0;
+        let mut paused = false;
+
+        label.instant_display();
+
+        loop {
+            match get_timed_event(&mut buttons) {
+                Some(TimedEvent::Ticker) if !paused && last_offset > 0 => {
+                    ticks += 1;
+                    let delay = if offset == 0 || offset == last_offset {
+                        self.pause_at_ends
+                    } else {
+                        self.speed
+                    };
+                    if ticks >= delay {
+                        ticks = 0;
+                        // Restart from the beginning once the end has been displayed
+                        offset = if offset == last_offset { 0 } else { offset + 1 };
+                        label.erase();
+                        label.text = self.chunk(offset);
+                        label.instant_display();
+                    }
+                }
+                Some(TimedEvent::Button(ButtonEvent::LeftButtonRelease))
+                | Some(TimedEvent::Button(ButtonEvent::RightButtonRelease)) => {
+                    paused = !paused;
+                }
+                Some(TimedEvent::Button(ButtonEvent::BothButtonsRelease)) => break,
+                Some(_) | None => (),
+            }
+        }
+    }
 }
 
-pub struct Field<'a> {
-    pub name: &'a str,
-    pub value: &'a str,
+/// Maximum length of a [`TextInput`] entry, in bytes
+pub const TEXT_INPUT_MAX_LEN: usize = 64;
+
+/// Lowercase letters and digits, the default [`TextInput`] characters
+pub const LOWERCASE_DIGITS: &str = "abcdefghijklmnopqrstuvwxyz0123456789";
+/// All the printable ASCII characters, for passphrases
+pub const PRINTABLE: &str = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+/// Text entered with a [`TextInput`].
+/// The text is erased from memory when the entry is dropped.
+pub struct TextEntry {
+    text: [u8; TEXT_INPUT_MAX_LEN],
+    len: usize,
 }
 
+impl TextEntry {
+    pub fn as_str(&self) -> &str {
+        // Only ASCII characters are entered
+        unsafe { core::str::from_utf8_unchecked(&self.text[..self.len]) }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.text[..self.len]
+    }
+}
+
+/// Cleanup text from memory when dropping this structure.
+impl Drop for TextEntry {
+    #[inline(never)]
+    fn drop(&mut self) {
+        self.text.zeroize();
+        self.len = 0;
+    }
+}
+
+/// A character picker to enter a text, such as a passphrase
+/// or a label, with the buttons.
+///
+/// The left and right buttons select a character, and pressing
+/// both buttons appends it to the text. The "Delete" and "Done"
+/// entries, after the characters, remove the last character and
+/// confirm the text. "Delete" becomes "Cancel" when the text is
+/// empty.
+///
+/// # Examples
+///
+/// ```
+/// let passphrase = TextInput::new("Passphrase")
+///     .charset(PRINTABLE)
+///     .masked()
+///     .show();
+/// if let Some(passphrase) = passphrase {
+///     derive_with_passphrase(passphrase.as_bytes());
+/// }
+/// ```
+pub struct TextInput<'a> {
+    title: &'a str,
+    charset: &'a str,
+    max_len: usize,
+    masked: bool,
+}
+
+impl<'a> TextInput<'a> {
+    pub const fn new(title: &'a str) -> Self {
+        TextInput {
+            title,
+            charset: LOWERCASE_DIGITS,
+            max_len: TEXT_INPUT_MAX_LEN,
+            masked: false,
+        }
+    }
+
+    /// Sets the characters which can be entered, in the order they
+    /// are picked. Only ASCII characters are supported.
+    pub const fn charset(self, charset: &'a str) -> Self {
+        TextInput { charset, ..self }
+    }
+
+    /// Sets the maximum length of the text, at most [`TEXT_INPUT_MAX_LEN`].
+    pub const fn max_len(self, max_len: usize) -> Self {
+        TextInput {
+            max_len: if max_len < TEXT_INPUT_MAX_LEN {
+                max_len
+            } else {
+                TEXT_INPUT_MAX_LEN
+            },
+            ..self
+        }
+    }
+
+    /// Displays the entered characters as '*'.
+    pub const fn masked(self) -> Self {
+        TextInput {
+            masked: true,
+            ..self
+        }
+    }
+
+    /// Displays the input until the user confirms the text, or
+    /// cancels the entry, in which case `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the charset contains non ASCII characters.
+    pub fn show(&self) -> Option<TextEntry> {
+        if !self.charset.is_ascii() {
+            panic!("Non ASCII charset.");
+        }
+        const STARS: &str = "*****************";
+        let delete = self.charset.len();
+        let done = delete + 1;
+        let choice_count = done + 1;
+
+        let mut entry = TextEntry {
+            text: [0; TEXT_INPUT_MAX_LEN],
+            len: 0,
+        };
+        let mut index = 0;
+
+        let draw = |entry: &TextEntry, index: usize| {
+            clear_screen();
+            let text = if entry.len == 0 {
+                self.title
+            } else if self.masked {
+                &STARS[..entry.len.min(MAX_CHAR_PER_LINE)]
+            } else {
+                // Only the end of the text fits on the screen
+                let start = entry.len.saturating_sub(MAX_CHAR_PER_LINE);
+                &entry.as_str()[start..]
+            };
+            text.place(Location::Top, Layout::Centered, false);
+            let choice = match index {
+                i if i == delete && entry.len == 0 => "Cancel",
+                i if i == delete => "Delete",
+                i if i == done => "Done",
+                i => &self.charset[i..i + 1],
+            };
+            choice.place(Location::Bottom, Layout::Centered, true);
+            LEFT_ARROW.display();
+            RIGHT_ARROW.display();
+            crate::ui::screen_util::screen_update();
+        };
+
+        draw(&entry, index);
+
+        let mut buttons = ButtonsState::new();
+        loop {
+            match get_event(&mut buttons) {
+                Some(ButtonEvent::LeftButtonPress) => {
+                    LEFT_S_ARROW.instant_display();
+                }
+                Some(ButtonEvent::RightButtonPress) => {
+                    RIGHT_S_ARROW.instant_display();
+                }
+                Some(ButtonEvent::LeftButtonRelease) => {
+                    index = (index + choice_count - 1) % choice_count;
+                }
+                Some(ButtonEvent::RightButtonRelease) => {
+                    index = (index + 1) % choice_count;
+                }
+                Some(ButtonEvent::BothButtonsRelease) => match index {
+                    i if i == delete => {
+                        if entry.len == 0 {
+                            return None;
+                        }
+                        entry.len -= 1;
+                        entry.text[entry.len] = 0;
+                    }
+                    i if i == done => {
+                        if entry.len > 0 {
+                            return Some(entry);
+                        }
+                    }
+                    i => {
+                        if entry.len < self.max_len {
+                            entry.text[entry.len] = self.charset.as_bytes()[i];
+                            entry.len += 1;
+                        }
+                    }
+                },
+                _ => continue,
+            }
+            draw(&entry, index);
+        }
+    }
+}
+
+/// Number of bytes displayed on each line of a [`HexViewer`]
+const HEX_BYTES_PER_LINE: usize = MAX_CHAR_PER_LINE / 2;
+
+/// A viewer displaying a byte buffer of any length, such as a
+/// hash or calldata, as hexadecimal over several pages with a
+/// "`title` (x/y)" header.
+/// Left and right buttons change the page, and pressing both
+/// buttons exits.
+///
+/// # Examples
+///
+/// ```
+/// HexViewer::new("Calldata", &tx.data).show();
+/// ```
+pub struct HexViewer<'a> {
+    title: &'a str,
+    data: &'a [u8],
+}
+
+impl<'a> HexViewer<'a> {
+    pub const fn new(title: &'a str, data: &'a [u8]) -> Self {
+        HexViewer { title, data }
+    }
+
+    /// Number of bytes displayed on each page
+    const fn bytes_per_page() -> usize {
+        HEX_BYTES_PER_LINE * (layout::MAX_LINES - 1)
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.data.len().div_ceil(Self::bytes_per_page()).max(1)
+    }
+
+    pub fn show(&self) {
+        let page_count = self.page_count();
+        let format = HexFormat::new().uppercase();
+
+        let draw = |page: usize| {
+            clear_screen();
+            let mut lines = [Label::default(); layout::MAX_LINES];
+            let mut line_bufs = [[0u8; 2 * HEX_BYTES_PER_LINE]; layout::MAX_LINES - 1];
+            let start = page * Self::bytes_per_page();
+            let end = (start + Self::bytes_per_page()).min(self.data.len());
+            for ((line, buf), chunk) in lines[1..]
+                .iter_mut()
+                .zip(line_bufs.iter_mut())
+                .zip(self.data[start..end].chunks(HEX_BYTES_PER_LINE))
+            {
+                *line = Label::from(format.format(chunk, buf).unwrap_or(""));
+            }
+
+            let mut header_buf = [b' '; MAX_CHAR_PER_LINE + 12];
+            let mut buf_page = [0u8; 5];
+            let mut buf_count = [0u8; 5];
+            let page_str = (page + 1).numtoa_str(10, &mut buf_page);
+            let count_str = page_count.numtoa_str(10, &mut buf_count);
+            concatenate(
+                &[self.title, " (", page_str, "/", count_str, ")"],
+                &mut header_buf,
+            );
+            let header = core::str::from_utf8(&header_buf)
+                .unwrap_or("")
+                .trim_end_matches(' ');
+            lines[0] = Label::from(header).bold();
+
+            if page > 0 {
+                LEFT_ARROW.display();
+            }
+            if page + 1 < page_count {
+                RIGHT_ARROW.display();
+            }
+            lines.place(Location::Middle, Layout::Centered, false);
+            crate::ui::screen_util::screen_update();
+        };
+
+        let mut cur_page = 0;
+        draw(cur_page);
+
+        let mut buttons = ButtonsState::new();
+        loop {
+            match get_event(&mut buttons) {
+                Some(ButtonEvent::LeftButtonPress) => {
+                    LEFT_S_ARROW.instant_display();
+                }
+                Some(ButtonEvent::RightButtonPress) => {
+                    RIGHT_S_ARROW.instant_display();
+                }
+                Some(ButtonEvent::LeftButtonRelease) => {
+                    cur_page = cur_page.saturating_sub(1);
+                    // We need to draw anyway to clear button press arrow
+                    draw(cur_page);
+                }
+                Some(ButtonEvent::RightButtonRelease) => {
+                    if cur_page + 1 < page_count {
+                        cur_page += 1;
+                    }
+                    // We need to draw anyway to clear button press arrow
+                    draw(cur_page);
+                }
+                Some(ButtonEvent::BothButtonsRelease) => return,
+                _ => (),
+            }
+        }
+    }
+}
+
+pub use crate::display::Field;
+
 impl<'a> Field<'a> {
     pub fn event_loop(&self, incoming_direction: ButtonEvent) -> ButtonEvent {
         let mut buttons = ButtonsState::new();
@@ -794,6 +1496,7 @@ impl<'a> Field<'a> {
                     }
                     draw(cur_page);
                 }
+                None if crate::inactivity::dismissing() => return ButtonEvent::BothButtonsRelease,
                 Some(_) | None => (),
             }
         }
@@ -849,7 +1552,14 @@ impl<'a> MultiFieldReview<'a> {
         }
     }
 
+    /// Displays the review, and returns true if the user approved it. The
+    /// review is dismissed as rejected after the delay set with
+    /// [`inactivity::enable`](crate::inactivity::enable).
     pub fn show(&self) -> bool {
+        crate::inactivity::review(|| self.show_pages())
+    }
+
+    fn show_pages(&self) -> bool {
         let first_page = match self.review_message.len() {
             0 => Page::new(PageStyle::PictureNormal, ["", ""], self.review_glyph),
             1 => Page::new(
@@ -866,6 +1576,9 @@ impl<'a> MultiFieldReview<'a> {
 
         clear_screen();
         first_page.place_and_wait();
+        if crate::inactivity::dismissing() {
+            return false;
+        }
         crate::ui::screen_util::screen_update();
 
         let validation_page = Page::new(
@@ -900,6 +1613,7 @@ impl<'a> MultiFieldReview<'a> {
                                 break;
                             }
                             Some(ButtonEvent::BothButtonsRelease) => return false,
+                            None if crate::inactivity::dismissing() => return false,
                             _ => (),
                         }
                     }
@@ -916,12 +1630,16 @@ impl<'a> MultiFieldReview<'a> {
                                 break;
                             }
                             Some(ButtonEvent::BothButtonsRelease) => return true,
+                            None if crate::inactivity::dismissing() => return false,
                             _ => (),
                         }
                     }
                 }
                 _ => {
                     direction = self.fields[cur_page].event_loop(direction);
+                    if crate::inactivity::dismissing() {
+                        return false;
+                    }
                     match direction {
                         ButtonEvent::LeftButtonRelease => {
                             if cur_page == 0 {
@@ -940,3 +1658,96 @@ impl<'a> MultiFieldReview<'a> {
         }
     }
 }
+
+/// Builder of transaction reviews, equivalent to `NbglReview` on Nano
+/// devices, and taking the same [`Field`] values so that apps can describe
+/// their reviews once for all devices.
+///
+/// The review is made of a first page with the glyph and titles, the fields
+/// as tag/value pages navigated with the left and right buttons, and the
+/// reject and approve pages, selected by pressing both buttons.
+///
+/// # Examples
+///
+/// ```
+/// let fields = [
+///     Field { name: "Amount", value: amount.as_str() },
+///     Field { name: "To", value: destination.as_str() },
+/// ];
+/// let approved = Review::new()
+///     .titles("Review", "transaction", "Sign transaction")
+///     .glyph(&CRAB)
+///     .show(&fields);
+/// ```
+pub struct Review<'a> {
+    title: &'a str,
+    subtitle: &'a str,
+    finish_title: &'a str,
+    reject_title: &'a str,
+    glyph: Option<&'a Glyph<'a>>,
+}
+
+impl<'a> Review<'a> {
+    pub const fn new() -> Self {
+        Review {
+            title: "Review",
+            subtitle: "transaction",
+            finish_title: "Approve",
+            reject_title: "Reject",
+            glyph: Some(&EYE),
+        }
+    }
+
+    /// Sets the titles of the first page and the title of the approve page.
+    /// An empty `subtitle` displays the title alone on the first page.
+    pub const fn titles(self, title: &'a str, subtitle: &'a str, finish_title: &'a str) -> Self {
+        Review {
+            title,
+            subtitle,
+            finish_title,
+            ..self
+        }
+    }
+
+    /// Sets the title of the reject page, "Reject" by default.
+    pub const fn reject_title(self, reject_title: &'a str) -> Self {
+        Review {
+            reject_title,
+            ..self
+        }
+    }
+
+    /// Sets the glyph of the first page, an eye by default.
+    pub const fn glyph(self, glyph: &'a Glyph<'a>) -> Self {
+        Review {
+            glyph: Some(glyph),
+            ..self
+        }
+    }
+
+    /// Displays the review of `fields`, and returns true if the user approved it.
+    pub fn show(&self, fields: &[Field]) -> bool {
+        let titles = [self.title, self.subtitle];
+        let review_message = if self.subtitle.is_empty() {
+            &titles[..1]
+        } else {
+            &titles[..]
+        };
+        MultiFieldReview::new(
+            fields,
+            review_message,
+            self.glyph,
+            self.finish_title,
+            Some(&VALIDATE_14),
+            self.reject_title,
+            Some(&CROSSMARK),
+        )
+        .show()
+    }
+}
+
+impl Default for Review<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,238 @@
+//! Decoding of long presses and double clicks
+//!
+//! [`GestureDecoder`] turns the button and ticker events into gestures,
+//! measuring durations in ticker events (100 ms each), to build interactions
+//! such as "hold both buttons to confirm".
+//!
+//! # Examples
+//!
+//! ```
+//! let mut decoder = GestureDecoder::new().long_press_ticks(20);
+//! loop {
+//!     match decoder.next_gesture() {
+//!         Some(Gesture::LongPress(Button::Both)) => return true,
+//!         Some(Gesture::Click(Button::Left)) => return false,
+//!         _ => (),
+//!     }
+//! }
+//! ```
+
+use crate::ui::gadgets::{get_timed_event, TimedEvent};
+use ledger_secure_sdk_sys::buttons::{ButtonEvent, ButtonsState};
+
+/// Default duration of a long press, in ticker events
+pub const LONG_PRESS_TICKS: u32 = 10;
+
+/// Button, or pair of buttons, a [`Gesture`] is made with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Button {
+    Left,
+    Right,
+    Both,
+}
+
+/// Gesture decoded by [`GestureDecoder`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Gesture {
+    /// The button has just been pressed, to draw the pressed state.
+    Press(Button),
+    /// The button was pressed and released, and not clicked again in time
+    /// when double clicks are detected.
+    Click(Button),
+    /// The button was clicked twice in a row.
+    DoubleClick(Button),
+    /// The button has been held for the long press duration. It is reported
+    /// once, while the button is still held, and its release is ignored.
+    LongPress(Button),
+}
+
+/// Decoder of the button gestures.
+pub struct GestureDecoder {
+    buttons: ButtonsState,
+    long_press_ticks: u32,
+    double_click_ticks: u32,
+    /// Button held, and ticks elapsed since it was pressed
+    held: Option<(Button, u32)>,
+    long_press_reported: bool,
+    /// Button clicked once, and ticks elapsed since it was released
+    clicked: Option<(Button, u32)>,
+}
+
+impl GestureDecoder {
+    /// Creates a decoder reporting long presses after [`LONG_PRESS_TICKS`]
+    /// ticker events, and not detecting double clicks.
+    pub const fn new() -> Self {
+        GestureDecoder {
+            buttons: ButtonsState::new(),
+            long_press_ticks: LONG_PRESS_TICKS,
+            double_click_ticks: 0,
+            held: None,
+            long_press_reported: false,
+            clicked: None,
+        }
+    }
+
+    /// Sets the number of ticker events a button must be held to report a
+    /// long press. 0 disables the detection of long presses.
+    pub const fn long_press_ticks(self, ticks: u32) -> Self {
+        GestureDecoder {
+            long_press_ticks: ticks,
+            ..self
+        }
+    }
+
+    /// Enables the detection of double clicks, made of two clicks separated
+    /// by less than `ticks` ticker events.
+    ///
+    /// Clicks are then reported once this delay has elapsed without a second
+    /// click, so it should be kept short.
+    pub const fn double_click_ticks(self, ticks: u32) -> Self {
+        GestureDecoder {
+            double_click_ticks: ticks,
+            ..self
+        }
+    }
+
+    /// Waits for the next device event, and returns the gesture it
+    /// completes, if any.
+    pub fn next_gesture(&mut self) -> Option<Gesture> {
+        get_timed_event(&mut self.buttons).and_then(|event| self.decode(event))
+    }
+
+    /// Returns the gesture completed by `event`, if any.
+    pub fn decode(&mut self, event: TimedEvent) -> Option<Gesture> {
+        match event {
+            TimedEvent::Button(event) => match event {
+                ButtonEvent::LeftButtonPress => self.press(Button::Left),
+                ButtonEvent::RightButtonPress => self.press(Button::Right),
+                ButtonEvent::BothButtonsPress => self.press(Button::Both),
+                ButtonEvent::LeftButtonRelease => self.release(Button::Left),
+                ButtonEvent::RightButtonRelease => self.release(Button::Right),
+                ButtonEvent::BothButtonsRelease => self.release(Button::Both),
+            },
+            TimedEvent::Ticker => self.tick(),
+        }
+    }
+
+    fn press(&mut self, button: Button) -> Option<Gesture> {
+        self.held = Some((button, 0));
+        self.long_press_reported = false;
+        Some(Gesture::Press(button))
+    }
+
+    fn release(&mut self, button: Button) -> Option<Gesture> {
+        self.held = None;
+        if self.long_press_reported {
+            self.long_press_reported = false;
+            return None;
+        }
+        if self.double_click_ticks == 0 {
+            return Some(Gesture::Click(button));
+        }
+        match self.clicked.replace((button, 0)) {
+            Some((clicked, _)) if clicked == button => {
+                self.clicked = None;
+                Some(Gesture::DoubleClick(button))
+            }
+            // Another button was clicked first: report its single click
+            Some((clicked, _)) => Some(Gesture::Click(clicked)),
+            None => None,
+        }
+    }
+
+    fn tick(&mut self) -> Option<Gesture> {
+        if let Some((button, ticks)) = self.held.as_mut() {
+            *ticks += 1;
+            if !self.long_press_reported
+                && self.long_press_ticks > 0
+                && *ticks >= self.long_press_ticks
+            {
+                self.long_press_reported = true;
+                // A long press cancels a pending click
+                self.clicked = None;
+                return Some(Gesture::LongPress(*button));
+            }
+        }
+        if let Some((button, ticks)) = self.clicked.as_mut() {
+            *ticks += 1;
+            if *ticks >= self.double_click_ticks && self.held.is_none() {
+                let button = *button;
+                self.clicked = None;
+                return Some(Gesture::Click(button));
+            }
+        }
+        None
+    }
+}
+
+impl Default for GestureDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    fn decode_all(decoder: &mut GestureDecoder, events: [TimedEvent; 6]) -> [Option<Gesture>; 6] {
+        events.map(|event| decoder.decode(event))
+    }
+
+    #[test]
+    fn gestures() {
+        use ButtonEvent::*;
+        use TimedEvent::{Button as B, Ticker};
+
+        let mut decoder = GestureDecoder::new().long_press_ticks(2);
+        let gestures = decode_all(
+            &mut decoder,
+            [
+                B(BothButtonsPress),
+                Ticker,
+                Ticker,
+                Ticker,
+                B(BothButtonsRelease),
+                Ticker,
+            ],
+        );
+        assert_eq!(gestures[0], Some(Gesture::Press(Button::Both)));
+        assert_eq!(gestures[2], Some(Gesture::LongPress(Button::Both)));
+        assert_eq!(gestures[3], None);
+        assert_eq!(gestures[4], None);
+
+        let mut decoder = GestureDecoder::new().double_click_ticks(2);
+        let gestures = decode_all(
+            &mut decoder,
+            [
+                B(LeftButtonPress),
+                B(LeftButtonRelease),
+                Ticker,
+                B(LeftButtonPress),
+                B(LeftButtonRelease),
+                Ticker,
+            ],
+        );
+        assert_eq!(gestures[1], None);
+        assert_eq!(gestures[4], Some(Gesture::DoubleClick(Button::Left)));
+        assert_eq!(gestures[5], None);
+
+        let gestures = decode_all(
+            &mut decoder,
+            [
+                B(RightButtonPress),
+                B(RightButtonRelease),
+                Ticker,
+                Ticker,
+                Ticker,
+                Ticker,
+            ],
+        );
+        assert_eq!(gestures[1], None);
+        assert_eq!(gestures[3], Some(Gesture::Click(Button::Right)));
+        assert_eq!(gestures[4], None);
+    }
+}
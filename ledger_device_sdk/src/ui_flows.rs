@@ -0,0 +1,341 @@
+//! Device independent UI flows
+//!
+//! The flows of this module are displayed with NBGL on Stax and Flex, and with
+//! the BAGL gadgets on Nano S, S+ and X, so that applications made of the usual
+//! screens (home, address review, transaction review and status) can share a
+//! single UI implementation across devices. Applications needing screens
+//! specific to a device still use the `nbgl` or `ui` modules directly.
+//!
+//! Glyphs are declared with [`flow_glyph!`](crate::flow_glyph), which builds
+//! the glyph type of the device from a small and a large image.
+//!
+//! # Examples
+//!
+//! ```
+//! const CRAB: FlowGlyph = flow_glyph!("crab_14x14.gif", "crab_64x64.gif");
+//!
+//! let mut comm = Comm::new();
+//! let home = HomeFlow::new("Crab", &CRAB).version(env!("CARGO_PKG_VERSION"));
+//! loop {
+//!     match home.show::<Instruction>(&mut comm) {
+//!         Event::Command(Instruction::Sign) => {
+//!             let fields = [Field { name: "Amount", value: "1 CRAB" }];
+//!             let approved = review_transaction(&CRAB, "Review transaction", "Sign transaction", &fields);
+//!             status(if approved { "Transaction signed" } else { "Transaction rejected" }, approved);
+//!         }
+//!         _ => (),
+//!     }
+//! }
+//! ```
+
+pub use crate::display::Field;
+use crate::io::{ApduHeader, Comm, Event, Reply};
+
+#[cfg(any(test, feature = "mock_ui"))]
+pub mod mock;
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use crate::nbgl::*;
+
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+use crate::ui::{
+    bitmaps::{CROSSMARK, DASHBOARD, VALIDATE_14},
+    gadgets::*,
+};
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+use ledger_secure_sdk_sys::buttons::{ButtonEvent, ButtonsState};
+
+/// Glyph displayed by the flows: an `NbglGlyph` on Stax and Flex, and a BAGL
+/// `Glyph` on Nano devices. Declared with [`flow_glyph!`](crate::flow_glyph).
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+pub type FlowGlyph<'a> = crate::nbgl::NbglGlyph<'a>;
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+pub type FlowGlyph<'a> = crate::ui::bitmaps::Glyph<'a>;
+
+/// Builds a [`FlowGlyph`] from the image used on Nano devices (14x14 pixels,
+/// 1bpp) and the image used on Stax and Flex (64x64 pixels), both given as
+/// paths relative to the application crate, as with `include_gif!`.
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+#[macro_export]
+macro_rules! flow_glyph {
+    ($nano:literal, $nbgl:literal) => {
+        $crate::nbgl::NbglGlyph::from_include($crate::include_gif!($nbgl, NBGL))
+    };
+}
+
+/// Builds a [`FlowGlyph`] from the image used on Nano devices (14x14 pixels,
+/// 1bpp) and the image used on Stax and Flex (64x64 pixels), both given as
+/// paths relative to the application crate, as with `include_gif!`.
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+#[macro_export]
+macro_rules! flow_glyph {
+    ($nano:literal, $nbgl:literal) => {
+        $crate::ui::bitmaps::Glyph::from_include($crate::include_gif!($nano))
+    };
+}
+
+/// Number of ticker events (100 ms each) a status page is displayed on Nano
+/// devices, unless a button is pressed.
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+const STATUS_TICKS: u32 = 20;
+
+/// Outcome of a review.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReviewResult {
+    Approved,
+    Rejected,
+}
+
+impl ReviewResult {
+    pub fn is_approved(&self) -> bool {
+        *self == ReviewResult::Approved
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum StatusType {
+    Transaction,
+    Message,
+    Operation,
+    Address,
+}
+
+/// Standard status pages closing a review flow, displayed with
+/// `NbglReviewStatus::show_status` on Stax and Flex.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReviewStatus {
+    TransactionSigned,
+    TransactionRejected,
+    MessageSigned,
+    MessageRejected,
+    OperationSigned,
+    OperationRejected,
+    AddressVerified,
+    AddressRejected,
+}
+
+impl ReviewStatus {
+    /// Returns the status closing a review of the given type.
+    pub fn new(status_type: StatusType, result: ReviewResult) -> ReviewStatus {
+        match (status_type, result.is_approved()) {
+            (StatusType::Transaction, true) => ReviewStatus::TransactionSigned,
+            (StatusType::Transaction, false) => ReviewStatus::TransactionRejected,
+            (StatusType::Message, true) => ReviewStatus::MessageSigned,
+            (StatusType::Message, false) => ReviewStatus::MessageRejected,
+            (StatusType::Operation, true) => ReviewStatus::OperationSigned,
+            (StatusType::Operation, false) => ReviewStatus::OperationRejected,
+            (StatusType::Address, true) => ReviewStatus::AddressVerified,
+            (StatusType::Address, false) => ReviewStatus::AddressRejected,
+        }
+    }
+}
+
+/// Screens used by review flows: reviews, choices and status pages.
+///
+/// `NbglUi` is the implementation displaying them on Stax and Flex. Flows
+/// written against this trait can also run with [`mock::MockUi`], with the
+/// `mock_ui` feature, which records the screens and answers with scripted
+/// user choices, in order to unit test them on any target without a user
+/// interacting with the device. Screens displayed with the NBGL wrappers
+/// directly, instead of through this trait, cannot be mocked.
+///
+/// # Examples
+///
+/// ```
+/// fn sign_flow<U: ReviewUi>(ui: &mut U, amount: &str) -> bool {
+///     let fields = [Field {
+///         name: "Amount",
+///         value: amount,
+///     }];
+///     let result = ui.review(None, "Review transaction", "", "Sign transaction", &fields);
+///     ui.review_status(ReviewStatus::new(StatusType::Transaction, result));
+///     result.is_approved()
+/// }
+///
+/// let mut ui = MockUi::new(&[false]);
+/// assert_eq!(sign_flow(&mut ui, "1 CRAB"), false);
+/// assert_eq!(
+///     ui.screens()[1],
+///     Screen::ReviewStatus(ReviewStatus::TransactionRejected)
+/// );
+/// ```
+pub trait ReviewUi {
+    /// Displays the review of `fields`.
+    fn review(
+        &mut self,
+        glyph: Option<&FlowGlyph>,
+        title: &str,
+        subtitle: &str,
+        finish_title: &str,
+        fields: &[Field],
+    ) -> ReviewResult;
+
+    /// Displays the review of `address`.
+    fn review_address(&mut self, glyph: Option<&FlowGlyph>, title: &str, address: &str) -> bool;
+
+    /// Asks the user to choose between two options.
+    fn choice(&mut self, message: &str, sub_message: &str, confirm: &str, cancel: &str) -> bool;
+
+    /// Displays a transient status page.
+    fn status(&mut self, message: &str, success: bool);
+
+    /// Displays the status page closing a review flow.
+    fn review_status(&mut self, status: ReviewStatus);
+}
+
+/// Home screen of the application, displaying its name, glyph and version,
+/// and a button to quit the application.
+pub struct HomeFlow<'a> {
+    app_name: &'a str,
+    glyph: &'a FlowGlyph<'a>,
+    version: &'a str,
+    author: &'a str,
+}
+
+impl<'a> HomeFlow<'a> {
+    pub fn new(app_name: &'a str, glyph: &'a FlowGlyph<'a>) -> HomeFlow<'a> {
+        HomeFlow {
+            app_name,
+            glyph,
+            version: "",
+            author: "",
+        }
+    }
+
+    pub fn version(self, version: &'a str) -> HomeFlow<'a> {
+        HomeFlow { version, ..self }
+    }
+
+    pub fn author(self, author: &'a str) -> HomeFlow<'a> {
+        HomeFlow { author, ..self }
+    }
+
+    /// Displays the home screen until an APDU command is received, or the
+    /// screen must be redisplayed.
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    pub fn show<T: TryFrom<ApduHeader>>(&self, comm: &mut Comm) -> Event<T>
+    where
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        init_comm(comm);
+        NbglHomeAndSettings::new()
+            .glyph(self.glyph)
+            .infos(self.app_name, self.version, self.author)
+            .show()
+    }
+
+    /// Displays the home screen until an APDU command is received, or the
+    /// screen must be redisplayed.
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    pub fn show<T: TryFrom<ApduHeader>>(&self, comm: &mut Comm) -> Event<T>
+    where
+        Reply: From<<T as TryFrom<ApduHeader>>::Error>,
+    {
+        fn nothing() {}
+        fn quit() {
+            crate::return_to_dashboard();
+        }
+
+        let ready = Page::new(
+            PageStyle::PictureNormal,
+            [self.app_name, "is ready"],
+            Some(self.glyph),
+        );
+        let version = Page::new(PageStyle::BoldNormal, ["Version", self.version], None);
+        let author = Page::new(PageStyle::BoldNormal, ["Developer", self.author], None);
+        let quit_page = Page::new(PageStyle::PictureBold, ["Quit", ""], Some(&DASHBOARD));
+
+        let mut items = [MenuItem::Action(ready, nothing); 4];
+        let mut len = 1;
+        for (shown, page) in [
+            (!self.version.is_empty(), version),
+            (!self.author.is_empty(), author),
+        ] {
+            if shown {
+                items[len] = MenuItem::Action(page, nothing);
+                len += 1;
+            }
+        }
+        items[len] = MenuItem::Action(quit_page, quit);
+        len += 1;
+
+        NestedMenu::new(comm, &items[..len]).show()
+    }
+}
+
+/// Displays the review of a transaction made of `fields`, and returns true if
+/// the user approved it.
+pub fn review_transaction(
+    glyph: &FlowGlyph,
+    title: &str,
+    finish_title: &str,
+    fields: &[Field],
+) -> bool {
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        NbglReview::new()
+            .titles(title, "", finish_title)
+            .glyph(glyph)
+            .show(fields)
+    }
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        Review::new()
+            .titles(title, "", finish_title)
+            .glyph(glyph)
+            .show(fields)
+    }
+}
+
+/// Displays `address` for verification, and returns true if the user
+/// confirmed it.
+pub fn review_address(glyph: &FlowGlyph, title: &str, address: &str) -> bool {
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        NbglAddressReview::new()
+            .glyph(glyph)
+            .verify_str(title)
+            .show(address)
+    }
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        Review::new()
+            .titles(title, "", "Approve")
+            .glyph(glyph)
+            .show(&[Field {
+                name: "Address",
+                value: address,
+            }])
+    }
+}
+
+/// Displays a status page with `message`, typically at the end of a review,
+/// for a couple of seconds.
+pub fn status(message: &str, success: bool) {
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    NbglStatus::new().text(message).show(success);
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        let glyph = if success { &VALIDATE_14 } else { &CROSSMARK };
+        clear_screen();
+        Page::new(PageStyle::PictureBold, [message, ""], Some(glyph)).place();
+        crate::ui::screen_util::screen_update();
+
+        let mut buttons = ButtonsState::new();
+        let mut ticks = 0;
+        while ticks < STATUS_TICKS {
+            match get_timed_event(&mut buttons) {
+                Some(TimedEvent::Ticker) => ticks += 1,
+                Some(TimedEvent::Button(
+                    ButtonEvent::LeftButtonRelease
+                    | ButtonEvent::RightButtonRelease
+                    | ButtonEvent::BothButtonsRelease,
+                )) => break,
+                _ => (),
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Recording [`ReviewUi`] used to unit test UI flows
+//!
+//! [`MockUi`] does not display anything: it records the screens a flow would
+//! display, and answers the reviews and choices with scripted user choices,
+//! so that flows can be tested without a user interacting with the device.
+//!
+//! It is only built for the tests of the SDK, or with the `mock_ui` feature
+//! for the tests of applications, and builds for any target, including the
+//! host.
+//!
+//! Only flows written against [`ReviewUi`] can be mocked: the NBGL and BAGL
+//! layers are not, and screens displayed by calling `NbglReview`,
+//! `NbglAddressReview`, `NbglChoice` or the other NBGL wrappers directly are
+//! neither recorded nor answered. To test such a flow, write it against
+//! [`ReviewUi`], and pass it `NbglUi` on the device and [`MockUi`] in the
+//! tests.
+
+extern crate alloc;
+
+use super::{Field, FlowGlyph, ReviewResult, ReviewStatus, ReviewUi};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Screen recorded by [`MockUi`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Screen {
+    Review {
+        /// Size (width, height) of the glyph, if any
+        glyph: Option<(u32, u32)>,
+        title: String,
+        subtitle: String,
+        finish_title: String,
+        /// Fields, as (name, value)
+        fields: Vec<(String, String)>,
+    },
+    AddressReview {
+        /// Size (width, height) of the glyph, if any
+        glyph: Option<(u32, u32)>,
+        title: String,
+        address: String,
+    },
+    Choice {
+        message: String,
+        sub_message: String,
+    },
+    Status {
+        message: String,
+        success: bool,
+    },
+    ReviewStatus(ReviewStatus),
+}
+
+/// UI recording screens and answering with scripted user choices.
+pub struct MockUi<'a> {
+    choices: &'a [bool],
+    next: usize,
+    screens: Vec<Screen>,
+}
+
+impl<'a> MockUi<'a> {
+    /// Creates a UI answering reviews and choices with `choices` in order:
+    /// `true` to approve or confirm, `false` to reject or cancel.
+    pub fn new(choices: &'a [bool]) -> Self {
+        Self {
+            choices,
+            next: 0,
+            screens: Vec::new(),
+        }
+    }
+
+    /// Screens displayed so far, in order.
+    pub fn screens(&self) -> &[Screen] {
+        &self.screens
+    }
+
+    /// Number of scripted choices not used yet.
+    pub fn remaining_choices(&self) -> usize {
+        self.choices.len() - self.next
+    }
+
+    /// Returns the next scripted choice.
+    ///
+    /// # Panics
+    ///
+    /// Panics when all choices have been used.
+    fn next_choice(&mut self) -> bool {
+        let choice = *self.choices.get(self.next).expect("no more choices");
+        self.next += 1;
+        choice
+    }
+}
+
+fn glyph_size(glyph: Option<&FlowGlyph>) -> Option<(u32, u32)> {
+    glyph.map(|g| (g.width.into(), g.height.into()))
+}
+
+impl ReviewUi for MockUi<'_> {
+    fn review(
+        &mut self,
+        glyph: Option<&FlowGlyph>,
+        title: &str,
+        subtitle: &str,
+        finish_title: &str,
+        fields: &[Field],
+    ) -> ReviewResult {
+        self.screens.push(Screen::Review {
+            glyph: glyph_size(glyph),
+            title: title.to_string(),
+            subtitle: subtitle.to_string(),
+            finish_title: finish_title.to_string(),
+            fields: fields
+                .iter()
+                .map(|f| (f.name.to_string(), f.value.to_string()))
+                .collect(),
+        });
+        if self.next_choice() {
+            ReviewResult::Approved
+        } else {
+            ReviewResult::Rejected
+        }
+    }
+
+    fn review_address(&mut self, glyph: Option<&FlowGlyph>, title: &str, address: &str) -> bool {
+        self.screens.push(Screen::AddressReview {
+            glyph: glyph_size(glyph),
+            title: title.to_string(),
+            address: address.to_string(),
+        });
+        self.next_choice()
+    }
+
+    fn choice(&mut self, message: &str, sub_message: &str, _confirm: &str, _cancel: &str) -> bool {
+        self.screens.push(Screen::Choice {
+            message: message.to_string(),
+            sub_message: sub_message.to_string(),
+        });
+        self.next_choice()
+    }
+
+    fn status(&mut self, message: &str, success: bool) {
+        self.screens.push(Screen::Status {
+            message: message.to_string(),
+            success,
+        });
+    }
+
+    fn review_status(&mut self, status: ReviewStatus) {
+        self.screens.push(Screen::ReviewStatus(status));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use crate::ui_flows::StatusType;
+    use testmacro::test_item as test;
+
+    fn sign_flow<U: ReviewUi>(ui: &mut U, amount: &str) -> bool {
+        let fields = [Field {
+            name: "Amount",
+            value: amount,
+        }];
+        let result = ui.review(None, "Review transaction", "", "Sign transaction", &fields);
+        ui.review_status(ReviewStatus::new(StatusType::Transaction, result));
+        result.is_approved()
+    }
+
+    #[test]
+    fn mock_ui() {
+        let mut ui = MockUi::new(&[true, false]);
+        assert_eq!(sign_flow(&mut ui, "1 CRAB"), true);
+        assert_eq!(sign_flow(&mut ui, "2 CRAB"), false);
+        assert_eq!(ui.remaining_choices(), 0);
+        assert_eq!(ui.screens().len(), 4);
+        assert_eq!(
+            ui.screens()[3],
+            Screen::ReviewStatus(ReviewStatus::TransactionRejected)
+        );
+        assert_eq!(
+            matches!(&ui.screens()[2], Screen::Review { fields, .. } if fields[0].1 == "2 CRAB"),
+            true
+        );
+    }
+}
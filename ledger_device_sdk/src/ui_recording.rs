@@ -0,0 +1,269 @@
+//! Recording and replay of the user interactions
+//!
+//! With the `ui_recording` feature, the button and touch events received
+//! from the MCU are recorded, along with the number of ticker events elapsed
+//! since the previous one, whoever processes them. The recorded trace can be
+//! sent in a response with [`dump`], and [`replay`]ed later: its events are
+//! injected at the same pace, so that a UI flow test or a bug report goes
+//! through the exact same interactions.
+//!
+//! The feature enables `seph_injection`, so it is limited to debug builds
+//! and Speculos as well: a replayed trace approves whatever its recording
+//! approved.
+//!
+//! # Examples
+//!
+//! ```
+//! match ins {
+//!     Instruction::StartRecording => ui_recording::start(),
+//!     Instruction::DumpRecording => {
+//!         ui_recording::stop();
+//!         ui_recording::dump(comm)?;
+//!     }
+//!     Instruction::Replay => ui_recording::replay(comm.get_data()?)?,
+//!     ...
+//! }
+//! ```
+
+use crate::io::{Comm, Reply, StatusWords, TooLong};
+use crate::seph::Events;
+use ledger_secure_sdk_sys::seph as sys_seph;
+
+/// Maximum number of recorded or replayed interactions
+pub const CAPACITY: usize = 64;
+
+/// Maximum size of a recorded SEPH packet, longer ones are truncated
+pub const MAX_PACKET_LEN: usize = 16;
+
+/// Button or touch event, see [`interactions`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Interaction {
+    /// Ticker events received since the previous interaction
+    pub delay: u16,
+    packet: [u8; MAX_PACKET_LEN],
+    len: u8,
+}
+
+impl Interaction {
+    const EMPTY: Interaction = Interaction {
+        delay: 0,
+        packet: [0; MAX_PACKET_LEN],
+        len: 0,
+    };
+
+    fn new(delay: u16, packet: &[u8]) -> Interaction {
+        let len = packet.len().min(MAX_PACKET_LEN);
+        let mut interaction = Interaction {
+            delay,
+            len: len as u8,
+            ..Interaction::EMPTY
+        };
+        interaction.packet[..len].copy_from_slice(&packet[..len]);
+        // The length of the payload is updated if the packet is truncated
+        if len >= 3 {
+            interaction.packet[1..3].copy_from_slice(&(len as u16 - 3).to_be_bytes());
+        }
+        interaction
+    }
+
+    /// Returns the SEPH packet of the event: its tag, the length of its
+    /// payload on 2 bytes, and the payload.
+    pub fn packet(&self) -> &[u8] {
+        &self.packet[..self.len as usize]
+    }
+}
+
+/// Error returned by [`replay`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The trace is truncated or a packet is too long
+    InvalidTrace,
+    /// The trace has more than [`CAPACITY`] interactions
+    TooLong,
+}
+
+impl From<ReplayError> for Reply {
+    fn from(_: ReplayError) -> Reply {
+        StatusWords::BadLen.into()
+    }
+}
+
+struct Recorder {
+    recorded: [Interaction; CAPACITY],
+    recorded_len: usize,
+    recording: bool,
+    /// Ticker events since the last recorded interaction
+    ticks: u16,
+    replayed: [Interaction; CAPACITY],
+    replayed_len: usize,
+    /// Index of the next interaction to replay
+    next: usize,
+    /// Ticker events before the next interaction is replayed
+    remaining: u16,
+}
+
+static mut RECORDER: Recorder = Recorder {
+    recorded: [Interaction::EMPTY; CAPACITY],
+    recorded_len: 0,
+    recording: false,
+    ticks: 0,
+    replayed: [Interaction::EMPTY; CAPACITY],
+    replayed_len: 0,
+    next: 0,
+    remaining: 0,
+};
+
+fn recorder() -> &'static mut Recorder {
+    unsafe { &mut *core::ptr::addr_of_mut!(RECORDER) }
+}
+
+/// Starts recording the interactions, discarding the previous ones. Once
+/// [`CAPACITY`] interactions are recorded, the next ones are ignored.
+pub fn start() {
+    let recorder = recorder();
+    recorder.recorded_len = 0;
+    recorder.ticks = 0;
+    recorder.recording = true;
+    sys_seph::set_recv_hook(Some(on_event));
+}
+
+/// Stops recording the interactions.
+pub fn stop() {
+    recorder().recording = false;
+}
+
+/// Returns the recorded interactions, from the oldest to the most recent.
+pub fn interactions() -> impl Iterator<Item = Interaction> {
+    let recorder = recorder();
+    recorder.recorded[..recorder.recorded_len].iter().copied()
+}
+
+/// Appends the recorded interactions to the response of `comm`, in the
+/// format expected by [`replay`]: for each interaction, its delay on 2
+/// big-endian bytes, the length of its packet on one byte, and the packet.
+pub fn dump(comm: &mut Comm) -> Result<(), TooLong> {
+    for interaction in interactions() {
+        comm.try_append(&interaction.delay.to_be_bytes())?;
+        comm.try_append(&[interaction.len])?;
+        comm.try_append(interaction.packet())?;
+    }
+    Ok(())
+}
+
+/// Replays `trace`, recorded with [`dump`]: each interaction is injected
+/// once its delay has elapsed, counted from the previous one.
+pub fn replay(trace: &[u8]) -> Result<(), ReplayError> {
+    let recorder = recorder();
+    let mut len = 0;
+    let mut rest = trace;
+    while !rest.is_empty() {
+        let [d0, d1, packet_len, tail @ ..] = rest else {
+            return Err(ReplayError::InvalidTrace);
+        };
+        let packet_len = *packet_len as usize;
+        if packet_len > MAX_PACKET_LEN || packet_len > tail.len() {
+            return Err(ReplayError::InvalidTrace);
+        }
+        if len == CAPACITY {
+            return Err(ReplayError::TooLong);
+        }
+        let delay = u16::from_be_bytes([*d0, *d1]);
+        recorder.replayed[len] = Interaction::new(delay, &tail[..packet_len]);
+        len += 1;
+        rest = &tail[packet_len..];
+    }
+    recorder.replayed_len = len;
+    recorder.next = 0;
+    recorder.remaining = recorder.replayed[0].delay;
+    sys_seph::set_recv_hook(Some(on_event));
+    Ok(())
+}
+
+/// Returns true while interactions remain to be replayed.
+pub fn is_replaying() -> bool {
+    let recorder = recorder();
+    recorder.next < recorder.replayed_len
+}
+
+/// Hook called with each event received from the MCU.
+fn on_event(packet: &[u8]) {
+    let recorder = recorder();
+    match Events::from(packet[0]) {
+        Events::TickerEvent => {
+            if recorder.recording {
+                recorder.ticks = recorder.ticks.saturating_add(1);
+            }
+            if recorder.next < recorder.replayed_len {
+                recorder.remaining = recorder.remaining.saturating_sub(1);
+                replay_next(recorder);
+            }
+        }
+        Events::ButtonPush | Events::ScreenTouch if recorder.recording => {
+            if recorder.recorded_len < CAPACITY {
+                recorder.recorded[recorder.recorded_len] = Interaction::new(recorder.ticks, packet);
+                recorder.recorded_len += 1;
+            }
+            recorder.ticks = 0;
+        }
+        _ => (),
+    }
+}
+
+/// Injects the interactions whose delay has elapsed. If the injection queue
+/// is full, they are injected on the next ticker events.
+fn replay_next(recorder: &mut Recorder) {
+    while recorder.remaining == 0 && recorder.next < recorder.replayed_len {
+        if !sys_seph::inject(recorder.replayed[recorder.next].packet()) {
+            return;
+        }
+        recorder.next += 1;
+        if recorder.next < recorder.replayed_len {
+            recorder.remaining = recorder.replayed[recorder.next].delay;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    const TICKER: [u8; 3] = [
+        ledger_secure_sdk_sys::SEPROXYHAL_TAG_TICKER_EVENT as u8,
+        0,
+        0,
+    ];
+    const BUTTON: [u8; 4] = [
+        ledger_secure_sdk_sys::SEPROXYHAL_TAG_BUTTON_PUSH_EVENT as u8,
+        0,
+        1,
+        0x02,
+    ];
+
+    #[test]
+    fn record_and_replay() {
+        start();
+        for _ in 0..3 {
+            on_event(&TICKER);
+        }
+        on_event(&BUTTON);
+        stop();
+        sys_seph::set_recv_hook(None);
+        let recorded = interactions().next().unwrap();
+        assert_eq!(recorded.delay, 3);
+        assert_eq!(recorded.packet(), &BUTTON[..]);
+
+        assert_eq!(replay(&[0, 2, 4]), Err(ReplayError::InvalidTrace));
+        assert_eq!(replay(&[0, 2, 4, BUTTON[0], 0, 1, 0x02]), Ok(()));
+        sys_seph::set_recv_hook(None);
+        on_event(&TICKER);
+        assert_eq!(is_replaying(), true);
+        on_event(&TICKER);
+        assert_eq!(is_replaying(), false);
+        let mut buffer = [0u8; 8];
+        assert_eq!(sys_seph::seph_recv(&mut buffer, 0), 4);
+        assert_eq!(&buffer[..4], &BUTTON[..]);
+    }
+}
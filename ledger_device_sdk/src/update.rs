@@ -0,0 +1,94 @@
+//! Detection of the first run after an update of the application
+//!
+//! The version of the application is stored in an NVM slot managed by the
+//! SDK. At startup, [`on_first_run_after_update`] compares it with the
+//! installed version, and calls a hook when they differ, so that settings can
+//! be migrated and caches invalidated before they are used.
+//!
+//! The NVM data of an application is usually reset when the application is
+//! reinstalled. The stored version is then missing, and the hook is called
+//! as on a first run after installation.
+//!
+//! # Examples
+//!
+//! ```
+//! #[no_mangle]
+//! extern "C" fn sample_main() {
+//!     update::on_first_run_after_update(|old, new| {
+//!         if old == Some("1.0.0") {
+//!             migrate_settings_from_1_0();
+//!         }
+//!         debug_println!("updated to {}", new);
+//!     });
+//!     let mut comm = Comm::new();
+//!     ...
+//! }
+//! ```
+
+use crate::nvm::{AtomicStorage, SingleStorage};
+use crate::NVMData;
+
+/// Maximum length of the stored version, longer versions are truncated
+const VERSION_SIZE: usize = 32;
+
+/// Version of the application at its last run, padded with zeros, all zeros
+/// if it has never run since its installation
+#[link_section = ".nvm_data"]
+static mut LAST_VERSION: NVMData<AtomicStorage<[u8; VERSION_SIZE]>> =
+    NVMData::new(AtomicStorage::new(&[0; VERSION_SIZE]));
+
+/// Returns the version stored in `slot`, or `None` if there is none.
+fn stored_version(slot: &[u8; VERSION_SIZE]) -> Option<&str> {
+    let len = slot.iter().position(|&b| b == 0).unwrap_or(VERSION_SIZE);
+    match len {
+        0 => None,
+        _ => core::str::from_utf8(&slot[..len]).ok(),
+    }
+}
+
+/// Returns `version` in the format stored in NVM.
+fn to_slot(version: &str) -> [u8; VERSION_SIZE] {
+    let mut slot = [0; VERSION_SIZE];
+    let len = version.len().min(VERSION_SIZE);
+    slot[..len].copy_from_slice(&version.as_bytes()[..len]);
+    slot
+}
+
+/// Calls `hook` with the previous and the installed versions of the
+/// application if it is the first run since the application was updated or
+/// installed, in which case the previous version is `None`. Meant to be
+/// called once, at the start of the application.
+///
+/// The installed version is stored only once `hook` returns: if the device
+/// is powered off during the migration, the hook is called again at the next
+/// start and must cope with partially migrated data.
+pub fn on_first_run_after_update(hook: impl FnOnce(Option<&str>, &str)) {
+    let mut buf = [0u8; VERSION_SIZE];
+    let Some(installed) = crate::registry::app_version(&mut buf) else {
+        return;
+    };
+    let storage = unsafe { LAST_VERSION.get_mut() };
+    let slot = to_slot(installed);
+    if *storage.get_ref() == slot {
+        return;
+    }
+    let last = *storage.get_ref();
+    hook(stored_version(&last), installed);
+    storage.update(&slot);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn version_slot() {
+        assert_eq!(stored_version(&[0; VERSION_SIZE]), None);
+        assert_eq!(stored_version(&to_slot("1.2.3")), Some("1.2.3"));
+        let long = "1.2.3-0123456789abcdef0123456789abcdef";
+        assert_eq!(stored_version(&to_slot(long)), Some(&long[..VERSION_SIZE]));
+    }
+}
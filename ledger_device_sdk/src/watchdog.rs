@@ -0,0 +1,109 @@
+//! Software watchdog
+//!
+//! Once started, the watchdog must be petted regularly from the main loop of
+//! the application. If it has not been petted before its deadline, a fatal
+//! error screen is displayed and the application exits, so that a flow stuck
+//! waiting for an event which never comes becomes a diagnosable failure
+//! instead of a silent hang.
+//!
+//! The deadline is checked on the ticker events processed by
+//! [`Comm`](crate::io::Comm), which includes the events processed during
+//! [`Comm::with_keepalive`](crate::io::Comm::with_keepalive): long
+//! computations must pet the watchdog as well.
+//!
+//! # Examples
+//!
+//! ```
+//! watchdog::start(30_000);
+//! loop {
+//!     watchdog::pet();
+//!     match comm.next_event::<Instruction>() {
+//!         ...
+//!     }
+//! }
+//! ```
+
+use crate::io::TICKER_PERIOD_MS;
+
+#[derive(Copy, Clone)]
+struct Watchdog {
+    timeout_ms: u32,
+    /// Ticker events before the deadline
+    remaining: u32,
+}
+
+static mut WATCHDOG: Option<Watchdog> = None;
+
+fn ticks(timeout_ms: u32) -> u32 {
+    timeout_ms.div_ceil(TICKER_PERIOD_MS).max(1)
+}
+
+/// Starts the watchdog, or restarts it with a new timeout, with a deadline
+/// `timeout_ms` milliseconds from now.
+pub fn start(timeout_ms: u32) {
+    unsafe {
+        WATCHDOG = Some(Watchdog {
+            timeout_ms,
+            remaining: ticks(timeout_ms),
+        })
+    };
+}
+
+/// Postpones the deadline of the watchdog to its timeout from now.
+pub fn pet() {
+    if let Some(watchdog) = unsafe { (*core::ptr::addr_of_mut!(WATCHDOG)).as_mut() } {
+        watchdog.remaining = ticks(watchdog.timeout_ms);
+    }
+}
+
+/// Stops the watchdog, for instance before a flow waiting for the user
+/// without a time limit.
+pub fn stop() {
+    unsafe { WATCHDOG = None };
+}
+
+/// Returns true if the watchdog is started.
+pub fn is_running() -> bool {
+    unsafe { (*core::ptr::addr_of!(WATCHDOG)).is_some() }
+}
+
+/// Called on each ticker event, to check the deadline of the watchdog.
+pub(crate) fn tick() {
+    let Some(watchdog) = (unsafe { (*core::ptr::addr_of_mut!(WATCHDOG)).as_mut() }) else {
+        return;
+    };
+    watchdog.remaining -= 1;
+    if watchdog.remaining > 0 {
+        return;
+    }
+    let timeout_ms = watchdog.timeout_ms;
+    stop();
+    // Replied first, so that automated clients do not wait for the user
+    let mut comm = crate::io::Comm::new();
+    comm.reply(crate::io::StatusWords::Panic);
+    crate::panic_diagnostics::show_fatal(
+        "Watchdog expired\0",
+        format_args!("The application has not responded for {} ms", timeout_ms),
+    );
+    crate::exit(crate::ExitCode::Failure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_err as assert_eq;
+    use crate::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn deadline() {
+        assert_eq!(ticks(0), 1);
+        assert_eq!(ticks(TICKER_PERIOD_MS + 1), 2);
+        start(1000);
+        tick();
+        pet();
+        assert_eq!(unsafe { WATCHDOG.unwrap().remaining }, ticks(1000));
+        stop();
+        assert_eq!(is_running(), false);
+    }
+}
@@ -8,6 +8,12 @@ use std::{env, fs::File, io::BufRead, io::BufReader, io::Read};
 const DEFINES_CCID: [(&str, Option<&str>); 2] =
     [("HAVE_USB_CLASS_CCID", None), ("HAVE_CCID", None)];
 
+#[cfg(feature = "debug_serial")]
+const DEFINES_CDC: [(&str, Option<&str>); 1] = [("HAVE_CDCUSB", None)];
+
+/// Heap size used when the application does not set `HEAP_SIZE`
+const DEFAULT_HEAP_SIZE: usize = 8192;
+
 const AUX_C_FILES: [&str; 2] = ["./src/c/src.c", "./src/c/sjlj.s"];
 
 const SDK_C_FILES: [&str; 9] = [
@@ -344,6 +350,19 @@ impl SDKBuilder {
         println!("cargo:warning=Device is {:?}", self.device);
     }
 
+    /// Selects the device whose bindings are generated when building for the
+    /// host with the `host` feature: the Nano S+, as the SDK compiles the
+    /// user interface of the Nano devices for targets other than Stax and
+    /// Flex.
+    pub fn host_device(&mut self) {
+        self.device = Device::NanoSPlus;
+        println!("cargo:rustc-env=TARGET={}", self.device);
+        println!(
+            "cargo:warning=Building for the host with the bindings of {:?}",
+            self.device
+        );
+    }
+
     pub fn bolos_sdk(&mut self) -> Result<(), SDKBuildError> {
         println!("cargo:rerun-if-env-changed=LEDGER_SDK_PATH");
         let sdk_path = match env::var("LEDGER_SDK_PATH") {
@@ -447,6 +466,12 @@ impl SDKBuilder {
         //     command.files(str2path(&self.bolos_sdk, &CCID_FILES));
         // }
 
+        // The CDC-ACM class is implemented in `usbd_impl.c`
+        #[cfg(feature = "debug_serial")]
+        for (define, value) in DEFINES_CDC {
+            command.define(define, value);
+        }
+
         match self.device {
             Device::NanoS => finalize_nanos_configuration(&mut command, &self.bolos_sdk),
             Device::NanoX => finalize_nanox_configuration(&mut command, &self.bolos_sdk),
@@ -495,7 +520,9 @@ impl SDKBuilder {
             .prepend_enum_name(false)
             .generate_comments(false)
             .derive_default(true)
-            .use_core();
+            .use_core()
+            // The layouts are the ones of the 32-bit device
+            .layout_tests(!cfg!(feature = "host"));
 
         // Target specific files
         let (include_path, header) = match self.device {
@@ -580,13 +607,35 @@ impl SDKBuilder {
 fn main() {
     let mut sdk_builder = SDKBuilder::new();
     sdk_builder.gcc_toolchain();
-    sdk_builder.device();
+    if cfg!(feature = "host") {
+        sdk_builder.host_device();
+    } else {
+        sdk_builder.device();
+    }
+    heap_size();
     sdk_builder.bolos_sdk().unwrap();
     sdk_builder.cxdefines();
-    sdk_builder.build_c_sdk();
+    // The syscalls are implemented in Rust when building for the host
+    if !cfg!(feature = "host") {
+        sdk_builder.build_c_sdk();
+    }
     sdk_builder.generate_bindings();
 }
 
+/// Exports the heap size set by the application with the `HEAP_SIZE`
+/// environment variable, or the default one.
+fn heap_size() {
+    println!("cargo:rerun-if-env-changed=HEAP_SIZE");
+    let heap_size = match env::var("HEAP_SIZE") {
+        Ok(size) => match size.parse::<usize>() {
+            Ok(size) if size > 0 => size,
+            _ => panic!("invalid HEAP_SIZE `{size}`, expected a number of bytes"),
+        },
+        Err(_) => DEFAULT_HEAP_SIZE,
+    };
+    println!("cargo:rustc-env=HEAP_SIZE={heap_size}");
+}
+
 fn finalize_nanos_configuration(command: &mut cc::Build, bolos_sdk: &Path) {
     let defines = header2define("sdk_nanos.h");
     for (define, value) in defines {
@@ -13,7 +13,7 @@
   #include "ledger_ble.h"
 #endif
 
-extern void sample_main();
+extern void sample_main(int arg0);
 extern void heap_init();
 
 struct SectionSrc;
@@ -257,7 +257,46 @@ void link_pass_nvram(
 uint8_t G_io_apdu_buffer[260];
 #endif
 
-int c_main(void) {
+// Resets the IO state and restarts the USB and BLE transports, as done by
+// `io_seproxyhal_init`. Not called when the application is started as a
+// library, as the caller is still using the transports.
+void c_reset_io(void) {
+    // below is a 'manual' implementation of `io_seproxyhal_init`
+#ifdef HAVE_MCU_PROTECT
+    unsigned char c[4];
+    c[0] = SEPROXYHAL_TAG_MCU;
+    c[1] = 0;
+    c[2] = 1;
+    c[3] = SEPROXYHAL_TAG_MCU_TYPE_PROTECT;
+    io_seproxyhal_spi_send(c, 4);
+#ifdef HAVE_BLE
+    unsigned int plane = G_io_app.plane_mode;
+#endif
+#endif
+    memset(&G_io_app, 0, sizeof(G_io_app));
+
+#ifdef HAVE_BLE
+    G_io_app.plane_mode = plane;
+#endif
+    G_io_app.apdu_state = APDU_IDLE;
+    G_io_app.apdu_length = 0;
+    G_io_app.apdu_media = IO_APDU_MEDIA_NONE;
+
+    G_io_app.ms = 0;
+    io_usb_hid_init();
+
+    USB_power(0);
+    USB_power(1);
+#ifdef HAVE_CCID
+    io_usb_ccid_set_card_inserted(1);
+#endif
+
+#ifdef HAVE_BLE
+    LEDGER_BLE_init();
+#endif
+}
+
+int c_main(int arg0) {
   __asm volatile("cpsie i");
 
   // Update pointers for pic(), only issuing nvm_write() if we actually changed a pointer in the block.
@@ -292,46 +331,17 @@ int c_main(void) {
   for(;;) {
     BEGIN_TRY {
       TRY {
-        // below is a 'manual' implementation of `io_seproxyhal_init`
-    #ifdef HAVE_MCU_PROTECT
-        unsigned char c[4];
-        c[0] = SEPROXYHAL_TAG_MCU;
-        c[1] = 0;
-        c[2] = 1;
-        c[3] = SEPROXYHAL_TAG_MCU_TYPE_PROTECT;
-        io_seproxyhal_spi_send(c, 4);
-    #ifdef HAVE_BLE
-        unsigned int plane = G_io_app.plane_mode;
-    #endif
-    #endif
-        memset(&G_io_app, 0, sizeof(G_io_app));
-
-    #ifdef HAVE_BLE
-        G_io_app.plane_mode = plane;
-    #endif
-        G_io_app.apdu_state = APDU_IDLE;
-        G_io_app.apdu_length = 0;
-        G_io_app.apdu_media = IO_APDU_MEDIA_NONE;
-
-        G_io_app.ms = 0;
-        io_usb_hid_init();
-
-        USB_power(0);
-        USB_power(1);
-    #ifdef HAVE_CCID
-        io_usb_ccid_set_card_inserted(1);
-    #endif
-
-    #ifdef HAVE_BLE
-        LEDGER_BLE_init();
-    #endif
+        // The caller keeps using the IO when started through `os_lib_call`
+        if (arg0 == 0) {
+          c_reset_io();
+        }
 
     #if !defined(HAVE_BOLOS) && defined(HAVE_PENDING_REVIEW_SCREEN)
         check_audited_app();
     #endif // !defined(HAVE_BOLOS) && defined(HAVE_PENDING_REVIEW_SCREEN)
 
         heap_init();
-        sample_main();
+        sample_main(arg0);
       }
       CATCH(EXCEPTION_IO_RESET) {
         continue;
@@ -0,0 +1,76 @@
+//! Host implementations of the syscalls, with the `host` feature
+//!
+//! Building for the host allows to fuzz or test the code of an application
+//! which does not depend on the device, such as APDU, TLV or transaction
+//! parsers, on a Linux workstation, for instance with `cargo fuzz`. The
+//! bindings are the ones of the Nano S+, and the C SDK is not compiled.
+//!
+//! The syscalls used by the SDK to access memory, NVM, randomness and to
+//! exit are implemented below with host resources. The other ones, such as
+//! the cryptographic, display and IO syscalls, are left undefined: a binary
+//! calling them fails to link.
+
+extern crate std;
+
+use core::ffi::{c_uint, c_void};
+
+/// State of the generator used by [`cx_rng_no_throw`]
+static mut RNG_STATE: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Addresses are not relocated on the host.
+#[no_mangle]
+pub extern "C" fn pic(linked_address: *mut c_void) -> *mut c_void {
+    linked_address
+}
+
+/// Exits the process, with the status of the application.
+#[no_mangle]
+pub extern "C" fn os_sched_exit(exit_code: crate::bolos_task_status_t) -> ! {
+    std::process::exit(exit_code as i32)
+}
+
+/// NVM is emulated in RAM: the statics of the `.nvm_data` section are
+/// written directly.
+///
+/// # Safety
+///
+/// `dst_adr` and `src_adr` must be valid for `src_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nvm_write(dst_adr: *mut c_void, src_adr: *mut c_void, src_len: c_uint) {
+    core::ptr::copy(src_adr as *const u8, dst_adr as *mut u8, src_len as usize);
+}
+
+/// Fills `buffer` with pseudo-random bytes from a generator with a fixed
+/// seed, so that runs can be reproduced. See [`seed_rng`].
+///
+/// # Safety
+///
+/// `buffer` must be valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cx_rng_no_throw(buffer: *mut u8, len: usize) {
+    let bytes = core::slice::from_raw_parts_mut(buffer, len);
+    for chunk in bytes.chunks_mut(8) {
+        let value = next_random();
+        chunk.copy_from_slice(&value.to_le_bytes()[..chunk.len()]);
+    }
+}
+
+/// Returns the next output of the xorshift64* generator.
+fn next_random() -> u64 {
+    let state = unsafe { &mut *core::ptr::addr_of_mut!(RNG_STATE) };
+    *state ^= *state >> 12;
+    *state ^= *state << 25;
+    *state ^= *state >> 27;
+    state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+}
+
+/// Reseeds the generator of [`cx_rng_no_throw`], for instance with the input
+/// of a fuzzing iteration.
+pub fn seed_rng(seed: u64) {
+    unsafe { RNG_STATE = seed | 1 };
+}
+
+/// Writes `s` to the standard error, for the debug output of the SDK.
+pub fn print(s: &str) {
+    std::eprint!("{s}");
+}
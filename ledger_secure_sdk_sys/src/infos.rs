@@ -30,10 +30,13 @@ const fn const_parse_api_level(x: &str) -> u8 {
     res
 }
 
+#[cfg(not(target_os = "nanos"))]
+pub(crate) const SDK_API_LEVEL: u8 = const_parse_api_level(env!("API_LEVEL"));
+
 /// Expose the API_LEVEL
 #[used]
 #[cfg(not(target_os = "nanos"))]
-static API_LEVEL: u8 = const_parse_api_level(env!("API_LEVEL"));
+static API_LEVEL: u8 = SDK_API_LEVEL;
 
 // Store metadata in the ELF file
 #[cfg(not(target_os = "nanos"))]
@@ -8,9 +8,18 @@ use core::ffi::c_void;
 use core::mem::MaybeUninit;
 
 pub mod buttons;
+#[cfg(feature = "host")]
+pub mod host;
 mod infos;
 pub mod seph;
 
+/// API level of the C SDK the application is built with, `None` on Nano S
+/// whose SDK predates API levels.
+#[cfg(not(target_os = "nanos"))]
+pub const API_LEVEL: Option<u8> = Some(infos::SDK_API_LEVEL);
+#[cfg(target_os = "nanos")]
+pub const API_LEVEL: Option<u8> = None;
+
 /// Wrapper for 'os_sched_exit'
 /// Exit application with status
 pub fn exit_app(status: u8) -> ! {
@@ -35,14 +44,91 @@ pub fn pic_rs_mut<T>(x: &mut T) -> &mut T {
     unsafe { &mut *ptr }
 }
 
+#[cfg(feature = "heap")]
+use core::alloc::{GlobalAlloc, Layout};
 #[cfg(feature = "heap")]
 use critical_section::RawRestoreState;
 #[cfg(feature = "heap")]
 use embedded_alloc::Heap;
 
+/// Size of the heap in bytes, set at build time with the `HEAP_SIZE`
+/// environment variable (8192 bytes by default), for instance in the
+/// `[env]` section of the application's `.cargo/config.toml`.
+#[cfg(feature = "heap")]
+pub const HEAP_SIZE: usize = parse_heap_size(env!("HEAP_SIZE"));
+
+/// Parses the decimal heap size exported by the build script, which has
+/// already validated it.
+#[cfg(feature = "heap")]
+const fn parse_heap_size(size: &str) -> usize {
+    let bytes = size.as_bytes();
+    let mut value = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as usize;
+        i += 1;
+    }
+    value
+}
+
+/// Global allocator, calling the allocation error hook when the heap is
+/// exhausted.
 #[cfg(feature = "heap")]
-#[global_allocator]
-static HEAP: Heap = Heap::empty();
+struct SdkHeap {
+    heap: Heap,
+}
+
+// The heap is never initialized when building for the host, whose allocator
+// is used instead
+#[cfg(feature = "heap")]
+#[cfg_attr(not(feature = "host"), global_allocator)]
+static HEAP: SdkHeap = SdkHeap {
+    heap: Heap::empty(),
+};
+
+/// Hook called when an allocation fails, see [`set_alloc_error_hook`].
+#[cfg(feature = "heap")]
+static mut ALLOC_ERROR_HOOK: Option<fn(Layout)> = None;
+
+#[cfg(feature = "heap")]
+unsafe impl GlobalAlloc for SdkHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.heap.alloc(layout);
+        if ptr.is_null() {
+            if let Some(hook) = ALLOC_ERROR_HOOK {
+                hook(layout);
+            }
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.heap.dealloc(ptr, layout)
+    }
+}
+
+/// Sets the function called when an allocation fails because the heap is
+/// exhausted, before the allocation error is raised. The hook usually
+/// displays an error screen and exits the application; if it returns, the
+/// allocation error panics as usual.
+#[cfg(feature = "heap")]
+pub fn set_alloc_error_hook(hook: fn(Layout)) {
+    unsafe {
+        ALLOC_ERROR_HOOK = Some(hook);
+    }
+}
+
+/// Returns the number of bytes currently allocated on the heap.
+#[cfg(feature = "heap")]
+pub fn heap_used() -> usize {
+    HEAP.heap.used()
+}
+
+/// Returns the number of bytes still available on the heap.
+#[cfg(feature = "heap")]
+pub fn heap_free() -> usize {
+    HEAP.heap.free()
+}
 
 #[cfg(feature = "heap")]
 struct CriticalSection;
@@ -58,14 +144,13 @@ unsafe impl critical_section::Impl for CriticalSection {
 
 /// Initializes the heap memory for the global allocator.
 ///
-/// The heap is stored in the stack, and has a fixed size.
+/// The heap is stored in a static buffer of [`HEAP_SIZE`] bytes.
 /// This method is called just before [sample_main].
 #[no_mangle]
 #[cfg(feature = "heap")]
 extern "C" fn heap_init() {
-    const HEAP_SIZE: usize = 8192;
     static mut HEAP_MEM: [MaybeUninit<u8>; HEAP_SIZE] = [MaybeUninit::uninit(); HEAP_SIZE];
-    unsafe { HEAP.init(HEAP_MEM.as_ptr() as usize, HEAP_SIZE) }
+    unsafe { HEAP.heap.init(HEAP_MEM.as_ptr() as usize, HEAP_SIZE) }
 }
 
 #[no_mangle]
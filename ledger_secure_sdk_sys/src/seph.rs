@@ -1,8 +1,77 @@
 use crate::{
-    io_seph_is_status_sent, io_seph_recv, io_seph_send, SEPROXYHAL_TAG_GENERAL_STATUS,
-    SEPROXYHAL_TAG_RAPDU, SEPROXYHAL_TAG_SCREEN_DISPLAY_STATUS,
+    io_seph_is_status_sent, io_seph_recv, io_seph_send, SEPROXYHAL_TAG_BATTERY_NOTIFICATION_EVENT,
+    SEPROXYHAL_TAG_GENERAL_STATUS, SEPROXYHAL_TAG_RAPDU, SEPROXYHAL_TAG_SCREEN_DISPLAY_STATUS,
+    SEPROXYHAL_TAG_TICKER_EVENT,
 };
 
+/// Number of ticker events received with [`seph_recv`]
+static mut TICKER_EVENTS: u32 = 0;
+
+/// Called when a battery notification is received with [`seph_recv`]
+static mut BATTERY_CRITICAL_HOOK: Option<fn()> = None;
+
+/// Called with each event received from the MCU by [`seph_recv`]
+#[cfg(feature = "ui_recording")]
+static mut RECV_HOOK: Option<fn(&[u8])> = None;
+
+/// Maximum number of pending injected events, see [`inject`]
+#[cfg(feature = "seph_injection")]
+const MAX_INJECTED_EVENTS: usize = 4;
+/// Maximum size of an injected event
+#[cfg(feature = "seph_injection")]
+const MAX_INJECTED_EVENT_SIZE: usize = 128;
+
+/// Synthetic events returned by [`seph_recv`] before the ones of the MCU
+#[cfg(feature = "seph_injection")]
+struct InjectedEvents {
+    events: [([u8; MAX_INJECTED_EVENT_SIZE], usize); MAX_INJECTED_EVENTS],
+    /// Index of the next event to return
+    head: usize,
+    pending: usize,
+}
+
+#[cfg(feature = "seph_injection")]
+static mut INJECTED_EVENTS: InjectedEvents = InjectedEvents {
+    events: [([0; MAX_INJECTED_EVENT_SIZE], 0); MAX_INJECTED_EVENTS],
+    head: 0,
+    pending: 0,
+};
+
+/// Queues `event`, a raw SEPH packet which may be malformed, to be returned
+/// by the next call to [`seph_recv`] instead of an event of the MCU, so that
+/// the processing of events can be tested against unexpected tags or
+/// truncated packets. The MCU is not notified of injected events.
+///
+/// Returns false if `event` is longer than 128 bytes or if 4 events are
+/// already pending.
+#[cfg(feature = "seph_injection")]
+pub fn inject(event: &[u8]) -> bool {
+    let queue = unsafe { &mut *core::ptr::addr_of_mut!(INJECTED_EVENTS) };
+    if event.len() > MAX_INJECTED_EVENT_SIZE || queue.pending == MAX_INJECTED_EVENTS {
+        return false;
+    }
+    let (buf, len) = &mut queue.events[(queue.head + queue.pending) % MAX_INJECTED_EVENTS];
+    buf[..event.len()].copy_from_slice(event);
+    *len = event.len();
+    queue.pending += 1;
+    true
+}
+
+/// Copies the next injected event into `buffer`, truncated to its length.
+#[cfg(feature = "seph_injection")]
+fn recv_injected(buffer: &mut [u8]) -> Option<u16> {
+    let queue = unsafe { &mut *core::ptr::addr_of_mut!(INJECTED_EVENTS) };
+    if queue.pending == 0 {
+        return None;
+    }
+    let (buf, len) = &queue.events[queue.head];
+    let len = (*len).min(buffer.len());
+    buffer[..len].copy_from_slice(&buf[..len]);
+    queue.head = (queue.head + 1) % MAX_INJECTED_EVENTS;
+    queue.pending -= 1;
+    Some(len as u16)
+}
+
 /// Directly send buffer over the SPI channel to the MCU
 pub fn seph_send(buffer: &[u8]) {
     unsafe { io_seph_send(buffer.as_ptr(), buffer.len() as u16) };
@@ -10,7 +79,49 @@ pub fn seph_send(buffer: &[u8]) {
 
 /// Receive the next APDU into 'buffer'
 pub fn seph_recv(buffer: &mut [u8], flags: u32) -> u16 {
-    unsafe { io_seph_recv(buffer.as_mut_ptr(), buffer.len() as u16, flags) }
+    #[cfg(feature = "seph_injection")]
+    if let Some(len) = recv_injected(buffer) {
+        return len;
+    }
+    let len = unsafe { io_seph_recv(buffer.as_mut_ptr(), buffer.len() as u16, flags) };
+    if len == 0 {
+        return len;
+    }
+    #[cfg(feature = "ui_recording")]
+    if let Some(hook) = unsafe { RECV_HOOK } {
+        hook(&buffer[..len as usize]);
+    }
+    match buffer[0] as u32 {
+        SEPROXYHAL_TAG_TICKER_EVENT => unsafe { TICKER_EVENTS = TICKER_EVENTS.wrapping_add(1) },
+        SEPROXYHAL_TAG_BATTERY_NOTIFICATION_EVENT => {
+            if let Some(hook) = unsafe { BATTERY_CRITICAL_HOOK } {
+                hook();
+            }
+        }
+        _ => (),
+    }
+    len
+}
+
+/// Registers a hook called by [`seph_recv`] when the MCU notifies that the
+/// battery level is critical, whoever processes the events. Passing `None`
+/// removes the hook.
+pub fn set_battery_critical_hook(hook: Option<fn()>) {
+    unsafe { BATTERY_CRITICAL_HOOK = hook };
+}
+
+/// Registers a hook called by [`seph_recv`] with each event received from
+/// the MCU, injected events excluded, whoever processes the events. Passing
+/// `None` removes the hook.
+#[cfg(feature = "ui_recording")]
+pub fn set_recv_hook(hook: Option<fn(&[u8])>) {
+    unsafe { RECV_HOOK = hook };
+}
+
+/// Returns the number of ticker events received since the application
+/// started, whoever processed them.
+pub fn ticker_events() -> u32 {
+    unsafe { TICKER_EVENTS }
 }
 
 /// Wrapper for 'io_seph_is_status_sent'
@@ -0,0 +1,63 @@
+//! Screenshots requested by the application on the Speculos console
+//!
+//! `ledger_device_sdk::testing::screenshot` writes [`SCREENSHOT_MARKER`]
+//! followed by the screenshot name on the console. The output of Speculos is
+//! read by background threads, which capture the screen when they see the
+//! marker.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Written on the console before the name of a requested screenshot
+pub(crate) const SCREENSHOT_MARKER: &str = "[speculos_harness:screenshot] ";
+
+/// Names of the screenshots captured so far
+pub(crate) type Captures = Arc<Mutex<Vec<String>>>;
+
+/// Reads the console `output` of Speculos until it exits, saving the
+/// screenshots requested by the application into `dir`.
+pub(crate) fn spawn_reader(
+    output: impl Read + Send + 'static,
+    port: u16,
+    dir: PathBuf,
+    captures: Captures,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(output).split(b'\n') {
+            let Ok(line) = line else {
+                return;
+            };
+            let line = String::from_utf8_lossy(&line);
+            let Some(name) = screenshot_name(&line) else {
+                continue;
+            };
+            if save(port, &dir, name).is_ok() {
+                captures.lock().unwrap().push(name.into());
+            }
+        }
+    });
+}
+
+/// Returns the name of the screenshot requested on `line`, if any. Names are
+/// restricted to characters which are valid in file names.
+pub(crate) fn screenshot_name(line: &str) -> Option<&str> {
+    let start = line.find(SCREENSHOT_MARKER)? + SCREENSHOT_MARKER.len();
+    let name = line[start..].trim();
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(name)
+}
+
+/// Saves a screenshot of the current screen as `name.png` in `dir`.
+pub(crate) fn save(port: u16, dir: &Path, name: &str) -> Result<PathBuf, crate::Error> {
+    let png = crate::fetch_screenshot(port)?;
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{name}.png"));
+    fs::write(&path, png)?;
+    Ok(path)
+}
@@ -0,0 +1,597 @@
+//! Host-side harness for integration tests of Ledger device applications
+//!
+//! [`Speculos`] boots an application under the
+//! [Speculos](https://github.com/LedgerHQ/speculos) emulator, then exchanges
+//! APDUs with it and drives its screen through the automation REST API, so
+//! that integration tests are written in Rust, next to the application.
+//!
+//! The `speculos` executable must be in the `PATH`, or given with
+//! [`SpeculosBuilder::executable`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use speculos_harness::{Button, Model, Speculos};
+//!
+//! let speculos = Speculos::builder("target/nanosplus/release/app-crab")
+//!     .model(Model::NanoSPlus)
+//!     .launch()
+//!     .unwrap();
+//!
+//! speculos
+//!     .exchange_apdu(0xe0, 0x01, 0x00, 0x00, &[])
+//!     .unwrap()
+//!     .assert_sw(0x9000);
+//!
+//! speculos.wait_for_text("is ready").unwrap();
+//! speculos.press(Button::Right).unwrap();
+//! speculos.assert_text("Version");
+//! ```
+
+mod console;
+mod http;
+
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Port of the REST API used when none is set
+pub const DEFAULT_API_PORT: u16 = 5000;
+
+/// Delay between two polls of the screen content
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Maximum time Speculos takes to boot and serve its API
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Directory of the screenshots used when none is set
+pub const DEFAULT_SCREENSHOT_DIR: &str = "target/screenshots";
+
+/// Environment variable replacing the reference screenshots of
+/// [`Speculos::assert_screenshot`] when set to `1`
+pub const UPDATE_SCREENSHOTS_VAR: &str = "UPDATE_SCREENSHOTS";
+
+/// Errors of the harness
+#[derive(Debug)]
+pub enum Error {
+    /// Speculos could not be started, or the connection to its API failed.
+    Io(std::io::Error),
+    /// The API answered a request with an error status code.
+    Http(u16, String),
+    /// The API answered with an unexpected content.
+    InvalidResponse(String),
+    /// The expected condition was not met in time.
+    Timeout(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Http(status, body) => write!(f, "HTTP error {status}: {body}"),
+            Error::InvalidResponse(reason) => write!(f, "invalid response: {reason}"),
+            Error::Timeout(reason) => write!(f, "timeout: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// Device emulated by Speculos
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Model {
+    NanoS,
+    NanoSPlus,
+    NanoX,
+    Stax,
+    Flex,
+}
+
+impl Model {
+    /// Name of the model on the Speculos command line
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            Model::NanoS => "nanos",
+            Model::NanoSPlus => "nanosp",
+            Model::NanoX => "nanox",
+            Model::Stax => "stax",
+            Model::Flex => "flex",
+        }
+    }
+}
+
+/// Button of the Nano devices
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Button {
+    Left,
+    Right,
+    Both,
+}
+
+impl Button {
+    fn as_path(&self) -> &'static str {
+        match self {
+            Button::Left => "/button/left",
+            Button::Right => "/button/right",
+            Button::Both => "/button/both",
+        }
+    }
+}
+
+/// Response to an APDU command
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Response {
+    /// Data returned before the status word
+    pub data: Vec<u8>,
+    /// Status word
+    pub sw: u16,
+}
+
+impl Response {
+    /// Splits a raw response into its data and status word, or returns
+    /// `None` if it is shorter than a status word.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Response> {
+        let len = bytes.len().checked_sub(2)?;
+        Some(Response {
+            data: bytes[..len].to_vec(),
+            sw: u16::from_be_bytes([bytes[len], bytes[len + 1]]),
+        })
+    }
+
+    /// Returns true if the status word is 0x9000.
+    pub fn is_ok(&self) -> bool {
+        self.sw == 0x9000
+    }
+
+    /// Panics if the status word is not `expected`, a `u16` or any status
+    /// word type converting to it.
+    #[track_caller]
+    pub fn assert_sw(&self, expected: impl Into<u16>) -> &Self {
+        let expected = expected.into();
+        assert_eq!(
+            self.sw, expected,
+            "unexpected status word {:#06x}, expected {:#06x}",
+            self.sw, expected
+        );
+        self
+    }
+}
+
+/// Text displayed on the screen, with its position
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScreenText {
+    pub text: String,
+    pub x: i64,
+    pub y: i64,
+}
+
+/// Launch settings of Speculos, created with [`Speculos::builder`].
+pub struct SpeculosBuilder {
+    app: PathBuf,
+    executable: PathBuf,
+    model: Model,
+    api_port: u16,
+    seed: Option<String>,
+    args: Vec<String>,
+    screenshot_dir: PathBuf,
+}
+
+impl SpeculosBuilder {
+    /// Sets the path of the `speculos` executable.
+    pub fn executable(self, executable: impl Into<PathBuf>) -> Self {
+        SpeculosBuilder {
+            executable: executable.into(),
+            ..self
+        }
+    }
+
+    /// Sets the emulated device, Nano S+ by default.
+    pub fn model(self, model: Model) -> Self {
+        SpeculosBuilder { model, ..self }
+    }
+
+    /// Sets the port of the REST API, [`DEFAULT_API_PORT`] by default.
+    /// Tests running in parallel need distinct ports.
+    pub fn api_port(self, api_port: u16) -> Self {
+        SpeculosBuilder { api_port, ..self }
+    }
+
+    /// Sets the seed of the emulated device, as a BIP39 mnemonic.
+    pub fn seed(self, seed: &str) -> Self {
+        SpeculosBuilder {
+            seed: Some(seed.into()),
+            ..self
+        }
+    }
+
+    /// Sets the directory where the screenshots requested by the application
+    /// and the mismatching screenshots of [`Speculos::assert_screenshot`] are
+    /// saved, [`DEFAULT_SCREENSHOT_DIR`] by default.
+    pub fn screenshot_dir(self, screenshot_dir: impl Into<PathBuf>) -> Self {
+        SpeculosBuilder {
+            screenshot_dir: screenshot_dir.into(),
+            ..self
+        }
+    }
+
+    /// Adds an argument to the Speculos command line.
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Starts Speculos, and returns once its API is ready.
+    pub fn launch(self) -> Result<Speculos, Error> {
+        let mut command = Command::new(&self.executable);
+        command
+            .arg("--model")
+            .arg(self.model.as_arg())
+            .arg("--display")
+            .arg("headless")
+            .arg("--api-port")
+            .arg(self.api_port.to_string())
+            // Disables the raw APDU TCP port, APDUs are sent through the API
+            .arg("--apdu-port")
+            .arg("0");
+        if let Some(seed) = &self.seed {
+            command.arg("--seed").arg(seed);
+        }
+        command
+            .args(&self.args)
+            .arg(&self.app)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command.spawn()?;
+        let captures = console::Captures::default();
+        // The console is read until Speculos exits, to capture the
+        // screenshots requested by the application
+        let dir = &self.screenshot_dir;
+        if let Some(stdout) = child.stdout.take() {
+            console::spawn_reader(stdout, self.api_port, dir.clone(), captures.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            console::spawn_reader(stderr, self.api_port, dir.clone(), captures.clone());
+        }
+
+        let mut speculos = Speculos {
+            child,
+            port: self.api_port,
+            model: self.model,
+            screenshot_dir: self.screenshot_dir,
+            captures,
+        };
+
+        let start = Instant::now();
+        loop {
+            if http::request(speculos.port, "GET", "/events", None).is_ok() {
+                return Ok(speculos);
+            }
+            if let Some(status) = speculos.child.try_wait()? {
+                return Err(Error::Io(std::io::Error::other(format!(
+                    "speculos exited with {status}"
+                ))));
+            }
+            if start.elapsed() > LAUNCH_TIMEOUT {
+                return Err(Error::Timeout("speculos API not available".into()));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Application running under Speculos, stopped when dropped.
+pub struct Speculos {
+    child: Child,
+    port: u16,
+    model: Model,
+    screenshot_dir: PathBuf,
+    captures: console::Captures,
+}
+
+impl Speculos {
+    /// Returns the launch settings of the application whose ELF file is at
+    /// `app`.
+    pub fn builder(app: impl Into<PathBuf>) -> SpeculosBuilder {
+        SpeculosBuilder {
+            app: app.into(),
+            executable: "speculos".into(),
+            model: Model::NanoSPlus,
+            api_port: DEFAULT_API_PORT,
+            seed: None,
+            args: Vec::new(),
+            screenshot_dir: DEFAULT_SCREENSHOT_DIR.into(),
+        }
+    }
+
+    /// Returns the emulated device.
+    pub fn model(&self) -> Model {
+        self.model
+    }
+
+    fn request(&self, method: &str, path: &str, body: Option<Value>) -> Result<Value, Error> {
+        let body = body.map(|b| b.to_string());
+        let (status, response) = http::request(self.port, method, path, body.as_deref())?;
+        let response = String::from_utf8_lossy(&response).into_owned();
+        if !(200..300).contains(&status) {
+            return Err(Error::Http(status, response));
+        }
+        if response.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&response).map_err(|e| Error::InvalidResponse(e.to_string()))
+    }
+
+    /// Sends a raw APDU, and returns the raw response, status word included.
+    ///
+    /// The call blocks until the application replies, so the screens
+    /// displayed meanwhile, such as a review, must be driven from another
+    /// thread.
+    pub fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, Error> {
+        let response = self.request("POST", "/apdu", Some(json!({ "data": to_hex(apdu) })))?;
+        response["data"]
+            .as_str()
+            .and_then(from_hex)
+            .ok_or_else(|| Error::InvalidResponse(format!("APDU response {response}")))
+    }
+
+    /// Sends an APDU command made of its header and `data`, and returns its
+    /// response.
+    pub fn exchange_apdu(
+        &self,
+        cla: u8,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+    ) -> Result<Response, Error> {
+        let lc = u8::try_from(data.len())
+            .map_err(|_| Error::InvalidResponse("APDU data longer than 255 bytes".into()))?;
+        let mut apdu = vec![cla, ins, p1, p2, lc];
+        apdu.extend_from_slice(data);
+        Response::from_bytes(&self.exchange(&apdu)?)
+            .ok_or_else(|| Error::InvalidResponse("APDU response without status word".into()))
+    }
+
+    /// Presses and releases `button`.
+    pub fn press(&self, button: Button) -> Result<(), Error> {
+        self.request(
+            "POST",
+            button.as_path(),
+            Some(json!({ "action": "press-and-release" })),
+        )
+        .map(|_| ())
+    }
+
+    /// Presses `button` `count` times.
+    pub fn press_times(&self, button: Button, count: usize) -> Result<(), Error> {
+        (0..count).try_for_each(|_| self.press(button))
+    }
+
+    /// Touches the screen of Stax and Flex at (`x`, `y`).
+    pub fn touch(&self, x: u16, y: u16) -> Result<(), Error> {
+        self.request(
+            "POST",
+            "/finger",
+            Some(json!({ "action": "press-and-release", "x": x, "y": y })),
+        )
+        .map(|_| ())
+    }
+
+    /// Returns the texts of the current screen.
+    pub fn screen_texts(&self) -> Result<Vec<ScreenText>, Error> {
+        let response = self.request("GET", "/events?currentscreenonly=true", None)?;
+        let events = response["events"]
+            .as_array()
+            .ok_or_else(|| Error::InvalidResponse(format!("events {response}")))?;
+        Ok(events
+            .iter()
+            .filter_map(|event| {
+                Some(ScreenText {
+                    text: event["text"].as_str()?.into(),
+                    x: event["x"].as_i64().unwrap_or(0),
+                    y: event["y"].as_i64().unwrap_or(0),
+                })
+            })
+            .collect())
+    }
+
+    /// Returns true if one of the texts of the current screen contains `text`.
+    pub fn screen_contains(&self, text: &str) -> Result<bool, Error> {
+        Ok(self
+            .screen_texts()?
+            .iter()
+            .any(|screen_text| screen_text.text.contains(text)))
+    }
+
+    /// Waits until the current screen contains `text`, for 10 seconds at
+    /// most.
+    pub fn wait_for_text(&self, text: &str) -> Result<(), Error> {
+        self.wait_for_text_timeout(text, Duration::from_secs(10))
+    }
+
+    /// Waits until the current screen contains `text`, for `timeout` at most.
+    pub fn wait_for_text_timeout(&self, text: &str, timeout: Duration) -> Result<(), Error> {
+        let start = Instant::now();
+        loop {
+            if self.screen_contains(text)? {
+                return Ok(());
+            }
+            if start.elapsed() > timeout {
+                return Err(Error::Timeout(format!("text {text:?} not displayed")));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Presses `button` until the current screen contains `text`, at most
+    /// `max_presses` times, and returns the number of presses.
+    pub fn navigate_until_text(
+        &self,
+        button: Button,
+        text: &str,
+        max_presses: usize,
+    ) -> Result<usize, Error> {
+        for presses in 0..=max_presses {
+            if self.screen_contains(text)? {
+                return Ok(presses);
+            }
+            if presses < max_presses {
+                self.press(button)?;
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+        Err(Error::Timeout(format!(
+            "text {text:?} not found after {max_presses} presses"
+        )))
+    }
+
+    /// Panics if the current screen does not contain `text` within 10
+    /// seconds.
+    #[track_caller]
+    pub fn assert_text(&self, text: &str) {
+        if let Err(e) = self.wait_for_text(text) {
+            let texts = self.screen_texts().unwrap_or_default();
+            let texts: Vec<&str> = texts.iter().map(|t| t.text.as_str()).collect();
+            panic!("{e}, current screen: {texts:?}");
+        }
+    }
+
+    /// Returns a PNG screenshot of the current screen.
+    pub fn screenshot(&self) -> Result<Vec<u8>, Error> {
+        fetch_screenshot(self.port)
+    }
+
+    /// Saves a screenshot of the current screen as `name.png` in the
+    /// screenshot directory, and returns its path.
+    pub fn save_screenshot(&self, name: &str) -> Result<PathBuf, Error> {
+        console::save(self.port, &self.screenshot_dir, name)
+    }
+
+    /// Waits until the screenshot `name` requested by the application with
+    /// `ledger_device_sdk::testing::screenshot` has been saved, for 10 seconds
+    /// at most, and returns its path.
+    pub fn wait_for_screenshot(&self, name: &str) -> Result<PathBuf, Error> {
+        let start = Instant::now();
+        loop {
+            if self.captures.lock().unwrap().iter().any(|c| c == name) {
+                return Ok(self.screenshot_dir.join(format!("{name}.png")));
+            }
+            if start.elapsed() > Duration::from_secs(10) {
+                return Err(Error::Timeout(format!("screenshot {name:?} not captured")));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Panics if the current screen differs from the reference screenshot
+    /// at `reference`, a PNG file saved by a previous run. The mismatching
+    /// screenshot is then saved in the screenshot directory.
+    ///
+    /// The reference is saved instead if it does not exist, or if the
+    /// [`UPDATE_SCREENSHOTS_VAR`] environment variable is set to `1`.
+    #[track_caller]
+    pub fn assert_screenshot(&self, reference: impl AsRef<Path>) {
+        let reference = reference.as_ref();
+        let png = self.screenshot().expect("screenshot failed");
+        let update = std::env::var(UPDATE_SCREENSHOTS_VAR).is_ok_and(|v| v == "1");
+        if update || !reference.exists() {
+            if let Some(parent) = reference.parent() {
+                fs::create_dir_all(parent).expect("cannot create screenshot directory");
+            }
+            fs::write(reference, png).expect("cannot save reference screenshot");
+            return;
+        }
+        let expected = fs::read(reference).expect("cannot read reference screenshot");
+        if png != expected {
+            let name = reference.file_name().unwrap_or("screenshot.png".as_ref());
+            let actual = self.screenshot_dir.join(name);
+            let saved = fs::create_dir_all(&self.screenshot_dir)
+                .and_then(|_| fs::write(&actual, &png))
+                .is_ok();
+            if saved {
+                panic!(
+                    "screen differs from {}, see {}",
+                    reference.display(),
+                    actual.display()
+                );
+            }
+            panic!("screen differs from {}", reference.display());
+        }
+    }
+}
+
+/// Returns a PNG screenshot of the screen of the Speculos instance whose API
+/// is on `port`.
+fn fetch_screenshot(port: u16) -> Result<Vec<u8>, Error> {
+    let (status, png) = http::request(port, "GET", "/screenshot", None)?;
+    if status != 200 {
+        return Err(Error::Http(
+            status,
+            String::from_utf8_lossy(&png).into_owned(),
+        ));
+    }
+    Ok(png)
+}
+
+impl Drop for Speculos {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(to_hex(&[0xe0, 0x01, 0x00]), "e00100");
+        assert_eq!(from_hex("E0019000"), Some(vec![0xe0, 0x01, 0x90, 0x00]));
+        assert_eq!(from_hex("e00"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+
+    #[test]
+    fn apdu_response() {
+        let response = Response::from_bytes(&[0x01, 0x02, 0x90, 0x00]).unwrap();
+        assert_eq!(response.data, [0x01, 0x02]);
+        assert!(response.is_ok());
+        assert_eq!(Response::from_bytes(&[0x6e, 0x00]).unwrap().sw, 0x6e00);
+        assert!(Response::from_bytes(&[0x90]).is_none());
+    }
+
+    #[test]
+    fn screenshot_request() {
+        let line = format!("{}review_1\n", console::SCREENSHOT_MARKER);
+        assert_eq!(console::screenshot_name(&line), Some("review_1"));
+        let line = format!("{}../review", console::SCREENSHOT_MARKER);
+        assert_eq!(console::screenshot_name(&line), None);
+        assert_eq!(console::screenshot_name("review_1"), None);
+    }
+}
@@ -21,3 +21,30 @@ pub fn test_item(_attr: TokenStream, item: TokenStream) -> TokenStream {
     };
     r.into()
 }
+
+/// Turns a function taking a `&mut Bencher` into a test measuring the
+/// throughput of the operation it passes to `Bencher::iter`, which prints
+/// the time per iteration.
+#[proc_macro_attribute]
+pub fn bench_item(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as syn::ItemFn);
+    let name = &input.sig.ident.to_string();
+    let inputs = &input.sig.inputs;
+    let func = &input.block;
+
+    let r = quote! {
+        #[test_case]
+        const t: TestType = TestType {
+            modname: module_path!(),
+            name: #name,
+            f: || -> Result<(),()> {
+                fn bench_body(#inputs) #func
+                let mut bencher = Default::default();
+                bench_body(&mut bencher);
+                bencher.report();
+                Ok(())
+            }
+        };
+    };
+    r.into()
+}